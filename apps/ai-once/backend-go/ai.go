@@ -1,6 +1,7 @@
 package main
 
 import (
+	"bufio"
 	"bytes"
 	"context"
 	"encoding/base64"
@@ -92,6 +93,11 @@ func parseModels(body []byte) ([]string, error) {
 }
 
 func (s *service) ask(ctx context.Context, req AskRequest) (HistoryEntry, error) {
+	return s.askStream(ctx, req, nil)
+}
+
+// askStream 与 ask 相同；onDelta 不为空时向模型要流式输出，每收到一段文字回调一次。
+func (s *service) askStream(ctx context.Context, req AskRequest, onDelta func(string)) (HistoryEntry, error) {
 	data, err := s.readData()
 	if err != nil {
 		return HistoryEntry{}, err
@@ -128,8 +134,8 @@ func (s *service) ask(ctx context.Context, req AskRequest) (HistoryEntry, error)
 		return HistoryEntry{}, err
 	}
 	messages := buildMessages(tpl.SystemPrompt, input, req.Images)
-	payload := map[string]any{"model": model, "messages": messages, "temperature": 0.2, "stream": false}
-	out, callErr := callChatCompletion(ctx, p, payload, data.Settings.Timeouts)
+	payload := map[string]any{"model": model, "messages": messages, "temperature": 0.2, "stream": onDelta != nil}
+	out, callErr := callChatCompletion(ctx, p, payload, data.Settings.Timeouts, onDelta)
 	if errors.Is(callErr, context.Canceled) {
 		return HistoryEntry{}, callErr
 	}
@@ -169,7 +175,7 @@ func buildMessages(systemPrompt, input string, images []DraftImage) []map[string
 	return append(messages, map[string]any{"role": "user", "content": parts})
 }
 
-func callChatCompletion(ctx context.Context, p Provider, payload map[string]any, timeoutSettings TimeoutSettings) (string, error) {
+func callChatCompletion(ctx context.Context, p Provider, payload map[string]any, timeoutSettings TimeoutSettings, onDelta func(string)) (string, error) {
 	b, _ := json.Marshal(payload)
 	timeout := modelRequestTimeout(timeoutSettings)
 	reqCtx, cancel := context.WithTimeout(ctx, timeout)
@@ -182,21 +188,35 @@ func callChatCompletion(ctx context.Context, p Provider, payload map[string]any,
 	req.Header.Set("Authorization", "Bearer "+strings.TrimSpace(p.APIKey))
 	res, err := httpClient.Do(req)
 	if err != nil {
-		if errors.Is(err, context.Canceled) {
-			return "", context.Canceled
-		}
-		if errors.Is(err, context.DeadlineExceeded) {
-			return "", fmt.Errorf("AI 请求超时（%d 秒）", int(timeout/time.Second))
-		}
-		return "", err
+		return "", chatRequestError(reqCtx, err, timeout)
 	}
 	defer res.Body.Close()
+	success := res.StatusCode >= 200 && res.StatusCode < 300
+	if onDelta != nil && success && strings.HasPrefix(res.Header.Get("Content-Type"), "text/event-stream") {
+		out, err := readChatCompletionStream(res.Body, onDelta)
+		if err != nil {
+			return out, chatRequestError(reqCtx, err, timeout)
+		}
+		return out, nil
+	}
 	body, _ := io.ReadAll(io.LimitReader(res.Body, 8<<20))
 	var raw map[string]any
 	_ = json.Unmarshal(body, &raw)
-	if res.StatusCode < 200 || res.StatusCode >= 300 {
+	if !success {
 		return "", fmt.Errorf("AI HTTP %d: %s", res.StatusCode, errorFromBody(raw, body))
 	}
+	text, err := chatCompletionText(raw)
+	if err != nil {
+		return "", err
+	}
+	// 不支持流式的服务会直接回完整 JSON，整段当作一次输出
+	if onDelta != nil {
+		onDelta(text)
+	}
+	return text, nil
+}
+
+func chatCompletionText(raw map[string]any) (string, error) {
 	choices, _ := raw["choices"].([]any)
 	if len(choices) == 0 {
 		return "", errors.New("响应为空（choices 不存在）")
@@ -213,6 +233,62 @@ func callChatCompletion(ctx context.Context, p Provider, payload map[string]any,
 	return "", errors.New("响应为空（choices[0].message.content 不存在）")
 }
 
+// readChatCompletionStream 读 SSE 流：每个 data 行取 choices[0].delta.content，遇到 [DONE] 结束。
+func readChatCompletionStream(body io.Reader, onDelta func(string)) (string, error) {
+	scanner := bufio.NewScanner(body)
+	scanner.Buffer(make([]byte, 0, 64<<10), 4<<20)
+	var out strings.Builder
+	for scanner.Scan() {
+		data, ok := strings.CutPrefix(strings.TrimSpace(scanner.Text()), "data:")
+		if !ok {
+			continue
+		}
+		data = strings.TrimSpace(data)
+		if data == "[DONE]" {
+			break
+		}
+		var event struct {
+			Choices []struct {
+				Delta struct {
+					Content string `json:"content"`
+				} `json:"delta"`
+			} `json:"choices"`
+			Error *struct {
+				Message string `json:"message"`
+			} `json:"error"`
+		}
+		if err := json.Unmarshal([]byte(data), &event); err != nil {
+			return out.String(), fmt.Errorf("AI 流式响应格式不支持: %w", err)
+		}
+		if event.Error != nil && event.Error.Message != "" {
+			return out.String(), errors.New(event.Error.Message)
+		}
+		if len(event.Choices) == 0 || event.Choices[0].Delta.Content == "" {
+			continue
+		}
+		text := event.Choices[0].Delta.Content
+		out.WriteString(text)
+		onDelta(text)
+	}
+	if err := scanner.Err(); err != nil {
+		return out.String(), err
+	}
+	if out.Len() == 0 {
+		return "", errors.New("响应为空（流式输出没有内容）")
+	}
+	return out.String(), nil
+}
+
+func chatRequestError(ctx context.Context, err error, timeout time.Duration) error {
+	if errors.Is(err, context.Canceled) || errors.Is(ctx.Err(), context.Canceled) {
+		return context.Canceled
+	}
+	if errors.Is(err, context.DeadlineExceeded) || errors.Is(ctx.Err(), context.DeadlineExceeded) {
+		return fmt.Errorf("AI 请求超时（%d 秒）", int(timeout/time.Second))
+	}
+	return err
+}
+
 func validateImages(images []DraftImage, maxCount int, maxMB float64) error {
 	if maxCount <= 0 {
 		maxCount = defaultMaxCount
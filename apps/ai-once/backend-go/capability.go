@@ -12,6 +12,9 @@ type capabilityInvokeRequest struct {
 	CapabilityID string
 	Input        string
 	Config       json.RawMessage
+
+	// OnChunk 不为空时按流式调用，每段输出回调一次
+	OnChunk func(string)
 }
 
 type capabilityQueryOptionsRequest struct {
@@ -56,7 +59,7 @@ func askOnce(ctx context.Context, svc *service, req capabilityInvokeRequest) (an
 		return nil, err
 	}
 
-	return executeAskOnceRequest(ctx, svc, askReq)
+	return executeAskOnceRequest(ctx, svc, askReq, req.OnChunk)
 }
 
 func queryCapabilityOptions(svc *service, req capabilityQueryOptionsRequest) (any, error) {
@@ -145,11 +148,11 @@ func buildAskOnceRequest(svc *service, input string, config askOnceConfig) (AskR
 	}, nil
 }
 
-func executeAskOnceRequest(ctx context.Context, svc *service, req AskRequest) (any, error) {
+func executeAskOnceRequest(ctx context.Context, svc *service, req AskRequest, onChunk func(string)) (any, error) {
 	svc.mu.Lock()
 	defer svc.mu.Unlock()
 
-	entry, err := svc.ask(ctx, req)
+	entry, err := svc.askStream(ctx, req, onChunk)
 	if err != nil {
 		return nil, err
 	}
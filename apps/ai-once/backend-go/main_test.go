@@ -1,6 +1,7 @@
 package main
 
 import (
+	"bufio"
 	"context"
 	"encoding/json"
 	"errors"
@@ -8,6 +9,7 @@ import (
 	"net/http/httptest"
 	"os"
 	"path/filepath"
+	"strings"
 	"testing"
 	"time"
 )
@@ -421,3 +423,142 @@ func TestAskUsesConfiguredRequestTimeout(t *testing.T) {
 		t.Fatalf("configured timeout was not applied quickly enough: %v", time.Since(started))
 	}
 }
+
+func TestAskStreamForwardsChatCompletionDeltas(t *testing.T) {
+	t.Setenv("FW_APP_DATA_DIR", t.TempDir())
+	var got map[string]any
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path != "/v1/chat/completions" {
+			http.NotFound(w, r)
+			return
+		}
+		if err := json.NewDecoder(r.Body).Decode(&got); err != nil {
+			t.Error(err)
+		}
+		w.Header().Set("Content-Type", "text/event-stream")
+		_, _ = w.Write([]byte("data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n"))
+		_, _ = w.Write([]byte("data: {\"choices\":[{\"delta\":{\"content\":\"he\"}}]}\n\n"))
+		_, _ = w.Write([]byte("data: {\"choices\":[{\"delta\":{\"content\":\"llo\"}}]}\n\n"))
+		_, _ = w.Write([]byte("data: [DONE]\n\n"))
+	}))
+	defer server.Close()
+	svc, err := newService()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := svc.ensureReady(); err != nil {
+		t.Fatal(err)
+	}
+	data, err := svc.readData()
+	if err != nil {
+		t.Fatal(err)
+	}
+	data.Settings.Providers[0].BaseURL = server.URL + "/v1"
+	data.Settings.Providers[0].APIKey = "key"
+	data.Spaces[0].DefaultModelByProvider[data.Settings.Providers[0].ID] = "m"
+	if _, err := svc.saveData(data); err != nil {
+		t.Fatal(err)
+	}
+	var deltas []string
+	entry, err := svc.askStream(context.Background(), AskRequest{SpaceID: data.Spaces[0].ID, Input: "hello"}, func(text string) {
+		deltas = append(deltas, text)
+	})
+	if err != nil {
+		t.Fatal(err)
+	}
+	if got["stream"] != true {
+		t.Fatalf("expected streaming payload: %#v", got)
+	}
+	if strings.Join(deltas, "|") != "he|llo" || entry.Output != "hello" {
+		t.Fatalf("bad stream: deltas=%q output=%q", deltas, entry.Output)
+	}
+}
+
+func TestControlStreamsAskOnceAndCancelsByInvocationID(t *testing.T) {
+	t.Setenv("FW_APP_DATA_DIR", t.TempDir())
+	provider := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path != "/v1/chat/completions" {
+			http.NotFound(w, r)
+			return
+		}
+		w.Header().Set("Content-Type", "text/event-stream")
+		_, _ = w.Write([]byte("data: {\"choices\":[{\"delta\":{\"content\":\"he\"}}]}\n\n"))
+		w.(http.Flusher).Flush()
+		<-r.Context().Done()
+	}))
+	defer provider.Close()
+	svc, err := newService()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := svc.ensureReady(); err != nil {
+		t.Fatal(err)
+	}
+	data, err := svc.readData()
+	if err != nil {
+		t.Fatal(err)
+	}
+	data.Settings.Providers[0].BaseURL = provider.URL + "/v1"
+	data.Settings.Providers[0].APIKey = "key"
+	data.Spaces[0].DefaultModelByProvider[data.Settings.Providers[0].ID] = "m"
+	if _, err := svc.saveData(data); err != nil {
+		t.Fatal(err)
+	}
+	server := &rpcServer{svc: svc, token: "test"}
+	control := httptest.NewServer(http.HandlerFunc(server.handleControl))
+	defer control.Close()
+	post := func(body map[string]any) *http.Response {
+		payload, _ := json.Marshal(body)
+		req, err := http.NewRequest(http.MethodPost, control.URL+"/control", strings.NewReader(string(payload)))
+		if err != nil {
+			t.Fatal(err)
+		}
+		req.Header.Set("X-FW-Control-Token", "test")
+		res, err := http.DefaultClient.Do(req)
+		if err != nil {
+			t.Fatal(err)
+		}
+		return res
+	}
+
+	res := post(map[string]any{
+		"action":       "invokeCapability",
+		"capabilityId": capabilityAskOnce,
+		"input":        "hello",
+		"config":       map[string]any{"spaceId": data.Spaces[0].ID},
+		"stream":       true,
+		"invocationId": "inv-1",
+	})
+	defer res.Body.Close()
+	lines := bufio.NewReader(res.Body)
+	readLine := func() map[string]any {
+		line, err := lines.ReadBytes('\n')
+		if err != nil {
+			t.Fatalf("read stream line: %v", err)
+		}
+		var value map[string]any
+		if err := json.Unmarshal(line, &value); err != nil {
+			t.Fatalf("bad stream line %q: %v", line, err)
+		}
+		return value
+	}
+	if first := readLine(); first["type"] != "chunk" || first["text"] != "he" {
+		t.Fatalf("expected first chunk, got %#v", first)
+	}
+
+	cancelRes := post(map[string]any{"action": "cancelCapability", "invocationId": "inv-1"})
+	cancelRes.Body.Close()
+	if cancelRes.StatusCode != http.StatusOK {
+		t.Fatalf("cancel status %d", cancelRes.StatusCode)
+	}
+	if last := readLine(); last["type"] != "error" || last["error"] != "请求已取消" {
+		t.Fatalf("expected canceled error line, got %#v", last)
+	}
+	doc, err := svc.readHistoryRaw()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if len(doc.Items) != 0 {
+		t.Fatalf("expected no history for canceled invocation: %#v", doc.Items)
+	}
+}
@@ -17,11 +17,15 @@ import (
 const (
 	controlActionInvokeCapability       = "invokeCapability"
 	controlActionQueryCapabilityOptions = "queryCapabilityOptions"
+	controlActionCancelCapability       = "cancelCapability"
 )
 
 type rpcServer struct {
 	svc   *service
 	token string
+
+	invocationsMu sync.Mutex
+	invocations   map[string]context.CancelFunc
 }
 type rpcRequest struct {
 	ID     string          `json:"id"`
@@ -45,6 +49,8 @@ type controlRequestBody struct {
 	Input        *string         `json:"input"`
 	OptionSource string          `json:"optionSource"`
 	Config       json.RawMessage `json:"config"`
+	Stream       bool            `json:"stream"`
+	InvocationID string          `json:"invocationId"`
 }
 
 func startRPC(svc *service) error {
@@ -234,11 +240,22 @@ func (s *rpcServer) handleControl(w http.ResponseWriter, r *http.Request) {
 			writeJSONError(w, http.StatusBadRequest, inputErr.Error())
 			return
 		}
-		result, err = invokeCapability(r.Context(), s.svc, capabilityInvokeRequest{
+		ctx, done := s.trackInvocation(r.Context(), body.InvocationID)
+		defer done()
+		req := capabilityInvokeRequest{
 			CapabilityID: body.CapabilityID,
 			Input:        input,
 			Config:       body.Config,
-		})
+		}
+		if body.Stream {
+			streamCapability(ctx, w, s.svc, req)
+			return
+		}
+		result, err = invokeCapability(ctx, s.svc, req)
+	case controlActionCancelCapability:
+		s.cancelInvocation(body.InvocationID)
+		writeJSONResponse(w, http.StatusOK, map[string]any{"ok": true})
+		return
 	case controlActionQueryCapabilityOptions:
 		result, err = queryCapabilityOptions(s.svc, capabilityQueryOptionsRequest{
 			CapabilityID: body.CapabilityID,
@@ -256,6 +273,73 @@ func (s *rpcServer) handleControl(w http.ResponseWriter, r *http.Request) {
 	writeJSONResponse(w, http.StatusOK, result)
 }
 
+// streamCapability 按行回 JSON（NDJSON）：若干 {"type":"chunk"}，最后一行 {"type":"result"} 或 {"type":"error"}。
+func streamCapability(ctx context.Context, w http.ResponseWriter, svc *service, req capabilityInvokeRequest) {
+	flusher, _ := w.(http.Flusher)
+	encoder := json.NewEncoder(w)
+	flush := func() {
+		if flusher != nil {
+			flusher.Flush()
+		}
+	}
+	writeLine := func(line map[string]any) {
+		_ = encoder.Encode(line)
+		flush()
+	}
+	w.Header().Set("Content-Type", "application/x-ndjson")
+	w.WriteHeader(http.StatusOK)
+	flush()
+
+	req.OnChunk = func(text string) {
+		writeLine(map[string]any{"type": "chunk", "text": text})
+	}
+	result, err := invokeCapability(ctx, svc, req)
+	if err != nil {
+		if errors.Is(err, context.Canceled) {
+			err = errors.New("请求已取消")
+		}
+		writeLine(map[string]any{"type": "error", "error": err.Error()})
+		return
+	}
+	line := map[string]any{"type": "result"}
+	if fields, ok := result.(map[string]any); ok {
+		for key, value := range fields {
+			line[key] = value
+		}
+	}
+	writeLine(line)
+}
+
+// trackInvocation 登记带 invocationId 的能力调用，cancelCapability 按编号取消；done 在调用结束时注销。
+func (s *rpcServer) trackInvocation(parent context.Context, invocationID string) (context.Context, func()) {
+	ctx, cancel := context.WithCancel(parent)
+	id := strings.TrimSpace(invocationID)
+	if id == "" {
+		return ctx, cancel
+	}
+	s.invocationsMu.Lock()
+	if s.invocations == nil {
+		s.invocations = map[string]context.CancelFunc{}
+	}
+	s.invocations[id] = cancel
+	s.invocationsMu.Unlock()
+	return ctx, func() {
+		s.invocationsMu.Lock()
+		delete(s.invocations, id)
+		s.invocationsMu.Unlock()
+		cancel()
+	}
+}
+
+func (s *rpcServer) cancelInvocation(invocationID string) {
+	s.invocationsMu.Lock()
+	cancel := s.invocations[strings.TrimSpace(invocationID)]
+	s.invocationsMu.Unlock()
+	if cancel != nil {
+		cancel()
+	}
+}
+
 func requiredControlText(value *string, field string) (string, error) {
 	if value == nil || strings.TrimSpace(*value) == "" {
 		return "", fmt.Errorf("%s 不能为空", field)
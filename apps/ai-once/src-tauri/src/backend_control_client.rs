@@ -1,19 +1,53 @@
+use std::io::Write;
 use std::sync::Arc;
 use std::time::Duration;
 
 use tauri::Manager;
 
 use crate::backend_sidecar::BackendState;
-use crate::control_http_client::{post_json_control_request, HttpControlEndpoint};
+use crate::control_http_client::{
+    post_json_control_request, relay_json_control_request, HttpControlEndpoint,
+};
 
 const ACTION_INVOKE_CAPABILITY: &str = "invokeCapability";
 const ACTION_QUERY_CAPABILITY_OPTIONS: &str = "queryCapabilityOptions";
+const ACTION_CANCEL_CAPABILITY: &str = "cancelCapability";
 const BACKEND_CONTROL_PATH: &str = "/control";
+// 流式回答两段输出之间允许的最长间隔，慢模型首字可能要等一会儿
+const CAPABILITY_STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
 
 pub(crate) fn is_capability_action(action: &str) -> bool {
     matches!(
         action,
-        ACTION_INVOKE_CAPABILITY | ACTION_QUERY_CAPABILITY_OPTIONS
+        ACTION_INVOKE_CAPABILITY | ACTION_QUERY_CAPABILITY_OPTIONS | ACTION_CANCEL_CAPABILITY
+    )
+}
+
+/// 宿主带 stream: true 调用能力时，后台按行输出回答，桌面壳原样转给宿主。
+pub(crate) fn is_streaming_capability_request(action: &str, value: &serde_json::Value) -> bool {
+    action == ACTION_INVOKE_CAPABILITY
+        && value
+            .get("stream")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+}
+
+/// 把流式能力调用转给后台，响应边读边写回 `out`。
+/// 返回错误时还没向 `out` 写过任何内容，调用方可以照常回错误响应。
+pub(crate) fn relay_capability_stream(
+    app: &tauri::AppHandle,
+    value: &serde_json::Value,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let body = capability_backend_body(ACTION_INVOKE_CAPABILITY, value)?;
+    let endpoint = backend_http_endpoint(app)?;
+    relay_json_control_request(
+        &endpoint,
+        BACKEND_CONTROL_PATH,
+        body,
+        CAPABILITY_STREAM_IDLE_TIMEOUT,
+        out,
+        "AI Once 后台能力请求失败",
     )
 }
 
@@ -37,6 +71,13 @@ fn capability_backend_body(
     action: &str,
     value: &serde_json::Value,
 ) -> Result<serde_json::Value, String> {
+    if action == ACTION_CANCEL_CAPABILITY {
+        return Ok(serde_json::json!({
+            "action": ACTION_CANCEL_CAPABILITY,
+            "invocationId": required_json_text(value, "invocationId")?,
+        }));
+    }
+
     let capability_id = required_json_text(value, "capabilityId")?;
     let config = value
         .get("config")
@@ -49,6 +90,11 @@ fn capability_backend_body(
             "capabilityId": capability_id,
             "input": required_json_text(value, "input")?,
             "config": config,
+            "stream": is_streaming_capability_request(action, value),
+            "invocationId": value
+                .get("invocationId")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default(),
         })),
         ACTION_QUERY_CAPABILITY_OPTIONS => Ok(serde_json::json!({
             "action": ACTION_QUERY_CAPABILITY_OPTIONS,
@@ -2,6 +2,8 @@ use std::io::{Read, Write};
 use std::net::TcpStream;
 use std::time::Duration;
 
+use crate::control_server::find_header_end;
+
 pub(crate) struct HttpControlEndpoint {
     addr: String,
     token: String,
@@ -23,6 +25,93 @@ pub(crate) fn post_json_control_request(
     timeout: Duration,
     error_context: &str,
 ) -> Result<serde_json::Value, String> {
+    let mut stream = send_json_control_request(endpoint, path, body, timeout, error_context)?;
+
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .map_err(|e| format!("{error_context}: 读取响应失败: {e}"))?;
+    let response = String::from_utf8_lossy(&response);
+    if !response.starts_with("HTTP/1.1 200") {
+        return Err(format!("{error_context}: {response}"));
+    }
+    let Some((_, body)) = response.split_once("\r\n\r\n") else {
+        return Err(format!("{error_context}: 响应缺少响应体"));
+    };
+    serde_json::from_str::<serde_json::Value>(body)
+        .map_err(|e| format!("{error_context}: 响应不是有效 JSON: {e}"))
+}
+
+/// 发出控制请求后把响应原样转给 `out`：状态行和响应头照抄（连接方式改为 close），
+/// 响应体收到多少写多少，分块编码也原样保留。`timeout` 是两次读到数据之间的最长间隔。
+/// 返回错误时还没向 `out` 写过任何内容；开始转发后对方断开只记日志。
+pub(crate) fn relay_json_control_request(
+    endpoint: &HttpControlEndpoint,
+    path: &str,
+    body: serde_json::Value,
+    timeout: Duration,
+    out: &mut impl Write,
+    error_context: &str,
+) -> Result<(), String> {
+    let mut stream = send_json_control_request(endpoint, path, body, timeout, error_context)?;
+
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let header_end = loop {
+        let n = stream
+            .read(&mut chunk)
+            .map_err(|e| format!("{error_context}: 读取响应失败: {e}"))?;
+        if n == 0 {
+            return Err(format!("{error_context}: 响应为空"));
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+        if let Some(end) = find_header_end(&buffer) {
+            break end;
+        }
+        if buffer.len() > 64 * 1024 {
+            return Err(format!("{error_context}: 响应头过大"));
+        }
+    };
+
+    let header = String::from_utf8_lossy(&buffer[..header_end - 4]);
+    let mut head = String::new();
+    for line in header.split("\r\n") {
+        let name = line.split_once(':').map(|(name, _)| name.trim());
+        if name.is_some_and(|name| {
+            name.eq_ignore_ascii_case("connection") || name.eq_ignore_ascii_case("keep-alive")
+        }) {
+            continue;
+        }
+        head.push_str(line);
+        head.push_str("\r\n");
+    }
+    head.push_str("Connection: close\r\n\r\n");
+
+    let relayed = out
+        .write_all(head.as_bytes())
+        .and_then(|()| out.write_all(&buffer[header_end..]))
+        .and_then(|()| out.flush())
+        .and_then(|()| loop {
+            let n = stream.read(&mut chunk)?;
+            if n == 0 {
+                return Ok(());
+            }
+            out.write_all(&chunk[..n])?;
+            out.flush()?;
+        });
+    if let Err(error) = relayed {
+        eprintln!("[ai-once] {error_context}: 转发中断: {error}");
+    }
+    Ok(())
+}
+
+fn send_json_control_request(
+    endpoint: &HttpControlEndpoint,
+    path: &str,
+    body: serde_json::Value,
+    timeout: Duration,
+    error_context: &str,
+) -> Result<TcpStream, String> {
     let body = body.to_string();
     let request = format!(
         "POST {path} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nX-FW-Control-Token: {}\r\nConnection: close\r\n\r\n{}",
@@ -43,18 +132,45 @@ pub(crate) fn post_json_control_request(
     stream
         .write_all(request.as_bytes())
         .map_err(|e| format!("{error_context}: 发送失败: {e}"))?;
+    Ok(stream)
+}
 
-    let mut response = Vec::new();
-    stream
-        .read_to_end(&mut response)
-        .map_err(|e| format!("{error_context}: 读取响应失败: {e}"))?;
-    let response = String::from_utf8_lossy(&response);
-    if !response.starts_with("HTTP/1.1 200") {
-        return Err(format!("{error_context}: {response}"));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn relays_chunked_response_verbatim() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let backend = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nConnection: close\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n")
+                .unwrap();
+            std::thread::sleep(Duration::from_millis(50));
+            stream.write_all(b"0\r\n\r\n").unwrap();
+        });
+
+        let endpoint = HttpControlEndpoint::new(addr, "token");
+        let mut out = Vec::new();
+        relay_json_control_request(
+            &endpoint,
+            "/control",
+            serde_json::json!({ "action": "invokeCapability" }),
+            Duration::from_secs(2),
+            &mut out,
+            "relay",
+        )
+        .unwrap();
+        backend.join().unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n5\r\nhello\r\n0\r\n\r\n"
+        );
     }
-    let Some((_, body)) = response.split_once("\r\n\r\n") else {
-        return Err(format!("{error_context}: 响应缺少响应体"));
-    };
-    serde_json::from_str::<serde_json::Value>(body)
-        .map_err(|e| format!("{error_context}: 响应不是有效 JSON: {e}"))
 }
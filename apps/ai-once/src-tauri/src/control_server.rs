@@ -5,7 +5,10 @@ use std::time::Duration;
 
 use serde::Serialize;
 
-use crate::backend_control_client::{handle_capability_action, is_capability_action};
+use crate::backend_control_client::{
+    handle_capability_action, is_capability_action, is_streaming_capability_request,
+    relay_capability_stream,
+};
 use crate::fw_window::{apply_control_action, FwWindowState};
 
 pub(crate) const AI_ONCE_APP_ID: &str = "ai-once";
@@ -160,14 +163,30 @@ pub(crate) fn start_control_server(
         .spawn(move || {
             for stream in listener.incoming() {
                 match stream {
-                    Ok(stream) => handle_control_connection(
-                        stream,
-                        &app,
-                        &window_state,
-                        &expected_token,
-                        app_id,
-                        server_id,
-                    ),
+                    Ok(stream) => {
+                        let app = app.clone();
+                        let window_state = window_state.clone();
+                        let expected_token = expected_token.clone();
+                        // 每条连接一个线程：流式能力调用占着连接时，cancelCapability 仍然进得来
+                        let spawned = thread::Builder::new()
+                            .name(format!("{server_name} connection"))
+                            .spawn(move || {
+                                handle_control_connection(
+                                    stream,
+                                    &app,
+                                    &window_state,
+                                    &expected_token,
+                                    app_id,
+                                    server_id,
+                                )
+                            });
+                        if let Err(error) = spawned {
+                            eprintln!(
+                                "[ai-once] {} connection thread failed: {error}",
+                                server_name
+                            );
+                        }
+                    }
                     Err(error) => {
                         eprintln!("[ai-once] {} connection failed: {error}", server_name);
                         break;
@@ -263,6 +282,17 @@ fn handle_control_connection(
         return;
     }
 
+    if is_streaming_capability_request(action, &value) {
+        if let Err(error) = relay_capability_stream(app, &value, &mut stream) {
+            write_control_response(
+                &mut stream,
+                400,
+                serde_json::json!({ "ok": false, "error": error }),
+            );
+        }
+        return;
+    }
+
     if is_capability_action(action) {
         match handle_capability_action(app, action, &value) {
             Ok(response) => write_control_response(&mut stream, 200, response),
@@ -358,7 +388,7 @@ fn read_control_request(stream: &mut TcpStream) -> Result<ControlRequest, String
     })
 }
 
-fn find_header_end(buffer: &[u8]) -> Option<usize> {
+pub(crate) fn find_header_end(buffer: &[u8]) -> Option<usize> {
     buffer
         .windows(4)
         .position(|w| w == b"\r\n\r\n")
@@ -3,7 +3,10 @@ mod registry;
 #[path = "selection_observer.rs"]
 pub(crate) mod selection_observer;
 
-use std::sync::{Arc, Mutex};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -13,6 +16,7 @@ use crate::{
     data_dir,
     selection_capture::SelectionCapture,
     toolbar_display::{ToolbarDisplayMode, ToolbarDisplayModeState},
+    toolbar_window,
 };
 
 const DATA_SCHEMA_VERSION: u32 = 1;
@@ -22,11 +26,18 @@ const MIGRATIONS_FILE: &str = "_migrations.json";
 
 pub(crate) struct QuickBarBackendState {
     registry_lock: Mutex<()>,
+    // 结果浮窗同一时间只展示一次流式调用：记下它的编号和取消信号
+    capability_stream: Mutex<Option<(u64, tokio::sync::oneshot::Sender<()>)>>,
+    next_capability_stream_id: AtomicU64,
 }
 
 impl Default for QuickBarBackendState {
     fn default() -> Self {
-        Self { registry_lock: Mutex::new(()) }
+        Self {
+            registry_lock: Mutex::new(()),
+            capability_stream: Mutex::new(None),
+            next_capability_stream_id: AtomicU64::new(1),
+        }
     }
 }
 
@@ -79,6 +90,28 @@ struct ToolbarButtonDirectResult {
     text: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolbarButtonCanceledResult {
+    kind: &'static str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResultPopupInvokeParams {
+    title: String,
+    #[serde(flatten)]
+    request: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResultPopupInvokeResult {
+    canceled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ToolbarButtonLazySelectResult {
@@ -132,6 +165,16 @@ impl QuickBarBackendState {
             "quickBar.registry.update" => to_value(self.with_registry_lock(|| registry::update(app, params))?),
             "quickBar.capability.list" => host_capability::list(params).await,
             "quickBar.capability.invoke" => host_capability::invoke(params).await,
+            "quickBar.capability.invokeStreaming" => {
+                let input: ResultPopupInvokeParams = decode_params(params)?;
+                let text = self
+                    .invoke_into_result_popup(app, input.title.trim(), input.request)
+                    .await?;
+                to_value(ResultPopupInvokeResult {
+                    canceled: text.is_none(),
+                    text,
+                })
+            }
             "quickBar.capability.options" => host_capability::query_options(params).await,
             "quickBar.toolbar.buttonClick" => self.handle_toolbar_button_click(app, params).await,
             other => Err(format!("未知 Quick Bar 后台方法: {other}")),
@@ -176,13 +219,75 @@ impl QuickBarBackendState {
                 config_fields,
             });
         }
-        let response = host_capability::invoke_for_button(&button, selected_text).await?;
+        let params = host_capability::button_invoke_params(&button, selected_text)?;
+        let Some(text) = self.invoke_into_result_popup(app, &button.title, params).await? else {
+            return to_value(ToolbarButtonCanceledResult { kind: "canceled" });
+        };
         to_value(ToolbarButtonDirectResult {
             kind: "directResult",
             title: button.title,
-            text: extract_response_text(response)?,
+            text,
         })
     }
+
+    /// 流式调用能力，已收到的文字实时推给结果浮窗；浮窗关闭或开始新调用时取消，返回 `None`。
+    async fn invoke_into_result_popup(
+        &self,
+        app: &tauri::AppHandle,
+        title: &str,
+        params: Value,
+    ) -> Result<Option<String>, String> {
+        let (stream_id, cancel) = self.begin_capability_stream()?;
+        let mut text = String::new();
+        let response = host_capability::invoke_streaming(
+            params,
+            |chunk| {
+                text.push_str(chunk);
+                if let Err(error) = toolbar_window::stream_result_text(app, title, &text) {
+                    eprintln!("[quick-bar] 刷新流式结果失败: {error}");
+                }
+            },
+            async {
+                let _ = cancel.await;
+            },
+        )
+        .await;
+        self.finish_capability_stream(stream_id);
+        match response? {
+            Some(response) => extract_response_text(response).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 开始新的流式调用；还在进行的旧调用随旧的取消信号被丢弃而取消。
+    fn begin_capability_stream(
+        &self,
+    ) -> Result<(u64, tokio::sync::oneshot::Receiver<()>), String> {
+        let id = self.next_capability_stream_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        *self
+            .capability_stream
+            .lock()
+            .map_err(|_| "Quick Bar 能力调用状态锁定失败".to_string())? = Some((id, tx));
+        Ok((id, rx))
+    }
+
+    fn finish_capability_stream(&self, id: u64) {
+        if let Ok(mut current) = self.capability_stream.lock() {
+            if current.as_ref().is_some_and(|(current_id, _)| *current_id == id) {
+                *current = None;
+            }
+        }
+    }
+
+    /// 结果浮窗关闭时调用，取消正在进行的流式调用。
+    pub(crate) fn cancel_capability_stream(&self) {
+        if let Ok(mut current) = self.capability_stream.lock() {
+            if let Some((_, tx)) = current.take() {
+                let _ = tx.send(());
+            }
+        }
+    }
 }
 
 pub(crate) fn ensure_ready(app: &tauri::AppHandle) -> Result<(), String> {
@@ -1,9 +1,13 @@
+use std::future::Future;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::quick_bar_backend::{self, registry::RegistryButton};
 
 const REQUEST_TIMEOUT_SECONDS: u64 = 30;
+// 流式调用没有总时长上限，只限制两段输出之间的间隔
+const STREAM_IDLE_TIMEOUT_SECONDS: u64 = 120;
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -23,6 +27,16 @@ struct HostCapabilityInvokeRequest<'a> {
     config: &'a Value,
 }
 
+/// 宿主 /capability/invoke-stream 按行输出的事件，最后一条是 end。
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum HostCapabilityStreamEvent {
+    Chunk { text: String },
+    Done { result: Value },
+    Error { message: String },
+    End { canceled: bool },
+}
+
 pub(crate) async fn list(params: Value) -> Result<Value, String> {
     let request: HostCapabilityListParams = if params.is_null() {
         HostCapabilityListParams { app_id: String::new(), launch_policy: String::new() }
@@ -51,7 +65,68 @@ pub(crate) async fn query_options(params: Value) -> Result<Value, String> {
     send_post("/capability/query-options", params).await
 }
 
-pub(crate) async fn invoke_for_button(
+/// 流式调用能力：收到的文字分块交给 `on_chunk`。`cancel` 先完成时断开请求，宿主会通知应用停止，
+/// 此时返回 `Ok(None)`。
+pub(crate) async fn invoke_streaming(
+    params: Value,
+    mut on_chunk: impl FnMut(&str),
+    cancel: impl Future<Output = ()>,
+) -> Result<Option<Value>, String> {
+    tokio::pin!(cancel);
+    let mut url = endpoint_url()?;
+    url.set_path("/capability/invoke-stream");
+    let client = stream_http_client()?;
+    let send = client
+        .post(url)
+        .header("X-FW-Control-Token", endpoint_token()?)
+        .header("Accept", "application/x-ndjson")
+        .json(&params)
+        .send();
+    let mut response = tokio::select! {
+        response = send => response.map_err(|e| format!("调用宿主能力失败: {e}"))?,
+        _ = &mut cancel => return Ok(None),
+    };
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("宿主能力服务返回 {status}: {}", text.trim()));
+    }
+
+    let idle = std::time::Duration::from_secs(STREAM_IDLE_TIMEOUT_SECONDS);
+    let mut pending = Vec::new();
+    let mut result = None;
+    loop {
+        let chunk = tokio::select! {
+            chunk = tokio::time::timeout(idle, response.chunk()) => chunk
+                .map_err(|_| "读取宿主能力响应超时".to_string())?
+                .map_err(|e| format!("读取宿主能力响应失败: {e}"))?,
+            _ = &mut cancel => return Ok(None),
+        };
+        let finished = chunk.is_none();
+        match chunk {
+            Some(bytes) => pending.extend_from_slice(&bytes),
+            // 最后一行可能没有换行
+            None => pending.push(b'\n'),
+        }
+        for event in drain_stream_events(&mut pending)? {
+            match event {
+                HostCapabilityStreamEvent::Chunk { text } => on_chunk(&text),
+                HostCapabilityStreamEvent::Done { result: value } => result = Some(value),
+                HostCapabilityStreamEvent::Error { message } => return Err(message),
+                HostCapabilityStreamEvent::End { canceled: true } => return Ok(None),
+                HostCapabilityStreamEvent::End { canceled: false } => {}
+            }
+        }
+        if finished {
+            break;
+        }
+    }
+    result
+        .map(Some)
+        .ok_or_else(|| "宿主能力流式响应缺少最终结果".to_string())
+}
+
+pub(crate) fn button_invoke_params(
     button: &RegistryButton,
     selected_text: &str,
 ) -> Result<Value, String> {
@@ -61,9 +136,24 @@ pub(crate) async fn invoke_for_button(
         input: selected_text,
         config: &button.config,
     };
-    let params = serde_json::to_value(request)
-        .map_err(|e| format!("生成能力调用请求失败: {e}"))?;
-    invoke(params).await
+    serde_json::to_value(request).map_err(|e| format!("生成能力调用请求失败: {e}"))
+}
+
+/// 取出缓冲里所有完整的行并解析成事件，不完整的尾巴留在缓冲里。
+fn drain_stream_events(pending: &mut Vec<u8>) -> Result<Vec<HostCapabilityStreamEvent>, String> {
+    let mut events = Vec::new();
+    while let Some(end) = pending.iter().position(|byte| *byte == b'\n') {
+        let line: Vec<u8> = pending.drain(..=end).collect();
+        let line = String::from_utf8_lossy(&line);
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let event =
+            serde_json::from_str(line).map_err(|e| format!("解析宿主能力流式响应失败: {e}"))?;
+        events.push(event);
+    }
+    Ok(events)
 }
 
 fn normalize_launch_policy(value: &str) -> Result<&'static str, String> {
@@ -123,6 +213,13 @@ fn http_client() -> Result<reqwest::Client, String> {
         .map_err(|e| format!("创建宿主能力访问客户端失败: {e}"))
 }
 
+fn stream_http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .connect_timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
+        .build()
+        .map_err(|e| format!("创建宿主能力访问客户端失败: {e}"))
+}
+
 fn endpoint_url() -> Result<reqwest::Url, String> {
     let value = std::env::var("FW_HOST_CAPABILITY_URL")
         .map_err(|_| "环境变量 FW_HOST_CAPABILITY_URL 未设置".to_string())?;
@@ -138,3 +235,35 @@ fn endpoint_token() -> Result<String, String> {
     }
     Ok(token)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drains_complete_stream_lines_and_keeps_the_tail() {
+        let mut pending =
+            b"{\"type\":\"chunk\",\"text\":\"he\"}\n\n{\"type\":\"chunk\",\"te".to_vec();
+        let events = drain_stream_events(&mut pending).unwrap();
+        assert!(matches!(&events[..], [HostCapabilityStreamEvent::Chunk { text }] if text == "he"));
+        assert_eq!(pending, b"{\"type\":\"chunk\",\"te".to_vec());
+
+        pending.extend_from_slice(b"xt\":\"llo\"}\n{\"type\":\"end\",\"canceled\":false}\n");
+        let events = drain_stream_events(&mut pending).unwrap();
+        assert!(matches!(
+            &events[..],
+            [
+                HostCapabilityStreamEvent::Chunk { text },
+                HostCapabilityStreamEvent::End { canceled: false }
+            ] if text == "llo"
+        ));
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_stream_line() {
+        let mut pending = b"not json\n".to_vec();
+        let error = drain_stream_events(&mut pending).unwrap_err();
+        assert!(error.contains("解析宿主能力流式响应失败"));
+    }
+}
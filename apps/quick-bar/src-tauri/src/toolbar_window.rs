@@ -14,7 +14,9 @@ use crate::{
         self, ResultWindowCloseMode, ResultWindowDisplayMode, ResultWindowPreferencesState,
         DEFAULT_RESULT_HEIGHT, DEFAULT_RESULT_WIDTH, MIN_RESULT_HEIGHT, MIN_RESULT_WIDTH,
     },
-    quick_bar_backend::{ToolbarExternalAction, ToolbarRuntimeCommand, ToolbarRuntimeFacts},
+    quick_bar_backend::{
+        QuickBarBackendState, ToolbarExternalAction, ToolbarRuntimeCommand, ToolbarRuntimeFacts,
+    },
     selection_capture::SelectionCapture,
 };
 
@@ -465,6 +467,35 @@ pub(crate) fn update_quick_bar_result_popup(
     payload: ResultPayload,
 ) -> Result<(), String> {
     validate_result_status(&payload.status)?;
+    publish_result(&app, state.inner(), payload)
+}
+
+/// 流式调用期间把已收到的文字推给结果浮窗。
+pub(crate) fn stream_result_text(
+    app: &tauri::AppHandle,
+    title: &str,
+    text: &str,
+) -> Result<(), String> {
+    let state = app.state::<Arc<ToolbarState>>();
+    let payload = ResultPayload {
+        title: title.trim().to_string(),
+        status: "streaming".to_string(),
+        text: Some(text.to_string()),
+        error_text: None,
+        selected_text: None,
+        app: None,
+        app_id: None,
+        capability_id: None,
+        config_fields: None,
+    };
+    publish_result(app, state.inner(), payload)
+}
+
+fn publish_result(
+    app: &tauri::AppHandle,
+    state: &ToolbarState,
+    payload: ResultPayload,
+) -> Result<(), String> {
     state.set_result(payload.clone())?;
     let Some(window) = app.get_webview_window(RESULT_LABEL) else {
         return Ok(());
@@ -520,6 +551,9 @@ fn set_toolbar_visibility(window: &WebviewWindow, visible: bool) {
 }
 
 fn hide_result_popup(app: &tauri::AppHandle) -> Result<(), String> {
+    if let Some(backend) = app.try_state::<Arc<QuickBarBackendState>>() {
+        backend.cancel_capability_stream();
+    }
     if let Some(window) = app.get_webview_window(RESULT_LABEL) {
         set_result_visibility(&window, false);
         if let Err(error) = window.set_ignore_cursor_events(true) {
@@ -807,7 +841,7 @@ fn popup_position(
 }
 
 fn validate_result_status(status: &str) -> Result<(), String> {
-    if matches!(status, "loading" | "done" | "error" | "selecting") {
+    if matches!(status, "loading" | "streaming" | "done" | "error" | "selecting") {
         return Ok(());
     }
     Err("Quick Bar 结果状态不合法".to_string())
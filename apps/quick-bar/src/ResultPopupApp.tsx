@@ -5,7 +5,7 @@ import { getCurrentWindow } from '@tauri-apps/api/window'
 import { writeText } from '@tauri-apps/plugin-clipboard-manager'
 import { createDirectClient } from './directClient'
 import {
-  invokeCapability,
+  invokeCapabilityStreaming,
   queryCapabilityOptions,
   type HostCapabilityConfigField,
 } from './hostCapabilityClient'
@@ -167,12 +167,13 @@ export function ResultPopupApp() {
     let client: Awaited<ReturnType<typeof createDirectClient>> | null = null
     try {
       client = await createDirectClient()
-      const response = await invokeCapability(client, {
+      const response = await invokeCapabilityStreaming(client, title, {
         app: payload.app,
         capabilityId: payload.capabilityId,
         input: payload.selectedText,
         config: configToRecord(configSelections, fields),
       })
+      if (response.canceled) return
       const donePayload: ResultPopupPayload = { title, status: 'done', text: response.text }
       setPayload(donePayload)
       await invoke('update_quick_bar_result_popup', { payload: donePayload })
@@ -222,7 +223,7 @@ export function ResultPopupApp() {
         <div className="quickbar-result-header">
           <div className="quickbar-result-drag-region" onMouseDown={handleStartDragging}>
             <span className="quickbar-result-title">{title}</span>
-            <span className="quickbar-result-subtitle">能力调用结果</span>
+            <span className="quickbar-result-subtitle">{payload?.status === 'streaming' ? '正在输出...' : '能力调用结果'}</span>
           </div>
           <div className="quickbar-result-actions">
             <button type="button" className="quickbar-result-action" onClick={handleCopy} disabled={!canCopy} aria-label="复制结果">
@@ -141,6 +141,7 @@ export function ToolbarApp() {
           buttonId: button.id,
           selectedText: text,
         })
+        if (response.kind === 'canceled') return
         if (response.kind === 'directResult') {
           if (typeof response.title !== 'string' || typeof response.text !== 'string') {
             throw new Error('能力调用结果格式不正确')
@@ -17,6 +17,9 @@ export type HostCapabilityItem = {
   hotkey?: string
   description?: string
   configFields?: HostCapabilityConfigField[]
+  inputSchema?: Record<string, unknown> | boolean
+  configSchema?: Record<string, unknown> | boolean
+  outputSchema?: Record<string, unknown> | boolean
 }
 
 export type CapabilityInvokeRequest = {
@@ -38,6 +41,12 @@ export type CapabilityInvokeResponse = {
   capabilityId: string
   response: unknown
   text: string
+  result?: unknown
+}
+
+export type CapabilityStreamingInvokeResponse = {
+  canceled: boolean
+  text?: string
 }
 
 export type CapabilityOption = {
@@ -82,8 +91,9 @@ export async function fetchCapabilities(client: DirectClient, request: HostCapab
   }
 }
 
-export async function invokeCapability(client: DirectClient, request: CapabilityInvokeRequest): Promise<CapabilityInvokeResponse> {
-  return client.request<CapabilityInvokeResponse>('quickBar.capability.invoke', request)
+// 后台边收边把文字推给结果浮窗；浮窗关闭或开始新调用时返回 canceled
+export async function invokeCapabilityStreaming(client: DirectClient, title: string, request: CapabilityInvokeRequest): Promise<CapabilityStreamingInvokeResponse> {
+  return client.request<CapabilityStreamingInvokeResponse>('quickBar.capability.invokeStreaming', { ...request, title })
 }
 
 export async function queryCapabilityOptions(client: DirectClient, request: CapabilityQueryOptionsRequest): Promise<CapabilityOptionsResponse> {
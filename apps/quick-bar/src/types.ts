@@ -21,7 +21,7 @@ export type ToolbarPayload = {
   layoutRequestId: number
 }
 
-export type ResultPopupStatus = 'loading' | 'done' | 'error' | 'selecting'
+export type ResultPopupStatus = 'loading' | 'streaming' | 'done' | 'error' | 'selecting'
 
 export type ResultPopupPayload = {
   title: string
@@ -103,7 +103,11 @@ export type ToolbarButtonLazySelectResult = {
   configFields: HostCapabilityConfigField[]
 }
 
-export type ToolbarButtonClickResult = ToolbarButtonDirectResult | ToolbarButtonLazySelectResult
+export type ToolbarButtonCanceledResult = {
+  kind: 'canceled'
+}
+
+export type ToolbarButtonClickResult = ToolbarButtonDirectResult | ToolbarButtonLazySelectResult | ToolbarButtonCanceledResult
 
 export const DEFAULT_LAUNCH_INFO: FwLaunchInfo = {
   launched: false,
@@ -22,6 +22,9 @@ pub(crate) struct ControlEndpoint {
     pub(crate) token: String,
 }
 
+// 空闲的 keep-alive 连接最多保留多久；宿主连接池的空闲超时比这个短
+const CONTROL_KEEP_ALIVE_IDLE: Duration = Duration::from_secs(5);
+
 pub(crate) struct ControlServerConfig {
     pub(crate) name: &'static str,
     pub(crate) app_id: &'static str,
@@ -45,6 +48,7 @@ struct ControlRequest {
     path: String,
     token: String,
     body: Vec<u8>,
+    keep_alive: bool,
 }
 
 pub(crate) fn available_commands() -> Vec<AppCommandDescriptor> {
@@ -120,14 +124,30 @@ pub(crate) fn start_control_server(
         .spawn(move || {
             for stream in listener.incoming() {
                 match stream {
-                    Ok(stream) => handle_control_connection(
-                        stream,
-                        &app,
-                        &window_state,
-                        &expected_token,
-                        app_id,
-                        server_id,
-                    ),
+                    Ok(stream) => {
+                        let app = app.clone();
+                        let window_state = window_state.clone();
+                        let expected_token = expected_token.clone();
+                        // 每条连接一个线程：空闲的 keep-alive 连接不会挡住其它连接
+                        let spawned = thread::Builder::new()
+                            .name(format!("{server_name} connection"))
+                            .spawn(move || {
+                                serve_control_connection(
+                                    stream,
+                                    &app,
+                                    &window_state,
+                                    &expected_token,
+                                    app_id,
+                                    server_id,
+                                )
+                            });
+                        if let Err(error) = spawned {
+                            eprintln!(
+                                "[v5-reference-app-go] {} connection thread failed: {error}",
+                                server_name
+                            );
+                        }
+                    }
                     Err(error) => {
                         eprintln!(
                             "[v5-reference-app-go] {} connection failed: {error}",
@@ -186,7 +206,8 @@ pub(crate) fn post_control_request(
     control_response_matches(body, expected_app_id, expected_server_id)
 }
 
-fn handle_control_connection(
+/// 在一条连接上依次处理请求，直到对方要求关闭、断开或空闲超时。
+fn serve_control_connection(
     mut stream: TcpStream,
     app: &tauri::AppHandle,
     window_state: &FwWindowState,
@@ -194,41 +215,61 @@ fn handle_control_connection(
     app_id: &str,
     server_id: &str,
 ) {
-    let request = match read_control_request(&mut stream) {
-        Ok(request) => request,
-        Err(error) => {
-            write_control_response(
-                &mut stream,
-                400,
-                serde_json::json!({ "ok": false, "error": error }),
-            );
+    loop {
+        let request = match read_control_request(&mut stream) {
+            Ok(Some(request)) => request,
+            Ok(None) => return,
+            Err(error) => {
+                write_control_response(
+                    &mut stream,
+                    400,
+                    serde_json::json!({ "ok": false, "error": error }),
+                    false,
+                );
+                return;
+            }
+        };
+        let (status, body) = handle_control_request(
+            &request,
+            app,
+            window_state,
+            expected_token,
+            app_id,
+            server_id,
+        );
+        if !write_control_response(&mut stream, status, body, request.keep_alive)
+            || !request.keep_alive
+        {
             return;
         }
-    };
+    }
+}
 
+fn handle_control_request(
+    request: &ControlRequest,
+    app: &tauri::AppHandle,
+    window_state: &FwWindowState,
+    expected_token: &str,
+    app_id: &str,
+    server_id: &str,
+) -> (u16, serde_json::Value) {
     if request.path != "/control" {
-        write_control_response(
-            &mut stream,
+        return (
             404,
             serde_json::json!({ "ok": false, "error": "控制入口不存在" }),
         );
-        return;
     }
     if request.method != "POST" {
-        write_control_response(
-            &mut stream,
+        return (
             405,
             serde_json::json!({ "ok": false, "error": "控制入口只接受 POST" }),
         );
-        return;
     }
     if request.token != expected_token {
-        write_control_response(
-            &mut stream,
+        return (
             401,
             serde_json::json!({ "ok": false, "error": "控制令牌无效" }),
         );
-        return;
     }
 
     let value = serde_json::from_slice::<serde_json::Value>(&request.body)
@@ -239,9 +280,21 @@ fn handle_control_connection(
         .unwrap_or("show");
     let command = value.get("command").and_then(|v| v.as_str());
 
+    // 宿主健康检查：能回包就说明控制通道正常
+    if action == "ping" {
+        return (
+            200,
+            serde_json::json!({
+                "ok": true,
+                "appId": app_id,
+                "serverId": server_id,
+                "protocolVersion": 1
+            }),
+        );
+    }
+
     if action == "describeCapabilities" {
-        write_control_response(
-            &mut stream,
+        return (
             200,
             serde_json::json!({
                 "ok": true,
@@ -251,12 +304,10 @@ fn handle_control_connection(
                 "capabilities": []
             }),
         );
-        return;
     }
 
     if action == "describeHostShortcuts" {
-        write_control_response(
-            &mut stream,
+        return (
             200,
             serde_json::json!({
                 "ok": true,
@@ -266,12 +317,10 @@ fn handle_control_connection(
                 "hostShortcuts": available_commands()
             }),
         );
-        return;
     }
 
     match apply_control_action(app, window_state, action, command) {
-        Ok(()) => write_control_response(
-            &mut stream,
+        Ok(()) => (
             200,
             serde_json::json!({
                 "ok": true,
@@ -280,11 +329,7 @@ fn handle_control_connection(
                 "protocolVersion": 1
             }),
         ),
-        Err(error) => write_control_response(
-            &mut stream,
-            400,
-            serde_json::json!({ "ok": false, "error": error }),
-        ),
+        Err(error) => (400, serde_json::json!({ "ok": false, "error": error })),
     }
 }
 
@@ -298,17 +343,23 @@ fn control_response_matches(body: &str, expected_app_id: &str, expected_server_i
         && value.server_id.as_deref() == Some(expected_server_id)
 }
 
-fn read_control_request(stream: &mut TcpStream) -> Result<ControlRequest, String> {
-    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+/// 读一个请求；连接在请求开始前被关闭或空闲超时时返回 None。
+fn read_control_request(stream: &mut TcpStream) -> Result<Option<ControlRequest>, String> {
+    let _ = stream.set_read_timeout(Some(CONTROL_KEEP_ALIVE_IDLE));
 
     let mut buffer = Vec::new();
     let mut chunk = [0u8; 1024];
     let header_end = loop {
-        let n = stream
-            .read(&mut chunk)
-            .map_err(|e| format!("读取控制请求失败: {e}"))?;
+        let n = match stream.read(&mut chunk) {
+            Ok(n) => n,
+            Err(_) if buffer.is_empty() => return Ok(None),
+            Err(e) => return Err(format!("读取控制请求失败: {e}")),
+        };
         if n == 0 {
-            return Err("控制请求为空".to_string());
+            if buffer.is_empty() {
+                return Ok(None);
+            }
+            return Err("控制请求不完整".to_string());
         }
         buffer.extend_from_slice(&chunk[..n]);
         if let Some(end) = find_header_end(&buffer) {
@@ -325,6 +376,8 @@ fn read_control_request(stream: &mut TcpStream) -> Result<ControlRequest, String
     let mut request_parts = request_line.split_whitespace();
     let method = request_parts.next().unwrap_or_default().to_string();
     let path = request_parts.next().unwrap_or_default().to_string();
+    // HTTP/1.1 默认保持连接，HTTP/1.0 默认关闭
+    let mut keep_alive = request_parts.next() == Some("HTTP/1.1");
 
     let mut content_length = 0usize;
     let mut token = String::new();
@@ -340,6 +393,9 @@ fn read_control_request(stream: &mut TcpStream) -> Result<ControlRequest, String
         if key.eq_ignore_ascii_case("x-fw-control-token") {
             token = value.to_string();
         }
+        if key.eq_ignore_ascii_case("connection") {
+            keep_alive = !value.eq_ignore_ascii_case("close");
+        }
     }
 
     let mut body = buffer[header_end..].to_vec();
@@ -354,12 +410,13 @@ fn read_control_request(stream: &mut TcpStream) -> Result<ControlRequest, String
     }
     body.truncate(content_length);
 
-    Ok(ControlRequest {
+    Ok(Some(ControlRequest {
         method,
         path,
         token,
         body,
-    })
+        keep_alive,
+    }))
 }
 
 fn find_header_end(buffer: &[u8]) -> Option<usize> {
@@ -369,7 +426,13 @@ fn find_header_end(buffer: &[u8]) -> Option<usize> {
         .map(|i| i + 4)
 }
 
-fn write_control_response(stream: &mut TcpStream, status: u16, body: serde_json::Value) {
+/// 写出响应；返回 false 表示连接已不可用。
+fn write_control_response(
+    stream: &mut TcpStream,
+    status: u16,
+    body: serde_json::Value,
+    keep_alive: bool,
+) -> bool {
     let reason = match status {
         200 => "OK",
         400 => "Bad Request",
@@ -379,13 +442,21 @@ fn write_control_response(stream: &mut TcpStream, status: u16, body: serde_json:
         _ => "Internal Server Error",
     };
     let payload = body.to_string();
+    let connection = if keep_alive {
+        format!(
+            "Connection: keep-alive\r\nKeep-Alive: timeout={}",
+            CONTROL_KEEP_ALIVE_IDLE.as_secs()
+        )
+    } else {
+        "Connection: close".to_string()
+    };
     let head = format!(
-        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{connection}\r\n\r\n",
         payload.as_bytes().len(),
     );
-    let _ = stream.write_all(head.as_bytes());
-    let _ = stream.write_all(payload.as_bytes());
-    let _ = stream.flush();
+    stream.write_all(head.as_bytes()).is_ok()
+        && stream.write_all(payload.as_bytes()).is_ok()
+        && stream.flush().is_ok()
 }
 
 fn write_stdout_json_line(value: serde_json::Value) {
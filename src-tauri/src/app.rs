@@ -6,23 +6,36 @@ use tauri::{
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     Manager, WindowEvent,
 };
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
 
 use crate::app_autostart::schedule_registered_app_auto_start;
 use crate::app_lifecycle::AppLifecycleManager;
-use crate::app_shortcuts::{refresh_registered_app_shortcuts, RegisteredAppShortcutState};
+use crate::app_shortcuts::refresh_registered_app_shortcuts;
+use crate::app_workflows::refresh_workflow_hotkeys;
+use crate::browser_downloads::BrowserDownloadsState;
+use crate::browser_filters::BrowserFilterState;
+use crate::browser_history::BrowserHistoryState;
 use crate::browser_stack::{
     browser_stack_bar_height_px, browser_stack_hide, browser_stack_hide_to_main,
-    browser_stack_is_closing, browser_stack_is_focused, browser_stack_is_pinned,
-    browser_stack_should_suppress_hide,
+    browser_stack_is_closing, browser_stack_is_focused, browser_stack_is_mini,
+    browser_stack_is_pinned, browser_stack_on_mini_moved, browser_stack_should_suppress_hide,
+};
+use crate::browser_tabs::{
+    browser_content_window, is_active_content_label, is_browser_content_label,
+    load_browser_session, BrowserTabsState,
 };
 use crate::clipboard_watch::ClipboardWatchManagerState;
+use crate::command_index::CommandIndexState;
 use crate::host_lifecycle::{host_shutdown_in_progress, request_host_shutdown, HostLifecycleState};
+use crate::hotkeys::{self, HotkeyRegistryState, HotkeySource};
 use crate::http_api::HttpStreamManagerState;
+use crate::http_cache::HttpCacheState;
+use crate::http_debug::HttpDebugState;
 use crate::plugin_assets::plugin_asset_protocol_response;
+use crate::plugin_shortcuts::PluginShortcutState;
 use crate::process_runtime::ProcessManagerState;
 use crate::sqlite_gateway::SqliteConnManager;
 use crate::tasks::TaskManagerState;
+use crate::usage::UsageState;
 use crate::wallpaper::{read_wallpaper_config, resolve_wallpaper_item};
 use crate::windowing::{
     load_browser_window_bounds_from_config, load_main_window_bounds_from_config,
@@ -31,12 +44,12 @@ use crate::windowing::{
     schedule_persist_main_window_bounds, BrowserWindowState, MainWindowFocusMode, WindowState,
 };
 use crate::{
-    app_data_dir, apply_bottom_rounded_corners, browser_ui_set_mode,
-    handle_main_window_mode_shortcut, handle_wake_shortcut, image_mime_by_ext,
+    app_data_dir, apply_bottom_rounded_corners, browser_ui_set_mode, image_mime_by_ext,
     load_auto_start_pref, load_main_window_focus_mode_pref, load_main_window_mode_shortcut,
-    load_wake_shortcut, migrate_legacy_plugin_store_files, query_get_param, safe_relative_path,
-    show_main_window, MainWindowModeShortcutState, WakeShortcutState, APP_STORAGE_ID,
-    AUTO_START_REG_VALUE, BROWSER_BAR_HEIGHT, BROWSER_BAR_WINDOW_LABEL, BROWSER_WINDOW_LABEL,
+    load_wake_shortcut, main_window_mode_hotkey_specs, migrate_legacy_plugin_store_files,
+    query_get_param, safe_relative_path, show_main_window, wake_hotkey_spec,
+    MainWindowModeShortcutState, WakeShortcutState, APP_STORAGE_ID, AUTO_START_REG_VALUE,
+    BROWSER_BAR_HEIGHT, BROWSER_BAR_WINDOW_LABEL,
 };
 use crate::{migrations, wake_logic};
 
@@ -134,12 +147,22 @@ pub(crate) fn builder_tail(builder: tauri::Builder<tauri::Wry>) -> tauri::Builde
             app.manage(Arc::new(TaskManagerState::default()));
             app.manage(Arc::new(ClipboardWatchManagerState::default()));
             app.manage(Arc::new(HttpStreamManagerState::default()));
+            app.manage(Arc::new(HttpCacheState::default()));
+            app.manage(Arc::new(HttpDebugState::default()));
             app.manage(Arc::new(SqliteConnManager::default()));
             app.manage(Arc::new(ProcessManagerState::default()));
             app.manage(Arc::new(AppLifecycleManager::default()));
             app.manage(Arc::new(HostLifecycleState::default()));
-            app.manage(RegisteredAppShortcutState::default());
+            app.manage(HotkeyRegistryState::load(app.handle()));
+            app.manage(PluginShortcutState::default());
             app.manage(BrowserWindowState::default());
+            app.manage(BrowserTabsState::default());
+            app.manage(BrowserHistoryState::default());
+            app.manage(BrowserDownloadsState::default());
+            app.manage(BrowserFilterState::default());
+            app.manage(CommandIndexState::default());
+            app.manage(UsageState::default());
+            crate::command_index::watch_registered_apps(app.handle());
 
             let lifecycle = app.state::<Arc<AppLifecycleManager>>().inner().clone();
             crate::capability_server::start_capability_server(app.handle().clone(), lifecycle)
@@ -183,6 +206,8 @@ pub(crate) fn builder_tail(builder: tauri::Builder<tauri::Wry>) -> tauri::Builde
                     *g = Some(saved);
                 };
             }
+            // 上次退出时未关闭的标签页：预载，首次唤醒时重建浏览栈
+            load_browser_session(app.handle());
 
             // 宿主私有 KV 迁移：插件数据由插件自行调用 storage.migrate 处理。
             let _ = migrations::migrate_plugin_storage(app.handle(), APP_STORAGE_ID);
@@ -192,14 +217,12 @@ pub(crate) fn builder_tail(builder: tauri::Builder<tauri::Wry>) -> tauri::Builde
 
             let (wake_shortcut, wake_shortcut_text) = load_wake_shortcut(app.handle());
             app.manage(WakeShortcutState {
-                current: Mutex::new(wake_shortcut),
-                paused: Mutex::new(false),
+                current: Mutex::new(wake_shortcut.clone()),
             });
 
             let (mode_shortcut, mode_shortcut_text) = load_main_window_mode_shortcut(app.handle());
             app.manage(MainWindowModeShortcutState {
-                current: Mutex::new(mode_shortcut),
-                paused: Mutex::new(false),
+                current: Mutex::new(mode_shortcut.clone()),
             });
 
             // 仅当配置文件显式设置过 autoStart 时，才同步到系统自启（避免默认行为影响用户空间）。
@@ -247,39 +270,25 @@ pub(crate) fn builder_tail(builder: tauri::Builder<tauri::Wry>) -> tauri::Builde
                 .build(app)?;
 
             // 注册全局快捷键（默认：Ctrl+Alt+Space，可在 data/app.json 的 wakeShortcut 配置）
-            if let Err(e) =
-                app.global_shortcut()
-                    .on_shortcut(wake_shortcut, move |app, _shortcut, event| {
-                        if event.state != ShortcutState::Pressed {
-                            return;
-                        }
-                        handle_wake_shortcut(app);
-                    })
-            {
+            for issue in hotkeys::replace_source(
+                app.handle(),
+                HotkeySource::Wake,
+                vec![wake_hotkey_spec(wake_shortcut)],
+            ) {
+                eprintln!("Failed to register wake shortcut {wake_shortcut_text}: {issue}");
+            }
+            for issue in hotkeys::replace_source(
+                app.handle(),
+                HotkeySource::MainWindowMode,
+                main_window_mode_hotkey_specs(mode_shortcut),
+            ) {
                 eprintln!(
-                    "Failed to register wake shortcut {}: {}",
-                    wake_shortcut_text, e
+                    "Failed to register mainWindowModeShortcut {mode_shortcut_text}: {issue}"
                 );
             }
 
-            if let Some(s) = mode_shortcut {
-                if let Err(e) =
-                    app.global_shortcut()
-                        .on_shortcut(s, move |app, _shortcut, event| {
-                            if event.state != ShortcutState::Pressed {
-                                return;
-                            }
-                            handle_main_window_mode_shortcut(app);
-                        })
-                {
-                    eprintln!(
-                        "Failed to register mainWindowModeShortcut {}: {}",
-                        mode_shortcut_text, e
-                    );
-                }
-            }
-
             let _ = refresh_registered_app_shortcuts(app.handle());
+            refresh_workflow_hotkeys(app.handle());
             schedule_registered_app_auto_start(app.handle());
 
             Ok(())
@@ -292,7 +301,7 @@ pub(crate) fn builder_tail(builder: tauri::Builder<tauri::Wry>) -> tauri::Builde
                 if let WindowEvent::Moved(_) = event {
                     if let (Some(bar), Some(content)) = (
                         app.get_webview_window(BROWSER_BAR_WINDOW_LABEL),
-                        app.get_webview_window(BROWSER_WINDOW_LABEL),
+                        browser_content_window(app),
                     ) {
                         let bar_pos = bar.outer_position().ok();
                         let bar_h = bar
@@ -323,7 +332,7 @@ pub(crate) fn builder_tail(builder: tauri::Builder<tauri::Wry>) -> tauri::Builde
                         let app = app.clone();
                         tauri::async_runtime::spawn(async move {
                             tokio::time::sleep(Duration::from_millis(120)).await;
-                            if browser_stack_is_pinned(&app) {
+                            if browser_stack_is_pinned(&app) || browser_stack_is_mini(&app) {
                                 return;
                             }
                             if browser_stack_is_focused(&app) {
@@ -338,12 +347,23 @@ pub(crate) fn builder_tail(builder: tauri::Builder<tauri::Wry>) -> tauri::Builde
                 }
                 return;
             }
-            if window.label() == BROWSER_WINDOW_LABEL {
+            if is_browser_content_label(window.label()) {
                 let app = window.app_handle();
+                // 后台标签的内容窗口是隐藏的，不参与浏览栈联动
+                if !is_active_content_label(app, window.label()) {
+                    return;
+                }
+                if browser_stack_is_mini(app) {
+                    // 迷你模式只有内容窗口：不带动顶部栏，单独记迷你窗口的边界
+                    if let WindowEvent::Moved(_) | WindowEvent::Resized(_) = event {
+                        browser_stack_on_mini_moved(app);
+                        return;
+                    }
+                }
                 if let WindowEvent::Moved(_) = event {
                     if let (Some(bar), Some(content)) = (
                         app.get_webview_window(BROWSER_BAR_WINDOW_LABEL),
-                        app.get_webview_window(BROWSER_WINDOW_LABEL),
+                        browser_content_window(app),
                     ) {
                         let content_pos = content.outer_position().ok();
                         let bar_h = browser_stack_bar_height_px(&bar);
@@ -361,7 +381,7 @@ pub(crate) fn builder_tail(builder: tauri::Builder<tauri::Wry>) -> tauri::Builde
                 if let WindowEvent::Resized(_) = event {
                     if let (Some(bar), Some(content)) = (
                         app.get_webview_window(BROWSER_BAR_WINDOW_LABEL),
-                        app.get_webview_window(BROWSER_WINDOW_LABEL),
+                        browser_content_window(app),
                     ) {
                         let bar_h = browser_stack_bar_height_px(&bar);
                         let content_w = content.inner_size().ok().map(|s| s.width).unwrap_or(0);
@@ -388,7 +408,7 @@ pub(crate) fn builder_tail(builder: tauri::Builder<tauri::Wry>) -> tauri::Builde
                         let app = app.clone();
                         tauri::async_runtime::spawn(async move {
                             tokio::time::sleep(Duration::from_millis(120)).await;
-                            if browser_stack_is_pinned(&app) {
+                            if browser_stack_is_pinned(&app) || browser_stack_is_mini(&app) {
                                 return;
                             }
                             if browser_stack_is_focused(&app) {
@@ -13,7 +13,7 @@ const QUICK_BAR_APP_ID: &str = "quick-bar";
 fn launch_options_for_app(app_id: &str) -> AppLaunchOptions {
     if app_id == QUICK_BAR_APP_ID {
         AppLaunchOptions {
-            extra_envs: crate::capability_server::capability_server_env_vars(),
+            extra_envs: crate::capability_server::capability_server_env_vars(app_id),
         }
     } else {
         AppLaunchOptions::default()
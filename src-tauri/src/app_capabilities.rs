@@ -1,6 +1,9 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
 
 use serde::{Deserialize, Serialize};
+use tauri::ipc::Channel;
 use tauri::AppHandle;
 
 use crate::app_lifecycle::{
@@ -8,7 +11,11 @@ use crate::app_lifecycle::{
         ensure_app_control_endpoint, running_app_control_endpoint, AppLaunchOptions,
         AppLifecycleManager,
     },
-    send_control_json, AppControlEndpoint, RegisteredAppLaunchConfig,
+    send_control_json, AppControlClient, AppControlEndpoint, AppControlTimeouts,
+    RegisteredAppLaunchConfig,
+};
+use crate::capability_schema::{
+    schema_result_summary, validate_against_schema, validate_schema_declaration,
 };
 
 #[derive(Deserialize)]
@@ -22,6 +29,49 @@ pub(crate) struct AppCapabilityInvokeRequest {
     config: serde_json::Value,
     #[serde(default)]
     launch_options: AppLaunchOptions,
+    /// 调用方不允许拉起应用时由能力服务置上
+    #[serde(skip)]
+    running_only: bool,
+}
+
+impl AppCapabilityInvokeRequest {
+    pub(crate) fn new(
+        app: RegisteredAppLaunchConfig,
+        capability_id: String,
+        input: serde_json::Value,
+        config: serde_json::Value,
+    ) -> Self {
+        Self {
+            app,
+            capability_id,
+            input,
+            config,
+            launch_options: AppLaunchOptions::default(),
+            running_only: false,
+        }
+    }
+
+    /// "<appId>/<capabilityId>"，用于访问控制和审计
+    pub(crate) fn target(&self) -> String {
+        format!("{}/{}", self.app.id.trim(), self.capability_id.trim())
+    }
+
+    /// 只调用已在运行的应用，不拉起
+    pub(crate) fn restrict_to_running(&mut self) {
+        self.running_only = true;
+    }
+
+    pub(crate) fn allows_launch(&self) -> bool {
+        self.launch_policy() == AppCapabilityLaunchPolicy::AllowLaunch
+    }
+
+    fn launch_policy(&self) -> AppCapabilityLaunchPolicy {
+        if self.running_only {
+            AppCapabilityLaunchPolicy::RunningOnly
+        } else {
+            AppCapabilityLaunchPolicy::AllowLaunch
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -36,6 +86,20 @@ pub(crate) struct AppCapabilityOptionsRequest {
     launch_policy: AppCapabilityLaunchPolicy,
 }
 
+impl AppCapabilityOptionsRequest {
+    pub(crate) fn target(&self) -> String {
+        format!("{}/{}", self.app.id.trim(), self.capability_id.trim())
+    }
+
+    pub(crate) fn allows_launch(&self) -> bool {
+        self.launch_policy == AppCapabilityLaunchPolicy::AllowLaunch
+    }
+
+    pub(crate) fn restrict_to_running(&mut self) {
+        self.launch_policy = AppCapabilityLaunchPolicy::RunningOnly;
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct AppCapabilityListRequest {
@@ -72,6 +136,15 @@ pub(crate) struct AppCapabilityInvokeResponse {
     capability_id: String,
     response: serde_json::Value,
     text: String,
+    /// 声明了 outputSchema 的能力返回的结构化结果
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<serde_json::Value>,
+}
+
+impl AppCapabilityInvokeResponse {
+    pub(crate) fn into_text(self) -> String {
+        self.text
+    }
 }
 
 #[derive(Serialize)]
@@ -127,8 +200,11 @@ pub(crate) struct AppCapabilityListError {
 }
 
 #[tauri::command]
-pub(crate) fn app_capability_env_vars() -> Vec<(String, String)> {
-    crate::capability_server::capability_server_env_vars()
+pub(crate) fn app_capability_env_vars(app_id: String) -> Result<Vec<(String, String)>, String> {
+    let app_id = validate_runtime_identifier(&app_id, "appId")?;
+    Ok(crate::capability_server::capability_server_env_vars(
+        &app_id,
+    ))
 }
 
 fn validate_runtime_identifier(value: &str, label: &str) -> Result<String, String> {
@@ -153,9 +229,7 @@ async fn send_app_capability_request(
     let endpoint =
         resolve_app_capability_endpoint(app_handle, state, app, launch_options, launch_policy)
             .await?;
-    tokio::task::spawn_blocking(move || send_control_json(endpoint, body))
-        .await
-        .map_err(|e| format!("应用能力调度任务失败: {e}"))?
+    send_control_json(endpoint, body).await
 }
 
 async fn resolve_app_capability_endpoint(
@@ -183,17 +257,18 @@ async fn describe_app_runtime_declarations(
     launch_policy: AppCapabilityLaunchPolicy,
     action: &'static str,
     response_field: RuntimeDeclarationField,
-) -> Result<Vec<crate::app_registry::AppRuntimeDeclaration>, String> {
+) -> Result<RuntimeDeclarations, String> {
     let endpoint =
         resolve_app_capability_endpoint(app_handle, state, app, launch_options, launch_policy)
             .await?;
-    let response = tokio::task::spawn_blocking(move || {
-        send_control_json(endpoint, serde_json::json!({ "action": action }))
-    })
-    .await
-    .map_err(|e| format!("应用运行时声明读取任务失败: {e}"))??;
+    let response =
+        send_control_json(endpoint.clone(), serde_json::json!({ "action": action })).await?;
 
-    runtime_declarations_from_response(response, response_field)
+    let described = runtime_declarations_from_response(response, response_field)?;
+    if matches!(response_field, RuntimeDeclarationField::Capabilities) {
+        remember_capability_schemas(app.id.trim(), &endpoint, &described);
+    }
+    Ok(described)
 }
 
 pub(crate) async fn describe_app_capabilities(
@@ -202,8 +277,8 @@ pub(crate) async fn describe_app_capabilities(
     app: &RegisteredAppLaunchConfig,
     launch_options: AppLaunchOptions,
     launch_policy: AppCapabilityLaunchPolicy,
-) -> Result<Vec<crate::app_registry::AppRuntimeDeclaration>, String> {
-    Ok(describe_app_runtime_declarations(
+) -> Result<RuntimeDeclarations, String> {
+    let mut described = describe_app_runtime_declarations(
         app_handle,
         state,
         app,
@@ -212,10 +287,11 @@ pub(crate) async fn describe_app_capabilities(
         "describeCapabilities",
         RuntimeDeclarationField::Capabilities,
     )
-    .await?
-    .into_iter()
-    .filter(|capability| capability.kind.as_deref() == Some("capability"))
-    .collect())
+    .await?;
+    described
+        .declarations
+        .retain(|capability| capability.kind.as_deref() == Some("capability"));
+    Ok(described)
 }
 
 pub(crate) async fn describe_app_host_shortcuts(
@@ -224,8 +300,8 @@ pub(crate) async fn describe_app_host_shortcuts(
     app: &RegisteredAppLaunchConfig,
     launch_options: AppLaunchOptions,
     launch_policy: AppCapabilityLaunchPolicy,
-) -> Result<Vec<crate::app_registry::AppRuntimeDeclaration>, String> {
-    Ok(describe_app_runtime_declarations(
+) -> Result<RuntimeDeclarations, String> {
+    describe_app_runtime_declarations(
         app_handle,
         state,
         app,
@@ -234,9 +310,7 @@ pub(crate) async fn describe_app_host_shortcuts(
         "describeHostShortcuts",
         RuntimeDeclarationField::HostShortcuts,
     )
-    .await?
-    .into_iter()
-    .collect())
+    .await
 }
 
 #[derive(Deserialize)]
@@ -254,15 +328,219 @@ enum RuntimeDeclarationField {
     HostShortcuts,
 }
 
+/// 应用声明的能力 / 宿主快捷命令；schema 不合法的条目单独剔除，不连累同一应用的其它声明。
+pub(crate) struct RuntimeDeclarations {
+    pub(crate) declarations: Vec<crate::app_registry::AppRuntimeDeclaration>,
+    /// 被剔除的声明 id 与原因
+    pub(crate) rejected: Vec<(String, String)>,
+}
+
+impl RuntimeDeclarations {
+    /// 合并成一条错误说明，列表接口放进 errors
+    pub(crate) fn rejected_message(&self) -> Option<String> {
+        if self.rejected.is_empty() {
+            return None;
+        }
+        let reasons: Vec<&str> = self
+            .rejected
+            .iter()
+            .map(|(_, reason)| reason.as_str())
+            .collect();
+        Some(format!("已忽略声明不合法的条目：{}", reasons.join("；")))
+    }
+}
+
 fn runtime_declarations_from_response(
     response: serde_json::Value,
     field: RuntimeDeclarationField,
-) -> Result<Vec<crate::app_registry::AppRuntimeDeclaration>, String> {
+) -> Result<RuntimeDeclarations, String> {
     let value = serde_json::from_value::<AppControlRuntimeDeclarationDescription>(response)
         .map_err(|e| format!("应用运行时声明响应解析失败: {e}"))?;
-    match field {
-        RuntimeDeclarationField::Capabilities => Ok(value.capabilities),
-        RuntimeDeclarationField::HostShortcuts => Ok(value.host_shortcuts),
+    let all = match field {
+        RuntimeDeclarationField::Capabilities => value.capabilities,
+        RuntimeDeclarationField::HostShortcuts => value.host_shortcuts,
+    };
+    let mut described = RuntimeDeclarations {
+        declarations: Vec::with_capacity(all.len()),
+        rejected: Vec::new(),
+    };
+    for declaration in all {
+        match validate_declaration_schemas(&declaration) {
+            Ok(()) => described.declarations.push(declaration),
+            Err(error) => described
+                .rejected
+                .push((declaration.id.trim().to_string(), error)),
+        }
+    }
+    Ok(described)
+}
+
+fn validate_declaration_schemas(
+    declaration: &crate::app_registry::AppRuntimeDeclaration,
+) -> Result<(), String> {
+    let schemas = [
+        ("inputSchema", &declaration.input_schema),
+        ("configSchema", &declaration.config_schema),
+        ("outputSchema", &declaration.output_schema),
+    ];
+    for (label, schema) in schemas {
+        if let Some(schema) = schema {
+            validate_schema_declaration(schema)
+                .map_err(|e| format!("能力 {} 的 {label} 不合法: {e}", declaration.id))?;
+        }
+    }
+    Ok(())
+}
+
+// ── 能力 schema ─────────────────────────────────────────────────────────────
+//
+// describeCapabilities 的结果按应用缓存能力的 schema，控制地址变了（应用重启）就重新读取。
+// schema 不合法的能力在清单里被剔除，调用时同样拒绝，不会绕过校验直接调用。
+// 声明了 inputSchema / configSchema 的能力在调用前校验请求；声明了 outputSchema 的能力
+// 必须在响应里给 result 并通过校验，text 可省略，由宿主按结果生成展示文字。
+
+#[derive(Clone, Default)]
+struct CapabilitySchemas {
+    input: Option<serde_json::Value>,
+    config: Option<serde_json::Value>,
+    output: Option<serde_json::Value>,
+}
+
+struct AppCapabilitySchemaCache {
+    control_url: String,
+    capabilities: HashMap<String, CapabilitySchemas>,
+    rejected: HashMap<String, String>,
+}
+
+fn capability_schema_cache() -> &'static Mutex<HashMap<String, AppCapabilitySchemaCache>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, AppCapabilitySchemaCache>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn remember_capability_schemas(
+    app_id: &str,
+    endpoint: &AppControlEndpoint,
+    described: &RuntimeDeclarations,
+) {
+    let capabilities = described
+        .declarations
+        .iter()
+        .map(|declaration| {
+            let schemas = CapabilitySchemas {
+                input: declaration.input_schema.clone(),
+                config: declaration.config_schema.clone(),
+                output: declaration.output_schema.clone(),
+            };
+            (declaration.id.trim().to_string(), schemas)
+        })
+        .collect();
+    if let Ok(mut cache) = capability_schema_cache().lock() {
+        cache.insert(
+            app_id.to_string(),
+            AppCapabilitySchemaCache {
+                control_url: endpoint.url.clone(),
+                capabilities,
+                rejected: described.rejected.iter().cloned().collect(),
+            },
+        );
+    }
+}
+
+fn cached_capability_schemas(
+    app_id: &str,
+    capability_id: &str,
+    endpoint: &AppControlEndpoint,
+) -> Option<Result<CapabilitySchemas, String>> {
+    let cache = capability_schema_cache().lock().ok()?;
+    let entry = cache
+        .get(app_id)
+        .filter(|entry| entry.control_url == endpoint.url)?;
+    if let Some(error) = entry.rejected.get(capability_id) {
+        return Some(Err(error.clone()));
+    }
+    Some(Ok(entry
+        .capabilities
+        .get(capability_id)
+        .cloned()
+        .unwrap_or_default()))
+}
+
+async fn capability_schemas(
+    app_id: &str,
+    capability_id: &str,
+    endpoint: &AppControlEndpoint,
+) -> Result<CapabilitySchemas, String> {
+    if let Some(schemas) = cached_capability_schemas(app_id, capability_id, endpoint) {
+        return schemas;
+    }
+    let described = send_control_json(
+        endpoint.clone(),
+        serde_json::json!({ "action": "describeCapabilities" }),
+    )
+    .await
+    .and_then(|response| {
+        runtime_declarations_from_response(response, RuntimeDeclarationField::Capabilities)
+    });
+    match described {
+        Ok(described) => remember_capability_schemas(app_id, endpoint, &described),
+        Err(error) => {
+            // 读不到声明时本次按未声明 schema 处理，不挡住调用；
+            // 不写缓存，下次调用重新读取
+            eprintln!("[app-capability] describe {app_id} for schemas failed: {error}");
+            return Ok(CapabilitySchemas::default());
+        }
+    }
+    cached_capability_schemas(app_id, capability_id, endpoint)
+        .unwrap_or_else(|| Ok(CapabilitySchemas::default()))
+}
+
+impl CapabilitySchemas {
+    fn validate_request(
+        &self,
+        input: &serde_json::Value,
+        config: &serde_json::Value,
+    ) -> Result<(), String> {
+        if let Some(schema) = &self.input {
+            validate_against_schema(schema, input, "input")
+                .map_err(|e| format!("能力输入不符合声明：{e}"))?;
+        }
+        if let Some(schema) = &self.config {
+            // 没传配置等同于空对象
+            let empty = serde_json::json!({});
+            let config = if config.is_null() { &empty } else { config };
+            validate_against_schema(schema, config, "config")
+                .map_err(|e| format!("能力配置不符合声明：{e}"))?;
+        }
+        Ok(())
+    }
+
+    /// 从应用响应取展示文字和结构化结果。
+    fn response_result(
+        &self,
+        response: &serde_json::Value,
+    ) -> Result<(String, Option<serde_json::Value>), String> {
+        let Some(schema) = &self.output else {
+            return Ok((capability_response_text(response)?, None));
+        };
+        let Some(value) = response.as_object() else {
+            return Err("能力返回结果格式不合法：响应必须是包含 result 的对象".to_string());
+        };
+        let Some(result) = value.get("result") else {
+            return Err("能力返回结果格式不合法：缺少 result".to_string());
+        };
+        validate_against_schema(schema, result, "result")
+            .map_err(|e| format!("能力返回结果不符合声明：{e}"))?;
+        let text = match value.get("text") {
+            None | Some(serde_json::Value::Null) => schema_result_summary(schema, result),
+            Some(serde_json::Value::String(text)) => text.trim().to_string(),
+            Some(_) => return Err("能力返回结果格式不合法：text 必须是文本".to_string()),
+        };
+        let text = if text.is_empty() {
+            "（无结果）".to_string()
+        } else {
+            text
+        };
+        Ok((text, Some(result.clone())))
     }
 }
 
@@ -296,10 +574,24 @@ pub(crate) async fn app_capability_list(
         )
         .await
         {
-            Ok(capabilities) => apps.push(AppCapabilityListApp {
-                app_id,
-                capabilities,
-            }),
+            Ok(described) => {
+                if let Some(message) = described.rejected_message() {
+                    errors.push(AppCapabilityListError {
+                        app_id: app_id.clone(),
+                        message,
+                        can_launch: false,
+                    });
+                }
+                crate::command_index::remember_app_capabilities(
+                    &app_handle,
+                    &app_id,
+                    &described.declarations,
+                );
+                apps.push(AppCapabilityListApp {
+                    app_id,
+                    capabilities: described.declarations,
+                })
+            }
             Err(error) => errors.push(AppCapabilityListError {
                 app_id,
                 message: error,
@@ -341,10 +633,24 @@ pub(crate) async fn app_host_shortcut_list(
         )
         .await
         {
-            Ok(host_shortcuts) => apps.push(AppHostShortcutListApp {
-                app_id,
-                host_shortcuts,
-            }),
+            Ok(described) => {
+                if let Some(message) = described.rejected_message() {
+                    errors.push(AppCapabilityListError {
+                        app_id: app_id.clone(),
+                        message,
+                        can_launch: false,
+                    });
+                }
+                crate::command_index::remember_app_host_shortcuts(
+                    &app_handle,
+                    &app_id,
+                    &described.declarations,
+                );
+                apps.push(AppHostShortcutListApp {
+                    app_id,
+                    host_shortcuts: described.declarations,
+                })
+            }
             Err(error) => errors.push(AppCapabilityListError {
                 app_id,
                 message: error,
@@ -372,12 +678,24 @@ pub(crate) async fn app_capability_invoke_inner(
 ) -> Result<AppCapabilityInvokeResponse, String> {
     let app_id = validate_runtime_identifier(&request.app.id, "appId")?;
     let capability_id = validate_runtime_identifier(&request.capability_id, "capabilityId")?;
-    let response = send_app_capability_request(
+    crate::usage::record_usage(
+        &app_handle,
+        crate::usage::UsageKind::Capability,
+        &format!("{app_id}/{capability_id}"),
+    );
+    let launch_policy = request.launch_policy();
+    let endpoint = resolve_app_capability_endpoint(
         app_handle,
         state,
         &request.app,
         request.launch_options,
-        AppCapabilityLaunchPolicy::AllowLaunch,
+        launch_policy,
+    )
+    .await?;
+    let schemas = capability_schemas(&app_id, &capability_id, &endpoint).await?;
+    schemas.validate_request(&request.input, &request.config)?;
+    let response = send_control_json(
+        endpoint,
         serde_json::json!({
             "action": "invokeCapability",
             "capabilityId": capability_id,
@@ -387,13 +705,14 @@ pub(crate) async fn app_capability_invoke_inner(
     )
     .await?;
 
-    let text = capability_response_text(&response)?;
+    let (text, result) = schemas.response_result(&response)?;
 
     Ok(AppCapabilityInvokeResponse {
         app_id,
         capability_id,
         response,
         text,
+        result,
     })
 }
 
@@ -415,6 +734,309 @@ fn required_capability_text(value: &str, label: &str) -> Result<String, String>
     Ok(text)
 }
 
+// ── 流式能力调用 ────────────────────────────────────────────────────────────
+//
+// 宿主在 invokeCapability 里带上 stream: true 与 invocationId。支持流式的应用按行回 JSON（NDJSON）：
+// 若干 {"type":"chunk","text":"..."}，最后一行 {"type":"result","text":"..."}，
+// 出错时回 {"type":"error","error":"..."}。不支持流式的应用照常回一个 JSON 对象，当作只有最终结果。
+// 调用方取消时宿主断开这次控制请求，并给应用发 {"action":"cancelCapability","invocationId":"..."}。
+
+const MAX_CAPABILITY_STREAM_BYTES: usize = 64 * 1024 * 1024;
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum AppCapabilityStreamEvent {
+    Chunk { text: String },
+    Done { result: AppCapabilityInvokeResponse },
+    Error { message: String },
+    End { canceled: bool },
+}
+
+#[derive(Debug, PartialEq)]
+enum CapabilityStreamLine {
+    Chunk(String),
+    Result(serde_json::Value),
+    Error(String),
+}
+
+/// 把应用的流式响应体拆成行；首个完整行决定是流式输出还是旧式的单个 JSON 响应。
+#[derive(Default)]
+struct CapabilityStreamDecoder {
+    pending: Vec<u8>,
+    streaming: Option<bool>,
+    result: Option<serde_json::Value>,
+    total: usize,
+}
+
+impl CapabilityStreamDecoder {
+    /// 喂入一段响应体，返回其中完整的文本分块。
+    fn push(&mut self, bytes: &[u8]) -> Result<Vec<String>, String> {
+        self.total += bytes.len();
+        if self.total > MAX_CAPABILITY_STREAM_BYTES {
+            return Err("能力流式输出过大".to_string());
+        }
+        self.pending.extend_from_slice(bytes);
+
+        let mut chunks = Vec::new();
+        while self.streaming != Some(false) {
+            let Some(end) = self.pending.iter().position(|byte| *byte == b'\n') else {
+                break;
+            };
+            let line = String::from_utf8_lossy(&self.pending[..end])
+                .trim()
+                .to_string();
+            if line.is_empty() {
+                self.pending.drain(..=end);
+                continue;
+            }
+            if self.streaming.is_none() {
+                self.streaming = Some(is_capability_stream_line(&line));
+                if self.streaming == Some(false) {
+                    break;
+                }
+            }
+            self.pending.drain(..=end);
+            if let Some(text) = self.accept_line(&line)? {
+                chunks.push(text);
+            }
+        }
+        Ok(chunks)
+    }
+
+    /// 响应体读完后取最终结果。
+    fn finish(mut self) -> Result<serde_json::Value, String> {
+        let rest = String::from_utf8_lossy(&self.pending).trim().to_string();
+        if self.streaming.is_none() && !rest.is_empty() {
+            self.streaming = Some(is_capability_stream_line(&rest));
+        }
+        if self.streaming != Some(true) {
+            if rest.is_empty() {
+                return Err("应用控制响应缺少响应体".to_string());
+            }
+            return serde_json::from_str(&rest)
+                .map_err(|e| format!("应用控制响应不是有效 JSON: {e}"));
+        }
+        if !rest.is_empty() {
+            // 最后一行可能没有换行，里面的分块已经来不及推送，最终结果会带上完整文本
+            self.accept_line(&rest)?;
+        }
+        self.result
+            .ok_or_else(|| "能力流式输出缺少最终结果".to_string())
+    }
+
+    fn accept_line(&mut self, line: &str) -> Result<Option<String>, String> {
+        if self.result.is_some() {
+            return Err("能力流式输出在最终结果之后还有内容".to_string());
+        }
+        match capability_stream_line(line)? {
+            CapabilityStreamLine::Chunk(text) => Ok(Some(text)),
+            CapabilityStreamLine::Result(value) => {
+                self.result = Some(value);
+                Ok(None)
+            }
+            CapabilityStreamLine::Error(error) => Err(error),
+        }
+    }
+}
+
+fn is_capability_stream_line(line: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(line)
+        .ok()
+        .and_then(|value| {
+            value
+                .get("type")
+                .and_then(serde_json::Value::as_str)
+                .map(|kind| matches!(kind, "chunk" | "result" | "error"))
+        })
+        .unwrap_or(false)
+}
+
+fn capability_stream_line(line: &str) -> Result<CapabilityStreamLine, String> {
+    let mut value = serde_json::from_str::<serde_json::Value>(line)
+        .map_err(|e| format!("能力流式输出格式不合法：{e}"))?;
+    let Some(object) = value.as_object_mut() else {
+        return Err("能力流式输出格式不合法：每行必须是对象".to_string());
+    };
+    let kind = object
+        .remove("type")
+        .and_then(|kind| kind.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let text_field = |field: &str| {
+        object
+            .get(field)
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| format!("能力流式输出格式不合法：{kind} 缺少 {field}"))
+    };
+    match kind.as_str() {
+        "chunk" => Ok(CapabilityStreamLine::Chunk(text_field("text")?)),
+        "error" => Ok(CapabilityStreamLine::Error(text_field("error")?)),
+        "result" => Ok(CapabilityStreamLine::Result(value)),
+        _ => Err(format!("能力流式输出格式不合法：未知类型 {kind}")),
+    }
+}
+
+fn capability_stream_cancels() -> &'static Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>>
+{
+    static CANCELS: OnceLock<Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>>> =
+        OnceLock::new();
+    CANCELS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub(crate) fn make_capability_invocation_id() -> String {
+    let stamp = crate::app_lifecycle::manager::now_ms();
+    format!("capinvoke-{stamp}-{:08x}", crate::rand_u32(stamp))
+}
+
+/// 流式调用应用能力：分块文本交给 `on_chunk`，`cancel` 完成时取消并通知应用。
+/// 取消时返回 `Ok(None)`。
+pub(crate) async fn app_capability_invoke_streaming(
+    app_handle: AppHandle,
+    state: Arc<AppLifecycleManager>,
+    request: AppCapabilityInvokeRequest,
+    invocation_id: &str,
+    mut on_chunk: impl FnMut(String),
+    cancel: impl Future<Output = ()>,
+) -> Result<Option<AppCapabilityInvokeResponse>, String> {
+    let app_id = validate_runtime_identifier(&request.app.id, "appId")?;
+    let capability_id = validate_runtime_identifier(&request.capability_id, "capabilityId")?;
+    crate::usage::record_usage(
+        &app_handle,
+        crate::usage::UsageKind::Capability,
+        &format!("{app_id}/{capability_id}"),
+    );
+    tokio::pin!(cancel);
+
+    // 应用还在启动时取消，不用通知应用
+    let launch_policy = request.launch_policy();
+    let endpoint = tokio::select! {
+        endpoint = resolve_app_capability_endpoint(
+            app_handle,
+            state,
+            &request.app,
+            request.launch_options,
+            launch_policy,
+        ) => endpoint?,
+        _ = &mut cancel => return Ok(None),
+    };
+    let schemas = tokio::select! {
+        schemas = capability_schemas(&app_id, &capability_id, &endpoint) => schemas?,
+        _ = &mut cancel => return Ok(None),
+    };
+    schemas.validate_request(&request.input, &request.config)?;
+
+    let body = serde_json::json!({
+        "action": "invokeCapability",
+        "capabilityId": capability_id,
+        "input": request.input,
+        "config": request.config,
+        "stream": true,
+        "invocationId": invocation_id,
+    });
+    let client = AppControlClient::for_endpoint(&endpoint)?;
+    let mut decoder = CapabilityStreamDecoder::default();
+    let send = client.send_streaming(
+        &body,
+        AppControlTimeouts::for_action("invokeCapability"),
+        |bytes| {
+            for text in decoder.push(bytes)? {
+                on_chunk(text);
+            }
+            Ok(())
+        },
+    );
+    tokio::select! {
+        result = send => result?,
+        _ = &mut cancel => {
+            let body = serde_json::json!({
+                "action": "cancelCapability",
+                "invocationId": invocation_id,
+            });
+            tauri::async_runtime::spawn(async move {
+                if let Err(error) = send_control_json(endpoint, body).await {
+                    eprintln!("[app-capability] cancel notification failed: {error}");
+                }
+            });
+            return Ok(None);
+        }
+    }
+
+    let response = decoder.finish()?;
+    let (text, result) = schemas.response_result(&response)?;
+    Ok(Some(AppCapabilityInvokeResponse {
+        app_id,
+        capability_id,
+        response,
+        text,
+        result,
+    }))
+}
+
+/// 前端用的流式调用：立即返回 invocationId，事件经 Channel 推送，最后一条是 end。
+#[tauri::command]
+pub(crate) fn app_capability_invoke_stream(
+    app_handle: AppHandle,
+    state: tauri::State<'_, Arc<AppLifecycleManager>>,
+    request: AppCapabilityInvokeRequest,
+    channel: Channel<AppCapabilityStreamEvent>,
+) -> Result<String, String> {
+    let invocation_id = make_capability_invocation_id();
+    let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+    capability_stream_cancels()
+        .lock()
+        .map_err(|_| "能力调用状态锁定失败".to_string())?
+        .insert(invocation_id.clone(), tx);
+
+    let state = state.inner().clone();
+    let id_for_task = invocation_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = app_capability_invoke_streaming(
+            app_handle,
+            state,
+            request,
+            &id_for_task,
+            |text| {
+                let _ = channel.send(AppCapabilityStreamEvent::Chunk { text });
+            },
+            async {
+                let _ = rx.await;
+            },
+        )
+        .await;
+        if let Ok(mut map) = capability_stream_cancels().lock() {
+            map.remove(&id_for_task);
+        }
+        let canceled = match result {
+            Ok(Some(result)) => {
+                let _ = channel.send(AppCapabilityStreamEvent::Done { result });
+                false
+            }
+            Ok(None) => true,
+            Err(message) => {
+                let _ = channel.send(AppCapabilityStreamEvent::Error { message });
+                false
+            }
+        };
+        let _ = channel.send(AppCapabilityStreamEvent::End { canceled });
+    });
+
+    Ok(invocation_id)
+}
+
+#[tauri::command]
+pub(crate) fn app_capability_invoke_stream_cancel(invocation_id: String) -> Result<(), String> {
+    let id = invocation_id.trim().to_string();
+    if id.is_empty() {
+        return Err("invocationId 不能为空".to_string());
+    }
+    if let Ok(mut map) = capability_stream_cancels().lock() {
+        if let Some(tx) = map.remove(&id) {
+            let _ = tx.send(());
+        }
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub(crate) async fn app_capability_query_options(
     app_handle: AppHandle,
@@ -602,4 +1224,152 @@ mod tests {
             .unwrap_err();
         assert!(error.contains("value 不能为空"));
     }
+
+    #[test]
+    fn stream_decoder_emits_chunks_and_result() {
+        let mut decoder = CapabilityStreamDecoder::default();
+        let chunks = decoder
+            .push(b"{\"type\":\"chunk\",\"text\":\"he\"}\n{\"type\":\"chu")
+            .unwrap();
+        assert_eq!(chunks, vec!["he".to_string()]);
+        let chunks = decoder
+            .push(b"nk\",\"text\":\"llo\"}\n\n{\"type\":\"result\",\"text\":\"hello\"}\n")
+            .unwrap();
+        assert_eq!(chunks, vec!["llo".to_string()]);
+        let response = decoder.finish().unwrap();
+        assert_eq!(capability_response_text(&response).unwrap(), "hello");
+        assert!(response.get("type").is_none());
+    }
+
+    #[test]
+    fn stream_decoder_accepts_plain_json_response() {
+        let mut decoder = CapabilityStreamDecoder::default();
+        assert!(decoder.push(b"{\n  \"text\": \"hi\"\n").unwrap().is_empty());
+        assert!(decoder.push(b"}\n").unwrap().is_empty());
+        let response = decoder.finish().unwrap();
+        assert_eq!(capability_response_text(&response).unwrap(), "hi");
+    }
+
+    #[test]
+    fn stream_decoder_reports_app_error() {
+        let mut decoder = CapabilityStreamDecoder::default();
+        decoder
+            .push(b"{\"type\":\"chunk\",\"text\":\"a\"}\n")
+            .unwrap();
+        let error = decoder
+            .push(b"{\"type\":\"error\",\"error\":\"model busy\"}\n")
+            .unwrap_err();
+        assert_eq!(error, "model busy");
+    }
+
+    #[test]
+    fn stream_decoder_requires_final_result() {
+        let mut decoder = CapabilityStreamDecoder::default();
+        decoder
+            .push(b"{\"type\":\"chunk\",\"text\":\"a\"}\n")
+            .unwrap();
+        let error = decoder.finish().unwrap_err();
+        assert!(error.contains("缺少最终结果"));
+
+        // 最后一行没有换行也能取到结果
+        let mut decoder = CapabilityStreamDecoder::default();
+        decoder
+            .push(b"{\"type\":\"result\",\"text\":\"done\"}")
+            .unwrap();
+        assert!(decoder.finish().is_ok());
+    }
+
+    #[test]
+    fn output_schema_validates_structured_result() {
+        let schemas = CapabilitySchemas {
+            output: Some(serde_json::json!({
+                "type": "array",
+                "items": { "type": "string", "format": "file-path" }
+            })),
+            ..CapabilitySchemas::default()
+        };
+        let (text, result) = schemas
+            .response_result(&serde_json::json!({ "result": ["a.txt", "b.txt"] }))
+            .unwrap();
+        assert_eq!(text, "a.txt\nb.txt");
+        assert_eq!(result, Some(serde_json::json!(["a.txt", "b.txt"])));
+
+        let error = schemas
+            .response_result(&serde_json::json!({ "result": [1] }))
+            .unwrap_err();
+        assert!(error.contains("result[0] 类型不符"));
+        let error = schemas
+            .response_result(&serde_json::json!({ "text": "hi" }))
+            .unwrap_err();
+        assert!(error.contains("缺少 result"));
+    }
+
+    #[test]
+    fn input_and_config_schemas_validate_requests() {
+        let schemas = CapabilitySchemas {
+            input: Some(serde_json::json!({ "type": "string", "minLength": 1 })),
+            config: Some(serde_json::json!({
+                "type": "object",
+                "required": ["spaceId"]
+            })),
+            ..CapabilitySchemas::default()
+        };
+        let config = serde_json::json!({ "spaceId": "s1" });
+        assert!(schemas
+            .validate_request(&serde_json::json!("hello"), &config)
+            .is_ok());
+        let error = schemas
+            .validate_request(&serde_json::json!(""), &config)
+            .unwrap_err();
+        assert!(error.starts_with("能力输入不符合声明"));
+        let error = schemas
+            .validate_request(&serde_json::json!("hello"), &serde_json::Value::Null)
+            .unwrap_err();
+        assert!(error.contains("config 缺少必填字段 spaceId"));
+    }
+
+    #[test]
+    fn only_capabilities_with_invalid_schema_are_rejected() {
+        let response = serde_json::json!({
+            "capabilities": [
+                {
+                    "id": "ask",
+                    "title": "Ask",
+                    "kind": "capability",
+                    "inputSchema": { "type": "text" }
+                },
+                { "id": "summarize", "title": "Summarize", "kind": "capability" }
+            ]
+        });
+        let described =
+            runtime_declarations_from_response(response, RuntimeDeclarationField::Capabilities)
+                .unwrap();
+        let ids: Vec<&str> = described
+            .declarations
+            .iter()
+            .map(|declaration| declaration.id.as_str())
+            .collect();
+        assert_eq!(ids, ["summarize"]);
+        assert_eq!(described.rejected.len(), 1);
+        assert_eq!(described.rejected[0].0, "ask");
+        assert!(described
+            .rejected_message()
+            .unwrap()
+            .contains("能力 ask 的 inputSchema 不合法"));
+
+        let endpoint = AppControlEndpoint {
+            url: "http://127.0.0.1:1/schema-test".to_string(),
+            token: String::new(),
+        };
+        remember_capability_schemas("schema-test", &endpoint, &described);
+        let error = cached_capability_schemas("schema-test", "ask", &endpoint)
+            .unwrap()
+            .unwrap_err();
+        assert!(error.contains("inputSchema 不合法"));
+        assert!(
+            cached_capability_schemas("schema-test", "summarize", &endpoint)
+                .unwrap()
+                .is_ok()
+        );
+    }
 }
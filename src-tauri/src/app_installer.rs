@@ -9,10 +9,10 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use tauri::AppHandle;
-use tauri_plugin_global_shortcut::Shortcut;
 use tokio::io::AsyncWriteExt;
 
 use crate::app_lifecycle::{stop_registered_app_for_update, AppLifecycleManager};
+use crate::hotkeys::HotkeySequence;
 use crate::install_fs::begin_replace_dir_from_tmp;
 use crate::{
     app_apps_dir, ensure_writable_dir, is_https_url, normalize_zip_name, now_ms,
@@ -712,7 +712,7 @@ fn validate_commands(commands: &[AppPackageCommand]) -> Result<(), String> {
             .filter(|hotkey| !hotkey.is_empty())
         {
             hotkey
-                .parse::<Shortcut>()
+                .parse::<HotkeySequence>()
                 .map_err(|e| format!("fw-app.commands.hotkey 不合法: {id}, {e}"))?;
         }
     }
@@ -1,60 +1,340 @@
 use serde_json::Value;
-use std::io::{Read, Write};
-use std::net::TcpStream;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
 use std::time::Duration;
 
+// ── 应用控制通道客户端 ──────────────────────────────────────────────────────
+//
+// 每个控制地址复用一个 HTTP 客户端（keep-alive 连接池），不再每个动作新建 TCP 连接。
+// 应用回 `Connection: close` 时照常每次新建连接；空闲连接只留几秒，
+// 要短于应用控制服务自己的 keep-alive 超时（参考实现为 5 秒），免得复用一条对方刚关掉的连接。
+// 超时按动作区分：response 是等到响应头的上限，idle 是响应体两个分块之间的最长间隔；
+// 响应体按分块读取，慢但持续有输出的大结果不会被总超时打断。
+// 只连本机控制端口，不走系统代理。
+
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(3);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const MAX_RESPONSE_BYTES: usize = 64 * 1024 * 1024;
+const MAX_ERROR_BODY_BYTES: usize = 4 * 1024;
+
 #[derive(Clone)]
 pub(crate) struct AppControlEndpoint {
     pub(crate) url: String,
     pub(crate) token: String,
 }
 
-pub(crate) fn send_control_json(
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct AppControlTimeouts {
+    pub(crate) response: Duration,
+    pub(crate) idle: Duration,
+}
+
+impl AppControlTimeouts {
+    pub(crate) fn new(response: Duration, idle: Duration) -> Self {
+        Self { response, idle }
+    }
+
+    /// 各控制动作的默认超时
+    pub(crate) fn for_action(action: &str) -> Self {
+        let secs =
+            |response, idle| Self::new(Duration::from_secs(response), Duration::from_secs(idle));
+        match action {
+            "show" | "hide" | "toggle" | "close" | "ping" | "cancelCapability" => secs(5, 5),
+            "describeCapabilities" | "describeHostShortcuts" => secs(10, 10),
+            "queryCapabilityOptions" => secs(30, 15),
+            "invokeCapability" => secs(120, 30),
+            _ => secs(30, 15),
+        }
+    }
+}
+
+/// 一个控制地址对应的客户端；clone 开销很小，共用同一个连接池。
+#[derive(Clone)]
+pub(crate) struct AppControlClient {
+    endpoint: AppControlEndpoint,
+    http: reqwest::Client,
+}
+
+fn control_clients() -> &'static Mutex<HashMap<String, reqwest::Client>> {
+    static CLIENTS: OnceLock<Mutex<HashMap<String, reqwest::Client>>> = OnceLock::new();
+    CLIENTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+impl AppControlClient {
+    pub(crate) fn for_endpoint(endpoint: &AppControlEndpoint) -> Result<Self, String> {
+        let url = control_url(endpoint)?;
+        let mut clients = control_clients()
+            .lock()
+            .map_err(|_| "应用控制客户端锁定失败".to_string())?;
+        let http = match clients.get(&url) {
+            Some(http) => http.clone(),
+            None => {
+                let http = reqwest::Client::builder()
+                    .no_proxy()
+                    .http1_only()
+                    .connect_timeout(CONNECT_TIMEOUT)
+                    .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+                    .pool_max_idle_per_host(4)
+                    .build()
+                    .map_err(|e| format!("创建应用控制客户端失败: {e}"))?;
+                // 应用重启后端口会变，旧地址的客户端没必要留着
+                if clients.len() >= 32 {
+                    clients.clear();
+                }
+                clients.insert(url.clone(), http.clone());
+                http
+            }
+        };
+        Ok(Self {
+            endpoint: AppControlEndpoint {
+                url,
+                token: endpoint.token.clone(),
+            },
+            http,
+        })
+    }
+
+    /// 发出请求并等到响应头
+    async fn start(
+        &self,
+        body: &Value,
+        timeouts: AppControlTimeouts,
+    ) -> Result<reqwest::Response, String> {
+        let request = self
+            .http
+            .post(format!("{}/control", self.endpoint.url))
+            .header("X-FW-Control-Token", &self.endpoint.token)
+            .json(body)
+            .send();
+        tokio::time::timeout(timeouts.response, request)
+            .await
+            .map_err(|_| "应用控制通道响应超时".to_string())?
+            .map_err(|e| format!("发送应用控制指令失败: {e}"))
+    }
+
+    /// 发送控制请求，响应体按分块交给 `on_chunk`；非 2xx 响应返回错误。
+    pub(crate) async fn send_streaming(
+        &self,
+        body: &Value,
+        timeouts: AppControlTimeouts,
+        mut on_chunk: impl FnMut(&[u8]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let mut response = self.start(body, timeouts).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let mut detail = Vec::new();
+            while detail.len() < MAX_ERROR_BODY_BYTES {
+                match tokio::time::timeout(timeouts.idle, response.chunk()).await {
+                    Ok(Ok(Some(chunk))) => detail.extend_from_slice(&chunk),
+                    _ => break,
+                }
+            }
+            detail.truncate(MAX_ERROR_BODY_BYTES);
+            return Err(format!(
+                "应用控制指令失败: {status} {}",
+                control_error_text(&detail)
+            ));
+        }
+
+        loop {
+            let chunk = tokio::time::timeout(timeouts.idle, response.chunk())
+                .await
+                .map_err(|_| "应用控制响应读取超时".to_string())?
+                .map_err(|e| format!("读取应用控制响应失败: {e}"))?;
+            let Some(chunk) = chunk else {
+                return Ok(());
+            };
+            on_chunk(&chunk)?;
+        }
+    }
+
+    pub(crate) async fn send_json(
+        &self,
+        body: &Value,
+        timeouts: AppControlTimeouts,
+    ) -> Result<Value, String> {
+        let mut buffer = Vec::new();
+        self.send_streaming(body, timeouts, |chunk| {
+            if buffer.len() + chunk.len() > MAX_RESPONSE_BYTES {
+                return Err("应用控制响应过大".to_string());
+            }
+            buffer.extend_from_slice(chunk);
+            Ok(())
+        })
+        .await?;
+        if buffer.is_empty() {
+            return Err("应用控制响应缺少响应体".to_string());
+        }
+        serde_json::from_slice::<Value>(&buffer)
+            .map_err(|e| format!("应用控制响应不是有效 JSON: {e}"))
+    }
+}
+
+/// 按 body.action 取默认超时发送控制请求。
+pub(crate) async fn send_control_json(
     endpoint: AppControlEndpoint,
     body_value: Value,
 ) -> Result<Value, String> {
+    let action = body_value
+        .get("action")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let timeouts = AppControlTimeouts::for_action(action);
+    AppControlClient::for_endpoint(&endpoint)?
+        .send_json(&body_value, timeouts)
+        .await
+}
+
+/// 健康检查：发送 ping 并在 `timeout` 内等到完整响应即算存活。
+/// 不认识 ping 的旧版应用会回 HTTP 错误响应，同样说明控制通道还在工作。
+pub(crate) async fn ping_control(
+    endpoint: &AppControlEndpoint,
+    timeout: Duration,
+) -> Result<(), String> {
+    let timeouts = AppControlTimeouts::new(timeout, timeout);
+    let mut response = AppControlClient::for_endpoint(endpoint)?
+        .start(&serde_json::json!({ "action": "ping" }), timeouts)
+        .await?;
+    // 读完响应体，连接才能回到连接池
+    loop {
+        match tokio::time::timeout(timeouts.idle, response.chunk()).await {
+            Ok(Ok(Some(_))) => {}
+            Ok(Ok(None)) => return Ok(()),
+            Ok(Err(e)) => return Err(format!("读取应用控制响应失败: {e}")),
+            Err(_) => return Err("应用控制响应读取超时".to_string()),
+        }
+    }
+}
+
+fn control_url(endpoint: &AppControlEndpoint) -> Result<String, String> {
     let url = endpoint.url.trim().trim_end_matches('/');
     let Some(addr) = url.strip_prefix("http://") else {
         return Err("应用控制地址不支持".to_string());
     };
+    if addr.is_empty() || addr.contains('/') {
+        return Err("应用控制地址不支持".to_string());
+    }
+    Ok(url.to_string())
+}
 
-    let body = body_value.to_string();
-    let request = format!(
-        "POST /control HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nX-FW-Control-Token: {}\r\nConnection: close\r\n\r\n{}",
-        body.as_bytes().len(),
-        endpoint.token,
-        body,
-    );
-
-    let mut stream = TcpStream::connect(addr).map_err(|e| format!("连接应用控制通道失败: {e}"))?;
-    stream
-        .set_write_timeout(Some(Duration::from_secs(5)))
-        .map_err(|e| format!("设置应用控制写入超时失败: {e}"))?;
-    stream
-        .write_all(request.as_bytes())
-        .map_err(|e| format!("发送应用控制指令失败: {e}"))?;
-    let response = read_http_response(&mut stream)?;
-    if response.starts_with("HTTP/1.1 200") {
-        response_json_body(&response)
-    } else {
-        Err(format!("应用控制指令失败: {response}"))
-    }
-}
-
-fn response_json_body(response: &str) -> Result<Value, String> {
-    let Some((_, body)) = response.split_once("\r\n\r\n") else {
-        return Err("应用控制响应缺少响应体".to_string());
-    };
-    serde_json::from_str::<Value>(body).map_err(|e| format!("应用控制响应不是有效 JSON: {e}"))
-}
-
-fn read_http_response(stream: &mut TcpStream) -> Result<String, String> {
-    stream
-        .set_read_timeout(Some(Duration::from_secs(5)))
-        .map_err(|e| format!("设置应用控制读取超时失败: {e}"))?;
-    let mut buffer = Vec::new();
-    stream
-        .read_to_end(&mut buffer)
-        .map_err(|e| format!("读取应用控制响应失败: {e}"))?;
-    Ok(String::from_utf8_lossy(&buffer).to_string())
+/// 错误响应优先取 JSON 里的 error 字段
+fn control_error_text(body: &[u8]) -> String {
+    let text = String::from_utf8_lossy(body);
+    serde_json::from_str::<Value>(&text)
+        .ok()
+        .and_then(|value| {
+            value
+                .get("error")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| text.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpStream;
+
+    fn endpoint(url: &str) -> AppControlEndpoint {
+        AppControlEndpoint {
+            url: url.to_string(),
+            token: "t".to_string(),
+        }
+    }
+
+    #[test]
+    fn control_url_accepts_plain_local_http_only() {
+        assert_eq!(
+            control_url(&endpoint(" http://127.0.0.1:9001/ ")).unwrap(),
+            "http://127.0.0.1:9001"
+        );
+        assert!(control_url(&endpoint("https://127.0.0.1:9001")).is_err());
+        assert!(control_url(&endpoint("http://127.0.0.1:9001/control")).is_err());
+    }
+
+    #[test]
+    fn action_timeouts_give_capabilities_more_time() {
+        let window = AppControlTimeouts::for_action("show");
+        let invoke = AppControlTimeouts::for_action("invokeCapability");
+        assert_eq!(window.response, Duration::from_secs(5));
+        assert!(invoke.response > window.response);
+        assert!(invoke.idle < invoke.response);
+    }
+
+    /// 本机 keep-alive 控制服务：每个请求回一个分两块的 chunked 响应，并记下接受过几条连接。
+    fn start_keep_alive_server() -> (String, Arc<AtomicUsize>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(serve_keep_alive(stream));
+            }
+        });
+        (format!("http://{addr}"), connections)
+    }
+
+    async fn serve_keep_alive(stream: TcpStream) {
+        let mut reader = BufReader::new(stream);
+        loop {
+            let mut content_length = 0;
+            let mut line = String::new();
+            loop {
+                line.clear();
+                if reader.read_line(&mut line).await.unwrap_or(0) == 0 {
+                    return;
+                }
+                let header = line.trim_end();
+                if header.is_empty() {
+                    break;
+                }
+                if let Some((key, value)) = header.split_once(':') {
+                    if key.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).await.unwrap();
+            let response = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\nConnection: keep-alive\r\n\r\n6\r\n{\"ok\":\r\n5\r\ntrue}\r\n0\r\n\r\n";
+            reader
+                .get_mut()
+                .write_all(response.as_bytes())
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn reuses_keep_alive_connection_for_chunked_responses() {
+        let (url, connections) = start_keep_alive_server();
+        let client = AppControlClient::for_endpoint(&endpoint(&url)).unwrap();
+        let timeouts = AppControlTimeouts::for_action("ping");
+        for _ in 0..3 {
+            let value = client
+                .send_json(&serde_json::json!({ "action": "ping" }), timeouts)
+                .await
+                .unwrap();
+            assert_eq!(value, serde_json::json!({ "ok": true }));
+        }
+        ping_control(&endpoint(&url), Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn error_text_prefers_json_error_field() {
+        assert_eq!(control_error_text(br#"{"error":"not found"}"#), "not found");
+        assert_eq!(control_error_text(b" plain "), "plain");
+    }
 }
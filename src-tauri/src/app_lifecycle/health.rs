@@ -0,0 +1,343 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use super::control_channel::ping_control;
+use super::logs::{write_app_log, AppLogStream, SharedAppLog};
+use super::manager::{
+    app_launch_inner_with_options, build_registered_app_launch_args, force_stop_app, now_ms,
+    AppLifecycleManager, AppProcessEntry,
+};
+use super::supervisor::{registered_app_config, AppCrashOutcome, AppCrashRecord};
+
+// ── 健康检查 ────────────────────────────────────────────────────────────────
+//
+// 每个托管应用启动后跟一个检查任务：控制通道就绪后每隔 intervalSecs 发一次 ping，
+// timeoutMs 内没收到响应算一次失败；连续失败 failureThreshold 次标记为无响应，
+// 之后任意一次成功就恢复。最近一次成功时间与往返耗时记在进程条目上，app_status 可查。
+// restartUnresponsive 开启时，无响应的应用会被强制结束并重新启动；
+// 次数上限沿用 supervision 的 maxRestarts / windowSecs，与崩溃重启共用一份计数。
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppHealthCheckConfig {
+    #[serde(default = "default_enabled")]
+    pub(crate) enabled: bool,
+    #[serde(default = "default_interval_secs")]
+    pub(crate) interval_secs: u64,
+    #[serde(default = "default_timeout_ms")]
+    pub(crate) timeout_ms: u64,
+    #[serde(default = "default_failure_threshold")]
+    pub(crate) failure_threshold: u32,
+    #[serde(default)]
+    pub(crate) restart_unresponsive: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval_secs() -> u64 {
+    15
+}
+
+fn default_timeout_ms() -> u64 {
+    3_000
+}
+
+fn default_failure_threshold() -> u32 {
+    3
+}
+
+impl Default for AppHealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            interval_secs: default_interval_secs(),
+            timeout_ms: default_timeout_ms(),
+            failure_threshold: default_failure_threshold(),
+            restart_unresponsive: false,
+        }
+    }
+}
+
+impl AppHealthCheckConfig {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if !(5..=3_600).contains(&self.interval_secs) {
+            return Err("健康检查间隔必须在 5 秒到 1 小时之间".to_string());
+        }
+        if !(200..=30_000).contains(&self.timeout_ms) {
+            return Err("健康检查超时必须在 200 毫秒到 30 秒之间".to_string());
+        }
+        if self.timeout_ms >= self.interval_secs.saturating_mul(1_000) {
+            return Err("健康检查超时必须小于检查间隔".to_string());
+        }
+        if !(1..=20).contains(&self.failure_threshold) {
+            return Err("健康检查失败次数阈值必须在 1 到 20 之间".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 进程条目上的健康状态。
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AppHealthState {
+    pub(crate) last_healthy_ms: Option<u64>,
+    pub(crate) latency_ms: Option<u64>,
+    pub(crate) consecutive_failures: u32,
+    pub(crate) unresponsive: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum AppHealthChange {
+    Unchanged,
+    BecameUnresponsive,
+    Recovered,
+}
+
+impl AppHealthState {
+    pub(crate) fn record_success(&mut self, now_ms: u64, latency_ms: u64) -> AppHealthChange {
+        let was_unresponsive = self.unresponsive;
+        self.last_healthy_ms = Some(now_ms);
+        self.latency_ms = Some(latency_ms);
+        self.consecutive_failures = 0;
+        self.unresponsive = false;
+        if was_unresponsive {
+            AppHealthChange::Recovered
+        } else {
+            AppHealthChange::Unchanged
+        }
+    }
+
+    pub(crate) fn record_failure(&mut self, failure_threshold: u32) -> AppHealthChange {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if !self.unresponsive && self.consecutive_failures >= failure_threshold.max(1) {
+            self.unresponsive = true;
+            AppHealthChange::BecameUnresponsive
+        } else {
+            AppHealthChange::Unchanged
+        }
+    }
+}
+
+/// 启动后由 manager 调用；进程退出或被宿主停止后任务自行结束。
+pub(super) fn spawn_health_monitor(
+    app_handle: AppHandle,
+    state: Arc<AppLifecycleManager>,
+    app_id: String,
+    entry: Arc<AppProcessEntry>,
+    log: Option<SharedAppLog>,
+) {
+    let Some(launch_config) = registered_app_config(&app_handle, &app_id) else {
+        return;
+    };
+    let config = launch_config.health_check.clone();
+    if !config.enabled {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let interval = Duration::from_secs(config.interval_secs);
+        let timeout = Duration::from_millis(config.timeout_ms);
+        loop {
+            tokio::time::sleep(interval).await;
+            if !entry.is_monitored() {
+                return;
+            }
+            // 控制通道还没就绪时不算失败
+            let Some(endpoint) = entry.control_endpoint() else {
+                continue;
+            };
+
+            let started = Instant::now();
+            let result = ping_control(&endpoint, timeout).await;
+            if !entry.is_monitored() {
+                return;
+            }
+
+            let change = entry.update_health(|health| match &result {
+                Ok(()) => health.record_success(now_ms(), started.elapsed().as_millis() as u64),
+                Err(_) => health.record_failure(config.failure_threshold),
+            });
+            match change {
+                AppHealthChange::Unchanged => {}
+                AppHealthChange::Recovered => {
+                    write_app_log(&log, AppLogStream::Host, "恢复响应");
+                }
+                AppHealthChange::BecameUnresponsive => {
+                    let error = result.err().unwrap_or_default();
+                    write_app_log(
+                        &log,
+                        AppLogStream::Host,
+                        &format!(
+                            "连续 {} 次健康检查失败，标记为无响应：{error}",
+                            config.failure_threshold
+                        ),
+                    );
+                    eprintln!("[app-health] {app_id} is unresponsive: {error}");
+                    if config.restart_unresponsive {
+                        restart_unresponsive_app(app_handle, state, app_id, entry, log).await;
+                        return;
+                    }
+                    crate::host_primitives::emit_toast(
+                        &app_handle,
+                        format!("应用 {app_id} 没有响应"),
+                    );
+                }
+            }
+        }
+    });
+}
+
+async fn restart_unresponsive_app(
+    app_handle: AppHandle,
+    state: Arc<AppLifecycleManager>,
+    app_id: String,
+    entry: Arc<AppProcessEntry>,
+    log: Option<SharedAppLog>,
+) {
+    if crate::host_lifecycle::host_shutdown_in_progress(&app_handle) {
+        return;
+    }
+    let Some(config) = registered_app_config(&app_handle, &app_id) else {
+        return;
+    };
+    let reason = "长时间无响应".to_string();
+    let now = now_ms();
+    let window_ms = config.supervision.window_secs.saturating_mul(1_000);
+    let recent = state
+        .crash_log
+        .recent_restarts(&app_id, now, window_ms)
+        .len();
+    if recent >= config.supervision.max_restarts as usize {
+        state.crash_log.push(
+            &app_id,
+            AppCrashRecord {
+                at: now,
+                exit_code: None,
+                outcome: AppCrashOutcome::GaveUp,
+                error: Some(reason),
+            },
+        );
+        crate::host_primitives::emit_toast(
+            &app_handle,
+            format!(
+                "应用 {app_id} 没有响应，{} 秒内已重启 {} 次，不再自动重启",
+                config.supervision.window_secs, config.supervision.max_restarts
+            ),
+        );
+        return;
+    }
+
+    state.crash_log.note_restart(&app_id, now);
+    state.crash_log.push(
+        &app_id,
+        AppCrashRecord {
+            at: now,
+            exit_code: None,
+            outcome: AppCrashOutcome::Restarting,
+            error: Some(reason.clone()),
+        },
+    );
+    write_app_log(&log, AppLogStream::Host, "无响应，强制重启");
+
+    let launch_options = entry.launch_options();
+    let result = match force_stop_app(&state, &app_id).await {
+        // 停止期间宿主开始退出：不再拉起，免得应用活得比宿主久
+        Ok(_) if crate::host_lifecycle::host_shutdown_in_progress(&app_handle) => {
+            state.crash_log.finish_last(
+                &app_id,
+                AppCrashOutcome::NotRestarted,
+                Some(format!("{reason}：宿主正在退出")),
+            );
+            return;
+        }
+        Ok(_) => {
+            let args = build_registered_app_launch_args(&config, "hide", None);
+            app_launch_inner_with_options(
+                app_handle.clone(),
+                state.clone(),
+                app_id.clone(),
+                config.path.clone(),
+                args,
+                launch_options,
+            )
+            .await
+        }
+        Err(error) => Err(error),
+    };
+    match result {
+        Ok(()) => state
+            .crash_log
+            .finish_last(&app_id, AppCrashOutcome::Restarted, Some(reason)),
+        Err(error) => {
+            eprintln!("[app-health] failed to restart {app_id}: {error}");
+            crate::host_primitives::emit_toast(
+                &app_handle,
+                format!("应用 {app_id} 无响应，强制重启失败：{error}"),
+            );
+            state.crash_log.finish_last(
+                &app_id,
+                AppCrashOutcome::RestartFailed,
+                Some(format!("{reason}：{error}")),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consecutive_failures_mark_unresponsive_until_next_success() {
+        let mut health = AppHealthState::default();
+        assert_eq!(health.record_failure(3), AppHealthChange::Unchanged);
+        assert_eq!(health.record_failure(3), AppHealthChange::Unchanged);
+        assert_eq!(
+            health.record_failure(3),
+            AppHealthChange::BecameUnresponsive
+        );
+        assert!(health.unresponsive);
+        // 已经无响应时不再重复触发
+        assert_eq!(health.record_failure(3), AppHealthChange::Unchanged);
+
+        assert_eq!(health.record_success(1_000, 12), AppHealthChange::Recovered);
+        assert!(!health.unresponsive);
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.last_healthy_ms, Some(1_000));
+        assert_eq!(health.latency_ms, Some(12));
+    }
+
+    #[test]
+    fn success_resets_failure_streak() {
+        let mut health = AppHealthState::default();
+        health.record_failure(2);
+        assert_eq!(health.record_success(5, 1), AppHealthChange::Unchanged);
+        assert_eq!(health.record_failure(2), AppHealthChange::Unchanged);
+        assert!(!health.unresponsive);
+    }
+
+    #[test]
+    fn health_check_config_defaults_and_bounds() {
+        let parsed: AppHealthCheckConfig =
+            serde_json::from_value(serde_json::json!({ "restartUnresponsive": true })).unwrap();
+        assert!(parsed.enabled);
+        assert!(parsed.restart_unresponsive);
+        assert_eq!(parsed.interval_secs, 15);
+        assert!(parsed.validate().is_ok());
+
+        let slow_timeout = AppHealthCheckConfig {
+            interval_secs: 5,
+            timeout_ms: 5_000,
+            ..parsed.clone()
+        };
+        assert!(slow_timeout.validate().is_err());
+        let no_threshold = AppHealthCheckConfig {
+            failure_threshold: 0,
+            ..parsed
+        };
+        assert!(no_threshold.validate().is_err());
+    }
+}
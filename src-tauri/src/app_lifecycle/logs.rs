@@ -0,0 +1,306 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tauri::ipc::Channel;
+use tauri::AppHandle;
+
+use crate::rotating_file::{read_file_tail, rotated_path, RotatingAppendFile};
+
+// ── 应用输出日志 ────────────────────────────────────────────────────────────
+//
+// 托管应用的 stdout（协议消息 fw-app-* 除外）和 stderr 逐行写到
+// <data>/__app/app-logs/<appId>/app.log，每行带时间和来源；宿主自己记录启动 / 退出。
+// 超过 MAX_LOG_BYTES 时轮转为 app.1.log … app.<MAX_ROTATED_FILES>.log，最旧的丢弃。
+// app_logs_tail 读最后若干行；follow 时轮询文件追加内容，经 Channel 推给前端，直到取消。
+
+const APP_LOGS_DIR: &str = "app-logs";
+const LOG_FILE_NAME: &str = "app.log";
+const LOG_LABEL: &str = "应用日志";
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+const MAX_ROTATED_FILES: usize = 3;
+const DEFAULT_TAIL_LINES: usize = 200;
+const MAX_TAIL_LINES: usize = 5_000;
+const TAIL_READ_BYTES: u64 = 512 * 1024;
+const FOLLOW_POLL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy)]
+pub(super) enum AppLogStream {
+    Stdout,
+    Stderr,
+    Host,
+}
+
+impl AppLogStream {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Stdout => "stdout",
+            Self::Stderr => "stderr",
+            Self::Host => "host",
+        }
+    }
+}
+
+pub(super) fn app_log_dir(app: &AppHandle, app_id: &str) -> PathBuf {
+    crate::app_data_dir(app)
+        .join(crate::APP_STORAGE_ID)
+        .join(APP_LOGS_DIR)
+        .join(app_id)
+}
+
+pub(super) fn app_log_path(app: &AppHandle, app_id: &str) -> PathBuf {
+    app_log_dir(app, app_id).join(LOG_FILE_NAME)
+}
+
+pub(super) struct AppLogWriter {
+    file: RotatingAppendFile,
+}
+
+pub(super) type SharedAppLog = Arc<Mutex<AppLogWriter>>;
+
+impl AppLogWriter {
+    fn open_at(path: PathBuf, max_bytes: u64) -> Result<Self, String> {
+        RotatingAppendFile::open(path, max_bytes, MAX_ROTATED_FILES, LOG_LABEL)
+            .map(|file| Self { file })
+    }
+
+    pub(super) fn open(app: &AppHandle, app_id: &str) -> Result<SharedAppLog, String> {
+        Self::open_at(app_log_path(app, app_id), MAX_LOG_BYTES)
+            .map(|writer| Arc::new(Mutex::new(writer)))
+    }
+
+    fn write_line_at(&mut self, now_ms: u64, stream: AppLogStream, line: &str) {
+        let line = format!(
+            "{} [{}] {}\n",
+            crate::http_debug::format_iso8601_ms(now_ms),
+            stream.as_str(),
+            line.trim_end_matches(['\r', '\n'])
+        );
+        if let Err(error) = self.file.append(&line) {
+            eprintln!("[app-logs] {error}");
+        }
+    }
+
+    pub(super) fn write_line(&mut self, stream: AppLogStream, line: &str) {
+        self.write_line_at(super::manager::now_ms(), stream, line);
+    }
+}
+
+pub(super) fn write_app_log(log: &Option<SharedAppLog>, stream: AppLogStream, line: &str) {
+    if let Some(log) = log {
+        if let Ok(mut writer) = log.lock() {
+            writer.write_line(stream, line);
+        }
+    }
+}
+
+/// 取文本末尾 `count` 行（不含末尾空行）。
+fn last_lines(text: &str, count: usize) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(count);
+    lines[start..].iter().map(|line| line.to_string()).collect()
+}
+
+/// 当前日志不够时往前补一个轮转文件；返回行和当前文件长度（follow 从这里接着读）。
+fn tail_log(path: &Path, count: usize) -> Result<(Vec<String>, u64), String> {
+    let (text, len) = read_file_tail(path, TAIL_READ_BYTES, LOG_LABEL)?;
+    let mut lines = last_lines(&text, count);
+    if lines.len() < count {
+        let (older, _) = read_file_tail(&rotated_path(path, 1), TAIL_READ_BYTES, LOG_LABEL)?;
+        let mut merged = last_lines(&older, count - lines.len());
+        merged.append(&mut lines);
+        lines = merged;
+    }
+    Ok((lines, len))
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum AppLogTailEvent {
+    Lines { lines: Vec<String> },
+    End { canceled: bool },
+    Error { message: String },
+}
+
+fn tail_cancels() -> &'static Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>> {
+    static TAIL_CANCELS: OnceLock<Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>>> =
+        OnceLock::new();
+    TAIL_CANCELS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn make_tail_stream_id(app_id: &str) -> String {
+    let stamp = super::manager::now_ms();
+    format!("applog-{app_id}-{stamp}-{:08x}", crate::rand_u32(stamp))
+}
+
+/// 从 `offset` 读到文件末尾的完整行；文件变短（已轮转）时从头读。返回新行和新的读取位置。
+fn read_appended_lines(path: &Path, offset: u64) -> Result<(Vec<String>, u64), String> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(format!("读取应用日志失败: {e}")),
+    };
+    let len = file
+        .metadata()
+        .map_err(|e| format!("读取应用日志失败: {e}"))?
+        .len();
+    let offset = if len < offset { 0 } else { offset };
+    if len == offset {
+        return Ok((Vec::new(), offset));
+    }
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| format!("读取应用日志失败: {e}"))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)
+        .map_err(|e| format!("读取应用日志失败: {e}"))?;
+    // 只消费到最后一个换行，半行留到下次
+    let complete = bytes.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+    let text = String::from_utf8_lossy(&bytes[..complete]);
+    let lines = text.lines().map(str::to_string).collect();
+    Ok((lines, offset + complete as u64))
+}
+
+#[tauri::command]
+pub(crate) fn app_logs_tail(
+    app: AppHandle,
+    app_id: String,
+    lines: Option<usize>,
+    follow: Option<bool>,
+    channel: Channel<AppLogTailEvent>,
+) -> Result<String, String> {
+    let id = app_id.trim().to_string();
+    if !crate::is_safe_id(&id) {
+        return Err("appId 不合法".to_string());
+    }
+    let count = lines.unwrap_or(DEFAULT_TAIL_LINES).clamp(1, MAX_TAIL_LINES);
+    let path = app_log_path(&app, &id);
+    let (initial, mut offset) = tail_log(&path, count)?;
+    let stream_id = make_tail_stream_id(&id);
+
+    let _ = channel.send(AppLogTailEvent::Lines { lines: initial });
+    if !follow.unwrap_or(false) {
+        let _ = channel.send(AppLogTailEvent::End { canceled: false });
+        return Ok(stream_id);
+    }
+
+    let (tx, mut rx) = tokio::sync::oneshot::channel::<()>();
+    tail_cancels()
+        .lock()
+        .map_err(|_| "日志跟随状态锁定失败".to_string())?
+        .insert(stream_id.clone(), tx);
+
+    let sid_for_task = stream_id.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut rx => {
+                    let _ = channel.send(AppLogTailEvent::End { canceled: true });
+                    break;
+                }
+                _ = tokio::time::sleep(FOLLOW_POLL) => {}
+            }
+            match read_appended_lines(&path, offset) {
+                Ok((lines, next)) => {
+                    offset = next;
+                    if !lines.is_empty() && channel.send(AppLogTailEvent::Lines { lines }).is_err()
+                    {
+                        break;
+                    }
+                }
+                Err(message) => {
+                    let _ = channel.send(AppLogTailEvent::Error { message });
+                    break;
+                }
+            }
+        }
+        if let Ok(mut map) = tail_cancels().lock() {
+            map.remove(&sid_for_task);
+        }
+    });
+
+    Ok(stream_id)
+}
+
+#[tauri::command]
+pub(crate) fn app_logs_tail_cancel(stream_id: String) -> Result<(), String> {
+    let sid = stream_id.trim().to_string();
+    if sid.is_empty() {
+        return Err("streamId 不能为空".to_string());
+    }
+    if let Ok(mut map) = tail_cancels().lock() {
+        if let Some(tx) = map.remove(&sid) {
+            let _ = tx.send(());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "fw-app-logs-{name}-{}-{}",
+            std::process::id(),
+            super::super::manager::now_ms()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn writer_rotates_and_keeps_limited_files() {
+        let dir = temp_log_dir("rotate");
+        let path = dir.join(LOG_FILE_NAME);
+        let mut writer = AppLogWriter::open_at(path.clone(), 200).unwrap();
+        for i in 0..40 {
+            writer.write_line_at(0, AppLogStream::Stdout, &format!("line {i}"));
+        }
+
+        assert!(path.is_file());
+        assert!(rotated_path(&path, MAX_ROTATED_FILES).is_file());
+        assert!(!rotated_path(&path, MAX_ROTATED_FILES + 1).exists());
+        assert!(std::fs::metadata(&path).unwrap().len() <= 200);
+
+        let (lines, _) = tail_log(&path, 3).unwrap();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[2].ends_with("[stdout] line 39"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn tail_merges_previous_file_when_current_is_short() {
+        let dir = temp_log_dir("tail");
+        let path = dir.join(LOG_FILE_NAME);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(rotated_path(&path, 1), "a\nb\nc\n").unwrap();
+        std::fs::write(&path, "d\n").unwrap();
+
+        let (lines, len) = tail_log(&path, 3).unwrap();
+        assert_eq!(lines, vec!["b", "c", "d"]);
+        assert_eq!(len, 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn appended_lines_wait_for_newline_and_restart_after_rotation() {
+        let dir = temp_log_dir("follow");
+        let path = dir.join(LOG_FILE_NAME);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "one\ntw").unwrap();
+
+        let (lines, offset) = read_appended_lines(&path, 0).unwrap();
+        assert_eq!(lines, vec!["one"]);
+        assert_eq!(offset, 4);
+
+        std::fs::write(&path, "x\n").unwrap();
+        let (lines, offset) = read_appended_lines(&path, offset).unwrap();
+        assert_eq!(lines, vec!["x"]);
+        assert_eq!(offset, 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
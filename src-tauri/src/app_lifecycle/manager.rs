@@ -2,21 +2,31 @@ use base64::Engine as _;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::sync::Mutex as AsyncMutex;
 
 use super::control_channel::{send_control_json, AppControlEndpoint};
-use super::{ManagedAppChild, ManagedAppCommand, ManagedAppStdout};
+use super::health::{AppHealthCheckConfig, AppHealthState};
+use super::logs::{write_app_log, AppLogStream, AppLogWriter};
+use super::stdout_protocol::{
+    runtime_message_from_stdout_line, AppRuntimeMessage, AppRuntimeStatus,
+};
+use super::supervisor::{AppCrashLog, AppCrashRecord, AppSupervisionConfig};
+use super::{ManagedAppChild, ManagedAppCommand, ManagedAppOutput};
 
 const STOP_GRACE_TIMEOUT: Duration = Duration::from_millis(2_500);
 const STOP_GRACE_POLL: Duration = Duration::from_millis(100);
+pub(crate) const APP_RUNTIME_STATUS_EVENT: &str = "fast-window:app-runtime-status";
+pub(crate) const APP_RUNTIME_COMMANDS_EVENT: &str = "fast-window:app-runtime-commands";
 
 #[derive(Default)]
 pub(crate) struct AppLifecycleManager {
     processes: Mutex<HashMap<String, Arc<AppProcessEntry>>>,
+    pub(super) crash_log: AppCrashLog,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -47,6 +57,10 @@ pub(crate) struct RegisteredAppLaunchConfig {
     pub(crate) window_y: Option<i32>,
     #[serde(default)]
     pub(crate) auto_start: bool,
+    #[serde(default)]
+    pub(crate) supervision: AppSupervisionConfig,
+    #[serde(default)]
+    pub(crate) health_check: AppHealthCheckConfig,
 }
 
 #[derive(Clone, Default, Deserialize)]
@@ -62,18 +76,40 @@ impl AppLaunchOptions {
     }
 }
 
-struct AppProcessEntry {
+pub(super) struct AppProcessEntry {
     pid: u32,
     started_at_ms: u64,
     child: AsyncMutex<Option<ManagedAppChild>>,
     exit_code: Mutex<Option<i32>>,
     control: Mutex<Option<AppControlEndpoint>>,
+    // 宿主主动停止（含发送 close）后置位，退出时不交给崩溃守护
+    stop_requested: AtomicBool,
+    launch_options: AppLaunchOptions,
+    health: Mutex<AppHealthState>,
+    // 应用经 fw-app-status 上报的徽标与忙碌状态
+    runtime_status: Mutex<AppRuntimeStatus>,
+    // 应用经 fw-app-commands 上报的宿主快捷命令；只在运行期间有效，不写注册表
+    runtime_commands: Mutex<Option<Vec<crate::app_registry::AppRuntimeDeclaration>>>,
 }
 
-enum AppRuntimeMessage {
-    ControlReady(AppControlEndpoint),
-    WindowBounds(crate::app_registry::AppWindowBounds),
-    Ignore,
+impl AppProcessEntry {
+    /// 仍在运行且不是宿主主动停止的进程才需要健康检查
+    pub(super) fn is_monitored(&self) -> bool {
+        !self.stop_requested.load(Ordering::SeqCst) && entry_is_running(self).unwrap_or(false)
+    }
+
+    pub(super) fn control_endpoint(&self) -> Option<AppControlEndpoint> {
+        self.control.lock().ok().and_then(|g| g.clone())
+    }
+
+    pub(super) fn launch_options(&self) -> AppLaunchOptions {
+        self.launch_options.clone()
+    }
+
+    pub(super) fn update_health<T>(&self, f: impl FnOnce(&mut AppHealthState) -> T) -> T {
+        let mut health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut health)
+    }
 }
 
 enum AppStopMode {
@@ -83,6 +119,16 @@ enum AppStopMode {
 
 type AppStopOutcome = Result<AppStopResult, String>;
 
+impl AppLifecycleManager {
+    pub(super) fn is_running(&self, app_id: &str) -> bool {
+        self.processes
+            .lock()
+            .ok()
+            .and_then(|g| g.get(app_id).cloned())
+            .is_some_and(|entry| entry_is_running(&entry).unwrap_or(false))
+    }
+}
+
 impl Drop for AppLifecycleManager {
     fn drop(&mut self) {
         let entries: Vec<Arc<AppProcessEntry>> = self
@@ -100,7 +146,7 @@ impl Drop for AppLifecycleManager {
     }
 }
 
-fn now_ms() -> u64 {
+pub(super) fn now_ms() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_else(|_| Duration::from_millis(0))
@@ -316,6 +362,38 @@ fn entry_is_running(entry: &AppProcessEntry) -> Result<bool, String> {
     entry_exit_code(entry).map(|code| code.is_none())
 }
 
+/// 连续读错这么多次才放弃，避免管道坏掉时空转
+const OUTPUT_READ_MAX_ERRORS: usize = 8;
+
+/// 逐行读应用输出直到管道关闭。不按 UTF-8 严格解码（中文 Windows 上常是 GBK），
+/// 读错也继续读：提前停读会让子进程往满了的管道写时阻塞或报错。
+async fn drain_output_lines<R>(reader: R, mut on_line: impl FnMut(&str))
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let mut reader = BufReader::new(reader);
+    let mut buf = Vec::new();
+    let mut errors = 0;
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf).await {
+            Ok(0) => break,
+            Ok(_) => {
+                errors = 0;
+                let line = String::from_utf8_lossy(&buf);
+                on_line(line.trim_end_matches(['\n', '\r']));
+            }
+            Err(error) => {
+                errors += 1;
+                if errors >= OUTPUT_READ_MAX_ERRORS {
+                    eprintln!("[app-launcher] stop reading app output: {error}");
+                    break;
+                }
+            }
+        }
+    }
+}
+
 async fn wait_control_endpoint(entry: &Arc<AppProcessEntry>) -> Result<AppControlEndpoint, String> {
     for _ in 0..60 {
         if let Some(endpoint) = entry
@@ -337,18 +415,6 @@ async fn wait_control_endpoint(entry: &Arc<AppProcessEntry>) -> Result<AppContro
     Err("应用控制通道尚未就绪".to_string())
 }
 
-fn send_control_action(
-    endpoint: AppControlEndpoint,
-    action: String,
-    command: Option<String>,
-) -> Result<serde_json::Value, String> {
-    let mut body_value = serde_json::json!({ "action": action });
-    if let Some(command) = command {
-        body_value["command"] = serde_json::Value::String(command);
-    }
-    send_control_json(endpoint, body_value)
-}
-
 async fn send_control_action_async(
     entry: Arc<AppProcessEntry>,
     action: String,
@@ -356,9 +422,11 @@ async fn send_control_action_async(
 ) -> Result<(), String> {
     let endpoint = wait_control_endpoint(&entry).await?;
 
-    tokio::task::spawn_blocking(move || send_control_action(endpoint, action, command))
-        .await
-        .map_err(|e| format!("应用控制任务失败: {e}"))??;
+    let mut body_value = serde_json::json!({ "action": action });
+    if let Some(command) = command {
+        body_value["command"] = serde_json::Value::String(command);
+    }
+    send_control_json(endpoint, body_value).await?;
     Ok(())
 }
 
@@ -389,45 +457,6 @@ async fn kill_process_entry(entry: &Arc<AppProcessEntry>) -> Result<bool, String
     Ok(false)
 }
 
-fn runtime_message_from_stdout_line(line: &str) -> AppRuntimeMessage {
-    let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
-        return AppRuntimeMessage::Ignore;
-    };
-    match value.get("type").and_then(|v| v.as_str()) {
-        Some("fw-app-control-ready") => control_from_stdout_value(&value)
-            .map(AppRuntimeMessage::ControlReady)
-            .unwrap_or(AppRuntimeMessage::Ignore),
-        Some("fw-app-window-bounds") => value
-            .get("windowBounds")
-            .and_then(crate::app_registry::AppWindowBounds::from_value)
-            .map(AppRuntimeMessage::WindowBounds)
-            .unwrap_or(AppRuntimeMessage::Ignore),
-        Some("fw-app-commands") => AppRuntimeMessage::Ignore,
-        _ => AppRuntimeMessage::Ignore,
-    }
-}
-
-fn control_from_stdout_value(value: &serde_json::Value) -> Option<AppControlEndpoint> {
-    if value.get("type").and_then(|v| v.as_str()) != Some("fw-app-control-ready") {
-        return None;
-    }
-    let control = value.get("control")?;
-    let url = control
-        .get("url")
-        .and_then(|v| v.as_str())?
-        .trim()
-        .to_string();
-    let token = control
-        .get("token")
-        .and_then(|v| v.as_str())?
-        .trim()
-        .to_string();
-    if url.is_empty() || token.is_empty() {
-        return None;
-    }
-    Some(AppControlEndpoint { url, token })
-}
-
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct AppStatusResult {
@@ -438,6 +467,38 @@ pub(crate) struct AppStatusResult {
     pub started_at: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub crashes: Vec<AppCrashRecord>,
+    /// 当前日志文件；轮转出的旧日志在同目录
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_path: Option<String>,
+    /// 连续多次健康检查失败
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub unresponsive: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_healthy_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ping_latency_ms: Option<u64>,
+    /// 应用自己上报的徽标、忙碌状态
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime_status: Option<AppRuntimeStatus>,
+    /// 应用运行中声明的宿主快捷命令，展示时与用户保存的列表合并
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime_commands: Option<Vec<crate::app_registry::AppRuntimeDeclaration>>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppRuntimeStatusChangedPayload {
+    app_id: String,
+    status: AppRuntimeStatus,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppRuntimeCommandsChangedPayload {
+    app_id: String,
+    commands: Vec<crate::app_registry::AppRuntimeDeclaration>,
 }
 
 #[derive(Clone, Copy, Serialize)]
@@ -485,6 +546,13 @@ impl AppStatusResult {
             pid: None,
             started_at: None,
             exit_code: None,
+            crashes: Vec::new(),
+            log_path: None,
+            unresponsive: false,
+            last_healthy_at: None,
+            ping_latency_ms: None,
+            runtime_status: None,
+            runtime_commands: None,
         }
     }
 }
@@ -553,15 +621,18 @@ pub(crate) async fn app_launch(
     args: Vec<String>,
     options: Option<AppLaunchOptions>,
 ) -> Result<(), String> {
+    let usage_app_id = app_id.trim().to_string();
     app_launch_inner_with_options(
-        app_handle,
+        app_handle.clone(),
         state.inner().clone(),
         app_id,
         exe_path,
         args,
         options.unwrap_or_default(),
     )
-    .await
+    .await?;
+    crate::usage::record_usage(&app_handle, crate::usage::UsageKind::App, &usage_app_id);
+    Ok(())
 }
 
 #[tauri::command]
@@ -663,6 +734,9 @@ pub(crate) async fn app_launch_inner_with_cold_start_policy_and_options(
         if should_allow_foreground(&action) {
             allow_foreground_for_process(entry.pid);
         }
+        if action == "close" {
+            entry.stop_requested.store(true, Ordering::SeqCst);
+        }
         send_control_action_async(entry, action, command).await?;
         return Ok(AppLaunchOutcome::Activated);
     }
@@ -674,17 +748,42 @@ pub(crate) async fn app_launch_inner_with_cold_start_policy_and_options(
     let path = app_executable_path(exe_path)?;
 
     let action = launch_action(&args);
+    let log = match AppLogWriter::open(&app_handle, &id) {
+        Ok(log) => Some(log),
+        Err(error) => {
+            eprintln!("[app-launcher] failed to open log for {id}: {error}");
+            None
+        }
+    };
+    let launch_line = format!("启动 {} {}", path.display(), args.join(" "));
     let command = ManagedAppCommand::new(&path)
         .args(args)
-        .stdout(ManagedAppStdout::Piped)
+        .stdout(ManagedAppOutput::Piped)
+        .stderr(ManagedAppOutput::Piped)
         .envs(launch_options.env_vars());
-    let mut child = ManagedAppChild::spawn(command)?;
+    let mut child = match ManagedAppChild::spawn(command) {
+        Ok(child) => child,
+        Err(error) => {
+            write_app_log(
+                &log,
+                AppLogStream::Host,
+                &format!("{launch_line} 失败：{error}"),
+            );
+            return Err(error);
+        }
+    };
     let pid = child.id();
+    write_app_log(
+        &log,
+        AppLogStream::Host,
+        &format!("{launch_line}（pid {pid}）"),
+    );
     if should_allow_foreground(&action) {
         allow_foreground_for_process(pid);
     }
     let started_at_ms = now_ms();
     let stdout = child.stdout();
+    let stderr = child.stderr();
 
     let entry = Arc::new(AppProcessEntry {
         pid,
@@ -692,16 +791,21 @@ pub(crate) async fn app_launch_inner_with_cold_start_policy_and_options(
         child: AsyncMutex::new(Some(child)),
         exit_code: Mutex::new(None),
         control: Mutex::new(None),
+        stop_requested: AtomicBool::new(false),
+        launch_options,
+        health: Mutex::new(AppHealthState::default()),
+        runtime_status: Mutex::new(AppRuntimeStatus::default()),
+        runtime_commands: Mutex::new(None),
     });
 
     if let Some(stdout) = stdout {
         let entry_stdout = entry.clone();
         let app_stdout = app_handle.clone();
         let id_stdout = id.clone();
+        let log_stdout = log.clone();
         tauri::async_runtime::spawn(async move {
-            let mut lines = BufReader::new(stdout).lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                match runtime_message_from_stdout_line(&line) {
+            drain_output_lines(stdout, |line| {
+                match runtime_message_from_stdout_line(line) {
                     AppRuntimeMessage::ControlReady(endpoint) => {
                         if let Ok(mut g) = entry_stdout.control.lock() {
                             *g = Some(endpoint);
@@ -716,14 +820,76 @@ pub(crate) async fn app_launch_inner_with_cold_start_policy_and_options(
                             eprintln!("[app-launcher] failed to persist window bounds for {id_stdout}: {error}");
                         }
                     }
+                    AppRuntimeMessage::Commands(commands) => {
+                        let changed = match entry_stdout.runtime_commands.lock() {
+                            Ok(mut g) if g.as_ref() != Some(&commands) => {
+                                *g = Some(commands.clone());
+                                true
+                            }
+                            _ => false,
+                        };
+                        if changed {
+                            crate::command_index::remember_app_host_shortcuts(
+                                &app_stdout,
+                                &id_stdout,
+                                &commands,
+                            );
+                            let _ = app_stdout.emit(
+                                APP_RUNTIME_COMMANDS_EVENT,
+                                AppRuntimeCommandsChangedPayload {
+                                    app_id: id_stdout.clone(),
+                                    commands,
+                                },
+                            );
+                        }
+                    }
+                    AppRuntimeMessage::Notify(notification) => {
+                        let text = notification.toast_text(&id_stdout);
+                        write_app_log(&log_stdout, AppLogStream::Host, &format!("通知：{text}"));
+                        crate::host_primitives::emit_toast(&app_stdout, text);
+                    }
+                    AppRuntimeMessage::Status(status) => {
+                        if let Ok(mut g) = entry_stdout.runtime_status.lock() {
+                            *g = status.clone();
+                        }
+                        let _ = app_stdout.emit(
+                            APP_RUNTIME_STATUS_EVENT,
+                            AppRuntimeStatusChangedPayload {
+                                app_id: id_stdout.clone(),
+                                status,
+                            },
+                        );
+                    }
+                    AppRuntimeMessage::Output => {
+                        write_app_log(&log_stdout, AppLogStream::Stdout, line);
+                    }
+                    AppRuntimeMessage::Invalid(error) => {
+                        write_app_log(&log_stdout, AppLogStream::Host, &error);
+                    }
                     AppRuntimeMessage::Ignore => {}
                 }
-            }
+            })
+            .await;
         });
     }
 
+    if let Some(stderr) = stderr {
+        let log_stderr = log.clone();
+        tauri::async_runtime::spawn(async move {
+            drain_output_lines(stderr, |line| {
+                write_app_log(&log_stderr, AppLogStream::Stderr, line);
+            })
+            .await;
+        });
+    }
+
+    let log_health = log.clone();
+
     // spawn reaper
     let entry_reap = entry.clone();
+    let app_reap = app_handle.clone();
+    let state_reap = state.clone();
+    let id_reap = id.clone();
     tauri::async_runtime::spawn(async move {
         loop {
             let code = {
@@ -749,6 +915,19 @@ pub(crate) async fn app_launch_inner_with_cold_start_policy_and_options(
                 if let Ok(mut g) = entry_reap.exit_code.lock() {
                     *g = code;
                 }
+                let exit_text =
+                    code.map_or("被强制结束".to_string(), |c| format!("退出码 {c}"));
+                write_app_log(&log, AppLogStream::Host, &format!("退出（{exit_text}）"));
+                if !entry_reap.stop_requested.load(Ordering::SeqCst) {
+                    super::supervisor::handle_app_exit(
+                        app_reap,
+                        state_reap,
+                        id_reap,
+                        code,
+                        entry_reap.launch_options.clone(),
+                        now_ms(),
+                    );
+                }
                 return;
             }
             tokio::time::sleep(Duration::from_millis(300)).await;
@@ -756,8 +935,9 @@ pub(crate) async fn app_launch_inner_with_cold_start_policy_and_options(
     });
 
     if let Ok(mut g) = state.processes.lock() {
-        g.insert(id, entry);
+        g.insert(id.clone(), entry.clone());
     }
+    super::health::spawn_health_monitor(app_handle, state, id, entry, log_health);
 
     Ok(AppLaunchOutcome::Activated)
 }
@@ -795,6 +975,7 @@ async fn app_stop_with_mode(
     let Some(entry) = entry else {
         return Ok(AppStopResult::already_stopped());
     };
+    entry.stop_requested.store(true, Ordering::SeqCst);
 
     let result = match mode {
         AppStopMode::Graceful => {
@@ -823,6 +1004,13 @@ async fn app_stop_with_mode(
     Ok(result)
 }
 
+pub(super) async fn force_stop_app(
+    state: &Arc<AppLifecycleManager>,
+    app_id: &str,
+) -> Result<AppStopResult, String> {
+    app_stop_with_mode(state, app_id.to_string(), AppStopMode::Force).await
+}
+
 pub(crate) async fn stop_registered_app_for_update(
     state: &Arc<AppLifecycleManager>,
     app_id: &str,
@@ -863,27 +1051,43 @@ pub(crate) async fn stop_all_running_apps(
 
 #[tauri::command]
 pub(crate) fn app_status(
+    app_handle: AppHandle,
     state: tauri::State<'_, Arc<AppLifecycleManager>>,
     app_id: String,
 ) -> Result<AppStatusResult, String> {
     let id = normalize_app_id(&app_id)?;
-    app_status_inner(state.inner(), &id)
+    app_status_with_log(&app_handle, state.inner(), &id)
 }
 
 #[tauri::command]
 pub(crate) fn app_status_many(
+    app_handle: AppHandle,
     state: tauri::State<'_, Arc<AppLifecycleManager>>,
     app_ids: Vec<String>,
 ) -> Result<HashMap<String, AppStatusResult>, String> {
     let mut out = HashMap::new();
     for id in app_ids {
         let id = normalize_app_id(&id)?;
-        let status = app_status_inner(state.inner(), &id)?;
+        let status = app_status_with_log(&app_handle, state.inner(), &id)?;
         out.insert(id, status);
     }
     Ok(out)
 }
 
+fn app_status_with_log(
+    app_handle: &AppHandle,
+    state: &Arc<AppLifecycleManager>,
+    id: &str,
+) -> Result<AppStatusResult, String> {
+    let log_path = super::logs::app_log_path(app_handle, id);
+    Ok(AppStatusResult {
+        log_path: log_path
+            .is_file()
+            .then(|| log_path.to_string_lossy().to_string()),
+        ..app_status_inner(state, id)?
+    })
+}
+
 #[tauri::command]
 pub(crate) fn app_icon_data_url(exe_path: String) -> Result<String, String> {
     let path = app_executable_path(exe_path)?;
@@ -926,31 +1130,70 @@ fn app_status_inner(state: &Arc<AppLifecycleManager>, id: &str) -> Result<AppSta
         .get(id)
         .cloned();
 
+    let crashes = state.crash_log.records(id);
     let Some(entry) = entry else {
-        return Ok(AppStatusResult::stopped());
+        return Ok(AppStatusResult {
+            crashes,
+            ..AppStatusResult::stopped()
+        });
     };
 
     let exit_code = entry_exit_code(&entry)?;
     if exit_code.is_some() {
-        return Ok(AppStatusResult::stopped());
+        return Ok(AppStatusResult {
+            exit_code,
+            crashes,
+            ..AppStatusResult::stopped()
+        });
     }
 
+    let health = entry.update_health(|health| health.clone());
+    let runtime_status = entry
+        .runtime_status
+        .lock()
+        .ok()
+        .map(|status| status.clone())
+        .filter(|status| !status.is_empty());
+    let runtime_commands = entry
+        .runtime_commands
+        .lock()
+        .ok()
+        .and_then(|commands| commands.clone());
     Ok(AppStatusResult {
         running: true,
         pid: Some(entry.pid),
         started_at: Some(entry.started_at_ms),
         exit_code: None,
+        crashes,
+        log_path: None,
+        unresponsive: health.unresponsive,
+        last_healthy_at: health.last_healthy_ms,
+        ping_latency_ms: health.latency_ms,
+        runtime_status,
+        runtime_commands,
     })
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{launch_display_mode, running_instance_action};
+    use super::{drain_output_lines, launch_display_mode, running_instance_action};
 
     fn args(parts: &[&str]) -> Vec<String> {
         parts.iter().map(|part| (*part).to_string()).collect()
     }
 
+    #[tokio::test]
+    async fn drains_non_utf8_output_lines() {
+        let output: &[u8] = b"ok\r\n\xc4\xe3\xba\xc3\nlast";
+        let mut lines = Vec::new();
+        drain_output_lines(output, |line| lines.push(line.to_string())).await;
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "ok");
+        assert!(lines[1].contains('\u{fffd}'));
+        assert_eq!(lines[2], "last");
+    }
+
     #[test]
     fn parses_registered_app_window_mode() {
         let parts = args(&["app", "--fw-mode", "window"]);
@@ -1,11 +1,19 @@
 mod control_channel;
+mod health;
+pub(crate) mod logs;
 pub(crate) mod manager;
 mod process_owner;
+mod stdout_protocol;
+mod supervisor;
 
-pub(crate) use control_channel::{send_control_json, AppControlEndpoint};
+pub(crate) use control_channel::{
+    send_control_json, AppControlClient, AppControlEndpoint, AppControlTimeouts,
+};
+pub(crate) use health::AppHealthCheckConfig;
 pub(crate) use manager::{
     app_launch_inner_with_cold_start_policy, app_launch_inner_with_options,
     build_registered_app_launch_args, stop_all_running_apps, stop_registered_app_for_update,
     AppColdStartPolicy, AppLaunchOptions, AppLifecycleManager, RegisteredAppLaunchConfig,
 };
-pub(crate) use process_owner::{ManagedAppChild, ManagedAppCommand, ManagedAppStdout};
+pub(crate) use process_owner::{ManagedAppChild, ManagedAppCommand, ManagedAppOutput};
+pub(crate) use supervisor::AppSupervisionConfig;
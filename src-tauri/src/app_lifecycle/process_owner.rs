@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 use tokio::process::{Child, Command};
 
 #[derive(Clone, Copy)]
-pub(crate) enum ManagedAppStdout {
+pub(crate) enum ManagedAppOutput {
     Null,
     Piped,
 }
@@ -12,7 +12,8 @@ pub(crate) enum ManagedAppStdout {
 pub(crate) struct ManagedAppCommand {
     executable: PathBuf,
     args: Vec<String>,
-    stdout: ManagedAppStdout,
+    stdout: ManagedAppOutput,
+    stderr: ManagedAppOutput,
     envs: Vec<(String, String)>,
 }
 
@@ -21,7 +22,8 @@ impl ManagedAppCommand {
         Self {
             executable: executable.into(),
             args: Vec::new(),
-            stdout: ManagedAppStdout::Null,
+            stdout: ManagedAppOutput::Null,
+            stderr: ManagedAppOutput::Null,
             envs: Vec::new(),
         }
     }
@@ -31,11 +33,16 @@ impl ManagedAppCommand {
         self
     }
 
-    pub(crate) fn stdout(mut self, stdout: ManagedAppStdout) -> Self {
+    pub(crate) fn stdout(mut self, stdout: ManagedAppOutput) -> Self {
         self.stdout = stdout;
         self
     }
 
+    pub(crate) fn stderr(mut self, stderr: ManagedAppOutput) -> Self {
+        self.stderr = stderr;
+        self
+    }
+
     pub(crate) fn envs(mut self, envs: Vec<(String, String)>) -> Self {
         self.envs = envs;
         self
@@ -67,6 +74,10 @@ impl ManagedAppChild {
         self.inner.stdout()
     }
 
+    pub(crate) fn stderr(&mut self) -> Option<tokio::process::ChildStderr> {
+        self.inner.stderr()
+    }
+
     pub(crate) fn try_wait(&mut self) -> Result<Option<std::process::ExitStatus>, String> {
         self.inner.try_wait()
     }
@@ -78,7 +89,7 @@ impl ManagedAppChild {
 
 #[cfg(target_os = "windows")]
 mod platform {
-    use super::{ManagedAppCommand, ManagedAppStdout};
+    use super::{ManagedAppCommand, ManagedAppOutput};
     use std::ffi::OsStr;
     use std::mem::{size_of, zeroed};
     use std::os::windows::ffi::OsStrExt;
@@ -86,7 +97,7 @@ mod platform {
     use std::path::Path;
     use std::process::ExitStatus;
 
-    use tokio::process::ChildStdout;
+    use tokio::process::{ChildStderr, ChildStdout};
     use windows::core::{PCWSTR, PWSTR};
     use windows::Win32::Foundation::{
         CloseHandle, SetHandleInformation, HANDLE, HANDLE_FLAG_INHERIT, WAIT_OBJECT_0, WAIT_TIMEOUT,
@@ -116,6 +127,7 @@ mod platform {
         process_handle: OwnedHandle,
         job_handle: OwnedHandle,
         stdout: Option<ChildStdout>,
+        stderr: Option<ChildStderr>,
     }
 
     impl PlatformManagedAppChild {
@@ -127,6 +139,10 @@ mod platform {
             self.stdout.take()
         }
 
+        pub(super) fn stderr(&mut self) -> Option<ChildStderr> {
+            self.stderr.take()
+        }
+
         pub(super) fn try_wait(&mut self) -> Result<Option<ExitStatus>, String> {
             let wait = unsafe { WaitForSingleObject(self.process_handle.raw(), 0) };
             if wait == WAIT_TIMEOUT {
@@ -151,10 +167,11 @@ mod platform {
     pub(super) fn spawn(command: ManagedAppCommand) -> Result<PlatformManagedAppChild, String> {
         let job_handle = create_host_owned_job()?;
         let stdin = open_nul_for_child(FILE_GENERIC_READ.0)?;
-        let stdout = create_stdout(command.stdout)?;
-        let stderr = open_nul_for_child(FILE_GENERIC_WRITE.0)?;
+        let stdout = create_output(command.stdout)?;
+        let stderr = create_output(command.stderr)?;
 
-        let mut inheritable_handles = vec![stdin.raw(), stdout.child_handle(), stderr.raw()];
+        let mut inheritable_handles =
+            vec![stdin.raw(), stdout.child_handle(), stderr.child_handle()];
         let mut attributes = ProcThreadAttributes::new(2)?;
         let mut job_list = [job_handle.raw()];
         attributes.update(
@@ -173,7 +190,7 @@ mod platform {
         startup_info.StartupInfo.dwFlags = STARTF_USESTDHANDLES;
         startup_info.StartupInfo.hStdInput = stdin.raw();
         startup_info.StartupInfo.hStdOutput = stdout.child_handle();
-        startup_info.StartupInfo.hStdError = stderr.raw();
+        startup_info.StartupInfo.hStdError = stderr.child_handle();
         startup_info.lpAttributeList = attributes.as_mut_ptr();
 
         let mut process_info: PROCESS_INFORMATION = unsafe { zeroed() };
@@ -217,7 +234,20 @@ mod platform {
             pid: process_info.dwProcessId,
             process_handle,
             job_handle,
-            stdout: stdout.into_parent_stdout()?,
+            stdout: stdout
+                .into_parent_handle()
+                .map(|handle| {
+                    ChildStdout::from_std(std::process::ChildStdout::from(handle))
+                        .map_err(|e| format!("接管应用输出管道失败: {e}"))
+                })
+                .transpose()?,
+            stderr: stderr
+                .into_parent_handle()
+                .map(|handle| {
+                    ChildStderr::from_std(std::process::ChildStderr::from(handle))
+                        .map_err(|e| format!("接管应用错误输出管道失败: {e}"))
+                })
+                .transpose()?,
         })
     }
 
@@ -325,7 +355,7 @@ mod platform {
         }
     }
 
-    enum ChildStdoutOwner {
+    enum ChildOutputOwner {
         Null {
             child_handle: OwnedHandle,
         },
@@ -335,7 +365,7 @@ mod platform {
         },
     }
 
-    impl ChildStdoutOwner {
+    impl ChildOutputOwner {
         fn child_handle(&self) -> HANDLE {
             match self {
                 Self::Null { child_handle } | Self::Piped { child_handle, .. } => {
@@ -344,32 +374,28 @@ mod platform {
             }
         }
 
-        fn into_parent_stdout(self) -> Result<Option<ChildStdout>, String> {
+        fn into_parent_handle(self) -> Option<std::os::windows::io::OwnedHandle> {
             match self {
                 Self::Piped {
                     mut parent_handle, ..
                 } => unsafe {
                     let handle = parent_handle.take();
-                    let owned = std::os::windows::io::OwnedHandle::from_raw_handle(handle.0);
-                    let stdout = std::process::ChildStdout::from(owned);
-                    ChildStdout::from_std(stdout)
-                        .map(Some)
-                        .map_err(|e| format!("接管应用输出管道失败: {e}"))
+                    Some(std::os::windows::io::OwnedHandle::from_raw_handle(handle.0))
                 },
-                Self::Null { .. } => Ok(None),
+                Self::Null { .. } => None,
             }
         }
     }
 
-    fn create_stdout(stdout: ManagedAppStdout) -> Result<ChildStdoutOwner, String> {
-        match stdout {
-            ManagedAppStdout::Null => open_nul_for_child(FILE_GENERIC_WRITE.0)
-                .map(|child_handle| ChildStdoutOwner::Null { child_handle }),
-            ManagedAppStdout::Piped => create_child_stdout_pipe(),
+    fn create_output(output: ManagedAppOutput) -> Result<ChildOutputOwner, String> {
+        match output {
+            ManagedAppOutput::Null => open_nul_for_child(FILE_GENERIC_WRITE.0)
+                .map(|child_handle| ChildOutputOwner::Null { child_handle }),
+            ManagedAppOutput::Piped => create_child_output_pipe(),
         }
     }
 
-    fn create_child_stdout_pipe() -> Result<ChildStdoutOwner, String> {
+    fn create_child_output_pipe() -> Result<ChildOutputOwner, String> {
         let mut read_handle = HANDLE::default();
         let mut write_handle = HANDLE::default();
         let security = inheritable_security_attributes();
@@ -387,7 +413,7 @@ mod platform {
         }
         .map_err(|e| format!("配置应用输出管道失败: {e}"))?;
 
-        Ok(ChildStdoutOwner::Piped {
+        Ok(ChildOutputOwner::Piped {
             child_handle,
             parent_handle,
         })
@@ -503,7 +529,7 @@ mod platform {
 
 #[cfg(not(target_os = "windows"))]
 mod platform {
-    use super::{Child, Command, ManagedAppCommand, ManagedAppStdout};
+    use super::{Child, Command, ManagedAppCommand, ManagedAppOutput};
 
     pub(super) struct PlatformManagedAppChild {
         child: Child,
@@ -518,6 +544,10 @@ mod platform {
             self.child.stdout.take()
         }
 
+        pub(super) fn stderr(&mut self) -> Option<tokio::process::ChildStderr> {
+            self.child.stderr.take()
+        }
+
         pub(super) fn try_wait(&mut self) -> Result<Option<std::process::ExitStatus>, String> {
             self.child
                 .try_wait()
@@ -535,8 +565,8 @@ mod platform {
         let mut cmd = Command::new(command.executable());
         cmd.args(command.args_ref());
         cmd.stdin(std::process::Stdio::null());
-        cmd.stdout(stdout_to_stdio(command.stdout));
-        cmd.stderr(std::process::Stdio::null());
+        cmd.stdout(output_to_stdio(command.stdout));
+        cmd.stderr(output_to_stdio(command.stderr));
         for (k, v) in &command.envs {
             cmd.env(k, v);
         }
@@ -546,10 +576,10 @@ mod platform {
         Ok(PlatformManagedAppChild { child })
     }
 
-    fn stdout_to_stdio(stdout: ManagedAppStdout) -> std::process::Stdio {
-        match stdout {
-            ManagedAppStdout::Null => std::process::Stdio::null(),
-            ManagedAppStdout::Piped => std::process::Stdio::piped(),
+    fn output_to_stdio(output: ManagedAppOutput) -> std::process::Stdio {
+        match output {
+            ManagedAppOutput::Null => std::process::Stdio::null(),
+            ManagedAppOutput::Piped => std::process::Stdio::piped(),
         }
     }
 }
@@ -0,0 +1,385 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::control_channel::AppControlEndpoint;
+use crate::app_registry::{AppRuntimeDeclaration, AppWindowBounds};
+
+// ── 应用 stdout 协议 ────────────────────────────────────────────────────────
+//
+// 托管应用每行输出一条 JSON，type 以 fw-app- 开头的是发给宿主的消息：
+// - fw-app-control-ready  { control: { url, token } }
+// - fw-app-window-bounds  { windowBounds: { x, y, width, height } }
+// - fw-app-commands       { commands: [{ id, title, icon?, description? }] }
+//   运行期间刷新主页与搜索里的宿主快捷命令（新声明的直接显示），不改写注册表
+// - fw-app-notify         { message, title?, level? }  宿主弹出提示
+// - fw-app-status         { badge?, busy?, text? }     启动器里应用旁的徽标与忙碌状态
+// 非 JSON 或非 fw-app- 的行写入应用日志；格式不对的 fw-app- 消息记一条宿主日志后忽略，
+// 不认识的 fw-app- 类型直接忽略（给以后的协议留余地）。
+
+const MAX_COMMANDS: usize = 64;
+const MAX_COMMAND_TITLE_LEN: usize = 80;
+const MAX_NOTIFY_TITLE_LEN: usize = 80;
+const MAX_NOTIFY_MESSAGE_LEN: usize = 500;
+const MAX_STATUS_BADGE_CHARS: usize = 12;
+const MAX_STATUS_TEXT_LEN: usize = 120;
+
+pub(super) enum AppRuntimeMessage {
+    ControlReady(AppControlEndpoint),
+    WindowBounds(AppWindowBounds),
+    Commands(Vec<AppRuntimeDeclaration>),
+    Notify(AppNotification),
+    Status(AppRuntimeStatus),
+    /// 非协议输出，写入应用日志
+    Output,
+    /// 协议消息格式不对
+    Invalid(String),
+    Ignore,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) enum AppNotifyLevel {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct AppNotification {
+    pub(super) message: String,
+    #[serde(default)]
+    pub(super) title: Option<String>,
+    #[serde(default)]
+    pub(super) level: AppNotifyLevel,
+}
+
+impl AppNotification {
+    fn validate(mut self) -> Result<Self, String> {
+        self.message = self.message.trim().to_string();
+        if self.message.is_empty() {
+            return Err("message 不能为空".to_string());
+        }
+        if self.message.len() > MAX_NOTIFY_MESSAGE_LEN {
+            return Err("message 过长".to_string());
+        }
+        self.title = self
+            .title
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty());
+        if self
+            .title
+            .as_ref()
+            .is_some_and(|title| title.len() > MAX_NOTIFY_TITLE_LEN)
+        {
+            return Err("title 过长".to_string());
+        }
+        Ok(self)
+    }
+
+    /// 宿主提示的文本
+    pub(super) fn toast_text(&self, app_id: &str) -> String {
+        let prefix = match self.level {
+            AppNotifyLevel::Info | AppNotifyLevel::Success => "",
+            AppNotifyLevel::Warning => "⚠ ",
+            AppNotifyLevel::Error => "✖ ",
+        };
+        match &self.title {
+            Some(title) => format!("{prefix}{app_id} · {title}：{}", self.message),
+            None => format!("{prefix}{app_id}：{}", self.message),
+        }
+    }
+}
+
+/// 应用自己上报的运行状态；随进程结束清空。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct AppRuntimeStatus {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) badge: Option<String>,
+    #[serde(default)]
+    pub(crate) busy: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) text: Option<String>,
+}
+
+impl AppRuntimeStatus {
+    fn validate(mut self) -> Result<Self, String> {
+        self.badge = self
+            .badge
+            .map(|badge| badge.trim().to_string())
+            .filter(|badge| !badge.is_empty());
+        if self
+            .badge
+            .as_ref()
+            .is_some_and(|badge| badge.chars().count() > MAX_STATUS_BADGE_CHARS)
+        {
+            return Err(format!("badge 不能超过 {MAX_STATUS_BADGE_CHARS} 个字符"));
+        }
+        self.text = self
+            .text
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty());
+        if self
+            .text
+            .as_ref()
+            .is_some_and(|text| text.len() > MAX_STATUS_TEXT_LEN)
+        {
+            return Err("text 过长".to_string());
+        }
+        Ok(self)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.badge.is_none() && !self.busy && self.text.is_none()
+    }
+}
+
+pub(super) fn runtime_message_from_stdout_line(line: &str) -> AppRuntimeMessage {
+    let Ok(value) = serde_json::from_str::<Value>(line) else {
+        return AppRuntimeMessage::Output;
+    };
+    let Some(kind) = value.get("type").and_then(Value::as_str) else {
+        return AppRuntimeMessage::Output;
+    };
+    let result = match kind {
+        "fw-app-control-ready" => control_from_stdout_value(&value)
+            .map(AppRuntimeMessage::ControlReady)
+            .ok_or_else(|| "control.url / control.token 缺失".to_string()),
+        // 窗口藏在屏幕外等情况也会给出不可用的边界，不算格式错误
+        "fw-app-window-bounds" => Ok(value
+            .get("windowBounds")
+            .and_then(AppWindowBounds::from_value)
+            .map(AppRuntimeMessage::WindowBounds)
+            .unwrap_or(AppRuntimeMessage::Ignore)),
+        "fw-app-commands" => commands_from_stdout_value(&value).map(AppRuntimeMessage::Commands),
+        "fw-app-notify" => message_body::<AppNotification>(&value)
+            .and_then(AppNotification::validate)
+            .map(AppRuntimeMessage::Notify),
+        "fw-app-status" => message_body::<AppRuntimeStatus>(&value)
+            .and_then(AppRuntimeStatus::validate)
+            .map(AppRuntimeMessage::Status),
+        kind if kind.starts_with("fw-app-") => Ok(AppRuntimeMessage::Ignore),
+        _ => Ok(AppRuntimeMessage::Output),
+    };
+    result.unwrap_or_else(|error| AppRuntimeMessage::Invalid(format!("{kind} 消息不合法：{error}")))
+}
+
+/// 去掉 type 后按消息结构严格解析，多余字段也算格式错误
+fn message_body<T: DeserializeOwned>(value: &Value) -> Result<T, String> {
+    let mut body = value.clone();
+    if let Some(record) = body.as_object_mut() {
+        record.remove("type");
+    }
+    serde_json::from_value(body).map_err(|e| e.to_string())
+}
+
+fn control_from_stdout_value(value: &Value) -> Option<AppControlEndpoint> {
+    let control = value.get("control")?;
+    let url = control
+        .get("url")
+        .and_then(|v| v.as_str())?
+        .trim()
+        .to_string();
+    let token = control
+        .get("token")
+        .and_then(|v| v.as_str())?
+        .trim()
+        .to_string();
+    if url.is_empty() || token.is_empty() {
+        return None;
+    }
+    Some(AppControlEndpoint { url, token })
+}
+
+fn commands_from_stdout_value(value: &Value) -> Result<Vec<AppRuntimeDeclaration>, String> {
+    let raw = value
+        .get("commands")
+        .filter(|commands| commands.is_array())
+        .ok_or_else(|| "commands 必须是数组".to_string())?;
+    let commands = serde_json::from_value::<Vec<AppRuntimeDeclaration>>(raw.clone())
+        .map_err(|e| e.to_string())?;
+    if commands.len() > MAX_COMMANDS {
+        return Err(format!("commands 不能超过 {MAX_COMMANDS} 条"));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    let mut out = Vec::with_capacity(commands.len());
+    for mut command in commands {
+        command.id = command.id.trim().to_string();
+        command.title = command.title.trim().to_string();
+        if !crate::is_safe_id(&command.id) {
+            return Err(format!("命令 ID 不合法: {}", command.id));
+        }
+        if !seen.insert(command.id.clone()) {
+            return Err(format!("命令 ID 重复: {}", command.id));
+        }
+        if command.title.is_empty() || command.title.len() > MAX_COMMAND_TITLE_LEN {
+            return Err(format!("命令 {} 的名称为空或过长", command.id));
+        }
+        // 快捷键由用户在宿主里设置，应用声明的不采用
+        command.hotkey = None;
+        out.push(command);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: Value) -> AppRuntimeMessage {
+        runtime_message_from_stdout_line(&value.to_string())
+    }
+
+    fn invalid(message: AppRuntimeMessage) -> String {
+        match message {
+            AppRuntimeMessage::Invalid(error) => error,
+            _ => panic!("expected invalid message"),
+        }
+    }
+
+    #[test]
+    fn plain_output_and_unknown_messages() {
+        assert!(matches!(
+            runtime_message_from_stdout_line("listening on 8080"),
+            AppRuntimeMessage::Output
+        ));
+        assert!(matches!(
+            parse(serde_json::json!({ "type": "log", "msg": "hi" })),
+            AppRuntimeMessage::Output
+        ));
+        assert!(matches!(
+            parse(serde_json::json!({ "type": "fw-app-future" })),
+            AppRuntimeMessage::Ignore
+        ));
+    }
+
+    #[test]
+    fn control_ready_requires_url_and_token() {
+        let ready = parse(serde_json::json!({
+            "type": "fw-app-control-ready",
+            "control": { "url": " http://127.0.0.1:9 ", "token": "t" }
+        }));
+        let AppRuntimeMessage::ControlReady(endpoint) = ready else {
+            panic!("expected control endpoint");
+        };
+        assert_eq!(endpoint.url, "http://127.0.0.1:9");
+
+        let missing = parse(serde_json::json!({
+            "type": "fw-app-control-ready",
+            "control": { "url": "http://127.0.0.1:9" }
+        }));
+        assert!(invalid(missing).starts_with("fw-app-control-ready"));
+    }
+
+    #[test]
+    fn window_bounds_outside_screen_are_ignored() {
+        let bounds = parse(serde_json::json!({
+            "type": "fw-app-window-bounds",
+            "windowBounds": { "x": 10, "y": 20, "width": 800, "height": 600 }
+        }));
+        assert!(matches!(bounds, AppRuntimeMessage::WindowBounds(_)));
+
+        let hidden = parse(serde_json::json!({
+            "type": "fw-app-window-bounds",
+            "windowBounds": { "x": -32000, "y": -32000, "width": 800, "height": 600 }
+        }));
+        assert!(matches!(hidden, AppRuntimeMessage::Ignore));
+    }
+
+    #[test]
+    fn commands_are_validated_and_drop_app_hotkeys() {
+        let message = parse(serde_json::json!({
+            "type": "fw-app-commands",
+            "commands": [
+                { "id": "open", "title": " 打开 ", "hotkey": "Ctrl+1" },
+                { "id": "sync", "title": "同步", "description": "立即同步" }
+            ]
+        }));
+        let AppRuntimeMessage::Commands(commands) = message else {
+            panic!("expected commands");
+        };
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].title, "打开");
+        assert_eq!(commands[0].hotkey, None);
+        assert_eq!(commands[1].description.as_deref(), Some("立即同步"));
+
+        let duplicated = parse(serde_json::json!({
+            "type": "fw-app-commands",
+            "commands": [{ "id": "a", "title": "A" }, { "id": "a", "title": "B" }]
+        }));
+        assert!(invalid(duplicated).contains("重复"));
+        let bad_id = parse(serde_json::json!({
+            "type": "fw-app-commands",
+            "commands": [{ "id": "a/b", "title": "A" }]
+        }));
+        assert!(invalid(bad_id).contains("不合法"));
+        let not_array = parse(serde_json::json!({ "type": "fw-app-commands", "commands": {} }));
+        assert!(invalid(not_array).contains("数组"));
+    }
+
+    #[test]
+    fn notify_requires_message_and_known_level() {
+        let message = parse(serde_json::json!({
+            "type": "fw-app-notify",
+            "title": "下载",
+            "message": "已完成",
+            "level": "success"
+        }));
+        let AppRuntimeMessage::Notify(notification) = message else {
+            panic!("expected notification");
+        };
+        assert_eq!(notification.level, AppNotifyLevel::Success);
+        assert_eq!(notification.toast_text("demo"), "demo · 下载：已完成");
+
+        let empty = parse(serde_json::json!({ "type": "fw-app-notify", "message": "  " }));
+        assert!(invalid(empty).contains("message"));
+        let bad_level = parse(serde_json::json!({
+            "type": "fw-app-notify",
+            "message": "x",
+            "level": "loud"
+        }));
+        assert!(matches!(bad_level, AppRuntimeMessage::Invalid(_)));
+        let extra = parse(serde_json::json!({
+            "type": "fw-app-notify",
+            "message": "x",
+            "sound": true
+        }));
+        assert!(matches!(extra, AppRuntimeMessage::Invalid(_)));
+    }
+
+    #[test]
+    fn status_badge_and_busy_state() {
+        let message = parse(serde_json::json!({
+            "type": "fw-app-status",
+            "badge": " 3 ",
+            "busy": true,
+            "text": "正在同步"
+        }));
+        let AppRuntimeMessage::Status(status) = message else {
+            panic!("expected status");
+        };
+        assert_eq!(status.badge.as_deref(), Some("3"));
+        assert!(status.busy);
+        assert!(!status.is_empty());
+
+        let AppRuntimeMessage::Status(cleared) =
+            parse(serde_json::json!({ "type": "fw-app-status", "badge": "" }))
+        else {
+            panic!("expected status");
+        };
+        assert!(cleared.is_empty());
+
+        let long_badge = parse(serde_json::json!({
+            "type": "fw-app-status",
+            "badge": "1234567890123"
+        }));
+        assert!(invalid(long_badge).contains("badge"));
+        let bad_busy = parse(serde_json::json!({ "type": "fw-app-status", "busy": "yes" }));
+        assert!(matches!(bad_busy, AppRuntimeMessage::Invalid(_)));
+    }
+}
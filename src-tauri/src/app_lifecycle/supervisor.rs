@@ -0,0 +1,442 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+
+use super::manager::{
+    app_launch_inner_with_options, build_registered_app_launch_args, AppLaunchOptions,
+    AppLifecycleManager, RegisteredAppLaunchConfig,
+};
+
+// ── 崩溃守护 ────────────────────────────────────────────────────────────────
+//
+// 注册应用退出时由进程回收任务调用 handle_app_exit：
+// - 宿主主动停止（app_stop / 重启 / 发送 close）的退出不算崩溃，也不重启。
+// - 其余退出按应用的 supervision 策略决定是否重启：never 不重启，onFailure 只在退出码非 0
+//   （或被信号结束）时重启，always 任何退出都重启。
+// - windowSecs 内最多重启 maxRestarts 次，超过就放弃并提示；每次重启前等待 backoffMs，
+//   窗口内每多重启一次等待时间翻倍（最长 MAX_BACKOFF）。
+// - 重启走正常启动路径（后台启动，沿用上次的启动选项）；崩溃记录只保存在内存，app_status 可查。
+
+const MAX_CRASH_RECORDS: usize = 20;
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum AppRestartPolicy {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppSupervisionConfig {
+    #[serde(default)]
+    pub(crate) restart: AppRestartPolicy,
+    #[serde(default = "default_max_restarts")]
+    pub(crate) max_restarts: u32,
+    #[serde(default = "default_window_secs")]
+    pub(crate) window_secs: u64,
+    #[serde(default = "default_backoff_ms")]
+    pub(crate) backoff_ms: u64,
+}
+
+fn default_max_restarts() -> u32 {
+    3
+}
+
+fn default_window_secs() -> u64 {
+    300
+}
+
+fn default_backoff_ms() -> u64 {
+    1_000
+}
+
+impl Default for AppSupervisionConfig {
+    fn default() -> Self {
+        Self {
+            restart: AppRestartPolicy::Never,
+            max_restarts: default_max_restarts(),
+            window_secs: default_window_secs(),
+            backoff_ms: default_backoff_ms(),
+        }
+    }
+}
+
+impl AppSupervisionConfig {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if !(1..=20).contains(&self.max_restarts) {
+            return Err("崩溃重启次数上限必须在 1 到 20 之间".to_string());
+        }
+        if !(10..=86_400).contains(&self.window_secs) {
+            return Err("崩溃重启统计窗口必须在 10 秒到 1 天之间".to_string());
+        }
+        if !(100..=60_000).contains(&self.backoff_ms) {
+            return Err("崩溃重启等待时间必须在 100 毫秒到 60 秒之间".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum AppCrashOutcome {
+    /// 策略不要求重启
+    NotRestarted,
+    Restarting,
+    Restarted,
+    RestartFailed,
+    /// 窗口内重启次数用完
+    GaveUp,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppCrashRecord {
+    pub(crate) at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) exit_code: Option<i32>,
+    pub(crate) outcome: AppCrashOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Default)]
+struct AppCrashHistory {
+    records: VecDeque<AppCrashRecord>,
+    restarts_at: VecDeque<u64>,
+}
+
+/// 各应用的崩溃记录；挂在 AppLifecycleManager 上。
+#[derive(Default)]
+pub(crate) struct AppCrashLog {
+    inner: Mutex<HashMap<String, AppCrashHistory>>,
+}
+
+impl AppCrashLog {
+    pub(crate) fn records(&self, app_id: &str) -> Vec<AppCrashRecord> {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|g| g.get(app_id).map(|h| h.records.iter().cloned().collect()))
+            .unwrap_or_default()
+    }
+
+    pub(super) fn push(&self, app_id: &str, record: AppCrashRecord) {
+        if let Ok(mut g) = self.inner.lock() {
+            let history = g.entry(app_id.to_string()).or_default();
+            history.records.push_back(record);
+            while history.records.len() > MAX_CRASH_RECORDS {
+                history.records.pop_front();
+            }
+        }
+    }
+
+    /// 更新最近一条记录（重启任务完成后回填结果）
+    pub(super) fn finish_last(
+        &self,
+        app_id: &str,
+        outcome: AppCrashOutcome,
+        error: Option<String>,
+    ) {
+        if let Ok(mut g) = self.inner.lock() {
+            if let Some(record) = g
+                .get_mut(app_id)
+                .and_then(|history| history.records.back_mut())
+            {
+                record.outcome = outcome;
+                record.error = error;
+            }
+        }
+    }
+
+    pub(super) fn recent_restarts(&self, app_id: &str, now_ms: u64, window_ms: u64) -> Vec<u64> {
+        let Ok(mut g) = self.inner.lock() else {
+            return Vec::new();
+        };
+        let history = g.entry(app_id.to_string()).or_default();
+        while history
+            .restarts_at
+            .front()
+            .is_some_and(|at| now_ms.saturating_sub(*at) >= window_ms)
+        {
+            history.restarts_at.pop_front();
+        }
+        history.restarts_at.iter().copied().collect()
+    }
+
+    pub(super) fn note_restart(&self, app_id: &str, at_ms: u64) {
+        if let Ok(mut g) = self.inner.lock() {
+            g.entry(app_id.to_string())
+                .or_default()
+                .restarts_at
+                .push_back(at_ms);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum RestartDecision {
+    Ignore,
+    Restart(Duration),
+    GiveUp,
+}
+
+fn is_failure(exit_code: Option<i32>) -> bool {
+    exit_code != Some(0)
+}
+
+/// `recent_restarts` 是统计窗口内已经做过的重启次数。
+fn plan_restart(
+    config: &AppSupervisionConfig,
+    exit_code: Option<i32>,
+    recent_restarts: usize,
+) -> RestartDecision {
+    let wanted = match config.restart {
+        AppRestartPolicy::Never => false,
+        AppRestartPolicy::OnFailure => is_failure(exit_code),
+        AppRestartPolicy::Always => true,
+    };
+    if !wanted {
+        return RestartDecision::Ignore;
+    }
+    if recent_restarts >= config.max_restarts as usize {
+        return RestartDecision::GiveUp;
+    }
+    let factor = 1u64 << recent_restarts.min(16);
+    let delay = Duration::from_millis(config.backoff_ms.saturating_mul(factor));
+    RestartDecision::Restart(delay.min(MAX_BACKOFF))
+}
+
+pub(super) fn registered_app_config(
+    app_handle: &AppHandle,
+    app_id: &str,
+) -> Option<RegisteredAppLaunchConfig> {
+    let record = crate::app_registry::load_registered_app_record(app_handle, app_id).ok()??;
+    serde_json::from_value(record).ok()
+}
+
+fn exit_code_text(exit_code: Option<i32>) -> String {
+    match exit_code {
+        Some(code) => format!("退出码 {code}"),
+        None => "被强制结束".to_string(),
+    }
+}
+
+/// 非宿主主动停止的退出；由进程回收任务调用。
+pub(super) fn handle_app_exit(
+    app_handle: AppHandle,
+    state: Arc<AppLifecycleManager>,
+    app_id: String,
+    exit_code: Option<i32>,
+    launch_options: AppLaunchOptions,
+    now_ms: u64,
+) {
+    let Some(config) = registered_app_config(&app_handle, &app_id) else {
+        return;
+    };
+    let supervision = config.supervision.clone();
+    if !is_failure(exit_code) && supervision.restart != AppRestartPolicy::Always {
+        // 正常退出且不要求常驻的，不算崩溃
+        return;
+    }
+
+    let window_ms = supervision.window_secs.saturating_mul(1_000);
+    let recent = state
+        .crash_log
+        .recent_restarts(&app_id, now_ms, window_ms)
+        .len();
+    let decision = plan_restart(&supervision, exit_code, recent);
+    let outcome = match decision {
+        RestartDecision::Ignore => AppCrashOutcome::NotRestarted,
+        RestartDecision::Restart(_) => AppCrashOutcome::Restarting,
+        RestartDecision::GiveUp => AppCrashOutcome::GaveUp,
+    };
+    state.crash_log.push(
+        &app_id,
+        AppCrashRecord {
+            at: now_ms,
+            exit_code,
+            outcome,
+            error: None,
+        },
+    );
+    eprintln!(
+        "[app-supervisor] {app_id} exited unexpectedly ({}), decision: {decision:?}",
+        exit_code_text(exit_code)
+    );
+
+    let delay = match decision {
+        RestartDecision::Ignore => return,
+        RestartDecision::GiveUp => {
+            crate::host_primitives::emit_toast(
+                &app_handle,
+                format!(
+                    "应用 {app_id} 反复异常退出（{}），{} 秒内已重启 {} 次，不再自动重启",
+                    exit_code_text(exit_code),
+                    supervision.window_secs,
+                    supervision.max_restarts
+                ),
+            );
+            return;
+        }
+        RestartDecision::Restart(delay) => delay,
+    };
+
+    state.crash_log.note_restart(&app_id, now_ms);
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        if crate::host_lifecycle::host_shutdown_in_progress(&app_handle) {
+            // 等待期间宿主开始退出，应用已被统一停止，不能再拉起
+            state.crash_log.finish_last(
+                &app_id,
+                AppCrashOutcome::NotRestarted,
+                Some("宿主正在退出".to_string()),
+            );
+            return;
+        }
+        if state.is_running(&app_id) {
+            // 等待期间用户已经手动启动
+            state
+                .crash_log
+                .finish_last(&app_id, AppCrashOutcome::Restarted, None);
+            return;
+        }
+        let args = build_registered_app_launch_args(&config, "hide", None);
+        let result = app_launch_inner_with_options(
+            app_handle.clone(),
+            state.clone(),
+            app_id.clone(),
+            config.path.clone(),
+            args,
+            launch_options,
+        )
+        .await;
+        match result {
+            Ok(()) => state
+                .crash_log
+                .finish_last(&app_id, AppCrashOutcome::Restarted, None),
+            Err(error) => {
+                eprintln!("[app-supervisor] failed to restart {app_id}: {error}");
+                crate::host_primitives::emit_toast(
+                    &app_handle,
+                    format!("应用 {app_id} 异常退出后重启失败：{error}"),
+                );
+                state
+                    .crash_log
+                    .finish_last(&app_id, AppCrashOutcome::RestartFailed, Some(error));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(restart: AppRestartPolicy) -> AppSupervisionConfig {
+        AppSupervisionConfig {
+            restart,
+            ..AppSupervisionConfig::default()
+        }
+    }
+
+    #[test]
+    fn policy_decides_which_exits_restart() {
+        let on_failure = config(AppRestartPolicy::OnFailure);
+        assert_eq!(
+            plan_restart(&on_failure, Some(0), 0),
+            RestartDecision::Ignore
+        );
+        assert_eq!(
+            plan_restart(&on_failure, Some(1), 0),
+            RestartDecision::Restart(Duration::from_millis(1_000))
+        );
+        assert_eq!(
+            plan_restart(&on_failure, None, 0),
+            RestartDecision::Restart(Duration::from_millis(1_000))
+        );
+
+        let always = config(AppRestartPolicy::Always);
+        assert!(matches!(
+            plan_restart(&always, Some(0), 0),
+            RestartDecision::Restart(_)
+        ));
+        assert_eq!(
+            plan_restart(&config(AppRestartPolicy::Never), Some(3), 0),
+            RestartDecision::Ignore
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_and_gives_up_after_max_restarts() {
+        let policy = config(AppRestartPolicy::OnFailure);
+        assert_eq!(
+            plan_restart(&policy, Some(1), 2),
+            RestartDecision::Restart(Duration::from_millis(4_000))
+        );
+        assert_eq!(plan_restart(&policy, Some(1), 3), RestartDecision::GiveUp);
+
+        let slow = AppSupervisionConfig {
+            backoff_ms: 60_000,
+            max_restarts: 5,
+            ..policy
+        };
+        assert_eq!(
+            plan_restart(&slow, Some(1), 4),
+            RestartDecision::Restart(MAX_BACKOFF)
+        );
+    }
+
+    #[test]
+    fn supervision_config_defaults_and_bounds() {
+        let parsed: AppSupervisionConfig =
+            serde_json::from_value(serde_json::json!({ "restart": "onFailure" })).unwrap();
+        assert_eq!(parsed.restart, AppRestartPolicy::OnFailure);
+        assert_eq!(parsed.max_restarts, 3);
+        assert!(parsed.validate().is_ok());
+
+        assert!(serde_json::from_value::<AppSupervisionConfig>(
+            serde_json::json!({ "restart": "sometimes" })
+        )
+        .is_err());
+        let bad = AppSupervisionConfig {
+            max_restarts: 0,
+            ..parsed
+        };
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn crash_log_expires_restarts_outside_window() {
+        let log = AppCrashLog::default();
+        log.note_restart("a", 1_000);
+        log.note_restart("a", 5_000);
+        assert_eq!(log.recent_restarts("a", 6_000, 10_000).len(), 2);
+        assert_eq!(log.recent_restarts("a", 12_000, 10_000), vec![5_000]);
+
+        for i in 0..(MAX_CRASH_RECORDS + 5) {
+            log.push(
+                "a",
+                AppCrashRecord {
+                    at: i as u64,
+                    exit_code: Some(1),
+                    outcome: AppCrashOutcome::Restarting,
+                    error: None,
+                },
+            );
+        }
+        log.finish_last("a", AppCrashOutcome::RestartFailed, Some("x".to_string()));
+        let records = log.records("a");
+        assert_eq!(records.len(), MAX_CRASH_RECORDS);
+        assert_eq!(records[0].at, 5);
+        assert_eq!(
+            records.last().unwrap().outcome,
+            AppCrashOutcome::RestartFailed
+        );
+    }
+}
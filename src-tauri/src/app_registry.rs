@@ -4,10 +4,12 @@ use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use tauri::{AppHandle, Emitter};
-use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::app_lifecycle::{AppHealthCheckConfig, AppSupervisionConfig};
+use crate::hotkeys::{HotkeyClaims, HotkeySequence};
 
 const REGISTRY_KEY: &str = "registeredApps";
-const REGISTERED_APPS_CHANGED_EVENT: &str = "fast-window:registered-apps-changed";
+pub(crate) const REGISTERED_APPS_CHANGED_EVENT: &str = "fast-window:registered-apps-changed";
 const COMMAND_ICON_DATA_URL_MAX_LEN: usize = 700 * 1024;
 const HIDDEN_POSITION_THRESHOLD: i32 = -9_000;
 const MAX_ABS_POSITION: i32 = 100_000;
@@ -39,6 +41,13 @@ pub(crate) struct AppRuntimeDeclaration {
     pub(crate) description: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub(crate) config_fields: Vec<AppCapabilityConfigField>,
+    /// 能力输入 / 配置 / 输出的 JSON Schema，见 capability_schema
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) input_schema: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) config_schema: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) output_schema: Option<Value>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
@@ -222,6 +231,8 @@ fn save_registry_and_refresh_shortcuts(
     validate_app_host_shortcuts(&registry)?;
     validate_app_hotkeys(&registry)?;
     validate_app_hotkey_launch_behaviors(&registry)?;
+    validate_app_supervision(&registry)?;
+    validate_app_health_check(&registry)?;
     crate::app_shortcuts::validate_registered_app_shortcuts_available(app, &registry)?;
 
     {
@@ -260,12 +271,11 @@ fn app_hotkey_from_value(value: &Value) -> Option<&str> {
 }
 
 fn validate_app_hotkeys(apps: &[Value]) -> Result<(), String> {
-    let mut seen: HashMap<String, String> = HashMap::new();
+    let mut claims = HotkeyClaims::default();
     for item in apps {
         let app_id = app_id_from_value(item).unwrap_or("");
         if let Some(raw_hotkey) = app_hotkey_from_value(item) {
-            register_unique_shortcut(
-                &mut seen,
+            claims.claim(
                 raw_hotkey,
                 format!("{app_id} 的应用快捷键"),
                 format!("{app_id} 的快捷键格式不合法"),
@@ -284,8 +294,7 @@ fn validate_app_hotkeys(apps: &[Value]) -> Result<(), String> {
             let Some(raw_hotkey) = command_hotkey_from_value(command) else {
                 continue;
             };
-            register_unique_shortcut(
-                &mut seen,
+            claims.claim(
                 raw_hotkey,
                 format!("{app_id}/{command_id} 的宿主快捷命令快捷键"),
                 format!("{app_id}/{command_id} 的宿主快捷命令快捷键格式不合法"),
@@ -303,22 +312,6 @@ fn command_hotkey_from_value(command: &Value) -> Option<&str> {
         .filter(|hotkey| !hotkey.is_empty())
 }
 
-fn register_unique_shortcut(
-    seen: &mut HashMap<String, String>,
-    raw_hotkey: &str,
-    owner: String,
-    invalid_message: String,
-) -> Result<(), String> {
-    let shortcut = Shortcut::from_str(raw_hotkey).map_err(|e| format!("{invalid_message}: {e}"))?;
-    let normalized = shortcut.to_string();
-    if let Some(existing_owner) = seen.insert(normalized.clone(), owner.clone()) {
-        return Err(format!(
-            "快捷键重复: {normalized}（{existing_owner} 和 {owner}）"
-        ));
-    }
-    Ok(())
-}
-
 fn validate_app_hotkey_launch_behaviors(apps: &[Value]) -> Result<(), String> {
     for item in apps {
         let app_id = app_id_from_value(item).unwrap_or("");
@@ -337,6 +330,34 @@ fn validate_app_hotkey_launch_behaviors(apps: &[Value]) -> Result<(), String> {
     Ok(())
 }
 
+fn validate_app_supervision(apps: &[Value]) -> Result<(), String> {
+    for item in apps {
+        let app_id = app_id_from_value(item).unwrap_or("");
+        let Some(value) = item.get("supervision") else {
+            continue;
+        };
+        serde_json::from_value::<AppSupervisionConfig>(value.clone())
+            .map_err(|e| format!("{app_id} 的崩溃重启设置不合法: {e}"))?
+            .validate()
+            .map_err(|e| format!("{app_id}：{e}"))?;
+    }
+    Ok(())
+}
+
+fn validate_app_health_check(apps: &[Value]) -> Result<(), String> {
+    for item in apps {
+        let app_id = app_id_from_value(item).unwrap_or("");
+        let Some(value) = item.get("healthCheck") else {
+            continue;
+        };
+        serde_json::from_value::<AppHealthCheckConfig>(value.clone())
+            .map_err(|e| format!("{app_id} 的健康检查设置不合法: {e}"))?
+            .validate()
+            .map_err(|e| format!("{app_id}：{e}"))?;
+    }
+    Ok(())
+}
+
 fn validate_app_host_shortcuts(apps: &[Value]) -> Result<(), String> {
     for item in apps {
         let app_id = app_id_from_value(item).unwrap_or("");
@@ -381,7 +402,7 @@ fn validate_host_shortcut_array(
         validate_declaration_config_fields(command.get("configFields"), app_id, label, command_id)?;
         validate_host_shortcut_icon(command.get("icon"), app_id, label, command_id)?;
         if let Some(raw_hotkey) = command_hotkey_from_value(command) {
-            Shortcut::from_str(raw_hotkey)
+            HotkeySequence::from_str(raw_hotkey)
                 .map_err(|e| format!("{app_id} 的{label}快捷键格式不合法: {e}"))?;
         }
     }
@@ -1,31 +1,20 @@
 use std::str::FromStr;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use tauri::{AppHandle, Manager};
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
 use crate::app_lifecycle::{
     app_launch_inner_with_cold_start_policy, build_registered_app_launch_args, AppColdStartPolicy,
     AppLifecycleManager, RegisteredAppLaunchConfig,
 };
-
-#[derive(Default)]
-pub(crate) struct RegisteredAppShortcutState {
-    current: Mutex<Vec<RegisteredAppShortcutBinding>>,
-    paused: Mutex<bool>,
-}
-
-#[derive(Clone)]
-struct RegisteredAppShortcutBinding {
-    target: RegisteredAppShortcutTarget,
-    shortcut: Shortcut,
-}
+use crate::hotkeys::{HotkeySequence, HotkeySource, HotkeySpec};
+use crate::usage::UsageKind;
 
 #[derive(Clone)]
 struct RegisteredAppShortcutTarget {
     app_id: String,
     command_id: Option<String>,
-    shortcut: Shortcut,
+    shortcut: HotkeySequence,
     cold_start_policy: AppColdStartPolicy,
 }
 
@@ -37,6 +26,13 @@ impl RegisteredAppShortcutTarget {
         }
     }
 
+    fn owner(&self) -> String {
+        match &self.command_id {
+            Some(_) => format!("{} 的宿主快捷命令快捷键", self.label()),
+            None => format!("{} 的应用快捷键", self.app_id),
+        }
+    }
+
     fn action(&self) -> &'static str {
         if self.command_id.is_some() {
             "show"
@@ -82,7 +78,7 @@ fn shortcut_targets_from_records(
             .map(str::trim)
             .filter(|hotkey| !hotkey.is_empty())
         {
-            let shortcut = Shortcut::from_str(hotkey)
+            let shortcut = HotkeySequence::from_str(hotkey)
                 .map_err(|e| format!("{app_id} 的快捷键格式不合法: {e}"))?;
             let cold_start_policy = cold_start_policy_from_record(value, &app_id)?;
             targets.push(RegisteredAppShortcutTarget {
@@ -112,7 +108,7 @@ fn shortcut_targets_from_records(
             else {
                 continue;
             };
-            let shortcut = Shortcut::from_str(hotkey).map_err(|e| {
+            let shortcut = HotkeySequence::from_str(hotkey).map_err(|e| {
                 format!("{app_id}/{command_id} 的宿主快捷命令快捷键格式不合法: {e}")
             })?;
             targets.push(RegisteredAppShortcutTarget {
@@ -131,6 +127,10 @@ async fn activate_registered_app_shortcut_target(
     app: AppHandle,
     target: RegisteredAppShortcutTarget,
 ) -> Result<(), String> {
+    match &target.command_id {
+        Some(_) => crate::usage::record_usage(&app, UsageKind::AppCommand, &target.label()),
+        None => crate::usage::record_usage(&app, UsageKind::App, &target.app_id),
+    }
     let Some(value) = crate::app_registry::load_registered_app_record(&app, &target.app_id)? else {
         return Err(format!("注册应用不存在: {}", target.app_id));
     };
@@ -149,20 +149,14 @@ async fn activate_registered_app_shortcut_target(
     .map(|_| ())
 }
 
-fn register_app_shortcut(
-    app: &AppHandle,
-    target: RegisteredAppShortcutTarget,
-) -> Result<RegisteredAppShortcutBinding, String> {
-    let shortcut = target.shortcut;
-    let shortcut_target = target.clone();
-    app.global_shortcut()
-        .on_shortcut(shortcut, move |app, _shortcut, event| {
-            if event.state != ShortcutState::Pressed {
-                return;
-            }
-
+fn shortcut_spec(target: RegisteredAppShortcutTarget) -> HotkeySpec {
+    HotkeySpec {
+        id: format!("app:{}", target.label()),
+        owner: target.owner(),
+        sequence: target.shortcut.clone(),
+        action: Arc::new(move |app: &AppHandle| {
             let app = app.clone();
-            let shortcut_target = shortcut_target.clone();
+            let shortcut_target = target.clone();
             tauri::async_runtime::spawn(async move {
                 let target_label = shortcut_target.label();
                 if let Err(error) =
@@ -172,194 +166,50 @@ fn register_app_shortcut(
                     crate::host_primitives::emit_toast(&app, format!("启动应用失败：{error}"));
                 }
             });
-        })
-        .map_err(|error| error.to_string())?;
-
-    Ok(RegisteredAppShortcutBinding { target, shortcut })
-}
-
-fn unregister_bindings(app: &AppHandle, bindings: Vec<RegisteredAppShortcutBinding>) {
-    for binding in bindings {
-        let _ = app.global_shortcut().unregister(binding.shortcut);
+        }),
     }
 }
 
-fn unregister_current(
-    app: &AppHandle,
-    state: &RegisteredAppShortcutState,
-) -> Vec<RegisteredAppShortcutBinding> {
-    let previous = state
-        .current
-        .lock()
-        .map(|mut guard| std::mem::take(&mut *guard))
-        .unwrap_or_default();
-
-    unregister_bindings(app, previous.clone());
-    previous
-}
-
-fn restore_bindings(
-    app: &AppHandle,
-    bindings: Vec<RegisteredAppShortcutBinding>,
-) -> Result<(), String> {
-    let mut restored = Vec::new();
-    let mut errors = Vec::new();
-    for binding in bindings {
-        match register_app_shortcut(app, binding.target.clone()) {
-            Ok(restored_binding) => restored.push(restored_binding),
-            Err(error) => {
-                errors.push(format!("{}: {error}", binding.shortcut));
-                eprintln!(
-                    "[app-shortcuts] failed to restore registered app shortcut {}: {}",
-                    binding.shortcut, error
-                );
-            }
-        }
-    }
-
-    let state = app.state::<RegisteredAppShortcutState>();
-    if let Ok(mut guard) = state.current.lock() {
-        *guard = restored;
-    }
-
-    if errors.is_empty() {
-        Ok(())
-    } else {
-        Err(format!("恢复注册应用快捷键失败: {}", errors.join("; ")))
-    }
+fn shortcut_specs_from_records(records: &[serde_json::Value]) -> Result<Vec<HotkeySpec>, String> {
+    Ok(shortcut_targets_from_records(records)?
+        .into_iter()
+        .map(shortcut_spec)
+        .collect())
 }
 
 pub(crate) fn refresh_registered_app_shortcuts(app: &AppHandle) -> Result<(), String> {
-    let state = app.state::<RegisteredAppShortcutState>();
-    if state
-        .paused
-        .lock()
-        .ok()
-        .map(|guard| *guard)
-        .unwrap_or(false)
-    {
-        return Ok(());
-    }
-
     let records = crate::app_registry::load_registered_app_records(app)?;
-    let targets = shortcut_targets_from_records(&records)?;
-    let previous = unregister_current(app, &state);
-    let mut next = Vec::new();
-    let mut errors = Vec::new();
-
-    for target in targets {
-        let shortcut_text = target.shortcut.to_string();
-        match register_app_shortcut(app, target) {
-            Ok(binding) => next.push(binding),
-            Err(error) => {
-                let message = format!("注册应用快捷键失败：{shortcut_text}（{error}）");
-                eprintln!("[app-shortcuts] {message}");
-                crate::host_primitives::emit_toast(app, message);
-                errors.push(format!("{shortcut_text}: {error}"));
-            }
-        }
+    let specs = shortcut_specs_from_records(&records)?;
+    let issues = crate::hotkeys::replace_source(app, HotkeySource::Apps, specs);
+    if issues.is_empty() {
+        return Ok(());
     }
 
-    if errors.is_empty() {
-        if let Ok(mut guard) = state.current.lock() {
-            *guard = next;
-        }
-        Ok(())
-    } else {
-        unregister_bindings(app, next);
-        let restore_error = restore_bindings(app, previous).err();
-        let message = format!("注册应用快捷键失败: {}", errors.join("; "));
-        match restore_error {
-            Some(error) => Err(format!("{message}; {error}")),
-            None => Err(message),
-        }
+    for issue in &issues {
+        eprintln!("[app-shortcuts] {issue}");
+        crate::host_primitives::emit_toast(app, format!("注册应用快捷键未生效：{issue}"));
     }
+    Err(format!("注册应用快捷键失败: {}", issues.join("; ")))
 }
 
+/// 保存注册表前预检：与宿主 / 插件快捷键冲突或被其它程序占用都拒绝保存。
 pub(crate) fn validate_registered_app_shortcuts_available(
     app: &AppHandle,
     records: &[serde_json::Value],
 ) -> Result<(), String> {
-    let state = app.state::<RegisteredAppShortcutState>();
-    let was_paused = state
-        .paused
-        .lock()
-        .ok()
-        .map(|guard| *guard)
-        .unwrap_or(false);
-    let previous = unregister_current(app, &state);
-
-    let targets = shortcut_targets_from_records(records);
-    let mut registered = Vec::new();
-    let mut result = Ok(());
-
-    match targets {
-        Ok(targets) => {
-            for target in targets {
-                let shortcut = target.shortcut;
-                let shortcut_text = shortcut.to_string();
-                if let Err(error) = app
-                    .global_shortcut()
-                    .on_shortcut(shortcut, |_app, _shortcut, _event| {})
-                {
-                    result = Err(format!("注册应用快捷键不可用：{shortcut_text}（{error}）"));
-                    break;
-                }
-                registered.push(shortcut);
-            }
-        }
-        Err(error) => {
-            result = Err(error);
-        }
-    }
-
-    for shortcut in registered {
-        let _ = app.global_shortcut().unregister(shortcut);
-    }
-
-    let restore_result = if !was_paused {
-        restore_bindings(app, previous)
-    } else {
-        Ok(())
-    };
-
-    match (result, restore_result) {
-        (Err(error), Err(restore_error)) => Err(format!("{error}; {restore_error}")),
-        (Err(error), Ok(())) => Err(error),
-        (Ok(()), Err(restore_error)) => Err(restore_error),
-        (Ok(()), Ok(())) => Ok(()),
-    }
+    let specs = shortcut_specs_from_records(records)?;
+    crate::hotkeys::check_source_available(app, HotkeySource::Apps, &specs)
+        .map_err(|e| format!("注册应用快捷键不可用：{e}"))
 }
 
 #[tauri::command]
 pub(crate) fn pause_registered_app_shortcuts(app: AppHandle) -> Result<(), String> {
-    let state = app.state::<RegisteredAppShortcutState>();
-    let mut paused = state
-        .paused
-        .lock()
-        .map_err(|_| "应用快捷键状态锁定失败".to_string())?;
-    if *paused {
-        return Ok(());
-    }
-
-    unregister_current(&app, &state);
-    *paused = true;
+    crate::hotkeys::set_source_paused(&app, HotkeySource::Apps, true);
     Ok(())
 }
 
 #[tauri::command]
 pub(crate) fn resume_registered_app_shortcuts(app: AppHandle) -> Result<(), String> {
-    let state = app.state::<RegisteredAppShortcutState>();
-    {
-        let mut paused = state
-            .paused
-            .lock()
-            .map_err(|_| "应用快捷键状态锁定失败".to_string())?;
-        if !*paused {
-            return Ok(());
-        }
-        *paused = false;
-    }
-
-    refresh_registered_app_shortcuts(&app)
+    crate::hotkeys::set_source_paused(&app, HotkeySource::Apps, false);
+    Ok(())
 }
@@ -0,0 +1,558 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::app_capabilities::{app_capability_invoke_inner, AppCapabilityInvokeRequest};
+use crate::app_lifecycle::{AppLifecycleManager, RegisteredAppLaunchConfig};
+use crate::hotkeys::{HotkeyClaims, HotkeySequence, HotkeySource, HotkeySpec};
+
+// ── 能力工作流 ──────────────────────────────────────────────────────────────
+//
+// 工作流是按顺序执行的一串应用能力调用，保存在 app.json 的 appWorkflows：
+// - 每一步的输入由模板生成：{{input}} 是工作流的输入，{{prev}} 是上一步输出（第一步即输入），
+//   {{steps.N}} 是第 N 步（从 1 开始）的输出；不写模板时等同 {{prev}}。
+// - 任一步失败即停止，后面的步骤标记为跳过；每步的结果和耗时都返回给调用方。
+// - 可从设置页、能力 HTTP 入口（/workflows、/workflow/run）或绑定的全局快捷键执行；
+//   快捷键执行时以剪贴板文本作为输入，结果以提示显示。
+
+const APP_WORKFLOWS_KEY: &str = "appWorkflows";
+const MAX_WORKFLOW_STEPS: usize = 20;
+const MAX_WORKFLOW_TITLE_CHARS: usize = 80;
+const DEFAULT_STEP_TEMPLATE: &str = "{{prev}}";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppWorkflowStep {
+    app_id: String,
+    capability_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    input: Option<String>,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    config: Value,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppWorkflow {
+    id: String,
+    title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hotkey: Option<String>,
+    steps: Vec<AppWorkflowStep>,
+}
+
+impl AppWorkflow {
+    pub(crate) fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum TemplatePart {
+    Text(String),
+    Input,
+    Prev,
+    Step(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum StepStatus {
+    Ok,
+    Failed,
+    Skipped,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppWorkflowStepResult {
+    index: usize,
+    app_id: String,
+    capability_id: String,
+    status: StepStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    elapsed_ms: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppWorkflowRunResult {
+    workflow_id: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    steps: Vec<AppWorkflowStepResult>,
+}
+
+impl AppWorkflowRunResult {
+    fn summary(&self) -> Result<&str, String> {
+        if let Some(text) = self.text.as_deref() {
+            return Ok(text);
+        }
+        let failed = self
+            .steps
+            .iter()
+            .find(|step| step.status == StepStatus::Failed);
+        Err(match failed {
+            Some(step) => format!(
+                "第 {} 步 {}/{} 失败：{}",
+                step.index,
+                step.app_id,
+                step.capability_id,
+                step.error.as_deref().unwrap_or_default()
+            ),
+            None => "工作流没有输出".to_string(),
+        })
+    }
+}
+
+/// 解析输入模板；`step_index` 从 1 开始，{{steps.N}} 只能引用之前的步骤。
+fn parse_input_template(template: &str, step_index: usize) -> Result<Vec<TemplatePart>, String> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            parts.push(TemplatePart::Text(rest[..start].to_string()));
+        }
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            return Err("输入模板中的 {{ 没有闭合".to_string());
+        };
+        let name = after[..end].trim();
+        let part = match name {
+            "input" => TemplatePart::Input,
+            "prev" => TemplatePart::Prev,
+            _ => {
+                let n = name
+                    .strip_prefix("steps.")
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .ok_or_else(|| format!("输入模板中的占位符不支持: {{{{{name}}}}}"))?;
+                if n == 0 || n >= step_index {
+                    return Err(format!(
+                        "输入模板只能引用之前的步骤: {{{{{name}}}}}（当前第 {step_index} 步）"
+                    ));
+                }
+                TemplatePart::Step(n)
+            }
+        };
+        parts.push(part);
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Text(rest.to_string()));
+    }
+    Ok(parts)
+}
+
+/// 按模板生成某一步的输入；`outputs` 是之前各步的输出。
+fn render_input_template(
+    template: &str,
+    input: &str,
+    outputs: &[String],
+) -> Result<String, String> {
+    let parts = parse_input_template(template, outputs.len() + 1)?;
+    let mut out = String::new();
+    for part in parts {
+        match part {
+            TemplatePart::Text(text) => out.push_str(&text),
+            TemplatePart::Input => out.push_str(input),
+            TemplatePart::Prev => out.push_str(outputs.last().map(String::as_str).unwrap_or(input)),
+            TemplatePart::Step(n) => out.push_str(&outputs[n - 1]),
+        }
+    }
+    Ok(out)
+}
+
+fn validate_workflow(workflow: &AppWorkflow) -> Result<(), String> {
+    if !crate::is_safe_id(&workflow.id) {
+        return Err(format!("工作流 ID 不合法: {}", workflow.id));
+    }
+    let title = workflow.title.trim();
+    if title.is_empty() {
+        return Err(format!("工作流 {} 缺少标题", workflow.id));
+    }
+    if title.chars().count() > MAX_WORKFLOW_TITLE_CHARS {
+        return Err(format!(
+            "工作流 {} 的标题不能超过 {MAX_WORKFLOW_TITLE_CHARS} 个字符",
+            workflow.id
+        ));
+    }
+    if workflow.steps.is_empty() || workflow.steps.len() > MAX_WORKFLOW_STEPS {
+        return Err(format!(
+            "工作流 {} 的步骤数必须在 1 到 {MAX_WORKFLOW_STEPS} 之间",
+            workflow.id
+        ));
+    }
+    for (i, step) in workflow.steps.iter().enumerate() {
+        let index = i + 1;
+        if !crate::is_safe_id(step.app_id.trim()) {
+            return Err(format!(
+                "工作流 {} 第 {index} 步的应用 ID 不合法",
+                workflow.id
+            ));
+        }
+        if !crate::is_safe_id(step.capability_id.trim()) {
+            return Err(format!(
+                "工作流 {} 第 {index} 步的能力 ID 不合法",
+                workflow.id
+            ));
+        }
+        if let Some(template) = step.input.as_deref() {
+            parse_input_template(template, index)
+                .map_err(|e| format!("工作流 {} 第 {index} 步：{e}", workflow.id))?;
+        }
+    }
+    Ok(())
+}
+
+/// 校验整组工作流：各自合法、ID 不重复、快捷键互不冲突。
+fn validate_workflows(workflows: &[AppWorkflow]) -> Result<(), String> {
+    let mut ids = HashSet::new();
+    let mut claims = HotkeyClaims::default();
+    for workflow in workflows {
+        validate_workflow(workflow)?;
+        if !ids.insert(workflow.id.as_str()) {
+            return Err(format!("工作流 ID 重复: {}", workflow.id));
+        }
+        if let Some(hotkey) = workflow_hotkey(workflow) {
+            claims.claim(
+                hotkey,
+                workflow_hotkey_owner(workflow),
+                format!("工作流 {} 的快捷键不合法", workflow.id),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn workflow_hotkey(workflow: &AppWorkflow) -> Option<&str> {
+    workflow
+        .hotkey
+        .as_deref()
+        .map(str::trim)
+        .filter(|hotkey| !hotkey.is_empty())
+}
+
+fn workflow_hotkey_owner(workflow: &AppWorkflow) -> String {
+    format!("{} 的工作流快捷键", workflow.id)
+}
+
+fn load_workflows(app: &AppHandle) -> Vec<AppWorkflow> {
+    let map = crate::read_app_config_map(app);
+    map.get(APP_WORKFLOWS_KEY)
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| serde_json::from_value::<AppWorkflow>(item.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn save_workflows(app: &AppHandle, workflows: &[AppWorkflow]) -> Result<(), String> {
+    let value = serde_json::to_value(workflows).map_err(|e| format!("序列化工作流失败: {e}"))?;
+    crate::update_app_config_map(app, |map| {
+        map.insert(APP_WORKFLOWS_KEY.to_string(), value);
+        Ok(())
+    })
+}
+
+fn registered_app_config(
+    app: &AppHandle,
+    app_id: &str,
+) -> Result<RegisteredAppLaunchConfig, String> {
+    let record = crate::app_registry::load_registered_app_record(app, app_id)?
+        .ok_or_else(|| format!("注册应用不存在: {app_id}"))?;
+    serde_json::from_value(record).map_err(|e| format!("注册应用配置不完整: {e}"))
+}
+
+async fn run_workflow(
+    app: AppHandle,
+    lifecycle: Arc<AppLifecycleManager>,
+    workflow: &AppWorkflow,
+    input: &str,
+) -> AppWorkflowRunResult {
+    let mut outputs: Vec<String> = Vec::new();
+    let mut steps = Vec::new();
+    let mut failed = false;
+
+    for (i, step) in workflow.steps.iter().enumerate() {
+        let app_id = step.app_id.trim().to_string();
+        let capability_id = step.capability_id.trim().to_string();
+        if failed {
+            steps.push(AppWorkflowStepResult {
+                index: i + 1,
+                app_id,
+                capability_id,
+                status: StepStatus::Skipped,
+                text: None,
+                error: None,
+                elapsed_ms: 0,
+            });
+            continue;
+        }
+
+        let started = Instant::now();
+        let template = step.input.as_deref().unwrap_or(DEFAULT_STEP_TEMPLATE);
+        let result = match render_input_template(template, input, &outputs) {
+            Ok(text) => match registered_app_config(&app, &app_id) {
+                Ok(config) => {
+                    let request = AppCapabilityInvokeRequest::new(
+                        config,
+                        capability_id.clone(),
+                        serde_json::json!({ "text": text }),
+                        step.config.clone(),
+                    );
+                    app_capability_invoke_inner(app.clone(), lifecycle.clone(), request)
+                        .await
+                        .map(|response| response.into_text())
+                }
+                Err(error) => Err(error),
+            },
+            Err(error) => Err(error),
+        };
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(text) => {
+                outputs.push(text.clone());
+                steps.push(AppWorkflowStepResult {
+                    index: i + 1,
+                    app_id,
+                    capability_id,
+                    status: StepStatus::Ok,
+                    text: Some(text),
+                    error: None,
+                    elapsed_ms,
+                });
+            }
+            Err(error) => {
+                failed = true;
+                steps.push(AppWorkflowStepResult {
+                    index: i + 1,
+                    app_id,
+                    capability_id,
+                    status: StepStatus::Failed,
+                    text: None,
+                    error: Some(error),
+                    elapsed_ms,
+                });
+            }
+        }
+    }
+
+    AppWorkflowRunResult {
+        workflow_id: workflow.id.clone(),
+        ok: !failed,
+        text: if failed { None } else { outputs.pop() },
+        steps,
+    }
+}
+
+/// 按 ID 执行已保存的工作流（能力 HTTP 入口也走这里）。
+pub(crate) async fn run_saved_workflow(
+    app: AppHandle,
+    lifecycle: Arc<AppLifecycleManager>,
+    workflow_id: &str,
+    input: &str,
+) -> Result<AppWorkflowRunResult, String> {
+    let workflow = load_workflows(&app)
+        .into_iter()
+        .find(|workflow| workflow.id == workflow_id)
+        .ok_or_else(|| format!("工作流不存在: {workflow_id}"))?;
+    Ok(run_workflow(app, lifecycle, &workflow, input).await)
+}
+
+pub(crate) fn list_saved_workflows(app: &AppHandle) -> Vec<AppWorkflow> {
+    load_workflows(app)
+}
+
+async fn run_workflow_from_hotkey(app: AppHandle, workflow_id: String) -> Result<String, String> {
+    let clipboard_app = app.clone();
+    let input = tauri::async_runtime::spawn_blocking(move || {
+        clipboard_app.clipboard().read_text().unwrap_or_default()
+    })
+    .await
+    .map_err(|e| format!("读取剪贴板失败: {e}"))?;
+    let lifecycle = app.state::<Arc<AppLifecycleManager>>().inner().clone();
+    let result = run_saved_workflow(app, lifecycle, &workflow_id, &input).await?;
+    result.summary().map(str::to_string)
+}
+
+fn workflow_hotkey_specs(workflows: &[AppWorkflow]) -> Vec<HotkeySpec> {
+    workflows
+        .iter()
+        .filter_map(|workflow| {
+            let sequence = workflow_hotkey(workflow)?.parse::<HotkeySequence>().ok()?;
+            let workflow_id = workflow.id.clone();
+            let title = workflow.title.trim().to_string();
+            Some(HotkeySpec {
+                id: format!("workflow:{}", workflow.id),
+                owner: workflow_hotkey_owner(workflow),
+                sequence,
+                action: Arc::new(move |app: &AppHandle| {
+                    let app = app.clone();
+                    let workflow_id = workflow_id.clone();
+                    let title = title.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let message = match run_workflow_from_hotkey(app.clone(), workflow_id).await
+                        {
+                            Ok(text) => format!("{title}：{text}"),
+                            Err(error) => format!("工作流 {title} 执行失败：{error}"),
+                        };
+                        crate::host_primitives::emit_toast(&app, message);
+                    });
+                }),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn refresh_workflow_hotkeys(app: &AppHandle) {
+    let specs = workflow_hotkey_specs(&load_workflows(app));
+    for issue in crate::hotkeys::replace_source(app, HotkeySource::Workflows, specs) {
+        eprintln!("[app-workflows] {issue}");
+        crate::host_primitives::emit_toast(app, format!("工作流快捷键未生效：{issue}"));
+    }
+}
+
+#[tauri::command]
+pub(crate) fn app_workflow_list(app: AppHandle) -> Vec<AppWorkflow> {
+    load_workflows(&app)
+}
+
+#[tauri::command]
+pub(crate) fn app_workflow_save(
+    app: AppHandle,
+    workflow: AppWorkflow,
+) -> Result<Vec<AppWorkflow>, String> {
+    let mut workflows = load_workflows(&app);
+    match workflows.iter_mut().find(|item| item.id == workflow.id) {
+        Some(existing) => *existing = workflow,
+        None => workflows.push(workflow),
+    }
+    validate_workflows(&workflows)?;
+    crate::hotkeys::check_source_available(
+        &app,
+        HotkeySource::Workflows,
+        &workflow_hotkey_specs(&workflows),
+    )
+    .map_err(|e| format!("工作流快捷键不可用：{e}"))?;
+
+    save_workflows(&app, &workflows)?;
+    refresh_workflow_hotkeys(&app);
+    Ok(workflows)
+}
+
+#[tauri::command]
+pub(crate) fn app_workflow_remove(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<Vec<AppWorkflow>, String> {
+    let mut workflows = load_workflows(&app);
+    let before = workflows.len();
+    workflows.retain(|workflow| workflow.id != workflow_id);
+    if workflows.len() == before {
+        return Err(format!("工作流不存在: {workflow_id}"));
+    }
+    save_workflows(&app, &workflows)?;
+    refresh_workflow_hotkeys(&app);
+    Ok(workflows)
+}
+
+#[tauri::command]
+pub(crate) async fn app_workflow_run(
+    app_handle: AppHandle,
+    state: tauri::State<'_, Arc<AppLifecycleManager>>,
+    workflow_id: String,
+    input: Option<String>,
+) -> Result<AppWorkflowRunResult, String> {
+    run_saved_workflow(
+        app_handle,
+        state.inner().clone(),
+        &workflow_id,
+        input.as_deref().unwrap_or_default(),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(app_id: &str, capability_id: &str, input: Option<&str>) -> AppWorkflowStep {
+        AppWorkflowStep {
+            app_id: app_id.to_string(),
+            capability_id: capability_id.to_string(),
+            input: input.map(str::to_string),
+            config: Value::Null,
+        }
+    }
+
+    fn workflow(id: &str, hotkey: Option<&str>, steps: Vec<AppWorkflowStep>) -> AppWorkflow {
+        AppWorkflow {
+            id: id.to_string(),
+            title: "翻译后总结".to_string(),
+            hotkey: hotkey.map(str::to_string),
+            steps,
+        }
+    }
+
+    #[test]
+    fn render_replaces_input_prev_and_earlier_steps() {
+        let outputs = vec!["one".to_string(), "two".to_string()];
+        assert_eq!(
+            render_input_template("{{input}}|{{ prev }}|{{steps.1}}", "in", &outputs).unwrap(),
+            "in|two|one"
+        );
+        assert_eq!(render_input_template("{{prev}}", "in", &[]).unwrap(), "in");
+        assert_eq!(render_input_template("plain", "in", &[]).unwrap(), "plain");
+    }
+
+    #[test]
+    fn render_rejects_bad_placeholders() {
+        assert!(render_input_template("{{prev", "in", &[]).is_err());
+        assert!(render_input_template("{{output}}", "in", &[]).is_err());
+        assert!(render_input_template("{{steps.1}}", "in", &[]).is_err());
+        assert!(render_input_template("{{steps.0}}", "in", &["a".to_string()]).is_err());
+    }
+
+    #[test]
+    fn validate_checks_steps_templates_and_duplicates() {
+        let ok = workflow("wf", None, vec![step("app", "cap", None)]);
+        assert!(validate_workflows(std::slice::from_ref(&ok)).is_ok());
+
+        assert!(validate_workflow(&workflow("wf", None, Vec::new())).is_err());
+        assert!(
+            validate_workflow(&workflow("bad id", None, vec![step("app", "cap", None)])).is_err()
+        );
+        assert!(validate_workflow(&workflow(
+            "wf",
+            None,
+            vec![step("app", "cap", Some("{{steps.1}}"))]
+        ))
+        .is_err());
+        assert!(validate_workflows(&[ok.clone(), ok]).is_err());
+    }
+
+    #[test]
+    fn validate_rejects_conflicting_hotkeys() {
+        let a = workflow("a", Some("control+KeyK"), vec![step("app", "cap", None)]);
+        let b = workflow(
+            "b",
+            Some("control+KeyK control+KeyM"),
+            vec![step("app", "cap", None)],
+        );
+        assert!(validate_workflows(std::slice::from_ref(&a)).is_ok());
+        assert!(validate_workflows(&[a, b]).is_err());
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::webview::DownloadEvent;
+use tauri::{Emitter, EventTarget, Manager};
+
+use crate::config_store::{
+    read_browser_download_dir_from_config, write_browser_download_dir_to_config,
+};
+use crate::host_primitives::emit_toast;
+use crate::plugin_assets::percent_decode_path;
+use crate::{ensure_writable_dir, open_dir_in_file_manager, BROWSER_BAR_WINDOW_LABEL};
+
+// ── 浏览栈下载 ───────────────────────────────────────────────────────────────
+//
+// 内容 WebView 的下载统一走宿主：保存到设置里的下载目录（默认系统“下载”文件夹），
+// 同名文件自动加 “ (1)” 后缀；开始/完成/失败都会 toast，并推送给顶部栏。
+// WebView 不提供字节级进度，所以“进度”只有开始与结束两个节点。
+// 下载列表只保存在内存里（本次运行），文件本身留在磁盘上。
+
+const BROWSER_DOWNLOADS_UPDATED_EVENT: &str = "fast-window:browser-downloads-updated";
+const BROWSER_DOWNLOADS_MAX: usize = 200;
+const BROWSER_DOWNLOAD_FILE_NAME_MAX_CHARS: usize = 180;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum BrowserDownloadStatus {
+    Downloading,
+    Completed,
+    Failed,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrowserDownload {
+    id: String,
+    url: String,
+    file_name: String,
+    path: String,
+    status: BrowserDownloadStatus,
+    started_at_ms: u64,
+    finished_at_ms: Option<u64>,
+}
+
+#[derive(Default)]
+pub(crate) struct BrowserDownloadsState {
+    /// 从旧到新
+    downloads: Mutex<VecDeque<BrowserDownload>>,
+    next_seq: AtomicU64,
+}
+
+/// 去掉路径分隔符、控制字符与 Windows 不允许的字符；空名回退为 `download`。
+fn sanitize_download_file_name(raw: &str) -> String {
+    let cleaned: String = raw
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let trimmed = cleaned.trim().trim_matches('.').trim();
+    let mut name: String = trimmed
+        .chars()
+        .take(BROWSER_DOWNLOAD_FILE_NAME_MAX_CHARS)
+        .collect();
+    if name.is_empty() {
+        name = "download".to_string();
+    }
+    let stem = name.split('.').next().unwrap_or("").to_ascii_uppercase();
+    let reserved = matches!(stem.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+        || ((stem.starts_with("COM") || stem.starts_with("LPT"))
+            && stem.len() == 4
+            && stem.as_bytes()[3].is_ascii_digit());
+    if reserved {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// 取 URL 路径最后一段（已解码）作为文件名。
+fn file_name_from_url(url: &tauri::Url) -> Option<String> {
+    let last = url.path_segments()?.next_back()?.trim();
+    if last.is_empty() {
+        return None;
+    }
+    Some(percent_decode_path(last).unwrap_or_else(|_| last.to_string()))
+}
+
+/// `a.txt` 已存在时依次尝试 `a (1).txt`、`a (2).txt`…
+fn unique_download_path(dir: &Path, file_name: &str, taken: impl Fn(&Path) -> bool) -> PathBuf {
+    let first = dir.join(file_name);
+    if !taken(&first) {
+        return first;
+    }
+    let (stem, ext) = match file_name.rfind('.') {
+        Some(i) if i > 0 => (&file_name[..i], &file_name[i..]),
+        _ => (file_name, ""),
+    };
+    for n in 1..10_000 {
+        let candidate = dir.join(format!("{stem} ({n}){ext}"));
+        if !taken(&candidate) {
+            return candidate;
+        }
+    }
+    dir.join(format!("{stem} ({}){ext}", crate::now_ms()))
+}
+
+fn default_download_dir(app: &tauri::AppHandle) -> PathBuf {
+    app.path()
+        .download_dir()
+        .unwrap_or_else(|_| crate::app_data_dir(app).join("downloads"))
+}
+
+fn resolve_download_dir(app: &tauri::AppHandle) -> PathBuf {
+    read_browser_download_dir_from_config(app).unwrap_or_else(|| default_download_dir(app))
+}
+
+fn emit_downloads_updated(app: &tauri::AppHandle, changed: &BrowserDownload) {
+    let _ = app.emit_to(
+        EventTarget::webview_window(BROWSER_BAR_WINDOW_LABEL),
+        BROWSER_DOWNLOADS_UPDATED_EVENT,
+        changed.clone(),
+    );
+    let _ = app.emit_to(
+        EventTarget::webview_window("main"),
+        BROWSER_DOWNLOADS_UPDATED_EVENT,
+        changed.clone(),
+    );
+}
+
+fn on_download_requested(
+    app: &tauri::AppHandle,
+    url: &tauri::Url,
+    destination: &mut PathBuf,
+) -> bool {
+    let suggested = destination
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .filter(|s| !s.trim().is_empty())
+        .or_else(|| file_name_from_url(url))
+        .unwrap_or_default();
+    let file_name = sanitize_download_file_name(&suggested);
+
+    let dir = resolve_download_dir(app);
+    if let Err(e) = ensure_writable_dir(&dir) {
+        emit_toast(app, format!("下载失败（{}）：{e}", dir.to_string_lossy()));
+        return false;
+    }
+
+    let state = app.state::<BrowserDownloadsState>();
+    let mut downloads = state.downloads.lock().unwrap_or_else(|e| e.into_inner());
+    // 同名文件可能还没落盘：正在下载的目标路径也算占用
+    let path = unique_download_path(&dir, &file_name, |p| {
+        p.exists()
+            || downloads
+                .iter()
+                .any(|d| d.status == BrowserDownloadStatus::Downloading && Path::new(&d.path) == p)
+    });
+    let seq = state
+        .next_seq
+        .fetch_add(1, Ordering::Relaxed)
+        .saturating_add(1);
+    let item = BrowserDownload {
+        id: format!("dl-{seq}"),
+        url: url.to_string(),
+        file_name: path
+            .file_name()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or(file_name),
+        path: path.to_string_lossy().to_string(),
+        status: BrowserDownloadStatus::Downloading,
+        started_at_ms: crate::now_ms(),
+        finished_at_ms: None,
+    };
+    downloads.push_back(item.clone());
+    while downloads.len() > BROWSER_DOWNLOADS_MAX {
+        downloads.pop_front();
+    }
+    drop(downloads);
+
+    *destination = path;
+    emit_toast(app, format!("开始下载：{}", item.file_name));
+    emit_downloads_updated(app, &item);
+    true
+}
+
+fn on_download_finished(
+    app: &tauri::AppHandle,
+    url: &tauri::Url,
+    path: Option<&Path>,
+    success: bool,
+) {
+    let state = app.state::<BrowserDownloadsState>();
+    let mut downloads = state.downloads.lock().unwrap_or_else(|e| e.into_inner());
+    let url = url.to_string();
+    // 优先按落盘路径匹配（macOS 不提供 path），找不到再按 URL
+    let active = |d: &BrowserDownload| d.status == BrowserDownloadStatus::Downloading;
+    let idx = path
+        .and_then(|p| {
+            downloads
+                .iter()
+                .rposition(|d| active(d) && Path::new(&d.path) == p)
+        })
+        .or_else(|| downloads.iter().rposition(|d| active(d) && d.url == url));
+    let Some(item) = idx.and_then(|i| downloads.get_mut(i)) else {
+        return;
+    };
+    item.status = if success {
+        BrowserDownloadStatus::Completed
+    } else {
+        BrowserDownloadStatus::Failed
+    };
+    item.finished_at_ms = Some(crate::now_ms());
+    let item = item.clone();
+    drop(downloads);
+
+    if success {
+        emit_toast(app, format!("下载完成：{}", item.file_name));
+    } else {
+        emit_toast(app, format!("下载失败：{}", item.file_name));
+    }
+    emit_downloads_updated(app, &item);
+}
+
+/// 内容 WebView 的 `on_download` 回调：返回 false 取消下载。
+pub(crate) fn browser_downloads_on_event(app: &tauri::AppHandle, event: DownloadEvent<'_>) -> bool {
+    match event {
+        DownloadEvent::Requested { url, destination } => {
+            on_download_requested(app, &url, destination)
+        }
+        DownloadEvent::Finished { url, path, success } => {
+            on_download_finished(app, &url, path.as_deref(), success);
+            true
+        }
+        _ => true,
+    }
+}
+
+#[tauri::command]
+pub(crate) fn browser_downloads_list(app: tauri::AppHandle) -> Vec<BrowserDownload> {
+    let state = app.state::<BrowserDownloadsState>();
+    let downloads = state.downloads.lock().unwrap_or_else(|e| e.into_inner());
+    downloads.iter().rev().cloned().collect()
+}
+
+/// 只清掉已结束的记录，进行中的下载保留。
+#[tauri::command]
+pub(crate) fn browser_downloads_clear(app: tauri::AppHandle) -> Vec<BrowserDownload> {
+    let state = app.state::<BrowserDownloadsState>();
+    let mut downloads = state.downloads.lock().unwrap_or_else(|e| e.into_inner());
+    downloads.retain(|d| d.status == BrowserDownloadStatus::Downloading);
+    downloads.iter().rev().cloned().collect()
+}
+
+/// 打开下载所在的文件夹；不传 id 时打开下载目录。
+#[tauri::command]
+pub(crate) fn browser_download_open_dir(
+    app: tauri::AppHandle,
+    id: Option<String>,
+) -> Result<(), String> {
+    let Some(id) = id else {
+        return open_dir_in_file_manager(&resolve_download_dir(&app));
+    };
+    let path = {
+        let state = app.state::<BrowserDownloadsState>();
+        let downloads = state.downloads.lock().unwrap_or_else(|e| e.into_inner());
+        downloads
+            .iter()
+            .find(|d| d.id == id)
+            .map(|d| PathBuf::from(&d.path))
+            .ok_or_else(|| "下载记录不存在".to_string())?
+    };
+    let dir = path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| resolve_download_dir(&app));
+    open_dir_in_file_manager(&dir)
+}
+
+#[tauri::command]
+pub(crate) fn browser_download_get_dir(app: tauri::AppHandle) -> String {
+    resolve_download_dir(&app).to_string_lossy().to_string()
+}
+
+#[tauri::command]
+pub(crate) fn browser_download_pick_dir(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    let current = resolve_download_dir(&app);
+    let Some(dir) = crate::host_dialog::pick_folder_with_default(&app, "选择下载目录", &current)
+    else {
+        return Ok(None);
+    };
+    ensure_writable_dir(&dir)?;
+    write_browser_download_dir_to_config(&app, Some(&dir))?;
+    Ok(Some(dir.to_string_lossy().to_string()))
+}
+
+/// 恢复为系统默认的“下载”文件夹。
+#[tauri::command]
+pub(crate) fn browser_download_reset_dir(app: tauri::AppHandle) -> Result<String, String> {
+    write_browser_download_dir_to_config(&app, None)?;
+    Ok(default_download_dir(&app).to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_download_file_name_strips_unsafe_parts() {
+        assert_eq!(sanitize_download_file_name("a/b\\c.txt"), "a_b_c.txt");
+        assert_eq!(sanitize_download_file_name("  ..  "), "download");
+        assert_eq!(sanitize_download_file_name("con.txt"), "_con.txt");
+        assert_eq!(sanitize_download_file_name("COM1"), "_COM1");
+        assert_eq!(sanitize_download_file_name("report?.pdf"), "report_.pdf");
+    }
+
+    #[test]
+    fn unique_download_path_appends_counter_before_extension() {
+        let dir = Path::new("/d");
+        let taken = [PathBuf::from("/d/a.txt"), PathBuf::from("/d/a (1).txt")];
+        let p = unique_download_path(dir, "a.txt", |p| taken.iter().any(|t| t == p));
+        assert_eq!(p, PathBuf::from("/d/a (2).txt"));
+        let p = unique_download_path(dir, "README", |p| p == Path::new("/d/README"));
+        assert_eq!(p, PathBuf::from("/d/README (1)"));
+        let p = unique_download_path(dir, ".env", |p| p == Path::new("/d/.env"));
+        assert_eq!(p, PathBuf::from("/d/.env (1)"));
+    }
+
+    #[test]
+    fn file_name_from_url_decodes_last_segment() {
+        let url = tauri::Url::parse("https://e.com/files/%E6%8A%A5%E5%91%8A.pdf?x=1").unwrap();
+        assert_eq!(file_name_from_url(&url).as_deref(), Some("报告.pdf"));
+        let url = tauri::Url::parse("https://e.com/").unwrap();
+        assert_eq!(file_name_from_url(&url), None);
+    }
+}
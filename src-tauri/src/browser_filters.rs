@@ -0,0 +1,1057 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{Emitter, EventTarget, Manager};
+
+use crate::{open_dir_in_file_manager, BROWSER_BAR_WINDOW_LABEL};
+
+// ── 浏览栈内容拦截 ───────────────────────────────────────────────────────────
+//
+// 过滤规则来自 <data>/__app/filter-lists/*.txt（EasyList 语法的子集，只支持网络规则）：
+//   `||ads.example.com^`、`|https://x.com/ad`、`/banner/*.gif`、`@@` 例外、
+//   `$third-party` / `$script` 等资源类型 / `$domain=a.com|~b.com`；
+//   元素隐藏（`##`）、正则规则和不认识的选项整条跳过，宁可漏拦也不误拦。
+// 顶层导航在所有平台都经 on_navigation 拦截；子资源只有 Windows（WebView2 提供
+// WebResourceRequested）能拦，其他平台的 WebView 不开放请求拦截。
+// 规则按 URL 中的词（token）建索引，每个请求只比对少量候选规则。
+
+const FILTER_LISTS_DIR: &str = "filter-lists";
+const BROWSER_FILTER_ENABLED_KEY: &str = "browserFilterEnabled";
+const BROWSER_FILTER_DISABLED_SITES_KEY: &str = "browserFilterDisabledSites";
+const BROWSER_FILTER_UPDATED_EVENT: &str = "fast-window:browser-filter-updated";
+
+const TYPE_DOCUMENT: u16 = 1;
+const TYPE_SUBDOCUMENT: u16 = 1 << 1;
+const TYPE_SCRIPT: u16 = 1 << 2;
+const TYPE_IMAGE: u16 = 1 << 3;
+const TYPE_STYLESHEET: u16 = 1 << 4;
+const TYPE_FONT: u16 = 1 << 5;
+const TYPE_MEDIA: u16 = 1 << 6;
+const TYPE_XHR: u16 = 1 << 7;
+const TYPE_WEBSOCKET: u16 = 1 << 8;
+const TYPE_PING: u16 = 1 << 9;
+const TYPE_OBJECT: u16 = 1 << 10;
+const TYPE_OTHER: u16 = 1 << 11;
+const TYPE_ALL: u16 = (1 << 12) - 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub(crate) enum FilterResourceType {
+    Document,
+    Subdocument,
+    Script,
+    Image,
+    Stylesheet,
+    Font,
+    Media,
+    Xhr,
+    Websocket,
+    Ping,
+    Object,
+    Other,
+}
+
+impl FilterResourceType {
+    fn bit(self) -> u16 {
+        match self {
+            Self::Document => TYPE_DOCUMENT,
+            Self::Subdocument => TYPE_SUBDOCUMENT,
+            Self::Script => TYPE_SCRIPT,
+            Self::Image => TYPE_IMAGE,
+            Self::Stylesheet => TYPE_STYLESHEET,
+            Self::Font => TYPE_FONT,
+            Self::Media => TYPE_MEDIA,
+            Self::Xhr => TYPE_XHR,
+            Self::Websocket => TYPE_WEBSOCKET,
+            Self::Ping => TYPE_PING,
+            Self::Object => TYPE_OBJECT,
+            Self::Other => TYPE_OTHER,
+        }
+    }
+}
+
+fn type_bit_from_option(name: &str) -> Option<u16> {
+    Some(match name {
+        "document" | "doc" => TYPE_DOCUMENT,
+        "subdocument" | "frame" => TYPE_SUBDOCUMENT,
+        "script" => TYPE_SCRIPT,
+        "image" => TYPE_IMAGE,
+        "stylesheet" | "css" => TYPE_STYLESHEET,
+        "font" => TYPE_FONT,
+        "media" => TYPE_MEDIA,
+        "xmlhttprequest" | "xhr" => TYPE_XHR,
+        "websocket" => TYPE_WEBSOCKET,
+        "ping" => TYPE_PING,
+        "object" => TYPE_OBJECT,
+        "other" => TYPE_OTHER,
+        _ => return None,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FilterAnchor {
+    /// 任意位置（模式前已补 `*`）
+    None,
+    /// `|`：URL 开头
+    Start,
+    /// `||`：主机名或其任意上级域名的开头
+    Host,
+}
+
+#[derive(Debug)]
+struct FilterRule {
+    /// 小写，不含锚点；`*` 通配，`^` 匹配分隔符或结尾
+    pattern: Vec<u8>,
+    anchor: FilterAnchor,
+    end_anchor: bool,
+    types: u16,
+    third_party: Option<bool>,
+    include_domains: Vec<String>,
+    exclude_domains: Vec<String>,
+}
+
+#[derive(Debug)]
+struct ParsedRule {
+    exception: bool,
+    rule: FilterRule,
+    token: Option<String>,
+}
+
+/// `*` 与 `^` 之外都按字面量比较；非锚定结尾时只要匹配 text 的前缀。
+fn glob_match(pattern: &[u8], text: &[u8], end_anchor: bool) -> bool {
+    fn is_separator(c: u8) -> bool {
+        !(c.is_ascii_alphanumeric() || matches!(c, b'_' | b'-' | b'.' | b'%'))
+    }
+    fn char_matches(p: u8, c: u8) -> bool {
+        if p == b'^' {
+            is_separator(c)
+        } else {
+            p == c
+        }
+    }
+
+    let (mut p, mut t) = (0usize, 0usize);
+    let mut star: Option<(usize, usize)> = None;
+    loop {
+        if p == pattern.len() {
+            if !end_anchor || t == text.len() {
+                return true;
+            }
+        } else if pattern[p] == b'*' {
+            star = Some((p, t));
+            p += 1;
+            continue;
+        } else if t < text.len() && char_matches(pattern[p], text[t]) {
+            p += 1;
+            t += 1;
+            continue;
+        } else if t == text.len() && pattern[p..].iter().all(|&c| c == b'*' || c == b'^') {
+            return true;
+        }
+        match star {
+            Some((sp, st)) if st < text.len() => {
+                star = Some((sp, st + 1));
+                p = sp + 1;
+                t = st + 1;
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// 选一个两侧都有确定边界的词作为索引（越长越少见）；选不出时放进兜底列表。
+fn pick_rule_token(pattern: &[u8], left_bounded: bool, right_bounded: bool) -> Option<String> {
+    let mut best: Option<&[u8]> = None;
+    let mut i = 0usize;
+    while i < pattern.len() {
+        if !pattern[i].is_ascii_alphanumeric() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < pattern.len() && pattern[i].is_ascii_alphanumeric() {
+            i += 1;
+        }
+        let left_ok = if start == 0 {
+            left_bounded
+        } else {
+            pattern[start - 1] != b'*'
+        };
+        let right_ok = if i == pattern.len() {
+            right_bounded
+        } else {
+            pattern[i] != b'*'
+        };
+        let run = &pattern[start..i];
+        if !left_ok || !right_ok || run.len() < 2 || run == b"http" || run == b"https" {
+            continue;
+        }
+        if best.is_none_or(|b| run.len() > b.len()) {
+            best = Some(run);
+        }
+    }
+    best.map(|b| String::from_utf8_lossy(b).to_string())
+}
+
+fn parse_filter_line(line: &str) -> Option<ParsedRule> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('!') || line.starts_with('[') {
+        return None;
+    }
+    // 元素隐藏 / 脚本注入类规则
+    if line.contains("##") || line.contains("#@#") || line.contains("#?#") || line.contains("#$#") {
+        return None;
+    }
+    let (exception, body) = match line.strip_prefix("@@") {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+
+    let (raw_pattern, raw_options) = match body.rfind('$') {
+        Some(i) if !body[i + 1..].contains('/') => (&body[..i], Some(&body[i + 1..])),
+        _ => (body, None),
+    };
+    // 正则规则不支持
+    if raw_pattern.len() > 1 && raw_pattern.starts_with('/') && raw_pattern.ends_with('/') {
+        return None;
+    }
+
+    let mut include_types = 0u16;
+    let mut exclude_types = 0u16;
+    let mut third_party = None;
+    let mut include_domains = Vec::new();
+    let mut exclude_domains = Vec::new();
+    for opt in raw_options.unwrap_or("").split(',') {
+        let opt = opt.trim().to_ascii_lowercase();
+        if opt.is_empty() || opt == "match-case" || opt == "important" {
+            continue;
+        }
+        match opt.as_str() {
+            "third-party" | "3p" => third_party = Some(true),
+            "~third-party" | "first-party" | "1p" | "~3p" => third_party = Some(false),
+            _ => {
+                if let Some(list) = opt.strip_prefix("domain=") {
+                    for d in list.split('|').map(str::trim).filter(|d| !d.is_empty()) {
+                        match d.strip_prefix('~') {
+                            Some(d) => exclude_domains.push(d.to_string()),
+                            None => include_domains.push(d.to_string()),
+                        }
+                    }
+                } else if let Some(bit) = opt.strip_prefix('~').and_then(type_bit_from_option) {
+                    exclude_types |= bit;
+                } else if let Some(bit) = type_bit_from_option(&opt) {
+                    include_types |= bit;
+                } else {
+                    // popup、redirect、csp 等无法表达的选项：整条跳过
+                    return None;
+                }
+            }
+        }
+    }
+
+    let mut pattern = raw_pattern.to_ascii_lowercase();
+    let anchor = if let Some(rest) = pattern.strip_prefix("||") {
+        pattern = rest.to_string();
+        FilterAnchor::Host
+    } else if let Some(rest) = pattern.strip_prefix('|') {
+        pattern = rest.to_string();
+        FilterAnchor::Start
+    } else {
+        FilterAnchor::None
+    };
+    let end_anchor = pattern.ends_with('|');
+    if end_anchor {
+        pattern.pop();
+    }
+    if pattern.trim_matches('*').is_empty() && include_domains.is_empty() {
+        // 空模式会命中所有请求
+        return None;
+    }
+
+    let pure_host = anchor == FilterAnchor::Host
+        && !end_anchor
+        && pattern
+            .trim_end_matches('^')
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'.' || c == b'-');
+    let types = if include_types != 0 {
+        include_types
+    } else if pure_host || exception {
+        TYPE_ALL
+    } else {
+        // 不写类型的路径规则不拦顶层页面，只拦页面里的资源
+        TYPE_ALL & !TYPE_DOCUMENT
+    } & !exclude_types;
+    if types == 0 {
+        return None;
+    }
+
+    let token = pick_rule_token(pattern.as_bytes(), anchor != FilterAnchor::None, end_anchor);
+    let pattern = if anchor == FilterAnchor::None && !pattern.starts_with('*') {
+        format!("*{pattern}")
+    } else {
+        pattern
+    };
+    Some(ParsedRule {
+        exception,
+        rule: FilterRule {
+            pattern: pattern.into_bytes(),
+            anchor,
+            end_anchor,
+            types,
+            third_party,
+            include_domains,
+            exclude_domains,
+        },
+        token,
+    })
+}
+
+fn host_matches_domain(host: &str, domain: &str) -> bool {
+    host == domain
+        || (host.len() > domain.len()
+            && host.ends_with(domain)
+            && host.as_bytes()[host.len() - domain.len() - 1] == b'.')
+}
+
+/// 粗略的“站点”：最后两段；`co.uk` 这类短二级域取三段。不引入公共后缀表。
+fn site_of(host: &str) -> &str {
+    let labels: Vec<&str> = host.rsplit('.').collect();
+    let take = if labels.len() >= 3 && labels[0].len() == 2 && labels[1].len() <= 3 {
+        3
+    } else {
+        2
+    };
+    if labels.len() <= take {
+        return host;
+    }
+    let keep: usize = labels[..take].iter().map(|l| l.len()).sum::<usize>() + take - 1;
+    &host[host.len() - keep..]
+}
+
+struct FilterRequest<'a> {
+    url: &'a str,
+    host: &'a str,
+    host_start: usize,
+    page_host: &'a str,
+    resource: FilterResourceType,
+    tokens: HashSet<&'a str>,
+}
+
+impl<'a> FilterRequest<'a> {
+    /// url 需已是小写的绝对 http(s) 地址。
+    fn new(url: &'a str, page_host: &'a str, resource: FilterResourceType) -> Option<Self> {
+        let scheme_end = url.find("://")? + 3;
+        let rest = &url[scheme_end..];
+        let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+        let authority = &rest[..authority_end];
+        let host_offset = authority.rfind('@').map(|i| i + 1).unwrap_or(0);
+        let host_with_port = &authority[host_offset..];
+        let host = match host_with_port.rfind(':') {
+            Some(i) if !host_with_port.ends_with(']') => &host_with_port[..i],
+            _ => host_with_port,
+        };
+        if host.is_empty() {
+            return None;
+        }
+        let tokens = url
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|t| t.len() >= 2)
+            .collect();
+        Some(Self {
+            url,
+            host,
+            host_start: scheme_end + host_offset,
+            page_host,
+            resource,
+            tokens,
+        })
+    }
+
+    fn is_third_party(&self) -> bool {
+        !self.page_host.is_empty() && site_of(self.host) != site_of(self.page_host)
+    }
+}
+
+impl FilterRule {
+    fn matches(&self, req: &FilterRequest<'_>) -> bool {
+        if self.types & req.resource.bit() == 0 {
+            return false;
+        }
+        if let Some(tp) = self.third_party {
+            if req.is_third_party() != tp {
+                return false;
+            }
+        }
+        if !self.include_domains.is_empty()
+            && !self
+                .include_domains
+                .iter()
+                .any(|d| host_matches_domain(req.page_host, d))
+        {
+            return false;
+        }
+        if self
+            .exclude_domains
+            .iter()
+            .any(|d| host_matches_domain(req.page_host, d))
+        {
+            return false;
+        }
+
+        let url = req.url.as_bytes();
+        match self.anchor {
+            FilterAnchor::None | FilterAnchor::Start => {
+                glob_match(&self.pattern, url, self.end_anchor)
+            }
+            FilterAnchor::Host => {
+                let host = req.host.as_bytes();
+                let mut at = 0usize;
+                loop {
+                    if glob_match(&self.pattern, &url[req.host_start + at..], self.end_anchor) {
+                        return true;
+                    }
+                    match host[at..].iter().position(|&c| c == b'.') {
+                        Some(dot) => at += dot + 1,
+                        None => return false,
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct FilterSet {
+    by_token: HashMap<String, Vec<FilterRule>>,
+    fallback: Vec<FilterRule>,
+}
+
+impl FilterSet {
+    fn insert(&mut self, token: Option<String>, rule: FilterRule) {
+        match token {
+            Some(token) => self.by_token.entry(token).or_default().push(rule),
+            None => self.fallback.push(rule),
+        }
+    }
+
+    fn matches(&self, req: &FilterRequest<'_>) -> bool {
+        req.tokens
+            .iter()
+            .filter_map(|t| self.by_token.get(*t))
+            .flatten()
+            .chain(self.fallback.iter())
+            .any(|rule| rule.matches(req))
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct FilterEngine {
+    block: FilterSet,
+    allow: FilterSet,
+    rule_count: usize,
+    list_count: usize,
+}
+
+impl FilterEngine {
+    fn add_list(&mut self, text: &str) {
+        for line in text.lines() {
+            let Some(parsed) = parse_filter_line(line) else {
+                continue;
+            };
+            if parsed.exception {
+                self.allow.insert(parsed.token, parsed.rule);
+            } else {
+                self.block.insert(parsed.token, parsed.rule);
+            }
+            self.rule_count += 1;
+        }
+        self.list_count += 1;
+    }
+
+    /// url / page_url 为绝对地址；page_url 为空表示顶层导航本身。
+    fn should_block(&self, url: &str, page_url: &str, resource: FilterResourceType) -> bool {
+        let url = url.to_ascii_lowercase();
+        let page_url = page_url.to_ascii_lowercase();
+        let page = FilterRequest::new(&page_url, "", FilterResourceType::Document);
+        let page_host = page.as_ref().map(|p| p.host).unwrap_or("");
+        let Some(req) = FilterRequest::new(&url, page_host, resource) else {
+            return false;
+        };
+        let req = if page_host.is_empty() {
+            // 顶层导航：以目标自身为“页面”
+            FilterRequest {
+                page_host: req.host,
+                ..req
+            }
+        } else {
+            req
+        };
+        if !self.block.matches(&req) {
+            return false;
+        }
+        if self.allow.matches(&req) {
+            return false;
+        }
+        // `@@...$document` 例外：整页放行
+        if let Some(page) = page {
+            let page = FilterRequest {
+                page_host: page.host,
+                ..page
+            };
+            if self.allow.matches(&page) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Clone, Default)]
+struct FilterPageStats {
+    host: String,
+    blocked: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct BrowserFilterState {
+    engine: RwLock<Option<Arc<FilterEngine>>>,
+    enabled: AtomicBool,
+    disabled_sites: RwLock<Vec<String>>,
+    /// 按标签内容窗口 label 分开计数，顶部栏只显示当前标签的
+    pages: Mutex<HashMap<String, FilterPageStats>>,
+    /// 最近一次放行的顶层导航：WebView2 会把它再报一遍资源请求
+    last_navigation: Mutex<String>,
+    emit_seq: AtomicU64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrowserFilterStatus {
+    enabled: bool,
+    list_count: usize,
+    rule_count: usize,
+    page_host: String,
+    site_disabled: bool,
+    blocked: u64,
+    /// 当前平台能否拦截页面内的子资源
+    subresources_supported: bool,
+}
+
+fn filter_lists_dir(app: &tauri::AppHandle) -> PathBuf {
+    crate::app_data_dir(app)
+        .join(crate::APP_STORAGE_ID)
+        .join(FILTER_LISTS_DIR)
+}
+
+fn load_filter_engine(app: &tauri::AppHandle) -> FilterEngine {
+    let mut engine = FilterEngine::default();
+    let Ok(entries) = std::fs::read_dir(filter_lists_dir(app)) else {
+        return engine;
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|s| s.eq_ignore_ascii_case("txt"))
+        })
+        .collect();
+    paths.sort();
+    for path in paths {
+        match std::fs::read(&path) {
+            Ok(bytes) => engine.add_list(&String::from_utf8_lossy(&bytes)),
+            Err(e) => eprintln!("[browser-filter] failed to read {}: {e}", path.display()),
+        }
+    }
+    engine
+}
+
+fn reload_filters(app: &tauri::AppHandle) {
+    let map = crate::read_app_config_map(app);
+    let enabled = map
+        .get(BROWSER_FILTER_ENABLED_KEY)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let sites: Vec<String> = map
+        .get(BROWSER_FILTER_DISABLED_SITES_KEY)
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.trim().to_ascii_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    let engine = load_filter_engine(app);
+
+    let state = app.state::<BrowserFilterState>();
+    state.enabled.store(enabled, Ordering::Relaxed);
+    *state
+        .disabled_sites
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = sites;
+    *state.engine.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(engine));
+}
+
+/// 打开浏览栈前调用：规则只在首次使用时从磁盘读取。
+pub(crate) fn browser_filters_ensure_loaded(app: &tauri::AppHandle) {
+    let loaded = app
+        .state::<BrowserFilterState>()
+        .engine
+        .read()
+        .map(|g| g.is_some())
+        .unwrap_or(false);
+    if !loaded {
+        reload_filters(app);
+    }
+}
+
+fn site_disabled(state: &BrowserFilterState, host: &str) -> bool {
+    let sites = state
+        .disabled_sites
+        .read()
+        .unwrap_or_else(|e| e.into_inner());
+    sites.iter().any(|s| host_matches_domain(host, s))
+}
+
+fn host_of(url: &str) -> String {
+    tauri::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_default()
+}
+
+fn should_block(
+    app: &tauri::AppHandle,
+    url: &str,
+    page_url: &str,
+    resource: FilterResourceType,
+) -> bool {
+    if !crate::is_http_url(url) {
+        return false;
+    }
+    let state = app.state::<BrowserFilterState>();
+    if !state.enabled.load(Ordering::Relaxed) {
+        return false;
+    }
+    let target_host = host_of(url);
+    // Tauri 自己的 http://*.localhost 协议不参与过滤
+    if target_host == "localhost" || target_host.ends_with(".localhost") {
+        return false;
+    }
+    let page_host = if page_url.is_empty() {
+        target_host
+    } else {
+        host_of(page_url)
+    };
+    if site_disabled(&state, &page_host) {
+        return false;
+    }
+    let engine = state
+        .engine
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    engine.is_some_and(|e| e.should_block(url, page_url, resource))
+}
+
+fn record_blocked(app: &tauri::AppHandle, label: &str) {
+    let state = app.state::<BrowserFilterState>();
+    state
+        .pages
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(label.to_string())
+        .or_default()
+        .blocked += 1;
+    if crate::browser_tabs::is_active_content_label(app, label) {
+        schedule_emit_filter_status(app);
+    }
+}
+
+fn active_page_stats(app: &tauri::AppHandle) -> FilterPageStats {
+    let Some(label) = crate::browser_tabs::active_content_label(app) else {
+        return FilterPageStats::default();
+    };
+    let state = app.state::<BrowserFilterState>();
+    let pages = state.pages.lock().unwrap_or_else(|e| e.into_inner());
+    pages.get(&label).cloned().unwrap_or_default()
+}
+
+fn filter_status(app: &tauri::AppHandle) -> BrowserFilterStatus {
+    let state = app.state::<BrowserFilterState>();
+    let (list_count, rule_count) = state
+        .engine
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|e| (e.list_count, e.rule_count))
+        .unwrap_or((0, 0));
+    let FilterPageStats {
+        host: page_host,
+        blocked,
+    } = active_page_stats(app);
+    BrowserFilterStatus {
+        enabled: state.enabled.load(Ordering::Relaxed),
+        list_count,
+        rule_count,
+        site_disabled: !page_host.is_empty() && site_disabled(&state, &page_host),
+        page_host,
+        blocked,
+        subresources_supported: cfg!(windows),
+    }
+}
+
+/// 拦截可能一次来几十个：合并后再推给顶部栏。
+fn schedule_emit_filter_status(app: &tauri::AppHandle) {
+    let state = app.state::<BrowserFilterState>();
+    let next = state
+        .emit_seq
+        .fetch_add(1, Ordering::Relaxed)
+        .saturating_add(1);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let state = app.state::<BrowserFilterState>();
+        if state.emit_seq.load(Ordering::Relaxed) != next {
+            return;
+        }
+        let _ = app.emit_to(
+            EventTarget::webview_window(BROWSER_BAR_WINDOW_LABEL),
+            BROWSER_FILTER_UPDATED_EVENT,
+            filter_status(&app),
+        );
+    });
+}
+
+/// 内容 WebView 的 on_navigation 回调：返回 false 阻止这次顶层导航。
+pub(crate) fn browser_filters_on_navigation(
+    app: &tauri::AppHandle,
+    label: &str,
+    url: &tauri::Url,
+) -> bool {
+    if should_block(app, url.as_str(), "", FilterResourceType::Document) {
+        record_blocked(app, label);
+        crate::host_primitives::emit_toast(
+            app,
+            format!("已拦截：{}", url.host_str().unwrap_or("")),
+        );
+        return false;
+    }
+    let state = app.state::<BrowserFilterState>();
+    *state
+        .last_navigation
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = url.to_string();
+    true
+}
+
+/// 标签里的新页面开始加载：这个标签的拦截计数归零。
+pub(crate) fn browser_filters_on_page_started(app: &tauri::AppHandle, label: &str, url: &str) {
+    let state = app.state::<BrowserFilterState>();
+    {
+        let mut pages = state.pages.lock().unwrap_or_else(|e| e.into_inner());
+        // 顺带清掉已关闭标签的计数
+        pages.retain(|l, _| l == label || app.get_webview_window(l).is_some());
+        pages.insert(
+            label.to_string(),
+            FilterPageStats {
+                host: host_of(url),
+                blocked: 0,
+            },
+        );
+    }
+    if crate::browser_tabs::is_active_content_label(app, label) {
+        schedule_emit_filter_status(app);
+    }
+}
+
+/// 切换标签后顶部栏改显示新标签的拦截计数。
+pub(crate) fn browser_filters_on_active_tab_changed(app: &tauri::AppHandle) {
+    schedule_emit_filter_status(app);
+}
+
+#[cfg(windows)]
+fn resource_type_from_context(
+    context: webview2_com::Microsoft::Web::WebView2::Win32::COREWEBVIEW2_WEB_RESOURCE_CONTEXT,
+) -> FilterResourceType {
+    use webview2_com::Microsoft::Web::WebView2::Win32::*;
+    match context {
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_DOCUMENT => FilterResourceType::Subdocument,
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_STYLESHEET => FilterResourceType::Stylesheet,
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_IMAGE => FilterResourceType::Image,
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_MEDIA | COREWEBVIEW2_WEB_RESOURCE_CONTEXT_TEXT_TRACK => {
+            FilterResourceType::Media
+        }
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_FONT => FilterResourceType::Font,
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_SCRIPT => FilterResourceType::Script,
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_XML_HTTP_REQUEST
+        | COREWEBVIEW2_WEB_RESOURCE_CONTEXT_FETCH
+        | COREWEBVIEW2_WEB_RESOURCE_CONTEXT_EVENT_SOURCE => FilterResourceType::Xhr,
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_WEBSOCKET => FilterResourceType::Websocket,
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_PING => FilterResourceType::Ping,
+        _ => FilterResourceType::Other,
+    }
+}
+
+#[cfg(windows)]
+unsafe fn attach_web_resource_filter(
+    app: tauri::AppHandle,
+    label: String,
+    controller: webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2Controller,
+    env: webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2Environment,
+) -> windows::core::Result<()> {
+    use webview2_com::Microsoft::Web::WebView2::Win32::*;
+    use webview2_com::{take_pwstr, WebResourceRequestedEventHandler};
+    use windows::core::{HSTRING, PWSTR};
+
+    let webview = controller.CoreWebView2()?;
+    webview.AddWebResourceRequestedFilter(
+        &HSTRING::from("*"),
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL,
+    )?;
+    let mut token = 0i64;
+    webview.add_WebResourceRequested(
+        &WebResourceRequestedEventHandler::create(Box::new(move |sender, args| {
+            let (Some(sender), Some(args)) = (sender, args) else {
+                return Ok(());
+            };
+            let uri = {
+                let mut uri = PWSTR::null();
+                args.Request()?.Uri(&mut uri)?;
+                take_pwstr(uri)
+            };
+            let page = {
+                let mut source = PWSTR::null();
+                sender.Source(&mut source)?;
+                take_pwstr(source)
+            };
+            let mut context = COREWEBVIEW2_WEB_RESOURCE_CONTEXT::default();
+            args.ResourceContext(&mut context)?;
+            if context == COREWEBVIEW2_WEB_RESOURCE_CONTEXT_DOCUMENT {
+                // 顶层导航已经在 on_navigation 里判过
+                let state = app.state::<BrowserFilterState>();
+                let last = state
+                    .last_navigation
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner());
+                if *last == uri {
+                    return Ok(());
+                }
+            }
+            if should_block(&app, &uri, &page, resource_type_from_context(context)) {
+                let response = env.CreateWebResourceResponse(
+                    None,
+                    403,
+                    &HSTRING::from("Blocked"),
+                    &HSTRING::new(),
+                )?;
+                args.SetResponse(&response)?;
+                record_blocked(&app, &label);
+            }
+            Ok(())
+        })),
+        &mut token,
+    )?;
+    Ok(())
+}
+
+/// 内容 WebView 创建后挂上子资源拦截（仅 Windows）。
+pub(crate) fn browser_filters_attach(webview: &tauri::WebviewWindow) {
+    #[cfg(windows)]
+    {
+        let app = webview.app_handle().clone();
+        let label = webview.label().to_string();
+        let _ = webview.with_webview(move |pw| unsafe {
+            if let Err(e) =
+                attach_web_resource_filter(app, label, pw.controller(), pw.environment())
+            {
+                eprintln!("[browser-filter] failed to attach: {e}");
+            }
+        });
+    }
+    #[cfg(not(windows))]
+    let _ = webview;
+}
+
+#[tauri::command]
+pub(crate) fn browser_filter_status(app: tauri::AppHandle) -> BrowserFilterStatus {
+    browser_filters_ensure_loaded(&app);
+    filter_status(&app)
+}
+
+/// 重新读取规则文件（修改 filter-lists 目录后调用）。
+#[tauri::command]
+pub(crate) fn browser_filter_reload(app: tauri::AppHandle) -> BrowserFilterStatus {
+    reload_filters(&app);
+    filter_status(&app)
+}
+
+#[tauri::command]
+pub(crate) fn browser_filter_set_enabled(
+    app: tauri::AppHandle,
+    enabled: bool,
+) -> Result<BrowserFilterStatus, String> {
+    crate::update_app_config_map(&app, |map| {
+        map.insert(BROWSER_FILTER_ENABLED_KEY.to_string(), Value::Bool(enabled));
+        Ok(())
+    })?;
+    browser_filters_ensure_loaded(&app);
+    app.state::<BrowserFilterState>()
+        .enabled
+        .store(enabled, Ordering::Relaxed);
+    Ok(filter_status(&app))
+}
+
+/// 对当前页面所在站点停用/恢复拦截，并刷新页面。
+#[tauri::command]
+pub(crate) fn browser_filter_toggle_site(
+    app: tauri::AppHandle,
+) -> Result<BrowserFilterStatus, String> {
+    browser_filters_ensure_loaded(&app);
+    let host = active_page_stats(&app).host;
+    if host.is_empty() {
+        return Err("当前没有打开网页".to_string());
+    }
+    let sites = {
+        let state = app.state::<BrowserFilterState>();
+        let mut sites = state
+            .disabled_sites
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        let before = sites.len();
+        sites.retain(|s| !host_matches_domain(&host, s));
+        if sites.len() == before {
+            sites.push(host);
+        }
+        sites.clone()
+    };
+    crate::update_app_config_map(&app, |map| {
+        map.insert(
+            BROWSER_FILTER_DISABLED_SITES_KEY.to_string(),
+            Value::Array(sites.into_iter().map(Value::String).collect()),
+        );
+        Ok(())
+    })?;
+    if let Some(w) = crate::browser_tabs::browser_content_window(&app) {
+        let _ = w.eval("location.reload()");
+    }
+    Ok(filter_status(&app))
+}
+
+#[tauri::command]
+pub(crate) fn browser_filter_open_dir(app: tauri::AppHandle) -> Result<(), String> {
+    open_dir_in_file_manager(&filter_lists_dir(&app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine(rules: &str) -> FilterEngine {
+        let mut e = FilterEngine::default();
+        e.add_list(rules);
+        e
+    }
+
+    #[test]
+    fn glob_match_handles_wildcards_and_separators() {
+        assert!(glob_match(b"ads.com^", b"ads.com/x", false));
+        assert!(glob_match(b"ads.com^", b"ads.com", false));
+        assert!(!glob_match(b"ads.com^", b"ads.community/", false));
+        assert!(glob_match(
+            b"*/banner/*.gif",
+            b"https://e.com/banner/a.gif",
+            true
+        ));
+        assert!(!glob_match(
+            b"*/banner/*.gif",
+            b"https://e.com/banner/a.gif?x",
+            true
+        ));
+        assert!(glob_match(
+            b"*/banner/*.gif",
+            b"https://e.com/banner/a.gif?x",
+            false
+        ));
+    }
+
+    #[test]
+    fn host_rules_match_subdomains_and_block_navigation() {
+        let e = engine("||ads.example.com^\n");
+        assert!(e.should_block(
+            "https://x.ads.example.com/a.js",
+            "https://news.com/",
+            FilterResourceType::Script
+        ));
+        assert!(e.should_block("https://ads.example.com/", "", FilterResourceType::Document));
+        assert!(!e.should_block(
+            "https://badads.example.com/a.js",
+            "https://news.com/",
+            FilterResourceType::Script
+        ));
+    }
+
+    #[test]
+    fn path_rules_skip_top_level_documents() {
+        let e = engine("/banner/*\n");
+        assert!(e.should_block(
+            "https://cdn.com/banner/1.png",
+            "https://news.com/",
+            FilterResourceType::Image
+        ));
+        assert!(!e.should_block("https://cdn.com/banner/", "", FilterResourceType::Document));
+    }
+
+    #[test]
+    fn options_and_exceptions_are_applied() {
+        let e = engine(
+            "||tracker.net^$third-party\n\
+             ||cdn.com/ads/$script,domain=news.com|~sub.news.com\n\
+             ||widgets.io^\n\
+             @@||widgets.io/embed.js\n\
+             @@||trusted.org^$document\n\
+             ##.ad-banner\n\
+             ||popup.com^$popup\n",
+        );
+        let p = "https://news.com/";
+        assert!(e.should_block("https://tracker.net/t.js", p, FilterResourceType::Script));
+        assert!(!e.should_block(
+            "https://tracker.net/t.js",
+            "https://www.tracker.net/",
+            FilterResourceType::Script
+        ));
+        assert!(e.should_block("https://cdn.com/ads/a.js", p, FilterResourceType::Script));
+        assert!(!e.should_block("https://cdn.com/ads/a.png", p, FilterResourceType::Image));
+        assert!(!e.should_block(
+            "https://cdn.com/ads/a.js",
+            "https://sub.news.com/",
+            FilterResourceType::Script
+        ));
+        assert!(e.should_block("https://widgets.io/w.js", p, FilterResourceType::Script));
+        assert!(!e.should_block("https://widgets.io/embed.js", p, FilterResourceType::Script));
+        assert!(!e.should_block(
+            "https://widgets.io/w.js",
+            "https://trusted.org/page",
+            FilterResourceType::Script
+        ));
+        assert!(!e.should_block("https://popup.com/", p, FilterResourceType::Script));
+        assert_eq!(e.rule_count, 5);
+    }
+
+    #[test]
+    fn site_of_keeps_short_second_level_domains() {
+        assert_eq!(site_of("a.b.example.com"), "example.com");
+        assert_eq!(site_of("www.bbc.co.uk"), "bbc.co.uk");
+        assert_eq!(site_of("localhost"), "localhost");
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{Emitter, EventTarget, Manager};
+
+// ── 浏览栈历史与“保存当前网页” ──────────────────────────────────────────────
+//
+// 历史：浏览窗口每完成一次页面加载记一条（URL、标题、打开它的插件、访问时间），
+// 存在 <data>/__app/browser-history.json，超过上限丢弃最旧的记录。
+// 保存当前网页：顶部栏把当前标签的 URL / 标题交给配置好的目标插件（如书签）。
+// 宿主先把内容暂存为“待领取”，再激活目标插件；插件启动后用 browser_capture_take 领取，
+// 已在运行的插件也可以监听 `fast-window:browser-page-captured` 事件。
+
+const BROWSER_HISTORY_FILE: &str = "browser-history.json";
+const BROWSER_HISTORY_MAX: usize = 5000;
+const BROWSER_HISTORY_SEARCH_DEFAULT_LIMIT: usize = 100;
+const BROWSER_HISTORY_SEARCH_MAX_LIMIT: usize = 1000;
+const BROWSER_CAPTURE_TARGET_KEY: &str = "browserCaptureTargetPluginId";
+const BROWSER_PAGE_CAPTURED_EVENT: &str = "fast-window:browser-page-captured";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrowserHistoryEntry {
+    url: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    plugin_id: Option<String>,
+    visited_at_ms: u64,
+}
+
+#[derive(Default)]
+struct BrowserHistory {
+    /// 按访问时间从旧到新
+    entries: VecDeque<BrowserHistoryEntry>,
+}
+
+impl BrowserHistory {
+    /// 连续访问同一 URL（刷新、重定向回写）只更新最后一条。
+    fn record(&mut self, url: &str, title: &str, plugin_id: Option<&str>, now: u64) {
+        if let Some(last) = self.entries.back_mut() {
+            if last.url == url {
+                if !title.is_empty() {
+                    last.title = title.to_string();
+                }
+                last.visited_at_ms = now;
+                return;
+            }
+        }
+        self.entries.push_back(BrowserHistoryEntry {
+            url: url.to_string(),
+            title: title.to_string(),
+            plugin_id: plugin_id.map(str::to_string),
+            visited_at_ms: now,
+        });
+        while self.entries.len() > BROWSER_HISTORY_MAX {
+            self.entries.pop_front();
+        }
+    }
+
+    /// 标题通常在加载完成后才变化：回写到最近一条同 URL 的记录。
+    fn update_title(&mut self, url: &str, title: &str) -> bool {
+        match self.entries.iter_mut().rev().take(8).find(|e| e.url == url) {
+            Some(entry) if entry.title != title => {
+                entry.title = title.to_string();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 新到旧；query 按空白拆词，所有词都要命中 URL 或标题（不区分大小写）。
+    fn search(&self, query: &str, limit: usize) -> Vec<BrowserHistoryEntry> {
+        let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+        self.entries
+            .iter()
+            .rev()
+            .filter(|e| {
+                if terms.is_empty() {
+                    return true;
+                }
+                let url = e.url.to_lowercase();
+                let title = e.title.to_lowercase();
+                terms
+                    .iter()
+                    .all(|t| url.contains(t.as_str()) || title.contains(t.as_str()))
+            })
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrowserPageCapture {
+    target_plugin_id: String,
+    url: String,
+    title: String,
+    source_plugin_id: Option<String>,
+    captured_at_ms: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct BrowserHistoryState {
+    /// 首次使用时从磁盘载入
+    history: Mutex<Option<BrowserHistory>>,
+    save_seq: AtomicU64,
+    /// 目标插件 -> 尚未领取的网页
+    captures: Mutex<HashMap<String, BrowserPageCapture>>,
+}
+
+fn history_path(app: &tauri::AppHandle) -> PathBuf {
+    crate::app_data_dir(app)
+        .join(crate::APP_STORAGE_ID)
+        .join(BROWSER_HISTORY_FILE)
+}
+
+fn load_history(app: &tauri::AppHandle) -> BrowserHistory {
+    let path = history_path(app);
+    if !path.is_file() {
+        return BrowserHistory::default();
+    }
+    let entries = crate::json_file::read_value(&path)
+        .ok()
+        .and_then(|v| serde_json::from_value::<Vec<BrowserHistoryEntry>>(v).ok())
+        .unwrap_or_default();
+    BrowserHistory {
+        entries: entries.into_iter().collect(),
+    }
+}
+
+fn with_history<T>(app: &tauri::AppHandle, f: impl FnOnce(&mut BrowserHistory) -> T) -> T {
+    let state = app.state::<BrowserHistoryState>();
+    let mut g = state.history.lock().unwrap_or_else(|e| e.into_inner());
+    let history = g.get_or_insert_with(|| load_history(app));
+    f(history)
+}
+
+fn persist_history(app: &tauri::AppHandle) {
+    let value = with_history(app, |h| {
+        serde_json::to_value(h.entries.iter().collect::<Vec<_>>()).unwrap_or(Value::Null)
+    });
+    if let Err(e) = crate::json_file::write_pretty(&history_path(app), &value) {
+        eprintln!("[browser-history] failed to persist: {e}");
+    }
+}
+
+/// 页面加载很密集：合并短时间内的多次变更再落盘。
+fn schedule_persist_history(app: &tauri::AppHandle) {
+    let state = app.state::<BrowserHistoryState>();
+    let next = state
+        .save_seq
+        .fetch_add(1, Ordering::Relaxed)
+        .saturating_add(1);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        let state = app.state::<BrowserHistoryState>();
+        if state.save_seq.load(Ordering::Relaxed) != next {
+            return;
+        }
+        persist_history(&app);
+    });
+}
+
+pub(crate) fn browser_history_record(
+    app: &tauri::AppHandle,
+    url: &str,
+    title: &str,
+    plugin_id: Option<&str>,
+) {
+    if !crate::is_http_url(url) {
+        return;
+    }
+    let now = crate::now_ms();
+    with_history(app, |h| h.record(url, title, plugin_id, now));
+    schedule_persist_history(app);
+}
+
+pub(crate) fn browser_history_update_title(app: &tauri::AppHandle, url: &str, title: &str) {
+    if title.is_empty() {
+        return;
+    }
+    if with_history(app, |h| h.update_title(url, title)) {
+        schedule_persist_history(app);
+    }
+}
+
+#[tauri::command]
+pub(crate) fn browser_history_search(
+    app: tauri::AppHandle,
+    query: Option<String>,
+    limit: Option<usize>,
+) -> Vec<BrowserHistoryEntry> {
+    let limit = limit
+        .unwrap_or(BROWSER_HISTORY_SEARCH_DEFAULT_LIMIT)
+        .clamp(1, BROWSER_HISTORY_SEARCH_MAX_LIMIT);
+    with_history(&app, |h| h.search(query.as_deref().unwrap_or(""), limit))
+}
+
+#[tauri::command]
+pub(crate) fn browser_history_clear(app: tauri::AppHandle) -> Result<(), String> {
+    with_history(&app, |h| h.entries.clear());
+    // 让尚未执行的延迟落盘失效
+    app.state::<BrowserHistoryState>()
+        .save_seq
+        .fetch_add(1, Ordering::Relaxed);
+    let path = history_path(&app);
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| format!("清空浏览历史失败: {e}"))?;
+    }
+    Ok(())
+}
+
+fn load_capture_target(app: &tauri::AppHandle) -> Option<String> {
+    crate::read_app_config_map(app)
+        .get(BROWSER_CAPTURE_TARGET_KEY)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| crate::is_safe_id(s))
+        .map(str::to_string)
+}
+
+#[tauri::command]
+pub(crate) fn browser_capture_get_target(app: tauri::AppHandle) -> Option<String> {
+    load_capture_target(&app)
+}
+
+#[tauri::command]
+pub(crate) fn browser_capture_set_target(
+    app: tauri::AppHandle,
+    plugin_id: Option<String>,
+) -> Result<Option<String>, String> {
+    let next = plugin_id
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    if let Some(pid) = next.as_deref() {
+        if !crate::is_safe_id(pid) {
+            return Err("pluginId 不合法".to_string());
+        }
+    }
+    crate::update_app_config_map(&app, |map| {
+        match next.as_deref() {
+            Some(pid) => {
+                map.insert(
+                    BROWSER_CAPTURE_TARGET_KEY.to_string(),
+                    Value::String(pid.to_string()),
+                );
+            }
+            None => {
+                map.remove(BROWSER_CAPTURE_TARGET_KEY);
+            }
+        }
+        Ok(())
+    })?;
+    Ok(next)
+}
+
+/// 顶部栏“保存当前网页”：把当前标签交给目标插件并切到主窗口显示它。
+#[tauri::command]
+pub(crate) fn browser_capture_current_page(app: tauri::AppHandle) -> Result<(), String> {
+    let target = load_capture_target(&app)
+        .ok_or_else(|| "尚未设置接收网页的插件：请在设置 → WebView 中选择".to_string())?;
+    let (url, title, source_plugin_id) = crate::browser_tabs::browser_tabs_active_page(&app)
+        .ok_or_else(|| "当前没有打开的网页".to_string())?;
+
+    let capture = BrowserPageCapture {
+        target_plugin_id: target.clone(),
+        url,
+        title,
+        source_plugin_id,
+        captured_at_ms: crate::now_ms(),
+    };
+    {
+        let state = app.state::<BrowserHistoryState>();
+        let mut g = state.captures.lock().unwrap_or_else(|e| e.into_inner());
+        g.insert(target.clone(), capture.clone());
+    }
+    let _ = app.emit_to(
+        EventTarget::webview_window("main"),
+        BROWSER_PAGE_CAPTURED_EVENT,
+        capture,
+    );
+
+    crate::browser_stack::browser_stack_hide(&app);
+    crate::host_primitives::host_activate_plugin(app.clone(), target)?;
+    crate::show_main_window(&app);
+    Ok(())
+}
+
+/// 目标插件领取暂存的网页（领取后清除）；pluginId 由网关按调用方注入。
+#[tauri::command]
+pub(crate) fn browser_capture_take(
+    app: tauri::AppHandle,
+    plugin_id: Option<String>,
+) -> Result<Option<BrowserPageCapture>, String> {
+    let pid = plugin_id
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "pluginId 不能为空".to_string())?;
+    if !crate::is_safe_id(&pid) {
+        return Err("pluginId 不合法".to_string());
+    }
+    let state = app.state::<BrowserHistoryState>();
+    let mut g = state.captures.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(g.remove(&pid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapses_repeated_visits_and_caps_size() {
+        let mut h = BrowserHistory::default();
+        h.record("https://a.com/", "", Some("demo"), 1);
+        h.record("https://a.com/", "A", None, 2);
+        assert_eq!(h.entries.len(), 1);
+        assert_eq!(h.entries[0].title, "A");
+        assert_eq!(h.entries[0].plugin_id.as_deref(), Some("demo"));
+        assert_eq!(h.entries[0].visited_at_ms, 2);
+
+        for i in 0..BROWSER_HISTORY_MAX + 5 {
+            h.record(&format!("https://a.com/{i}"), "", None, i as u64);
+        }
+        assert_eq!(h.entries.len(), BROWSER_HISTORY_MAX);
+        assert_eq!(h.entries[0].url, "https://a.com/5");
+    }
+
+    #[test]
+    fn searches_newest_first_with_all_terms() {
+        let mut h = BrowserHistory::default();
+        h.record("https://docs.rs/tauri", "Tauri Docs", None, 1);
+        h.record("https://example.com/", "Example", None, 2);
+        h.record("https://docs.rs/serde", "Serde Docs", None, 3);
+        assert!(h.update_title("https://example.com/", "Example Domain"));
+
+        let all = h.search("", 10);
+        assert_eq!(all[0].url, "https://docs.rs/serde");
+        let hits = h.search("DOCS tauri", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].url, "https://docs.rs/tauri");
+        assert_eq!(h.search("domain", 10).len(), 1);
+        assert_eq!(h.search("docs", 1).len(), 1);
+    }
+}
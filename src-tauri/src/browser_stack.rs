@@ -1,20 +1,34 @@
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
 use tauri::Manager;
 
+use crate::browser_tabs::{browser_content_window, browser_content_windows};
 use crate::wake_logic;
 use crate::windowing::{
-    persist_browser_window_bounds, save_browser_stack_bounds_if_valid, BrowserWindowState,
+    bounds_on_any_monitor, load_browser_mini_bounds_from_config, persist_browser_mini_bounds,
+    persist_browser_window_bounds, save_browser_stack_bounds_if_valid, snap_rect_to_work_area,
+    BrowserWindowState,
 };
 use crate::{
-    apply_bottom_rounded_corners, browser_ui_set_mode, hide_main_window, host_primitives, now_ms,
-    show_main_window, BROWSER_BAR_HEIGHT, BROWSER_BAR_WINDOW_LABEL, BROWSER_STACK_TOTAL_HEIGHT,
-    BROWSER_WINDOW_LABEL,
+    apply_bottom_rounded_corners, apply_window_opacity, browser_ui_set_mode, hide_main_window,
+    host_primitives, now_ms, show_main_window, BROWSER_BAR_HEIGHT, BROWSER_BAR_WINDOW_LABEL,
+    BROWSER_MINI_CLICK_THROUGH_KEY, BROWSER_MINI_OPACITY_KEY, BROWSER_STACK_TOTAL_HEIGHT,
 };
 
+const BROWSER_MINI_DEFAULT_WIDTH: f64 = 480.0;
+const BROWSER_MINI_DEFAULT_HEIGHT: f64 = 270.0;
+const BROWSER_MINI_MIN_OPACITY: f64 = 0.2;
+const BROWSER_MINI_SNAP_THRESHOLD: f64 = 48.0;
+const BROWSER_MINI_SNAP_MARGIN: f64 = 16.0;
+
 pub(crate) fn browser_stack_set_always_on_top(app: &tauri::AppHandle, enable: bool) {
     if let Some(w) = app.get_webview_window(BROWSER_BAR_WINDOW_LABEL) {
         let _ = w.set_always_on_top(enable);
     }
-    if let Some(w) = app.get_webview_window(BROWSER_WINDOW_LABEL) {
+    if let Some(w) = browser_content_window(app) {
         let _ = w.set_always_on_top(enable);
     }
 }
@@ -36,12 +50,12 @@ pub(crate) fn browser_stack_bar_height_px(bar: &tauri::WebviewWindow) -> u32 {
 
 pub(crate) fn browser_stack_exists(app: &tauri::AppHandle) -> bool {
     app.get_webview_window(BROWSER_BAR_WINDOW_LABEL).is_some()
-        && app.get_webview_window(BROWSER_WINDOW_LABEL).is_some()
+        && !browser_content_windows(app).is_empty()
 }
 
 pub(crate) fn browser_stack_is_visible(app: &tauri::AppHandle) -> bool {
     let bar = app.get_webview_window(BROWSER_BAR_WINDOW_LABEL);
-    let content = app.get_webview_window(BROWSER_WINDOW_LABEL);
+    let content = browser_content_window(app);
     bar.as_ref()
         .and_then(|w| w.is_visible().ok())
         .unwrap_or(false)
@@ -53,7 +67,7 @@ pub(crate) fn browser_stack_is_visible(app: &tauri::AppHandle) -> bool {
 
 pub(crate) fn browser_stack_is_focused(app: &tauri::AppHandle) -> bool {
     let bar = app.get_webview_window(BROWSER_BAR_WINDOW_LABEL);
-    let content = app.get_webview_window(BROWSER_WINDOW_LABEL);
+    let content = browser_content_window(app);
     bar.as_ref()
         .and_then(|w| w.is_focused().ok())
         .unwrap_or(false)
@@ -87,7 +101,7 @@ pub(crate) fn browser_stack_restore_or_center(app: &tauri::AppHandle) {
         Some(w) => w,
         None => return,
     };
-    let content = match app.get_webview_window(BROWSER_WINDOW_LABEL) {
+    let content = match browser_content_window(app) {
         Some(w) => w,
         None => return,
     };
@@ -140,19 +154,22 @@ pub(crate) fn browser_stack_show(app: &tauri::AppHandle) {
     if !browser_stack_exists(app) {
         return;
     }
+    if browser_stack_is_mini(app) {
+        let _ = browser_stack_leave_mini(app, false);
+    }
     // 强制不变量：浏览栈可见时，主窗口必须隐藏（避免“两个都响应/抢焦点”的错觉）。
     hide_main_window(app);
     // 显示/聚焦时会有短暂的焦点抖动，避免误触发“失焦隐藏”。
     browser_stack_set_suppress_hide(app, 800);
     browser_stack_restore_or_center(app);
-    if let Some(w) = app.get_webview_window(BROWSER_WINDOW_LABEL) {
+    if let Some(w) = browser_content_window(app) {
         apply_bottom_rounded_corners(&w, 16.0);
     }
 
     if let Some(w) = app.get_webview_window(BROWSER_BAR_WINDOW_LABEL) {
         let _ = w.show();
     }
-    if let Some(w) = app.get_webview_window(BROWSER_WINDOW_LABEL) {
+    if let Some(w) = browser_content_window(app) {
         let _ = w.show();
         let _ = w.set_focus();
     }
@@ -160,11 +177,15 @@ pub(crate) fn browser_stack_show(app: &tauri::AppHandle) {
 }
 
 pub(crate) fn browser_stack_hide(app: &tauri::AppHandle) {
+    // 迷你模式下先还原浏览栈布局，下次唤起时是正常的浏览栈
+    if browser_stack_is_mini(app) {
+        let _ = browser_stack_leave_mini(app, false);
+    }
     let bar = match app.get_webview_window(BROWSER_BAR_WINDOW_LABEL) {
         Some(w) => w,
         None => return,
     };
-    let content = match app.get_webview_window(BROWSER_WINDOW_LABEL) {
+    let content = match browser_content_window(app) {
         Some(w) => w,
         None => return,
     };
@@ -228,8 +249,9 @@ pub(crate) fn browser_stack_close(app: &tauri::AppHandle) {
     // “关闭浏览”应当真正销毁 WebView：否则只是 hide，会导致网页音频继续播放。
     browser_stack_set_closing(app, true);
     browser_stack_end_session(app);
+    crate::browser_tabs::browser_tabs_clear(app);
 
-    if let Some(w) = app.get_webview_window(BROWSER_WINDOW_LABEL) {
+    for w in browser_content_windows(app) {
         let _ = w.close();
     }
     if let Some(w) = app.get_webview_window(BROWSER_BAR_WINDOW_LABEL) {
@@ -244,9 +266,7 @@ pub(crate) fn browser_stack_apply_fullscreen(
     let bar = app
         .get_webview_window(BROWSER_BAR_WINDOW_LABEL)
         .ok_or_else(|| "顶部栏窗口不存在".to_string())?;
-    let content = app
-        .get_webview_window(BROWSER_WINDOW_LABEL)
-        .ok_or_else(|| "浏览窗口不存在".to_string())?;
+    let content = browser_content_window(app).ok_or_else(|| "浏览窗口不存在".to_string())?;
 
     let state = app.state::<BrowserWindowState>();
 
@@ -325,6 +345,64 @@ pub(crate) fn browser_stack_apply_fullscreen(
         )
     };
 
+    place_browser_stack(&bar, &content, pos, total);
+
+    if let Ok(mut g) = state.fullscreen.lock() {
+        *g = false;
+    }
+    if let Ok(mut g) = state.restore_bounds.lock() {
+        *g = None;
+    }
+    browser_stack_set_suppress_hide(app, 800);
+    let _ = bar.show();
+    let _ = content.show();
+    let _ = content.set_focus();
+    Ok(())
+}
+
+/// 切换标签：新标签的内容窗口接替旧窗口的位置、尺寸与迷你模式状态，再交换显示。
+pub(crate) fn browser_stack_swap_content(
+    app: &tauri::AppHandle,
+    prev: &tauri::WebviewWindow,
+    next: &tauri::WebviewWindow,
+) {
+    let visible = prev.is_visible().unwrap_or(false);
+    if let (Ok(pos), Ok(size)) = (prev.outer_position(), prev.inner_size()) {
+        let _ = next.set_size(size);
+        let _ = next.set_position(pos);
+    }
+    let _ = next.set_always_on_top(true);
+    let mini = browser_stack_is_mini(app).then(|| load_browser_mini_settings(app));
+    if let Some(settings) = mini {
+        let _ = apply_window_opacity(next, settings.opacity);
+        let _ = next.set_ignore_cursor_events(settings.click_through);
+        apply_bottom_rounded_corners(next, 0.0);
+        // 旧窗口回到普通状态，以后切回去时不带着迷你模式的透明度/穿透
+        let _ = apply_window_opacity(prev, 1.0);
+        let _ = prev.set_ignore_cursor_events(false);
+    } else {
+        apply_bottom_rounded_corners(next, 16.0);
+    }
+    if !visible {
+        let _ = next.hide();
+        return;
+    }
+    // 两个窗口交换显示时焦点会抖动，避免误触发“失焦隐藏”
+    browser_stack_set_suppress_hide(app, 800);
+    let _ = next.show();
+    if !mini.is_some_and(|s| s.click_through) {
+        let _ = next.set_focus();
+    }
+    let _ = prev.hide();
+}
+
+/// 把顶部栏和内容窗口按“总边界”摆好（顶部栏在上，内容在下）。
+fn place_browser_stack(
+    bar: &tauri::WebviewWindow,
+    content: &tauri::WebviewWindow,
+    pos: tauri::PhysicalPosition<i32>,
+    total: tauri::PhysicalSize<u32>,
+) {
     let scale = bar.scale_factor().unwrap_or(1.0);
     let bar_h = (BROWSER_BAR_HEIGHT * scale).round().max(1.0) as u32;
     let content_h = total.height.saturating_sub(bar_h).max(1);
@@ -334,17 +412,252 @@ pub(crate) fn browser_stack_apply_fullscreen(
 
     let _ = content.set_position(tauri::PhysicalPosition::new(pos.x, pos.y + bar_h as i32));
     let _ = content.set_size(tauri::PhysicalSize::new(total.width, content_h));
-    apply_bottom_rounded_corners(&content, 16.0);
+    apply_bottom_rounded_corners(content, 16.0);
+}
 
-    if let Ok(mut g) = state.fullscreen.lock() {
-        *g = false;
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrowserMiniSettings {
+    pub(crate) opacity: f64,
+    pub(crate) click_through: bool,
+}
+
+pub(crate) fn load_browser_mini_settings(app: &tauri::AppHandle) -> BrowserMiniSettings {
+    let map = crate::read_app_config_map(app);
+    let opacity = map
+        .get(BROWSER_MINI_OPACITY_KEY)
+        .and_then(|v| v.as_f64())
+        .filter(|v| v.is_finite())
+        .unwrap_or(1.0)
+        .clamp(BROWSER_MINI_MIN_OPACITY, 1.0);
+    let click_through = map
+        .get(BROWSER_MINI_CLICK_THROUGH_KEY)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    BrowserMiniSettings {
+        opacity,
+        click_through,
     }
-    if let Ok(mut g) = state.restore_bounds.lock() {
-        *g = None;
+}
+
+pub(crate) fn save_browser_mini_settings(
+    app: &tauri::AppHandle,
+    settings: BrowserMiniSettings,
+) -> Result<BrowserMiniSettings, String> {
+    let opacity = if settings.opacity.is_finite() {
+        settings.opacity.clamp(BROWSER_MINI_MIN_OPACITY, 1.0)
+    } else {
+        1.0
+    };
+    crate::update_app_config_map(app, |map| {
+        map.insert(BROWSER_MINI_OPACITY_KEY.to_string(), Value::from(opacity));
+        map.insert(
+            BROWSER_MINI_CLICK_THROUGH_KEY.to_string(),
+            Value::Bool(settings.click_through),
+        );
+        Ok(())
+    })?;
+    let saved = BrowserMiniSettings {
+        opacity,
+        click_through: settings.click_through,
+    };
+    // 迷你模式中修改立即生效
+    if browser_stack_is_mini(app) {
+        if let Some(content) = browser_content_window(app) {
+            apply_window_opacity(&content, saved.opacity)?;
+            let _ = content.set_ignore_cursor_events(saved.click_through);
+        }
     }
-    browser_stack_set_suppress_hide(app, 800);
-    let _ = bar.show();
+    Ok(saved)
+}
+
+pub(crate) fn browser_stack_is_mini(app: &tauri::AppHandle) -> bool {
+    let state = app.state::<BrowserWindowState>();
+    state.mini.lock().ok().map(|g| *g).unwrap_or(false)
+}
+
+/// 迷你模式：隐藏顶部栏，内容窗口缩成吸附在屏幕角落的小窗（边看视频边干活）。
+/// 按唤醒快捷键退出；开启点击穿透时这是唯一的退出方式。
+pub(crate) fn browser_stack_enter_mini(app: &tauri::AppHandle) -> Result<(), String> {
+    if browser_stack_is_mini(app) {
+        return Ok(());
+    }
+    let bar = app
+        .get_webview_window(BROWSER_BAR_WINDOW_LABEL)
+        .ok_or_else(|| "顶部栏窗口不存在".to_string())?;
+    let content = browser_content_window(app).ok_or_else(|| "浏览窗口不存在".to_string())?;
+    let state = app.state::<BrowserWindowState>();
+
+    if state.fullscreen.lock().ok().map(|g| *g).unwrap_or(false) {
+        browser_stack_apply_fullscreen(app, false)?;
+    }
+    save_browser_stack_bounds_if_valid(app);
+    let restore = state.last_bounds.lock().ok().and_then(|g| g.clone());
+    if let Ok(mut g) = state.mini_restore_bounds.lock() {
+        *g = restore;
+    }
+
+    let saved = state
+        .mini_bounds
+        .lock()
+        .ok()
+        .and_then(|g| g.clone())
+        .or_else(|| load_browser_mini_bounds_from_config(app))
+        .filter(|(pos, size)| bounds_on_any_monitor(&content, *pos, *size));
+    let (pos, size) = match saved {
+        Some(v) => v,
+        None => {
+            // 默认放在当前屏幕右下角
+            let monitor = content
+                .current_monitor()
+                .map_err(|e| format!("读取显示器信息失败: {e}"))?
+                .or_else(|| content.primary_monitor().ok().flatten())
+                .ok_or_else(|| "无法获取显示器信息".to_string())?;
+            let wa = *monitor.work_area();
+            let scale = monitor.scale_factor();
+            let size = tauri::PhysicalSize::new(
+                (BROWSER_MINI_DEFAULT_WIDTH * scale).round() as u32,
+                (BROWSER_MINI_DEFAULT_HEIGHT * scale).round() as u32,
+            );
+            let margin = (BROWSER_MINI_SNAP_MARGIN * scale).round() as i32;
+            let pos = tauri::PhysicalPosition::new(
+                wa.position.x + wa.size.width as i32 - size.width as i32 - margin,
+                wa.position.y + wa.size.height as i32 - size.height as i32 - margin,
+            );
+            (pos, size)
+        }
+    };
+
+    if let Ok(mut g) = state.mini.lock() {
+        *g = true;
+    }
+    if let Ok(mut g) = state.mini_bounds.lock() {
+        *g = Some((pos, size));
+    }
+    browser_stack_set_suppress_hide(app, 1200);
+    let _ = bar.hide();
+    let _ = content.unmaximize();
+    let _ = content.set_size(size);
+    let _ = content.set_position(pos);
+    apply_bottom_rounded_corners(&content, 0.0);
+
+    let settings = load_browser_mini_settings(app);
+    if let Err(e) = apply_window_opacity(&content, settings.opacity) {
+        host_primitives::emit_toast(app, e);
+    }
+    let _ = content.set_ignore_cursor_events(settings.click_through);
+    let _ = content.set_always_on_top(true);
     let _ = content.show();
-    let _ = content.set_focus();
+    if !settings.click_through {
+        let _ = content.set_focus();
+    }
+    Ok(())
+}
+
+/// 退出迷你模式并还原浏览栈；show=false 时只还原布局（随后会被隐藏）。
+pub(crate) fn browser_stack_leave_mini(app: &tauri::AppHandle, show: bool) -> Result<(), String> {
+    if !browser_stack_is_mini(app) {
+        return Ok(());
+    }
+    let bar = app
+        .get_webview_window(BROWSER_BAR_WINDOW_LABEL)
+        .ok_or_else(|| "顶部栏窗口不存在".to_string())?;
+    let content = browser_content_window(app).ok_or_else(|| "浏览窗口不存在".to_string())?;
+    let state = app.state::<BrowserWindowState>();
+
+    browser_stack_save_mini_bounds(app);
+    persist_browser_mini_bounds(app, &state);
+    if let Ok(mut g) = state.mini.lock() {
+        *g = false;
+    }
+    let _ = apply_window_opacity(&content, 1.0);
+    let _ = content.set_ignore_cursor_events(false);
+
+    let restore = state
+        .mini_restore_bounds
+        .lock()
+        .ok()
+        .and_then(|mut g| g.take())
+        .or_else(|| state.last_bounds.lock().ok().and_then(|g| g.clone()));
+    let (pos, total) = restore.unwrap_or_else(|| {
+        let pos = state
+            .last_position
+            .lock()
+            .ok()
+            .and_then(|g| g.clone())
+            .unwrap_or(tauri::PhysicalPosition::new(0, 0));
+        (
+            pos,
+            tauri::PhysicalSize::new(1020, BROWSER_STACK_TOTAL_HEIGHT.round().max(200.0) as u32),
+        )
+    });
+    place_browser_stack(&bar, &content, pos, total);
+    if let Ok(mut g) = state.last_position.lock() {
+        *g = Some(pos);
+    }
+
+    if show {
+        browser_stack_set_suppress_hide(app, 800);
+        let _ = bar.show();
+        let _ = content.show();
+        let _ = content.set_focus();
+    }
     Ok(())
 }
+
+fn browser_stack_save_mini_bounds(app: &tauri::AppHandle) {
+    let Some(content) = browser_content_window(app) else {
+        return;
+    };
+    let (Ok(pos), Ok(size)) = (content.outer_position(), content.outer_size()) else {
+        return;
+    };
+    if pos.x <= -9000 || pos.y <= -9000 || size.width < 160 || size.height < 90 {
+        return;
+    }
+    let state = app.state::<BrowserWindowState>();
+    if let Ok(mut g) = state.mini_bounds.lock() {
+        *g = Some((pos, size));
+    };
+}
+
+/// 迷你窗口被拖动/缩放：停下来后吸附到附近的屏幕边角并落盘。
+pub(crate) fn browser_stack_on_mini_moved(app: &tauri::AppHandle) {
+    browser_stack_save_mini_bounds(app);
+    let state = app.state::<BrowserWindowState>();
+    let next = state
+        .mini_snap_seq
+        .fetch_add(1, Ordering::Relaxed)
+        .saturating_add(1);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let state = app.state::<BrowserWindowState>();
+        if state.mini_snap_seq.load(Ordering::Relaxed) != next || !browser_stack_is_mini(&app) {
+            return;
+        }
+        let Some(content) = browser_content_window(&app) else {
+            return;
+        };
+        let (Ok(pos), Ok(size)) = (content.outer_position(), content.outer_size()) else {
+            return;
+        };
+        if let Ok(Some(monitor)) = content.current_monitor() {
+            let wa = *monitor.work_area();
+            let scale = monitor.scale_factor();
+            let snapped = snap_rect_to_work_area(
+                pos,
+                size,
+                wa.position,
+                wa.size,
+                (BROWSER_MINI_SNAP_THRESHOLD * scale).round() as i32,
+                (BROWSER_MINI_SNAP_MARGIN * scale).round() as i32,
+            );
+            if snapped != pos {
+                let _ = content.set_position(snapped);
+            }
+        }
+        browser_stack_save_mini_bounds(&app);
+        persist_browser_mini_bounds(&app, &state);
+    });
+}
@@ -0,0 +1,898 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, EventTarget, Manager};
+
+use crate::windowing::BrowserWindowState;
+use crate::{is_http_url, BROWSER_BAR_WINDOW_LABEL, BROWSER_WINDOW_LABEL};
+
+// ── 浏览栈标签页 ─────────────────────────────────────────────────────────────
+//
+// 每个标签有自己的内容窗口（label 为 `browser-<序号>`），都叠在顶部栏下方，只显示当前标签；
+// 切换标签只是显示/隐藏，滚动位置、表单、登录态和播放中的媒体都留在各自的 WebView 里。
+// 宿主另外记录每个标签的历史（URL + 标题）与当前位置，用于前进/后退和重启后恢复；
+// 由我们发起的导航会先记下 pending，避免页面加载回调把它当成“新跳转”压进历史。
+// 标签列表与浏览栈状态（图钉、返回插件）持久化到 app 配置，宿主重启后首次唤醒时恢复：
+// 只重建当前标签的 WebView，其它标签第一次切换过去时再创建。
+// 用户脚本只能在创建 WebView 时注册：脚本改过之后，标签下一次导航会换一个新建的内容窗口打开。
+
+const BROWSER_SESSION_KEY: &str = "browserSession";
+const BROWSER_TABS_UPDATED_EVENT: &str = "fast-window:browser-tabs-updated";
+const BROWSER_TABS_MAX: usize = 50;
+const BROWSER_TAB_HISTORY_MAX: usize = 100;
+const BROWSER_TAB_TITLE_MAX_CHARS: usize = 200;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BrowserHistoryEntry {
+    url: String,
+    #[serde(default)]
+    title: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BrowserTab {
+    id: String,
+    entries: Vec<BrowserHistoryEntry>,
+    index: usize,
+    /// 打开这个标签的插件（网页内新开的标签沿用来源标签的插件）
+    #[serde(default)]
+    plugin_id: Option<String>,
+    /// 我们主动发起、尚未开始加载的导航目标
+    #[serde(skip)]
+    pending_url: Option<String>,
+    /// 已开始加载、等待 Finished 修正最终 URL（重定向）
+    #[serde(skip)]
+    loading: bool,
+    /// 内容窗口 label；重启恢复的后台标签在第一次切换过去之前没有窗口
+    #[serde(skip)]
+    window_label: Option<String>,
+    /// 创建内容窗口时的用户脚本版本
+    #[serde(skip)]
+    scripts_revision: u64,
+}
+
+impl BrowserTab {
+    fn current(&self) -> &BrowserHistoryEntry {
+        &self.entries[self.index]
+    }
+
+    fn current_mut(&mut self) -> &mut BrowserHistoryEntry {
+        &mut self.entries[self.index]
+    }
+
+    fn push(&mut self, url: &str) {
+        self.entries.truncate(self.index + 1);
+        self.entries.push(BrowserHistoryEntry {
+            url: url.to_string(),
+            title: String::new(),
+        });
+        if self.entries.len() > BROWSER_TAB_HISTORY_MAX {
+            let overflow = self.entries.len() - BROWSER_TAB_HISTORY_MAX;
+            self.entries.drain(..overflow);
+        }
+        self.index = self.entries.len() - 1;
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrowserTabInfo {
+    id: String,
+    url: String,
+    title: String,
+    can_go_back: bool,
+    can_go_forward: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrowserTabsSnapshot {
+    tabs: Vec<BrowserTabInfo>,
+    active_tab_id: Option<String>,
+}
+
+#[derive(Default)]
+struct BrowserTabs {
+    tabs: Vec<BrowserTab>,
+    active: Option<String>,
+    next_seq: u64,
+    next_window_seq: u64,
+}
+
+impl BrowserTabs {
+    fn position(&self, id: &str) -> Option<usize> {
+        self.tabs.iter().position(|t| t.id == id)
+    }
+
+    fn tab(&self, id: &str) -> Option<&BrowserTab> {
+        self.tabs.iter().find(|t| t.id == id)
+    }
+
+    fn tab_mut(&mut self, id: &str) -> Option<&mut BrowserTab> {
+        self.tabs.iter_mut().find(|t| t.id == id)
+    }
+
+    fn active_tab(&self) -> Option<&BrowserTab> {
+        self.tab(self.active.as_deref()?)
+    }
+
+    fn active_tab_mut(&mut self) -> Option<&mut BrowserTab> {
+        let id = self.active.clone()?;
+        self.tab_mut(&id)
+    }
+
+    fn tab_id_by_window(&self, label: &str) -> Option<String> {
+        self.tabs
+            .iter()
+            .find(|t| t.window_label.as_deref() == Some(label))
+            .map(|t| t.id.clone())
+    }
+
+    /// 给标签分配一个新的内容窗口 label；换掉的旧窗口迟到的事件不会再算到这个标签上。
+    fn assign_window(&mut self, id: &str, scripts_revision: u64) -> Option<String> {
+        self.next_window_seq = self.next_window_seq.saturating_add(1);
+        let label = format!("{BROWSER_WINDOW_LABEL}-{}", self.next_window_seq);
+        let tab = self.tab_mut(id)?;
+        tab.window_label = Some(label.clone());
+        tab.scripts_revision = scripts_revision;
+        Some(label)
+    }
+
+    fn active_url(&self) -> Option<String> {
+        Some(self.active_tab()?.current().url.clone())
+    }
+
+    /// 在当前标签右侧打开新标签并激活；超过上限时拒绝。
+    /// 未指定插件时沿用当前标签的插件。
+    fn open(&mut self, url: &str, plugin_id: Option<&str>) -> Result<String, String> {
+        if self.tabs.len() >= BROWSER_TABS_MAX {
+            return Err(format!("标签页数量已达上限（{BROWSER_TABS_MAX}）"));
+        }
+        let plugin_id = match plugin_id {
+            Some(pid) => Some(pid.to_string()),
+            None => self
+                .active_tab_mut()
+                .and_then(|prev| prev.plugin_id.clone()),
+        };
+        self.next_seq = self.next_seq.saturating_add(1);
+        let id = format!("tab-{}", self.next_seq);
+        let at = self
+            .active
+            .as_deref()
+            .and_then(|a| self.position(a))
+            .map(|i| i + 1)
+            .unwrap_or(self.tabs.len());
+        self.tabs.insert(
+            at,
+            BrowserTab {
+                id: id.clone(),
+                entries: vec![BrowserHistoryEntry {
+                    url: url.to_string(),
+                    title: String::new(),
+                }],
+                index: 0,
+                plugin_id,
+                pending_url: Some(url.to_string()),
+                loading: false,
+                window_label: None,
+                scripts_revision: 0,
+            },
+        );
+        self.active = Some(id.clone());
+        Ok(id)
+    }
+
+    /// 激活标签；返回当前标签是否变了。
+    fn activate(&mut self, id: &str) -> Result<bool, String> {
+        if self.position(id).is_none() {
+            return Err("标签页不存在".to_string());
+        }
+        if self.active.as_deref() == Some(id) {
+            return Ok(false);
+        }
+        self.active = Some(id.to_string());
+        Ok(true)
+    }
+
+    /// 关闭标签；若关闭的是当前标签，激活相邻标签并返回 true。
+    fn close(&mut self, id: &str) -> Result<bool, String> {
+        let i = self
+            .position(id)
+            .ok_or_else(|| "标签页不存在".to_string())?;
+        self.tabs.remove(i);
+        if self.active.as_deref() != Some(id) {
+            return Ok(false);
+        }
+        self.active = None;
+        if self.tabs.is_empty() {
+            return Ok(true);
+        }
+        let next = self.tabs[i.min(self.tabs.len() - 1)].id.clone();
+        self.activate(&next)
+    }
+
+    fn move_to(&mut self, id: &str, to_index: usize) -> Result<(), String> {
+        let i = self
+            .position(id)
+            .ok_or_else(|| "标签页不存在".to_string())?;
+        let tab = self.tabs.remove(i);
+        let to = to_index.min(self.tabs.len());
+        self.tabs.insert(to, tab);
+        Ok(())
+    }
+
+    /// 当前标签在自己的历史里前进/后退，返回标签 id 与需要导航到的 URL。
+    fn go(&mut self, delta: isize) -> Option<(String, String)> {
+        let tab = self.active_tab_mut()?;
+        let next = tab.index.checked_add_signed(delta)?;
+        if next >= tab.entries.len() {
+            return None;
+        }
+        tab.index = next;
+        let url = tab.current().url.clone();
+        tab.pending_url = Some(url.clone());
+        tab.loading = false;
+        Some((tab.id.clone(), url))
+    }
+
+    fn page_started(&mut self, id: &str, url: &str) {
+        let Some(tab) = self.tab_mut(id) else {
+            return;
+        };
+        tab.loading = true;
+        if tab.pending_url.take().is_some() || tab.current().url == url {
+            return;
+        }
+        tab.push(url);
+    }
+
+    /// 返回本次加载完成的页面（URL、标题、插件），供记录浏览历史。
+    fn page_finished(&mut self, id: &str, url: &str) -> Option<(String, String, Option<String>)> {
+        let tab = self.tab_mut(id)?;
+        // 前进/后退打断了上一次加载：迟到的 Finished 不属于当前历史项，忽略
+        if !tab.loading {
+            return None;
+        }
+        tab.loading = false;
+        let plugin_id = tab.plugin_id.clone();
+        let current = tab.current_mut();
+        if current.url != url {
+            current.url = url.to_string();
+        }
+        Some((current.url.clone(), current.title.clone(), plugin_id))
+    }
+
+    /// 返回标题所属页面的 URL 与规范化后的标题。
+    fn set_title(&mut self, id: &str, title: &str) -> Option<(String, String)> {
+        let tab = self.tab_mut(id)?;
+        let current = tab.current_mut();
+        current.title = title
+            .trim()
+            .chars()
+            .take(BROWSER_TAB_TITLE_MAX_CHARS)
+            .collect();
+        Some((current.url.clone(), current.title.clone()))
+    }
+
+    fn active_page(&self) -> Option<(String, String, Option<String>)> {
+        let tab = self.active_tab()?;
+        let current = tab.current();
+        Some((
+            current.url.clone(),
+            current.title.clone(),
+            tab.plugin_id.clone(),
+        ))
+    }
+
+    fn snapshot(&self) -> BrowserTabsSnapshot {
+        BrowserTabsSnapshot {
+            tabs: self
+                .tabs
+                .iter()
+                .map(|t| BrowserTabInfo {
+                    id: t.id.clone(),
+                    url: t.current().url.clone(),
+                    title: t.current().title.clone(),
+                    can_go_back: t.index > 0,
+                    can_go_forward: t.index + 1 < t.entries.len(),
+                })
+                .collect(),
+            active_tab_id: self.active.clone(),
+        }
+    }
+
+    /// 从持久化数据恢复：丢弃不合法的标签，修正越界的位置与失效的当前标签。
+    fn restore(tabs: Vec<BrowserTab>, active: Option<String>) -> Self {
+        let mut out = BrowserTabs::default();
+        for mut tab in tabs.into_iter().take(BROWSER_TABS_MAX) {
+            tab.entries.retain(|e| is_http_url(&e.url));
+            if tab.entries.is_empty() || out.position(&tab.id).is_some() {
+                continue;
+            }
+            tab.index = tab.index.min(tab.entries.len() - 1);
+            tab.plugin_id = tab.plugin_id.filter(|pid| crate::is_safe_id(pid));
+            tab.pending_url = None;
+            tab.loading = false;
+            tab.window_label = None;
+            let seq = tab
+                .id
+                .strip_prefix("tab-")
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(0);
+            out.next_seq = out.next_seq.max(seq);
+            out.tabs.push(tab);
+        }
+        out.active = active
+            .filter(|a| out.position(a).is_some())
+            .or_else(|| out.tabs.first().map(|t| t.id.clone()));
+        out
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedBrowserSession {
+    #[serde(default)]
+    tabs: Vec<BrowserTab>,
+    #[serde(default)]
+    active_tab_id: Option<String>,
+    #[serde(default)]
+    return_to_plugin_id: Option<String>,
+    #[serde(default)]
+    pinned: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct BrowserTabsState {
+    tabs: Mutex<BrowserTabs>,
+    /// 宿主重启后读到了未关闭的会话：首次唤醒时重建浏览栈
+    restore_pending: AtomicBool,
+    save_seq: AtomicU64,
+}
+
+fn with_tabs<T>(app: &tauri::AppHandle, f: impl FnOnce(&mut BrowserTabs) -> T) -> T {
+    let state = app.state::<BrowserTabsState>();
+    let mut g = state.tabs.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut g)
+}
+
+/// 是否是标签内容窗口（label 为 `browser-<序号>`）。
+pub(crate) fn is_browser_content_label(label: &str) -> bool {
+    label
+        .strip_prefix(BROWSER_WINDOW_LABEL)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .is_some_and(|seq| !seq.is_empty() && seq.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// 给当前标签分配新的内容窗口 label，供新建浏览栈时创建窗口。
+pub(crate) fn assign_active_window_label(app: &tauri::AppHandle) -> Option<String> {
+    let revision = crate::browser_user_scripts::user_scripts_revision();
+    with_tabs(app, |t| {
+        let id = t.active.clone()?;
+        t.assign_window(&id, revision)
+    })
+}
+
+/// 当前标签的内容窗口 label。
+pub(crate) fn active_content_label(app: &tauri::AppHandle) -> Option<String> {
+    with_tabs(app, |t| t.active_tab()?.window_label.clone())
+}
+
+/// 当前标签的内容窗口。
+pub(crate) fn browser_content_window(app: &tauri::AppHandle) -> Option<tauri::WebviewWindow> {
+    app.get_webview_window(&active_content_label(app)?)
+}
+
+/// 所有已创建的标签内容窗口（含隐藏在后台的标签）。
+pub(crate) fn browser_content_windows(app: &tauri::AppHandle) -> Vec<tauri::WebviewWindow> {
+    app.webview_windows()
+        .into_iter()
+        .filter(|(label, _)| is_browser_content_label(label))
+        .map(|(_, w)| w)
+        .collect()
+}
+
+pub(crate) fn is_active_content_label(app: &tauri::AppHandle, label: &str) -> bool {
+    with_tabs(app, |t| {
+        t.active_tab()
+            .is_some_and(|tab| tab.window_label.as_deref() == Some(label))
+    })
+}
+
+/// 这个内容窗口创建之后用户脚本改过（它注册的初始化脚本已过期）。
+pub(crate) fn window_scripts_stale(app: &tauri::AppHandle, label: &str) -> bool {
+    let revision = crate::browser_user_scripts::user_scripts_revision();
+    with_tabs(app, |t| {
+        t.tabs.iter().any(|tab| {
+            tab.window_label.as_deref() == Some(label) && tab.scripts_revision != revision
+        })
+    })
+}
+
+fn navigate_browser(app: &tauri::AppHandle, tab_id: &str, url: &str) {
+    let Some(label) = with_tabs(app, |t| t.tab(tab_id)?.window_label.clone()) else {
+        return;
+    };
+    let Some(w) = app.get_webview_window(&label) else {
+        return;
+    };
+    if let Ok(parsed) = tauri::Url::parse(url) {
+        let _ = w.navigate(parsed);
+    }
+}
+
+/// 显示当前标签的内容窗口（还没有就按标签当前 URL 创建），由它接替 `prev` 的位置与状态。
+pub(crate) fn browser_tabs_show_active(
+    app: &tauri::AppHandle,
+    prev: Option<tauri::WebviewWindow>,
+) -> Result<(), String> {
+    let Some((id, url, label)) = with_tabs(app, |t| {
+        let tab = t.active_tab()?;
+        Some((
+            tab.id.clone(),
+            tab.current().url.clone(),
+            tab.window_label.clone(),
+        ))
+    }) else {
+        return Ok(());
+    };
+    let next = match label.and_then(|l| app.get_webview_window(&l)) {
+        Some(w) => w,
+        None => {
+            // 重启恢复的后台标签第一次切换过来：打开它的当前页，不算新跳转
+            let revision = crate::browser_user_scripts::user_scripts_revision();
+            let label = with_tabs(app, |t| {
+                let tab = t.tab_mut(&id)?;
+                tab.pending_url = Some(url.clone());
+                tab.loading = false;
+                t.assign_window(&id, revision)
+            })
+            .ok_or_else(|| "标签页不存在".to_string())?;
+            let parsed = tauri::Url::parse(&url).map_err(|e| format!("url 解析失败: {e}"))?;
+            crate::build_browser_content(app, &label, parsed)?
+        }
+    };
+    match prev.filter(|p| p.label() != next.label()) {
+        Some(prev) => crate::browser_stack::browser_stack_swap_content(app, &prev, &next),
+        None => crate::browser_stack::browser_stack_show(app),
+    }
+    crate::browser_filters::browser_filters_on_active_tab_changed(app);
+    Ok(())
+}
+
+/// 用户脚本改过的内容窗口要导航（已被取消）：换一个新建的窗口打开目标页，再关掉旧窗口。
+/// 导航回调里不能直接创建 WebView，放到异步任务里做。
+pub(crate) fn browser_tabs_reopen_with_new_scripts(
+    app: &tauri::AppHandle,
+    old_label: &str,
+    url: String,
+) {
+    let app = app.clone();
+    let old_label = old_label.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = reopen_tab_window(&app, &old_label, &url) {
+            crate::emit_toast(&app, e);
+        }
+    });
+}
+
+fn reopen_tab_window(app: &tauri::AppHandle, old_label: &str, url: &str) -> Result<(), String> {
+    let parsed = tauri::Url::parse(url).map_err(|e| format!("url 解析失败: {e}"))?;
+    let revision = crate::browser_user_scripts::user_scripts_revision();
+    let Some((label, active)) = with_tabs(app, |t| {
+        let id = t.tab_id_by_window(old_label)?;
+        let active = t.active.as_deref() == Some(id.as_str());
+        Some((t.assign_window(&id, revision)?, active))
+    }) else {
+        return Ok(());
+    };
+    let next = crate::build_browser_content(app, &label, parsed)?;
+    if let Some(old) = app.get_webview_window(old_label) {
+        if active {
+            crate::browser_stack::browser_stack_swap_content(app, &old, &next);
+        }
+        let _ = old.close();
+    }
+    Ok(())
+}
+
+fn browser_tabs_changed(app: &tauri::AppHandle) {
+    let snapshot = with_tabs(app, |t| t.snapshot());
+    let _ = app.emit_to(
+        EventTarget::webview_window(BROWSER_BAR_WINDOW_LABEL),
+        BROWSER_TABS_UPDATED_EVENT,
+        snapshot,
+    );
+    schedule_persist_browser_session(app);
+}
+
+/// 标题/加载事件很密集：合并短时间内的多次变更再落盘。
+fn schedule_persist_browser_session(app: &tauri::AppHandle) {
+    let state = app.state::<BrowserTabsState>();
+    let next = state
+        .save_seq
+        .fetch_add(1, Ordering::Relaxed)
+        .saturating_add(1);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let state = app.state::<BrowserTabsState>();
+        if state.save_seq.load(Ordering::Relaxed) != next {
+            return;
+        }
+        persist_browser_session(&app);
+    });
+}
+
+pub(crate) fn persist_browser_session(app: &tauri::AppHandle) {
+    let (tabs, active_tab_id) = with_tabs(app, |t| (t.tabs.clone(), t.active.clone()));
+    let browser = app.state::<BrowserWindowState>();
+    let session = PersistedBrowserSession {
+        tabs,
+        active_tab_id,
+        return_to_plugin_id: browser
+            .return_to_plugin_id
+            .lock()
+            .ok()
+            .and_then(|g| g.clone()),
+        pinned: browser.pinned.lock().ok().map(|g| *g).unwrap_or(false),
+    };
+    let Ok(v) = serde_json::to_value(session) else {
+        return;
+    };
+    if let Err(e) = crate::update_app_config_map(app, |map| {
+        map.insert(BROWSER_SESSION_KEY.to_string(), v);
+        Ok(())
+    }) {
+        eprintln!("[config] failed to persist browser session: {e}");
+    }
+}
+
+/// 启动时预载上次的浏览会话（真正重建浏览栈发生在首次唤醒时）。
+pub(crate) fn load_browser_session(app: &tauri::AppHandle) {
+    let map = crate::read_app_config_map(app);
+    let Some(session) = map
+        .get(BROWSER_SESSION_KEY)
+        .cloned()
+        .and_then(|v| serde_json::from_value::<PersistedBrowserSession>(v).ok())
+    else {
+        return;
+    };
+
+    let restored = BrowserTabs::restore(session.tabs, session.active_tab_id);
+    let has_tabs = !restored.tabs.is_empty();
+    with_tabs(app, |t| *t = restored);
+
+    let browser = app.state::<BrowserWindowState>();
+    if let Ok(mut g) = browser.pinned.lock() {
+        *g = session.pinned;
+    }
+    if let Ok(mut g) = browser.return_to_plugin_id.lock() {
+        *g = session
+            .return_to_plugin_id
+            .filter(|pid| crate::is_safe_id(pid));
+    }
+    app.state::<BrowserTabsState>()
+        .restore_pending
+        .store(has_tabs, Ordering::Relaxed);
+}
+
+pub(crate) fn has_pending_restore(app: &tauri::AppHandle) -> bool {
+    app.state::<BrowserTabsState>()
+        .restore_pending
+        .load(Ordering::Relaxed)
+}
+
+/// 取走“待恢复会话”标记；返回恢复后应显示的 URL。
+pub(crate) fn take_pending_restore(app: &tauri::AppHandle) -> Option<String> {
+    let state = app.state::<BrowserTabsState>();
+    if !state.restore_pending.swap(false, Ordering::Relaxed) {
+        return None;
+    }
+    with_tabs(app, |t| {
+        let url = t.active_url()?;
+        if let Some(tab) = t.active_tab_mut() {
+            tab.pending_url = Some(url.clone());
+        }
+        Some(url)
+    })
+}
+
+/// 在新标签中打开 URL（只改标签模型，调用方决定是显示新标签还是新建浏览栈）。
+pub(crate) fn browser_tabs_open(
+    app: &tauri::AppHandle,
+    url: &str,
+    plugin_id: Option<&str>,
+) -> Result<(), String> {
+    with_tabs(app, |t| t.open(url, plugin_id))?;
+    browser_tabs_changed(app);
+    Ok(())
+}
+
+/// 网页通过 window.open / target=_blank 请求新窗口，或顶部栏新建标签：打开并切到新标签。
+/// `source_label` 是发起请求的内容窗口，新标签沿用它的插件。
+pub(crate) fn browser_tabs_open_in_new_tab(
+    app: &tauri::AppHandle,
+    url: &str,
+    source_label: Option<&str>,
+) {
+    let prev = browser_content_window(app);
+    let opened = with_tabs(app, |t| {
+        let plugin_id = source_label
+            .and_then(|label| t.tab_id_by_window(label))
+            .and_then(|id| t.tab(&id)?.plugin_id.clone());
+        t.open(url, plugin_id.as_deref())
+    });
+    if let Err(e) = opened.and_then(|_| browser_tabs_show_active(app, prev)) {
+        crate::emit_toast(app, e);
+    }
+    browser_tabs_changed(app);
+}
+
+/// 关闭浏览栈时清空标签（下次打开是全新会话）。
+pub(crate) fn browser_tabs_clear(app: &tauri::AppHandle) {
+    with_tabs(app, |t| *t = BrowserTabs::default());
+    app.state::<BrowserTabsState>()
+        .restore_pending
+        .store(false, Ordering::Relaxed);
+    persist_browser_session(app);
+}
+
+pub(crate) fn browser_tabs_on_page_load(
+    app: &tauri::AppHandle,
+    label: &str,
+    event: tauri::webview::PageLoadEvent,
+    url: &str,
+) {
+    if !is_http_url(url) {
+        return;
+    }
+    let Some(id) = with_tabs(app, |t| t.tab_id_by_window(label)) else {
+        return;
+    };
+    match event {
+        tauri::webview::PageLoadEvent::Started => with_tabs(app, |t| t.page_started(&id, url)),
+        tauri::webview::PageLoadEvent::Finished => {
+            if let Some((url, title, plugin_id)) = with_tabs(app, |t| t.page_finished(&id, url)) {
+                crate::browser_history::browser_history_record(
+                    app,
+                    &url,
+                    &title,
+                    plugin_id.as_deref(),
+                );
+            }
+        }
+    }
+    browser_tabs_changed(app);
+}
+
+pub(crate) fn browser_tabs_on_title_changed(app: &tauri::AppHandle, label: &str, title: &str) {
+    let Some((url, title)) = with_tabs(app, |t| {
+        let id = t.tab_id_by_window(label)?;
+        t.set_title(&id, title)
+    }) else {
+        return;
+    };
+    crate::browser_history::browser_history_update_title(app, &url, &title);
+    browser_tabs_changed(app);
+}
+
+/// 当前标签的页面（URL、标题、打开它的插件）。
+pub(crate) fn browser_tabs_active_page(
+    app: &tauri::AppHandle,
+) -> Option<(String, String, Option<String>)> {
+    with_tabs(app, |t| t.active_page())
+}
+
+/// 当前标签后退/前进；返回 false 表示没有可用的标签历史。
+pub(crate) fn browser_tabs_go(app: &tauri::AppHandle, delta: isize) -> bool {
+    let Some((id, url)) = with_tabs(app, |t| t.go(delta)) else {
+        return false;
+    };
+    navigate_browser(app, &id, &url);
+    browser_tabs_changed(app);
+    true
+}
+
+#[tauri::command]
+pub(crate) fn browser_tabs_list(app: tauri::AppHandle) -> BrowserTabsSnapshot {
+    with_tabs(&app, |t| t.snapshot())
+}
+
+#[tauri::command]
+pub(crate) fn browser_tab_new(app: tauri::AppHandle, url: Option<String>) -> Result<(), String> {
+    // 不传 url 时复制当前标签
+    let url = match url.map(|u| u.trim().to_string()).filter(|u| !u.is_empty()) {
+        Some(u) => u,
+        None => {
+            with_tabs(&app, |t| t.active_url()).ok_or_else(|| "没有可复制的标签页".to_string())?
+        }
+    };
+    if !is_http_url(&url) {
+        return Err("url 必须以 http(s):// 开头".to_string());
+    }
+    let parsed = tauri::Url::parse(&url).map_err(|e| format!("url 解析失败: {e}"))?;
+    browser_tabs_open_in_new_tab(&app, parsed.as_str(), None);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn browser_tab_activate(app: tauri::AppHandle, tab_id: String) -> Result<(), String> {
+    let prev = browser_content_window(&app);
+    if with_tabs(&app, |t| t.activate(tab_id.trim()))? {
+        browser_tabs_show_active(&app, prev)?;
+    }
+    browser_tabs_changed(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn browser_tab_close(app: tauri::AppHandle, tab_id: String) -> Result<(), String> {
+    let tab_id = tab_id.trim();
+    let prev = browser_content_window(&app);
+    let (label, active_changed, empty) = with_tabs(&app, |t| {
+        let label = t.tab(tab_id).and_then(|tab| tab.window_label.clone());
+        t.close(tab_id)
+            .map(|changed| (label, changed, t.tabs.is_empty()))
+    })?;
+    if empty {
+        // 关掉最后一个标签等同于关闭浏览
+        crate::browser_stack::browser_stack_close(&app);
+        return Ok(());
+    }
+    if active_changed {
+        browser_tabs_show_active(&app, prev)?;
+    }
+    // 先让相邻标签接替位置，再销毁被关闭标签的 WebView（网页音频随之停止）
+    if let Some(w) = label.and_then(|l| app.get_webview_window(&l)) {
+        let _ = w.close();
+    }
+    browser_tabs_changed(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn browser_tab_move(
+    app: tauri::AppHandle,
+    tab_id: String,
+    to_index: usize,
+) -> Result<(), String> {
+    with_tabs(&app, |t| t.move_to(tab_id.trim(), to_index))?;
+    browser_tabs_changed(&app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn current(tabs: &BrowserTabs) -> String {
+        tabs.active_url().unwrap()
+    }
+
+    #[test]
+    fn opens_tabs_next_to_active_and_closes_to_neighbour() {
+        let mut tabs = BrowserTabs::default();
+        let a = tabs.open("https://a.com/", None).unwrap();
+        let b = tabs.open("https://b.com/", None).unwrap();
+        tabs.activate(&a).unwrap();
+        let c = tabs.open("https://c.com/", None).unwrap();
+        let order: Vec<_> = tabs.tabs.iter().map(|t| t.id.clone()).collect();
+        assert_eq!(order, vec![a.clone(), c.clone(), b.clone()]);
+
+        assert!(tabs.close(&c).unwrap());
+        assert_eq!(current(&tabs), "https://b.com/");
+        assert!(!tabs.close(&a).unwrap());
+        assert!(tabs.close(&b).unwrap());
+        assert!(tabs.active.is_none());
+    }
+
+    #[test]
+    fn new_tabs_inherit_the_opening_plugin() {
+        let mut tabs = BrowserTabs::default();
+        tabs.open("https://a.com/", Some("demo")).unwrap();
+        tabs.open("https://b.com/", None).unwrap();
+        let (url, _, plugin_id) = tabs.active_page().unwrap();
+        assert_eq!(url, "https://b.com/");
+        assert_eq!(plugin_id.as_deref(), Some("demo"));
+    }
+
+    #[test]
+    fn reassigning_a_window_detaches_the_old_label() {
+        let mut tabs = BrowserTabs::default();
+        let a = tabs.open("https://a.com/", None).unwrap();
+        let first = tabs.assign_window(&a, 1).unwrap();
+        let second = tabs.assign_window(&a, 2).unwrap();
+        assert_ne!(first, second);
+        assert!(is_browser_content_label(&second));
+        assert!(!is_browser_content_label(BROWSER_BAR_WINDOW_LABEL));
+        assert!(!is_browser_content_label("browser-"));
+
+        // 换掉的旧窗口迟到的事件不再对应任何标签
+        assert_eq!(tabs.tab_id_by_window(&first), None);
+        assert_eq!(tabs.tab_id_by_window(&second), Some(a.clone()));
+        assert_eq!(tabs.tab(&a).unwrap().scripts_revision, 2);
+    }
+
+    #[test]
+    fn per_tab_history_ignores_our_own_navigations() {
+        let mut tabs = BrowserTabs::default();
+        let a = tabs.open("https://a.com/", None).unwrap();
+        tabs.page_started(&a, "https://a.com/");
+        tabs.page_finished(&a, "https://a.com/");
+        tabs.page_started(&a, "https://a.com/next");
+        tabs.page_finished(&a, "https://a.com/next");
+
+        assert_eq!(tabs.go(-1), Some((a.clone(), "https://a.com/".to_string())));
+        tabs.page_started(&a, "https://a.com/");
+        tabs.page_finished(&a, "https://a.com/");
+        let snap = tabs.snapshot();
+        assert!(!snap.tabs[0].can_go_back);
+        assert!(snap.tabs[0].can_go_forward);
+
+        // 新跳转截断前进历史
+        tabs.page_started(&a, "https://a.com/other");
+        assert_eq!(tabs.go(1), None);
+        assert_eq!(current(&tabs), "https://a.com/other");
+    }
+
+    #[test]
+    fn background_tabs_keep_loading_and_late_events_are_ignored() {
+        let mut tabs = BrowserTabs::default();
+        let a = tabs.open("http://a.com/", None).unwrap();
+        tabs.page_started(&a, "http://a.com/");
+        tabs.page_finished(&a, "https://a.com/home");
+        assert_eq!(current(&tabs), "https://a.com/home");
+
+        // 切到新标签后，后台标签自己的加载照常记进它的历史
+        tabs.page_started(&a, "https://a.com/slow");
+        let b = tabs.open("https://b.com/", None).unwrap();
+        tabs.page_finished(&a, "https://a.com/slow");
+        tabs.set_title(&a, "Slow");
+        assert_eq!(current(&tabs), "https://b.com/");
+        assert!(tabs.activate(&a).unwrap());
+        assert_eq!(current(&tabs), "https://a.com/slow");
+        assert_eq!(tabs.active_page().unwrap().1, "Slow");
+
+        // 后退打断了加载：迟到的 Finished 不改写历史
+        tabs.page_started(&a, "https://a.com/late");
+        tabs.go(-1).unwrap();
+        tabs.page_finished(&a, "https://a.com/late");
+        assert_eq!(current(&tabs), "https://a.com/slow");
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn moves_and_restores_tabs() {
+        let mut tabs = BrowserTabs::default();
+        let a = tabs.open("https://a.com/", None).unwrap();
+        let b = tabs.open("https://b.com/", None).unwrap();
+        tabs.move_to(&b, 0).unwrap();
+        assert_eq!(tabs.tabs[0].id, b);
+
+        let mut saved = tabs.tabs.clone();
+        saved.push(BrowserTab {
+            id: "tab-9".to_string(),
+            entries: vec![BrowserHistoryEntry {
+                url: "file:///etc/passwd".to_string(),
+                title: String::new(),
+            }],
+            index: 3,
+            plugin_id: None,
+            pending_url: None,
+            loading: false,
+            window_label: Some("browser-1".to_string()),
+            scripts_revision: 0,
+        });
+        let mut restored = BrowserTabs::restore(saved, Some("missing".to_string()));
+        assert_eq!(restored.tabs.len(), 2);
+        assert!(restored.tabs.iter().all(|t| t.window_label.is_none()));
+        assert_eq!(restored.active.as_deref(), Some(b.as_str()));
+        let c = restored.open("https://c.com/", None).unwrap();
+        assert!(c != a && c != b);
+    }
+}
@@ -0,0 +1,440 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, EventTarget};
+
+use crate::{
+    load_webview_settings, now_ms, rand_u32, write_webview_settings, WebviewSettings,
+    BROWSER_BAR_WINDOW_LABEL, WEBVIEW_SETTINGS_UPDATED_EVENT,
+};
+
+// ── 浏览栈用户脚本 / 样式 ────────────────────────────────────────────────────
+//
+// 保存在 WebviewSettings.userScripts，每条包含 URL 匹配规则、注入时机与 JS/CSS。
+// 构建浏览窗口时每条启用的条目注册为一段初始化脚本：它在每次导航的页面里
+// 按 location.href 匹配规则，再按 runAt 时机执行脚本、插入样式。
+// 初始化脚本只能在创建 WebView 时注册：每次修改都会递增版本号，
+// 已打开的标签下一次导航/刷新时由 browser_tabs 换成带新脚本的窗口。
+// 匹配规则（与浏览器扩展一致）：`<all_urls>`、`*://*.example.com/*`、`https://example.com/path*`。
+
+const USER_SCRIPTS_MAX: usize = 100;
+const USER_SCRIPT_MATCHES_MAX: usize = 20;
+const USER_SCRIPT_NAME_MAX_CHARS: usize = 80;
+const USER_SCRIPT_CODE_MAX_BYTES: usize = 256 * 1024;
+static USER_SCRIPTS_REVISION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum WebviewUserScriptRunAt {
+    /// 文档开始解析前（此时可能还没有 body）
+    #[serde(rename = "documentStart")]
+    Start,
+    /// DOMContentLoaded
+    #[default]
+    #[serde(rename = "documentEnd")]
+    End,
+    /// load 之后的空闲时段
+    #[serde(rename = "documentIdle")]
+    Idle,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WebviewUserScript {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default = "default_true")]
+    enabled: bool,
+    #[serde(default)]
+    matches: Vec<String>,
+    #[serde(default)]
+    run_at: WebviewUserScriptRunAt,
+    #[serde(default)]
+    js: String,
+    #[serde(default)]
+    css: String,
+}
+
+fn escape_regex(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        if "\\^$.|?*+()[]{}/".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// 把匹配规则转成 JS 正则源码（匹配去掉 hash 的 location.href）。
+fn match_pattern_to_regex(pattern: &str) -> Result<String, String> {
+    let pattern = pattern.trim();
+    if pattern == "<all_urls>" {
+        return Ok("^https?://".to_string());
+    }
+    let invalid = || format!("匹配规则不合法: {pattern}");
+
+    let (scheme, rest) = pattern.split_once("://").ok_or_else(invalid)?;
+    let scheme = match scheme.to_ascii_lowercase().as_str() {
+        "*" => "https?".to_string(),
+        "http" | "https" => scheme.to_ascii_lowercase(),
+        _ => return Err(invalid()),
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/*"),
+    };
+    let authority = authority.to_ascii_lowercase();
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((h, p)) if !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()) => (h, Some(p)),
+        Some(_) => return Err(invalid()),
+        None => (authority.as_str(), None),
+    };
+    let host_ok = |h: &str| {
+        !h.is_empty()
+            && h.split('.').all(|label| {
+                !label.is_empty()
+                    && label
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            })
+    };
+    let host_re = if host == "*" {
+        "[^/:]+".to_string()
+    } else if let Some(domain) = host.strip_prefix("*.") {
+        if !host_ok(domain) {
+            return Err(invalid());
+        }
+        format!("(?:[^/:]+\\.)?{}", escape_regex(domain))
+    } else if host_ok(host) {
+        escape_regex(host)
+    } else {
+        return Err(invalid());
+    };
+    // 未写端口时匹配任意端口
+    let port_re = match port {
+        Some(p) => format!(":{p}"),
+        None => "(?::\\d+)?".to_string(),
+    };
+    let path_re = path
+        .split('*')
+        .map(escape_regex)
+        .collect::<Vec<_>>()
+        .join(".*");
+
+    Ok(format!("^{scheme}://{host_re}{port_re}{path_re}$"))
+}
+
+fn make_user_script_id() -> String {
+    let stamp = now_ms();
+    format!("us-{stamp}-{:08x}", rand_u32(stamp))
+}
+
+/// 保存前校验：补全 id、规范化字段，任何不合法项直接报错。
+fn validate_user_script(mut script: WebviewUserScript) -> Result<WebviewUserScript, String> {
+    script.id = script.id.trim().to_string();
+    if script.id.is_empty() {
+        script.id = make_user_script_id();
+    } else if !crate::is_safe_id(&script.id) {
+        return Err("脚本 id 不合法".to_string());
+    }
+
+    script.name = script
+        .name
+        .trim()
+        .chars()
+        .take(USER_SCRIPT_NAME_MAX_CHARS)
+        .collect();
+    if script.name.is_empty() {
+        return Err("脚本名称不能为空".to_string());
+    }
+
+    let matches: Vec<String> = script
+        .matches
+        .iter()
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .collect();
+    if matches.is_empty() {
+        return Err(format!("脚本「{}」至少需要一条匹配规则", script.name));
+    }
+    if matches.len() > USER_SCRIPT_MATCHES_MAX {
+        return Err(format!(
+            "脚本「{}」的匹配规则不能超过 {USER_SCRIPT_MATCHES_MAX} 条",
+            script.name
+        ));
+    }
+    for m in &matches {
+        match_pattern_to_regex(m)?;
+    }
+    script.matches = matches;
+
+    if script.js.trim().is_empty() && script.css.trim().is_empty() {
+        return Err(format!("脚本「{}」的 JS 与 CSS 不能同时为空", script.name));
+    }
+    if script.js.len() > USER_SCRIPT_CODE_MAX_BYTES || script.css.len() > USER_SCRIPT_CODE_MAX_BYTES
+    {
+        return Err(format!(
+            "脚本「{}」过大（JS / CSS 各不超过 256KB）",
+            script.name
+        ));
+    }
+    Ok(script)
+}
+
+/// 读取配置时的兜底：丢弃不合法或重复的条目，不报错。
+pub(crate) fn sanitize_user_scripts(list: Vec<WebviewUserScript>) -> Vec<WebviewUserScript> {
+    let mut out: Vec<WebviewUserScript> = Vec::new();
+    for script in list.into_iter().take(USER_SCRIPTS_MAX) {
+        if script.id.trim().is_empty() {
+            continue;
+        }
+        let Ok(script) = validate_user_script(script) else {
+            continue;
+        };
+        if out.iter().any(|s| s.id == script.id) {
+            continue;
+        }
+        out.push(script);
+    }
+    out
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UserScriptMeta<'a> {
+    id: &'a str,
+    patterns: Vec<String>,
+    run_at: WebviewUserScriptRunAt,
+    css: &'a str,
+}
+
+fn user_script_injection_script(script: &WebviewUserScript) -> Result<String, String> {
+    let meta = UserScriptMeta {
+        id: &script.id,
+        patterns: script
+            .matches
+            .iter()
+            .filter_map(|m| match_pattern_to_regex(m).ok())
+            .collect(),
+        run_at: script.run_at,
+        css: &script.css,
+    };
+    let json = serde_json::to_string(&meta).map_err(|e| format!("序列化配置失败: {e}"))?;
+    let quoted = serde_json::to_string(&json).map_err(|e| format!("序列化配置失败: {e}"))?;
+    let js = &script.js;
+
+    // 用户 JS 直接嵌进函数体（而不是 eval），不受站点 CSP 的 unsafe-eval 限制。
+    Ok(format!(
+        r#"(function () {{
+  const it = JSON.parse({quoted});
+  const href = String(location.href).split('#')[0];
+  const matched = it.patterns.some((p) => {{
+    try {{ return new RegExp(p).test(href); }} catch (_) {{ return false; }}
+  }});
+  if (!matched) return;
+  const key = '__fastwindowUserScript:' + it.id;
+  if (window[key]) return;
+  window[key] = true;
+
+  const main = function () {{
+{js}
+  }};
+  const run = () => {{
+    if (it.css) {{
+      try {{
+        const el = document.createElement('style');
+        el.setAttribute('data-fastwindow-user-style', it.id);
+        el.textContent = it.css;
+        (document.head || document.documentElement).appendChild(el);
+      }} catch (e) {{ console.warn('[fast-window] user style failed', it.id, e); }}
+    }}
+    try {{ main(); }} catch (e) {{ console.warn('[fast-window] user script failed', it.id, e); }}
+  }};
+  const whenReady = (fn) => {{
+    if (document.readyState === 'loading') document.addEventListener('DOMContentLoaded', fn, {{ once: true }});
+    else fn();
+  }};
+
+  if (it.runAt === 'documentStart') {{
+    if (document.documentElement) run();
+    else whenReady(run);
+  }} else if (it.runAt === 'documentIdle') {{
+    const idle = () => (window.requestIdleCallback ? window.requestIdleCallback(run, {{ timeout: 2000 }}) : setTimeout(run, 0));
+    if (document.readyState === 'complete') idle();
+    else window.addEventListener('load', idle, {{ once: true }});
+  }} else {{
+    whenReady(run);
+  }}
+}})();"#
+    ))
+}
+
+/// 浏览窗口的初始化脚本（每条启用的用户脚本一段）：每次导航都会执行，按当前 URL 决定是否注入。
+/// 分段注入，某条脚本有语法错误时不影响其它条目。
+pub(crate) fn browser_user_scripts_injection_scripts(
+    scripts: &[WebviewUserScript],
+) -> Result<Vec<String>, String> {
+    scripts
+        .iter()
+        .filter(|s| s.enabled)
+        .map(user_script_injection_script)
+        .collect()
+}
+
+/// 用户脚本的版本号（本次运行内每次保存/删除加一）。
+pub(crate) fn user_scripts_revision() -> u64 {
+    USER_SCRIPTS_REVISION.load(Ordering::Relaxed)
+}
+
+fn emit_webview_settings_updated(app: &tauri::AppHandle, settings: &WebviewSettings) {
+    let _ = app.emit_to(
+        EventTarget::webview_window(BROWSER_BAR_WINDOW_LABEL),
+        WEBVIEW_SETTINGS_UPDATED_EVENT,
+        settings.clone(),
+    );
+    let _ = app.emit_to(
+        EventTarget::webview_window("main"),
+        WEBVIEW_SETTINGS_UPDATED_EVENT,
+        settings.clone(),
+    );
+}
+
+fn update_user_scripts(
+    app: &tauri::AppHandle,
+    update: impl FnOnce(&mut Vec<WebviewUserScript>) -> Result<(), String>,
+) -> Result<Vec<WebviewUserScript>, String> {
+    let mut settings = load_webview_settings(app);
+    update(&mut settings.user_scripts)?;
+    let next = write_webview_settings(app, settings)?;
+    USER_SCRIPTS_REVISION.fetch_add(1, Ordering::Relaxed);
+    emit_webview_settings_updated(app, &next);
+    Ok(next.user_scripts)
+}
+
+#[tauri::command]
+pub(crate) fn browser_user_scripts_list(app: tauri::AppHandle) -> Vec<WebviewUserScript> {
+    load_webview_settings(&app).user_scripts
+}
+
+/// 新增或按 id 覆盖一条用户脚本。
+#[tauri::command]
+pub(crate) fn browser_user_script_save(
+    app: tauri::AppHandle,
+    script: WebviewUserScript,
+) -> Result<Vec<WebviewUserScript>, String> {
+    let script = validate_user_script(script)?;
+    update_user_scripts(&app, |list| {
+        match list.iter_mut().find(|s| s.id == script.id) {
+            Some(existing) => *existing = script,
+            None => {
+                if list.len() >= USER_SCRIPTS_MAX {
+                    return Err(format!("用户脚本数量已达上限（{USER_SCRIPTS_MAX}）"));
+                }
+                list.push(script);
+            }
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub(crate) fn browser_user_script_remove(
+    app: tauri::AppHandle,
+    id: String,
+) -> Result<Vec<WebviewUserScript>, String> {
+    let id = id.trim().to_string();
+    update_user_scripts(&app, |list| {
+        let before = list.len();
+        list.retain(|s| s.id != id);
+        if list.len() == before {
+            return Err("用户脚本不存在".to_string());
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(matches: &[&str], js: &str) -> WebviewUserScript {
+        WebviewUserScript {
+            id: "fix-site".to_string(),
+            name: " 修复站点 ".to_string(),
+            enabled: true,
+            matches: matches.iter().map(|m| m.to_string()).collect(),
+            run_at: WebviewUserScriptRunAt::default(),
+            js: js.to_string(),
+            css: String::new(),
+        }
+    }
+
+    #[test]
+    fn converts_match_patterns_to_regex() {
+        assert_eq!(match_pattern_to_regex("<all_urls>").unwrap(), "^https?://");
+        assert_eq!(
+            match_pattern_to_regex("*://*.example.com/*").unwrap(),
+            "^https?://(?:[^/:]+\\.)?example\\.com(?::\\d+)?\\/.*$"
+        );
+        assert_eq!(
+            match_pattern_to_regex("https://Example.com:8080/a?b*").unwrap(),
+            "^https://example\\.com:8080\\/a\\?b.*$"
+        );
+        assert_eq!(
+            match_pattern_to_regex("http://localhost").unwrap(),
+            "^http://localhost(?::\\d+)?\\/.*$"
+        );
+        assert!(match_pattern_to_regex("ftp://example.com/*").is_err());
+        assert!(match_pattern_to_regex("https://exa*mple.com/*").is_err());
+        assert!(match_pattern_to_regex("example.com/*").is_err());
+    }
+
+    #[test]
+    fn validates_scripts_before_saving() {
+        let ok = validate_user_script(script(&[" https://a.com/* ", ""], "1")).unwrap();
+        assert_eq!(ok.name, "修复站点");
+        assert_eq!(ok.matches, vec!["https://a.com/*".to_string()]);
+
+        assert!(validate_user_script(script(&[], "1")).is_err());
+        assert!(validate_user_script(script(&["https://a.com/*"], "  ")).is_err());
+        let mut bad_id = script(&["https://a.com/*"], "1");
+        bad_id.id = "../x".to_string();
+        assert!(validate_user_script(bad_id).is_err());
+
+        let mut generated = script(&["https://a.com/*"], "1");
+        generated.id = String::new();
+        assert!(validate_user_script(generated)
+            .unwrap()
+            .id
+            .starts_with("us-"));
+    }
+
+    #[test]
+    fn sanitize_drops_invalid_and_duplicate_entries() {
+        let list = vec![
+            script(&["https://a.com/*"], "1"),
+            script(&["https://b.com/*"], "2"),
+            script(&["nope"], "3"),
+        ];
+        let out = sanitize_user_scripts(list);
+        // 第二条与第一条 id 相同，第三条规则不合法
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].matches, vec!["https://a.com/*".to_string()]);
+    }
+
+    #[test]
+    fn injects_only_enabled_scripts() {
+        let mut off = script(&["https://a.com/*"], "console.log(1)");
+        off.enabled = false;
+        let on = script(&["https://b.com/*"], "console.log(2)");
+        let out = browser_user_scripts_injection_scripts(&[off, on]).unwrap();
+        assert_eq!(out.len(), 1);
+        assert!(out[0].contains("console.log(2)"));
+    }
+}
@@ -0,0 +1,727 @@
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::capability_server::{capability_discovery_path, CapabilityDiscoveryFile};
+
+// ── 命令行调用能力 ──────────────────────────────────────────────────────────
+//
+//   fast-window capability list [--app <appId>] [--launch] [--json]
+//   fast-window capability invoke <appId>/<capabilityId> [--input <文本>|-] [--input-json <JSON>]
+//                                 [--config <JSON>] [--stream] [--json]
+//   fast-window capability options <appId>/<capabilityId> <optionSource> [--config <JSON>]
+//                                  [--launch] [--json]
+//
+// 连接正在运行的宿主：优先用环境变量 FW_HOST_CAPABILITY_URL / FW_HOST_CAPABILITY_TOKEN
+// （托管应用里启动的脚本直接可用），否则读宿主写在数据目录里的 capability-server.json，
+// 调用方为 "@cli"，同样受 capabilityAccess 约束。数据目录的查找顺序与宿主一致：
+// FAST_WINDOW_DATA_DIR、程序所在目录、系统应用数据目录。
+// 默认输出纯文本（调用结果文字、每行一项的清单），--json 原样输出服务响应；
+// --stream 边收边输出应用返回的文字。退出码：0 成功，1 调用失败，2 用法错误。
+
+const CLI_COMMAND: &str = "capability";
+// 与 tauri.conf.json 的 identifier 一致，对应宿主 app_data_dir 的最后一级兜底
+const APP_IDENTIFIER: &str = "com.fastwindow.app";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+const USAGE: &str = "用法:
+  fast-window capability list [--app <appId>] [--launch] [--json]
+  fast-window capability invoke <appId>/<capabilityId> [--input <文本>|-] [--input-json <JSON>] [--config <JSON>] [--stream] [--json]
+  fast-window capability options <appId>/<capabilityId> <optionSource> [--config <JSON>] [--launch] [--json]
+
+  --launch       应用未运行时允许拉起（list / options；invoke 默认允许）
+  --input -      从标准输入读取输入文本
+  --stream       流式输出应用返回的文字
+  --json         输出原始 JSON 响应";
+
+#[derive(Debug, PartialEq)]
+enum CliInput {
+    Text(String),
+    Stdin,
+    Json(Value),
+}
+
+#[derive(Debug, PartialEq)]
+enum CliCommand {
+    Help,
+    List {
+        app_id: Option<String>,
+        launch: bool,
+    },
+    Invoke {
+        app_id: String,
+        capability_id: String,
+        input: Option<CliInput>,
+        config: Value,
+        stream: bool,
+    },
+    Options {
+        app_id: String,
+        capability_id: String,
+        option_source: String,
+        config: Value,
+        launch: bool,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+struct CliArgs {
+    command: CliCommand,
+    json: bool,
+}
+
+/// 第一个参数是 capability 时按命令行模式执行并返回退出码；否则返回 None，照常启动宿主。
+pub(crate) fn run_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) != Some(CLI_COMMAND) {
+        return None;
+    }
+    attach_parent_console();
+
+    let args = match parse_args(&args[1..]) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return Some(2);
+        }
+    };
+    if args.command == CliCommand::Help {
+        println!("{USAGE}");
+        return Some(0);
+    }
+
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(error) => {
+            eprintln!("启动命令行运行时失败: {error}");
+            return Some(1);
+        }
+    };
+    match runtime.block_on(run(args)) {
+        Ok(()) => Some(0),
+        Err(error) => {
+            eprintln!("{error}");
+            Some(1)
+        }
+    }
+}
+
+/// 发布版是 Windows 子系统程序，没有自己的控制台；从终端运行时借用父进程的控制台输出。
+/// 输出已被管道或重定向接走时不动它。
+#[cfg(target_os = "windows")]
+fn attach_parent_console() {
+    use windows::Win32::System::Console::{
+        AttachConsole, GetStdHandle, ATTACH_PARENT_PROCESS, STD_OUTPUT_HANDLE,
+    };
+    let redirected = unsafe { GetStdHandle(STD_OUTPUT_HANDLE) }
+        .is_ok_and(|handle| !handle.is_invalid() && !handle.0.is_null());
+    if !redirected {
+        let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_parent_console() {}
+
+fn parse_args(args: &[String]) -> Result<CliArgs, String> {
+    let mut positional = Vec::new();
+    let mut json = false;
+    let mut launch = false;
+    let mut stream = false;
+    let mut app_filter = None;
+    let mut input = None;
+    let mut config = Value::Object(Default::default());
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value_of = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{name} 缺少参数值"))
+        };
+        match arg.as_str() {
+            "-h" | "--help" | "help" => {
+                return Ok(CliArgs {
+                    command: CliCommand::Help,
+                    json,
+                })
+            }
+            "--json" => json = true,
+            "--launch" => launch = true,
+            "--stream" => stream = true,
+            "--app" => app_filter = Some(parse_id(&value_of("--app")?, "appId")?),
+            "--input" => {
+                let value = value_of("--input")?;
+                input = Some(if value == "-" {
+                    CliInput::Stdin
+                } else {
+                    CliInput::Text(value)
+                });
+            }
+            "--input-json" => {
+                let value = value_of("--input-json")?;
+                let value = serde_json::from_str(&value)
+                    .map_err(|e| format!("--input-json 不是有效 JSON: {e}"))?;
+                input = Some(CliInput::Json(value));
+            }
+            "--config" => {
+                let value = value_of("--config")?;
+                config = serde_json::from_str(&value)
+                    .map_err(|e| format!("--config 不是有效 JSON: {e}"))?;
+                if !config.is_object() {
+                    return Err("--config 必须是 JSON 对象".to_string());
+                }
+            }
+            other if other.starts_with("--") => return Err(format!("未知选项: {other}")),
+            other => positional.push(other.to_string()),
+        }
+    }
+
+    let Some((subcommand, rest)) = positional.split_first() else {
+        return Ok(CliArgs {
+            command: CliCommand::Help,
+            json,
+        });
+    };
+    let command = match (subcommand.as_str(), rest) {
+        ("list", []) => CliCommand::List {
+            app_id: app_filter,
+            launch,
+        },
+        ("invoke", [target]) => {
+            let (app_id, capability_id) = parse_target(target)?;
+            CliCommand::Invoke {
+                app_id,
+                capability_id,
+                input,
+                config,
+                stream,
+            }
+        }
+        ("options", [target, option_source]) => {
+            let (app_id, capability_id) = parse_target(target)?;
+            CliCommand::Options {
+                app_id,
+                capability_id,
+                option_source: parse_id(option_source, "optionSource")?,
+                config,
+                launch,
+            }
+        }
+        ("list" | "invoke" | "options", _) => {
+            return Err(format!("{subcommand} 的参数个数不对"));
+        }
+        _ => return Err(format!("未知子命令: {subcommand}")),
+    };
+    Ok(CliArgs { command, json })
+}
+
+fn parse_id(value: &str, label: &str) -> Result<String, String> {
+    let value = value.trim();
+    if !crate::is_safe_id(value) {
+        return Err(format!("{label} 不合法: {value}"));
+    }
+    Ok(value.to_string())
+}
+
+/// "<appId>/<capabilityId>"
+fn parse_target(target: &str) -> Result<(String, String), String> {
+    let Some((app_id, capability_id)) = target.split_once('/') else {
+        return Err(format!("能力应写成 <appId>/<capabilityId>: {target}"));
+    };
+    Ok((
+        parse_id(app_id, "appId")?,
+        parse_id(capability_id, "capabilityId")?,
+    ))
+}
+
+struct CliEndpoint {
+    url: String,
+    token: String,
+}
+
+/// 宿主可能用的基础目录，顺序与 workspace::paths::app_local_base_dir 相同
+fn host_base_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = crate::portable_base_dir_from_env() {
+        dirs.push(dir);
+    }
+    if let Some(dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from))
+    {
+        dirs.push(dir);
+    }
+    if let Some(dir) = platform_data_dir() {
+        dirs.push(dir.join(APP_IDENTIFIER));
+    }
+    dirs
+}
+
+#[cfg(target_os = "windows")]
+fn platform_data_dir() -> Option<PathBuf> {
+    std::env::var_os("APPDATA").map(PathBuf::from)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_data_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn platform_data_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+}
+
+fn discover_endpoint() -> Result<CliEndpoint, String> {
+    let env = |key: &str| {
+        std::env::var(key)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    if let (Some(url), Some(token)) = (
+        env("FW_HOST_CAPABILITY_URL"),
+        env("FW_HOST_CAPABILITY_TOKEN"),
+    ) {
+        return Ok(CliEndpoint { url, token });
+    }
+
+    for base in host_base_dirs() {
+        let path = capability_discovery_path(&base.join("data"));
+        let Ok(bytes) = std::fs::read(&path) else {
+            continue;
+        };
+        let file = serde_json::from_slice::<CapabilityDiscoveryFile>(&bytes)
+            .map_err(|e| format!("能力服务信息文件损坏（{}）: {e}", path.display()))?;
+        return Ok(CliEndpoint {
+            url: file.url,
+            token: file.token,
+        });
+    }
+    Err("找不到正在运行的 Fast Window，请先启动宿主".to_string())
+}
+
+struct CliClient {
+    endpoint: CliEndpoint,
+    http: reqwest::Client,
+}
+
+impl CliClient {
+    fn new(endpoint: CliEndpoint) -> Result<Self, String> {
+        let http = reqwest::Client::builder()
+            .no_proxy()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {e}"))?;
+        Ok(Self { endpoint, http })
+    }
+
+    async fn send(&self, path: &str, body: Option<&Value>) -> Result<reqwest::Response, String> {
+        let url = format!("{}{path}", self.endpoint.url.trim_end_matches('/'));
+        let request = match body {
+            Some(body) => self.http.post(url).json(body),
+            None => self.http.get(url),
+        };
+        let response = request
+            .header("X-FW-Control-Token", &self.endpoint.token)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    "连接不上 Fast Window 能力服务，宿主可能已经退出".to_string()
+                } else {
+                    format!("请求能力服务失败: {e}")
+                }
+            })?;
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        Err(error_text(status.as_u16(), &text))
+    }
+
+    async fn json(&self, path: &str, body: Option<&Value>) -> Result<Value, String> {
+        self.send(path, body)
+            .await?
+            .json::<Value>()
+            .await
+            .map_err(|e| format!("能力服务响应不是有效 JSON: {e}"))
+    }
+}
+
+fn error_text(status: u16, body: &str) -> String {
+    let message = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|value| {
+            value
+                .get("error")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| body.trim().to_string());
+    match status {
+        401 => format!("能力服务拒绝了令牌（{message}），宿主可能已重启"),
+        _ => format!("能力服务返回 {status}: {message}"),
+    }
+}
+
+async fn run(args: CliArgs) -> Result<(), String> {
+    let client = CliClient::new(discover_endpoint()?)?;
+    match args.command {
+        CliCommand::Help => Ok(()),
+        CliCommand::List { app_id, launch } => {
+            let mut query = url::form_urlencoded::Serializer::new(String::new());
+            if let Some(app_id) = &app_id {
+                query.append_pair("appId", app_id);
+            }
+            if launch {
+                query.append_pair("launchPolicy", "allowLaunch");
+            }
+            let query = query.finish();
+            let path = if query.is_empty() {
+                "/capabilities".to_string()
+            } else {
+                format!("/capabilities?{query}")
+            };
+            let body = client.json(&path, None).await?;
+            if args.json {
+                print_json(&body);
+            } else {
+                for error in list_errors_text(&body) {
+                    eprintln!("{error}");
+                }
+                print_text(&capability_list_text(&body));
+            }
+            Ok(())
+        }
+        CliCommand::Invoke {
+            app_id,
+            capability_id,
+            input,
+            config,
+            stream,
+        } => {
+            let input = match input {
+                None => Value::String(String::new()),
+                Some(CliInput::Text(text)) => Value::String(text),
+                Some(CliInput::Json(value)) => value,
+                Some(CliInput::Stdin) => {
+                    let mut text = String::new();
+                    std::io::stdin()
+                        .read_to_string(&mut text)
+                        .map_err(|e| format!("读取标准输入失败: {e}"))?;
+                    Value::String(text)
+                }
+            };
+            let body = serde_json::json!({
+                "appId": app_id,
+                "capabilityId": capability_id,
+                "input": input,
+                "config": config,
+            });
+            if stream {
+                return invoke_stream(&client, &body, args.json).await;
+            }
+            let response = client.json("/capability/invoke", Some(&body)).await?;
+            if args.json {
+                print_json(&response);
+            } else {
+                print_text(
+                    response
+                        .get("text")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default(),
+                );
+            }
+            Ok(())
+        }
+        CliCommand::Options {
+            app_id,
+            capability_id,
+            option_source,
+            config,
+            launch,
+        } => {
+            let body = serde_json::json!({
+                "appId": app_id,
+                "capabilityId": capability_id,
+                "optionSource": option_source,
+                "config": config,
+                "launchPolicy": if launch { "allowLaunch" } else { "runningOnly" },
+            });
+            let response = client
+                .json("/capability/query-options", Some(&body))
+                .await?;
+            if args.json {
+                print_json(&response);
+            } else {
+                print_text(&options_text(&response));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// 流式调用：纯文本模式边收边输出分块；应用不支持流式时没有分块，最后输出完整结果。
+async fn invoke_stream(client: &CliClient, body: &Value, json: bool) -> Result<(), String> {
+    let mut response = client.send("/capability/invoke-stream", Some(body)).await?;
+    let mut pending = Vec::new();
+    let mut printed = false;
+    let mut failure = None;
+    let mut out = std::io::stdout();
+    loop {
+        let chunk = response
+            .chunk()
+            .await
+            .map_err(|e| format!("读取流式响应失败: {e}"))?;
+        let Some(chunk) = chunk else {
+            break;
+        };
+        pending.extend_from_slice(&chunk);
+        while let Some(end) = pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            let Ok(event) = serde_json::from_slice::<Value>(&line) else {
+                continue;
+            };
+            if json {
+                let _ = writeln!(out, "{event}");
+                let _ = out.flush();
+                continue;
+            }
+            match event.get("type").and_then(Value::as_str) {
+                Some("chunk") => {
+                    let text = event
+                        .get("text")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    let _ = write!(out, "{text}");
+                    let _ = out.flush();
+                    printed = true;
+                }
+                Some("done") if !printed => {
+                    let text = event
+                        .pointer("/result/text")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    let _ = write!(out, "{text}");
+                    printed = true;
+                }
+                Some("error") => {
+                    failure = event
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .map(str::to_string);
+                }
+                _ => {}
+            }
+        }
+    }
+    if printed {
+        let _ = writeln!(out);
+    }
+    match failure {
+        Some(message) => Err(message),
+        None => Ok(()),
+    }
+}
+
+fn print_json(value: &Value) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).unwrap_or_default()
+    );
+}
+
+fn print_text(text: &str) {
+    if !text.is_empty() {
+        println!("{}", text.trim_end_matches('\n'));
+    }
+}
+
+/// 每行一项："<appId>/<capabilityId>\t<标题>"
+fn capability_list_text(body: &Value) -> String {
+    let field = |item: &Value, key: &str| {
+        item.get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    body.get("capabilities")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .map(|item| {
+                    let target =
+                        format!("{}/{}", field(item, "appId"), field(item, "capabilityId"));
+                    match field(item, "title") {
+                        title if title.is_empty() => target,
+                        title => format!("{target}\t{title}"),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+fn list_errors_text(body: &Value) -> Vec<String> {
+    body.get("errors")
+        .and_then(Value::as_array)
+        .map(|errors| {
+            errors
+                .iter()
+                .map(|error| {
+                    let app_id = error.get("appId").and_then(Value::as_str).unwrap_or("?");
+                    let message = error
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    format!("{app_id}: {message}")
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 每行一项："<value>\t<label>"，label 与 value 相同时只输出 value
+fn options_text(body: &Value) -> String {
+    body.get("options")
+        .and_then(Value::as_array)
+        .map(|options| {
+            options
+                .iter()
+                .filter_map(|option| {
+                    let value = option.get("value").and_then(Value::as_str)?;
+                    let label = option.get("label").and_then(Value::as_str).unwrap_or(value);
+                    Some(if label == value {
+                        value.to_string()
+                    } else {
+                        format!("{value}\t{label}")
+                    })
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(list: &[&str]) -> Result<CliArgs, String> {
+        parse_args(&list.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn parses_subcommands() {
+        assert_eq!(
+            args(&["list", "--app", "ocr", "--json"]).unwrap(),
+            CliArgs {
+                command: CliCommand::List {
+                    app_id: Some("ocr".to_string()),
+                    launch: false,
+                },
+                json: true,
+            }
+        );
+        assert_eq!(
+            args(&["invoke", "translator/translate", "--input", "-", "--stream"])
+                .unwrap()
+                .command,
+            CliCommand::Invoke {
+                app_id: "translator".to_string(),
+                capability_id: "translate".to_string(),
+                input: Some(CliInput::Stdin),
+                config: json!({}),
+                stream: true,
+            }
+        );
+        assert_eq!(
+            args(&[
+                "options",
+                "translator/translate",
+                "languages",
+                "--config",
+                r#"{"to":"en"}"#,
+                "--launch",
+            ])
+            .unwrap()
+            .command,
+            CliCommand::Options {
+                app_id: "translator".to_string(),
+                capability_id: "translate".to_string(),
+                option_source: "languages".to_string(),
+                config: json!({ "to": "en" }),
+                launch: true,
+            }
+        );
+        assert_eq!(args(&[]).unwrap().command, CliCommand::Help);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        let cases = [
+            (vec!["invoke", "translator"], "能力应写成"),
+            (vec!["invoke", "a/b", "extra"], "参数个数不对"),
+            (vec!["list", "--app"], "缺少参数值"),
+            (vec!["invoke", "a/b", "--config", "[1]"], "必须是 JSON 对象"),
+            (vec!["invoke", "a/b", "--input-json", "{"], "不是有效 JSON"),
+            (vec!["run"], "未知子命令"),
+            (vec!["list", "--verbose"], "未知选项"),
+            (vec!["invoke", "../x/b"], "appId 不合法"),
+        ];
+        for (list, expected) in cases {
+            let error = args(&list).unwrap_err();
+            assert!(error.contains(expected), "{list:?}: {error}");
+        }
+    }
+
+    #[test]
+    fn formats_plain_text_output() {
+        let list = json!({
+            "capabilities": [
+                { "appId": "ocr", "capabilityId": "capture", "title": "截图识字" },
+                { "appId": "notes", "capabilityId": "add" }
+            ],
+            "errors": [{ "appId": "player", "message": "应用未运行" }]
+        });
+        assert_eq!(
+            capability_list_text(&list),
+            "ocr/capture\t截图识字\nnotes/add"
+        );
+        assert_eq!(list_errors_text(&list), ["player: 应用未运行"]);
+
+        let options = json!({
+            "options": [
+                { "value": "en", "label": "English" },
+                { "value": "zh", "label": "zh" }
+            ]
+        });
+        assert_eq!(options_text(&options), "en\tEnglish\nzh");
+    }
+
+    #[test]
+    fn error_text_uses_server_message() {
+        assert_eq!(
+            error_text(
+                403,
+                r#"{"ok":false,"error":"调用方 @cli 无权调用 ocr/capture"}"#
+            ),
+            "能力服务返回 403: 调用方 @cli 无权调用 ocr/capture"
+        );
+        assert!(error_text(401, "").starts_with("能力服务拒绝了令牌"));
+    }
+}
@@ -0,0 +1,337 @@
+use serde_json::{Map, Value};
+
+// ── 能力输入 / 配置 / 输出的 JSON Schema ───────────────────────────────────
+//
+// 应用在能力声明里可带 inputSchema / configSchema / outputSchema，宿主调用前后各校验一次。
+// 只实现常用子集：type、enum、const、properties、required、additionalProperties、items、
+// minItems / maxItems、minLength / maxLength、minimum / maximum。
+// title、description、default、format、contentMediaType、contentEncoding 等注解原样交给调用方
+// 生成表单或渲染结果（图片用 contentMediaType + contentEncoding，文件列表用 format: "file-path"），
+// 不参与校验；其它不认识的关键字同样忽略。
+
+const MAX_SCHEMA_BYTES: usize = 32 * 1024;
+const MAX_SCHEMA_DEPTH: usize = 16;
+const SCHEMA_TYPES: [&str; 7] = [
+    "string", "number", "integer", "boolean", "object", "array", "null",
+];
+
+/// 校验应用声明的 schema 本身：只检查支持的关键字写法是否正确。
+pub(crate) fn validate_schema_declaration(schema: &Value) -> Result<(), String> {
+    if schema.to_string().len() > MAX_SCHEMA_BYTES {
+        return Err("schema 过大".to_string());
+    }
+    check_schema(schema, "schema", 0)
+}
+
+fn check_schema(schema: &Value, path: &str, depth: usize) -> Result<(), String> {
+    if depth > MAX_SCHEMA_DEPTH {
+        return Err(format!("{path} 嵌套过深"));
+    }
+    let object = match schema {
+        Value::Bool(_) => return Ok(()),
+        Value::Object(object) => object,
+        _ => return Err(format!("{path} 必须是对象或布尔值")),
+    };
+
+    if let Some(kind) = object.get("type") {
+        let kinds = match kind {
+            Value::String(kind) => vec![kind.as_str()],
+            Value::Array(kinds) if !kinds.is_empty() => kinds
+                .iter()
+                .map(|kind| kind.as_str().unwrap_or_default())
+                .collect(),
+            _ => return Err(format!("{path}.type 必须是字符串或非空数组")),
+        };
+        if let Some(kind) = kinds.iter().find(|kind| !SCHEMA_TYPES.contains(kind)) {
+            return Err(format!("{path}.type 不支持: {kind}"));
+        }
+    }
+    if object.get("enum").is_some_and(|value| !value.is_array()) {
+        return Err(format!("{path}.enum 必须是数组"));
+    }
+    if let Some(required) = object.get("required") {
+        let valid = required
+            .as_array()
+            .is_some_and(|keys| keys.iter().all(Value::is_string));
+        if !valid {
+            return Err(format!("{path}.required 必须是字符串数组"));
+        }
+    }
+    for key in ["minItems", "maxItems", "minLength", "maxLength"] {
+        if object
+            .get(key)
+            .is_some_and(|value| value.as_u64().is_none())
+        {
+            return Err(format!("{path}.{key} 必须是非负整数"));
+        }
+    }
+    for key in ["minimum", "maximum"] {
+        if object.get(key).is_some_and(|value| !value.is_number()) {
+            return Err(format!("{path}.{key} 必须是数字"));
+        }
+    }
+
+    if let Some(properties) = object.get("properties") {
+        let Some(properties) = properties.as_object() else {
+            return Err(format!("{path}.properties 必须是对象"));
+        };
+        for (key, property) in properties {
+            check_schema(property, &format!("{path}.properties.{key}"), depth + 1)?;
+        }
+    }
+    if let Some(additional) = object.get("additionalProperties") {
+        check_schema(
+            additional,
+            &format!("{path}.additionalProperties"),
+            depth + 1,
+        )?;
+    }
+    if let Some(items) = object.get("items") {
+        check_schema(items, &format!("{path}.items"), depth + 1)?;
+    }
+    Ok(())
+}
+
+/// 按 schema 校验一个值；`path` 是错误信息里的字段名，如 "input"。
+pub(crate) fn validate_against_schema(
+    schema: &Value,
+    value: &Value,
+    path: &str,
+) -> Result<(), String> {
+    let object = match schema {
+        Value::Bool(true) => return Ok(()),
+        Value::Bool(false) => return Err(format!("{path} 不允许出现")),
+        Value::Object(object) => object,
+        _ => return Ok(()),
+    };
+
+    if let Some(kind) = object.get("type") {
+        let kinds: Vec<&str> = match kind {
+            Value::String(kind) => vec![kind.as_str()],
+            Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !kinds.is_empty() && !kinds.iter().any(|kind| value_is_type(value, kind)) {
+            return Err(format!("{path} 类型不符，应为 {}", kinds.join(" / ")));
+        }
+    }
+    if let Some(options) = object.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            return Err(format!("{path} 不在可选值中"));
+        }
+    }
+    if let Some(expected) = object.get("const") {
+        if expected != value {
+            return Err(format!("{path} 必须是 {expected}"));
+        }
+    }
+
+    match value {
+        Value::Object(fields) => validate_object(object, fields, path)?,
+        Value::Array(items) => {
+            let count = items.len() as u64;
+            if let Some(min) = object.get("minItems").and_then(Value::as_u64) {
+                if count < min {
+                    return Err(format!("{path} 至少需要 {min} 项"));
+                }
+            }
+            if let Some(max) = object.get("maxItems").and_then(Value::as_u64) {
+                if count > max {
+                    return Err(format!("{path} 最多 {max} 项"));
+                }
+            }
+            if let Some(item_schema) = object.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_against_schema(item_schema, item, &format!("{path}[{index}]"))?;
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if let Some(min) = object.get("minLength").and_then(Value::as_u64) {
+                if length < min {
+                    return Err(format!("{path} 长度不能少于 {min}"));
+                }
+            }
+            if let Some(max) = object.get("maxLength").and_then(Value::as_u64) {
+                if length > max {
+                    return Err(format!("{path} 长度不能超过 {max}"));
+                }
+            }
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(min) = object.get("minimum").and_then(Value::as_f64) {
+                if number < min {
+                    return Err(format!("{path} 不能小于 {min}"));
+                }
+            }
+            if let Some(max) = object.get("maximum").and_then(Value::as_f64) {
+                if number > max {
+                    return Err(format!("{path} 不能大于 {max}"));
+                }
+            }
+        }
+        Value::Bool(_) | Value::Null => {}
+    }
+    Ok(())
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    fields: &Map<String, Value>,
+    path: &str,
+) -> Result<(), String> {
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !fields.contains_key(key) {
+                return Err(format!("{path} 缺少必填字段 {key}"));
+            }
+        }
+    }
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (key, field) in fields {
+        let field_path = format!("{path}.{key}");
+        match properties.and_then(|properties| properties.get(key)) {
+            Some(property) => validate_against_schema(property, field, &field_path)?,
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    return Err(format!("{path} 不允许字段 {key}"));
+                }
+                Some(additional) => validate_against_schema(additional, field, &field_path)?,
+                None => {}
+            },
+        }
+    }
+    Ok(())
+}
+
+fn value_is_type(value: &Value, kind: &str) -> bool {
+    match kind {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+/// 应用没给 text 时，按输出 schema 给结构化结果生成一段展示文字。
+pub(crate) fn schema_result_summary(schema: &Value, result: &Value) -> String {
+    if let Some(media_type) = schema.get("contentMediaType").and_then(Value::as_str) {
+        return format!("[{media_type}]");
+    }
+    match result {
+        Value::String(text) => text.trim().to_string(),
+        Value::Array(items) if items.iter().all(Value::is_string) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => serde_json::to_string_pretty(result).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn form_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["text"],
+            "additionalProperties": false,
+            "properties": {
+                "text": { "type": "string", "minLength": 1, "title": "内容" },
+                "count": { "type": "integer", "minimum": 1, "maximum": 5 },
+                "mode": { "enum": ["short", "long"] },
+                "tags": { "type": "array", "items": { "type": "string" }, "maxItems": 2 }
+            }
+        })
+    }
+
+    #[test]
+    fn accepts_valid_value() {
+        let value = json!({ "text": "hi", "count": 2, "mode": "short", "tags": ["a"] });
+        assert!(validate_against_schema(&form_schema(), &value, "input").is_ok());
+    }
+
+    #[test]
+    fn reports_path_of_invalid_field() {
+        let schema = form_schema();
+        let cases = [
+            (json!({}), "input 缺少必填字段 text"),
+            (json!({ "text": 1 }), "input.text 类型不符，应为 string"),
+            (json!({ "text": "a", "count": 9 }), "input.count 不能大于 5"),
+            (
+                json!({ "text": "a", "count": 1.5 }),
+                "input.count 类型不符，应为 integer",
+            ),
+            (
+                json!({ "text": "a", "mode": "x" }),
+                "input.mode 不在可选值中",
+            ),
+            (
+                json!({ "text": "a", "tags": [1] }),
+                "input.tags[0] 类型不符，应为 string",
+            ),
+            (
+                json!({ "text": "a", "tags": ["a", "b", "c"] }),
+                "input.tags 最多 2 项",
+            ),
+            (
+                json!({ "text": "a", "extra": true }),
+                "input 不允许字段 extra",
+            ),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(
+                validate_against_schema(&schema, &value, "input").unwrap_err(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn checks_schema_declarations() {
+        assert!(validate_schema_declaration(&form_schema()).is_ok());
+        assert!(validate_schema_declaration(&json!(true)).is_ok());
+        assert!(validate_schema_declaration(&json!("string")).is_err());
+        assert!(validate_schema_declaration(&json!({ "type": "text" })).is_err());
+        assert!(validate_schema_declaration(&json!({ "required": "text" })).is_err());
+        assert_eq!(
+            validate_schema_declaration(&json!({
+                "properties": { "a": { "maxLength": -1 } }
+            }))
+            .unwrap_err(),
+            "schema.properties.a.maxLength 必须是非负整数"
+        );
+    }
+
+    #[test]
+    fn summarizes_structured_results() {
+        let image = json!({ "type": "string", "contentEncoding": "base64", "contentMediaType": "image/png" });
+        assert_eq!(
+            schema_result_summary(&image, &json!("iVBOR")),
+            "[image/png]"
+        );
+        let files =
+            json!({ "type": "array", "items": { "type": "string", "format": "file-path" } });
+        assert_eq!(
+            schema_result_summary(&files, &json!(["C:\\a.txt", "C:\\b.txt"])),
+            "C:\\a.txt\nC:\\b.txt"
+        );
+        assert_eq!(
+            schema_result_summary(&json!({ "type": "object" }), &json!({ "n": 1 })),
+            "{\n  \"n\": 1\n}"
+        );
+    }
+}
@@ -1,30 +1,40 @@
 use std::io::{self, Write};
 use std::net::TcpListener;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock};
-use std::thread;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::AppHandle;
 
 use crate::app_lifecycle::manager::AppLifecycleManager;
 
+pub(super) mod access;
+pub(super) mod audit;
 pub(super) mod capability_service;
 pub(super) mod http_transport;
 
 use capability_service::CapabilityService;
 
 const BIND_ADDR: &str = "127.0.0.1:0";
+const DISCOVERY_FILE_NAME: &str = "capability-server.json";
+// 同时处理的请求上限；超出的请求排队等待
+const MAX_CONCURRENT_REQUESTS: usize = 16;
 
 static CAPABILITY_ENDPOINT: OnceLock<CapabilityServerEndpoint> = OnceLock::new();
 
-pub(crate) fn capability_server_env_vars() -> Vec<(String, String)> {
-    CAPABILITY_ENDPOINT.get().map_or(Vec::new(), |ep| {
-        vec![
-            ("FW_HOST_CAPABILITY_URL".to_string(), ep.url.clone()),
-            ("FW_HOST_CAPABILITY_TOKEN".to_string(), ep.token.clone()),
-        ]
-    })
+/// 注入给托管应用的环境变量；令牌按应用单独签发，能力服务据此认出调用方。
+pub(crate) fn capability_server_env_vars(caller_app_id: &str) -> Vec<(String, String)> {
+    let Some(ep) = CAPABILITY_ENDPOINT.get() else {
+        return Vec::new();
+    };
+    let Some(token) = access::issue_caller_token(caller_app_id) else {
+        return Vec::new();
+    };
+    vec![
+        ("FW_HOST_CAPABILITY_URL".to_string(), ep.url.clone()),
+        ("FW_HOST_CAPABILITY_TOKEN".to_string(), token),
+    ]
 }
 
 #[derive(Clone, Serialize)]
@@ -34,6 +44,54 @@ pub(crate) struct CapabilityServerEndpoint {
     pub(crate) token: String,
 }
 
+/// 宿主运行期间写在数据目录里的能力服务地址，供命令行模式查找；令牌属于调用方 "@cli"。
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CapabilityDiscoveryFile {
+    pub(crate) url: String,
+    pub(crate) token: String,
+    pub(crate) pid: u32,
+    pub(crate) protocol_version: u32,
+}
+
+/// `data_dir` 是宿主数据目录（<base>/data）
+pub(crate) fn capability_discovery_path(data_dir: &Path) -> PathBuf {
+    data_dir
+        .join(crate::APP_STORAGE_ID)
+        .join(DISCOVERY_FILE_NAME)
+}
+
+fn write_discovery_file(app: &AppHandle, endpoint: &CapabilityServerEndpoint) {
+    let Some(token) = access::issue_caller_token(access::CLI_CALLER) else {
+        return;
+    };
+    let file = CapabilityDiscoveryFile {
+        url: endpoint.url.clone(),
+        token,
+        pid: std::process::id(),
+        protocol_version: 1,
+    };
+    let path = capability_discovery_path(&crate::app_data_dir(app));
+    let result = serde_json::to_value(&file)
+        .map_err(|e| e.to_string())
+        .and_then(|value| crate::json_file::write_pretty(&path, &value));
+    if let Err(error) = result {
+        eprintln!("[capability-server] failed to write discovery file: {error}");
+    }
+}
+
+/// 宿主退出时调用；只删自己写的那份，避免误删另一个宿主实例的。
+pub(crate) fn remove_capability_discovery_file(app: &AppHandle) {
+    let path = capability_discovery_path(&crate::app_data_dir(app));
+    let owned = std::fs::read(&path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<CapabilityDiscoveryFile>(&bytes).ok())
+        .is_some_and(|file| file.pid == std::process::id());
+    if owned {
+        let _ = std::fs::remove_file(&path);
+    }
+}
+
 pub(crate) fn start_capability_server(
     app: AppHandle,
     lifecycle: Arc<AppLifecycleManager>,
@@ -50,26 +108,29 @@ pub(crate) fn start_capability_server(
     };
 
     let _ = CAPABILITY_ENDPOINT.set(endpoint.clone());
+    access::register_host_token(&endpoint.token);
     announce_server_ready(&endpoint);
-
-    let expected_token = endpoint.token.clone();
-    let service = CapabilityService::new(app, lifecycle);
-    thread::Builder::new()
-        .name("fw-capability-server".to_string())
-        .spawn(move || {
-            for stream in listener.incoming() {
-                match stream {
-                    Ok(stream) => {
-                        http_transport::handle_connection(stream, &expected_token, &service)
-                    }
-                    Err(error) => {
-                        eprintln!("[capability-server] connection failed: {error}");
-                        break;
-                    }
-                }
+    write_discovery_file(&app, &endpoint);
+
+    let service = Arc::new(CapabilityService::new(app, lifecycle));
+    tauri::async_runtime::spawn(http_transport::serve(
+        listener,
+        access::caller_for_token,
+        MAX_CONCURRENT_REQUESTS,
+        move |request: http_transport::CapabilityHttpRequest| {
+            let service = service.clone();
+            async move {
+                service
+                    .handle_request(
+                        &request.caller,
+                        &request.method,
+                        &request.path,
+                        &request.body,
+                    )
+                    .await
             }
-        })
-        .map_err(|e| format!("启动能力HTTP服务线程失败: {e}"))?;
+        },
+    ));
 
     Ok(endpoint)
 }
@@ -88,27 +149,40 @@ fn announce_server_ready(endpoint: &CapabilityServerEndpoint) {
 
 pub(super) struct CapabilityHttpResponse {
     pub(super) status: u16,
-    pub(super) body: Value,
+    pub(super) body: CapabilityHttpBody,
+}
+
+pub(super) enum CapabilityHttpBody {
+    Json(Value),
+    /// 按行输出的 NDJSON；调用方断开时接收端被丢弃，发送端据此取消
+    Stream(tokio::sync::mpsc::UnboundedReceiver<Value>),
 }
 
 impl CapabilityHttpResponse {
     pub(super) fn json(status: u16, body: Value) -> Self {
-        Self { status, body }
-    }
-
-    pub(super) fn error(status: u16, error: impl Into<String>) -> Self {
         Self {
             status,
-            body: error_body(error),
+            body: CapabilityHttpBody::Json(body),
         }
     }
 
+    pub(super) fn error(status: u16, error: impl Into<String>) -> Self {
+        Self::json(status, error_body(error))
+    }
+
     pub(super) fn serialized<T: Serialize>(status: u16, value: T) -> Self {
         match serde_json::to_value(value) {
             Ok(body) => Self::json(status, body),
             Err(error) => Self::error(500, format!("响应序列化失败: {error}")),
         }
     }
+
+    pub(super) fn stream(events: tokio::sync::mpsc::UnboundedReceiver<Value>) -> Self {
+        Self {
+            status: 200,
+            body: CapabilityHttpBody::Stream(events),
+        }
+    }
 }
 
 fn error_body(error: impl Into<String>) -> Value {
@@ -0,0 +1,334 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+// ── 能力调用方与访问策略 ────────────────────────────────────────────────────
+//
+// 注入能力服务环境变量时，每个托管应用拿到自己的调用方令牌（宿主运行期间不变），
+// 能力服务按令牌认出调用方；宿主启动时公布的主令牌对应调用方 "@host"，不受策略限制；
+// 命令行模式用数据目录里 capability-server.json 中的令牌，调用方为 "@cli"。
+// 策略存在应用配置 capabilityAccess 下：
+//   { "default": { "allow": ["*"], "allowLaunch": true },
+//     "callers": { "quick-bar": { "allow": ["translator/*", "@workflow/daily"], "allowLaunch": false } } }
+// allow 里的目标写成 "<appId>/<capabilityId>"，"<appId>/*" 匹配该应用全部能力，"*" 匹配全部；
+// 工作流写成 "@workflow/<workflowId>"。callers 里没列出的调用方用 default；
+// 没有配置时全部允许、允许拉起应用，与引入策略前一致。
+// 不允许拉起应用的调用方，请求里的 allowLaunch 按 runningOnly 处理；
+// 宿主以外的调用方只能用注册信息里的启动配置，请求里的 app、launchOptions 不生效。
+
+pub(crate) const HOST_CALLER: &str = "@host";
+pub(crate) const CLI_CALLER: &str = "@cli";
+pub(crate) const WORKFLOW_TARGET_PREFIX: &str = "@workflow/";
+const CAPABILITY_ACCESS_KEY: &str = "capabilityAccess";
+const MAX_ALLOW_ENTRIES: usize = 256;
+
+#[derive(Default)]
+struct CallerTokens {
+    by_token: HashMap<String, String>,
+    by_caller: HashMap<String, String>,
+}
+
+fn caller_tokens() -> &'static Mutex<CallerTokens> {
+    static TOKENS: OnceLock<Mutex<CallerTokens>> = OnceLock::new();
+    TOKENS.get_or_init(|| Mutex::new(CallerTokens::default()))
+}
+
+/// 登记宿主主令牌
+pub(super) fn register_host_token(token: &str) {
+    if let Ok(mut tokens) = caller_tokens().lock() {
+        tokens
+            .by_token
+            .insert(token.to_string(), HOST_CALLER.to_string());
+    }
+}
+
+/// 给调用方签发令牌；同一调用方重复注入时沿用已有令牌，重启后的应用仍能调用。
+pub(super) fn issue_caller_token(caller: &str) -> Option<String> {
+    let mut tokens = caller_tokens().lock().ok()?;
+    if let Some(token) = tokens.by_caller.get(caller) {
+        return Some(token.clone());
+    }
+    let token = super::random_token("fw-app-capability");
+    tokens.by_token.insert(token.clone(), caller.to_string());
+    tokens.by_caller.insert(caller.to_string(), token.clone());
+    Some(token)
+}
+
+/// 令牌对应的调用方；未知令牌返回 None
+pub(super) fn caller_for_token(token: &str) -> Option<String> {
+    if token.is_empty() {
+        return None;
+    }
+    caller_tokens().lock().ok()?.by_token.get(token).cloned()
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CapabilityCallerPolicy {
+    #[serde(default)]
+    pub(crate) allow: Vec<String>,
+    #[serde(default)]
+    pub(crate) allow_launch: bool,
+}
+
+impl CapabilityCallerPolicy {
+    fn unrestricted() -> Self {
+        Self {
+            allow: vec!["*".to_string()],
+            allow_launch: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CapabilityAccessPolicy {
+    #[serde(default = "CapabilityCallerPolicy::unrestricted")]
+    pub(crate) default: CapabilityCallerPolicy,
+    #[serde(default)]
+    pub(crate) callers: BTreeMap<String, CapabilityCallerPolicy>,
+}
+
+impl Default for CapabilityAccessPolicy {
+    fn default() -> Self {
+        Self {
+            default: CapabilityCallerPolicy::unrestricted(),
+            callers: BTreeMap::new(),
+        }
+    }
+}
+
+impl CapabilityAccessPolicy {
+    fn validate(&self) -> Result<(), String> {
+        validate_caller_policy(&self.default, "default")?;
+        for (caller, policy) in &self.callers {
+            if caller != CLI_CALLER && !crate::is_safe_id(caller) {
+                return Err(format!("调用方 {caller} 不合法"));
+            }
+            validate_caller_policy(policy, caller)?;
+        }
+        Ok(())
+    }
+
+    /// 某个调用方实际生效的权限
+    pub(super) fn access_for(&self, caller: &str) -> CallerAccess {
+        if caller == HOST_CALLER {
+            return CallerAccess {
+                caller: caller.to_string(),
+                policy: CapabilityCallerPolicy::unrestricted(),
+            };
+        }
+        CallerAccess {
+            caller: caller.to_string(),
+            policy: self.callers.get(caller).unwrap_or(&self.default).clone(),
+        }
+    }
+}
+
+fn validate_caller_policy(policy: &CapabilityCallerPolicy, label: &str) -> Result<(), String> {
+    if policy.allow.len() > MAX_ALLOW_ENTRIES {
+        return Err(format!("{label} 的 allow 条目过多"));
+    }
+    for pattern in &policy.allow {
+        if !is_valid_pattern(pattern) {
+            return Err(format!("{label} 的 allow 条目不合法: {pattern}"));
+        }
+    }
+    Ok(())
+}
+
+fn is_valid_pattern(pattern: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+    if let Some(workflow_id) = pattern.strip_prefix(WORKFLOW_TARGET_PREFIX) {
+        return workflow_id == "*" || crate::is_safe_id(workflow_id);
+    }
+    match pattern.split_once('/') {
+        Some((app_id, capability_id)) => {
+            crate::is_safe_id(app_id) && (capability_id == "*" || crate::is_safe_id(capability_id))
+        }
+        None => false,
+    }
+}
+
+fn pattern_matches(pattern: &str, target: &str) -> bool {
+    if pattern == "*" || pattern == target {
+        return true;
+    }
+    match pattern.strip_suffix("/*") {
+        Some(prefix) => target
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/')),
+        None => false,
+    }
+}
+
+/// 一次请求的调用方与其权限
+#[derive(Clone, Debug)]
+pub(super) struct CallerAccess {
+    pub(super) caller: String,
+    policy: CapabilityCallerPolicy,
+}
+
+impl CallerAccess {
+    /// `target` 形如 "<appId>/<capabilityId>" 或 "@workflow/<workflowId>"
+    pub(super) fn allows(&self, target: &str) -> bool {
+        self.policy
+            .allow
+            .iter()
+            .any(|pattern| pattern_matches(pattern, target))
+    }
+
+    /// 列能力清单时用：该应用是否至少有一项能力可能被允许
+    pub(super) fn allows_any_of_app(&self, app_id: &str) -> bool {
+        self.policy.allow.iter().any(|pattern| {
+            pattern == "*"
+                || pattern
+                    .split_once('/')
+                    .is_some_and(|(pattern_app, _)| pattern_app == app_id)
+        })
+    }
+
+    /// 宿主自身发起的请求，可以自带应用启动配置
+    pub(super) fn is_host(&self) -> bool {
+        self.caller == HOST_CALLER
+    }
+
+    pub(super) fn allow_launch(&self) -> bool {
+        self.policy.allow_launch
+    }
+
+    pub(super) fn denied_message(&self, target: &str) -> String {
+        format!("调用方 {} 无权调用 {target}", self.caller)
+    }
+}
+
+pub(crate) fn load_capability_access_policy(app: &AppHandle) -> CapabilityAccessPolicy {
+    let map = crate::read_app_config_map(app);
+    let Some(value) = map.get(CAPABILITY_ACCESS_KEY) else {
+        return CapabilityAccessPolicy::default();
+    };
+    match serde_json::from_value::<CapabilityAccessPolicy>(value.clone())
+        .map_err(|e| e.to_string())
+        .and_then(|policy| policy.validate().map(|_| policy))
+    {
+        Ok(policy) => policy,
+        Err(error) => {
+            // 配置损坏时宁可拒绝，也不放开所有调用方
+            eprintln!("[capability-server] invalid capabilityAccess: {error}");
+            CapabilityAccessPolicy {
+                default: CapabilityCallerPolicy {
+                    allow: Vec::new(),
+                    allow_launch: false,
+                },
+                callers: BTreeMap::new(),
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) fn capability_access_get(app: AppHandle) -> CapabilityAccessPolicy {
+    load_capability_access_policy(&app)
+}
+
+#[tauri::command]
+pub(crate) fn capability_access_set(
+    app: AppHandle,
+    policy: CapabilityAccessPolicy,
+) -> Result<CapabilityAccessPolicy, String> {
+    policy.validate()?;
+    let value =
+        serde_json::to_value(&policy).map_err(|e| format!("序列化能力访问策略失败: {e}"))?;
+    crate::update_app_config_map(&app, |map| {
+        map.insert(CAPABILITY_ACCESS_KEY.to_string(), value);
+        Ok(())
+    })?;
+    Ok(policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn policy(value: Value) -> CapabilityAccessPolicy {
+        let policy: CapabilityAccessPolicy = serde_json::from_value(value).unwrap();
+        policy.validate().unwrap();
+        policy
+    }
+
+    #[test]
+    fn missing_config_keeps_everything_allowed() {
+        let access = CapabilityAccessPolicy::default().access_for("quick-bar");
+        assert!(access.allows("translator/translate"));
+        assert!(access.allows("@workflow/daily"));
+        assert!(access.allow_launch());
+    }
+
+    #[test]
+    fn caller_entries_override_default() {
+        let policy = policy(json!({
+            "default": { "allow": [] },
+            "callers": {
+                "quick-bar": {
+                    "allow": ["translator/*", "ocr/capture", "@workflow/daily"],
+                    "allowLaunch": true
+                },
+                "@cli": { "allow": ["ocr/*"] }
+            }
+        }));
+        let quick_bar = policy.access_for("quick-bar");
+        assert!(quick_bar.allows("translator/translate"));
+        assert!(quick_bar.allows("ocr/capture"));
+        assert!(!quick_bar.allows("ocr/scan"));
+        assert!(!quick_bar.allows("translator-pro/translate"));
+        assert!(quick_bar.allows("@workflow/daily"));
+        assert!(!quick_bar.allows("@workflow/other"));
+        assert!(quick_bar.allow_launch());
+        assert!(quick_bar.allows_any_of_app("ocr"));
+        assert!(!quick_bar.allows_any_of_app("notes"));
+
+        let other = policy.access_for("notes");
+        assert!(!other.allows("translator/translate"));
+        assert!(!other.allow_launch());
+
+        let cli = policy.access_for(CLI_CALLER);
+        assert!(cli.allows("ocr/scan"));
+        assert!(!cli.allow_launch());
+
+        let host = policy.access_for(HOST_CALLER);
+        assert!(host.is_host());
+        assert!(!quick_bar.is_host());
+        assert!(host.allows("notes/anything"));
+        assert!(host.allow_launch());
+    }
+
+    #[test]
+    fn rejects_malformed_patterns() {
+        for pattern in ["translator", "a/b/c", "../x", "@workflow/", "*/x"] {
+            let policy = CapabilityAccessPolicy {
+                default: CapabilityCallerPolicy {
+                    allow: vec![pattern.to_string()],
+                    allow_launch: false,
+                },
+                callers: BTreeMap::new(),
+            };
+            assert!(policy.validate().is_err(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn caller_tokens_are_stable_and_distinct() {
+        let first = issue_caller_token("token-test-a").unwrap();
+        assert_eq!(issue_caller_token("token-test-a").unwrap(), first);
+        let second = issue_caller_token("token-test-b").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(caller_for_token(&first).as_deref(), Some("token-test-a"));
+        assert_eq!(caller_for_token("unknown"), None);
+        assert_eq!(caller_for_token(""), None);
+    }
+}
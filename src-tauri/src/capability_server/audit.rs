@@ -0,0 +1,291 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::rotating_file::{read_file_tail, rotated_path, RotatingAppendFile};
+
+// ── 能力调用审计日志 ────────────────────────────────────────────────────────
+//
+// 能力服务上的每次调用（含被策略拒绝的）追加一行 JSON 到 <data>/__app/capability-audit.log：
+// 时间、调用方、入口、目标、耗时和结果。超过 MAX_AUDIT_BYTES 时轮转为
+// capability-audit.1.log … capability-audit.<MAX_ROTATED_FILES>.log，最旧的丢弃。
+// 只记元数据，不记输入和结果内容。
+
+const AUDIT_FILE_STEM: &str = "capability-audit";
+const MAX_AUDIT_BYTES: u64 = 1024 * 1024;
+const MAX_ROTATED_FILES: usize = 3;
+const DEFAULT_READ_ENTRIES: usize = 200;
+const MAX_READ_ENTRIES: usize = 2_000;
+const MAX_ERROR_CHARS: usize = 500;
+const AUDIT_LABEL: &str = "能力审计日志";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum CapabilityAuditKind {
+    Invoke,
+    InvokeStream,
+    QueryOptions,
+    WorkflowRun,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum CapabilityAuditOutcome {
+    Ok,
+    Error,
+    Denied,
+    Canceled,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CapabilityAuditEntry {
+    pub(crate) at: u64,
+    pub(crate) caller: String,
+    pub(crate) kind: CapabilityAuditKind,
+    pub(crate) target: String,
+    /// 实际生效的拉起策略；调用方不允许拉起应用时为 false
+    pub(crate) allow_launch: bool,
+    pub(crate) duration_ms: u64,
+    pub(crate) outcome: CapabilityAuditOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
+
+/// 一次调用的审计记录；`finish` 时写入日志。
+/// 没 finish 就被丢弃（处理超时、调用方断开）时记为 canceled。
+pub(super) struct CapabilityAuditRecord {
+    log: Arc<CapabilityAuditLog>,
+    at: u64,
+    started: Instant,
+    caller: String,
+    kind: CapabilityAuditKind,
+    target: String,
+    allow_launch: bool,
+    finished: bool,
+}
+
+impl CapabilityAuditRecord {
+    fn entry(&self, outcome: CapabilityAuditOutcome, error: Option<&str>) -> CapabilityAuditEntry {
+        CapabilityAuditEntry {
+            at: self.at,
+            caller: self.caller.clone(),
+            kind: self.kind,
+            target: self.target.clone(),
+            allow_launch: self.allow_launch,
+            duration_ms: self.started.elapsed().as_millis() as u64,
+            outcome,
+            error: error.map(|error| error.chars().take(MAX_ERROR_CHARS).collect()),
+        }
+    }
+
+    pub(super) fn finish(mut self, outcome: CapabilityAuditOutcome, error: Option<&str>) {
+        self.finished = true;
+        self.log.record(self.entry(outcome, error));
+    }
+
+    /// 按调用结果记 ok / error
+    pub(super) fn finish_with<T>(self, result: &Result<T, String>) {
+        match result {
+            Ok(_) => self.finish(CapabilityAuditOutcome::Ok, None),
+            Err(error) => self.finish(CapabilityAuditOutcome::Error, Some(error)),
+        }
+    }
+}
+
+impl Drop for CapabilityAuditRecord {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.log
+                .record(self.entry(CapabilityAuditOutcome::Canceled, Some("请求被中断")));
+        }
+    }
+}
+
+pub(super) fn audit_log_path(app: &AppHandle) -> PathBuf {
+    crate::app_data_dir(app)
+        .join(crate::APP_STORAGE_ID)
+        .join(format!("{AUDIT_FILE_STEM}.log"))
+}
+
+/// 审计日志写入端；首次写入时才打开文件
+pub(super) struct CapabilityAuditLog {
+    writer: Mutex<RotatingAppendFile>,
+}
+
+impl CapabilityAuditLog {
+    fn at_path(path: PathBuf, max_bytes: u64) -> Self {
+        Self {
+            writer: Mutex::new(RotatingAppendFile::new(
+                path,
+                max_bytes,
+                MAX_ROTATED_FILES,
+                AUDIT_LABEL,
+            )),
+        }
+    }
+
+    pub(super) fn new(app: &AppHandle) -> Self {
+        Self::at_path(audit_log_path(app), MAX_AUDIT_BYTES)
+    }
+
+    fn write_entry(&self, entry: &CapabilityAuditEntry) -> Result<(), String> {
+        let line = serde_json::to_string(entry)
+            .map_err(|e| format!("序列化能力审计记录失败: {e}"))?
+            + "\n";
+        self.writer
+            .lock()
+            .map_err(|_| "能力审计日志锁定失败".to_string())?
+            .append(&line)
+    }
+
+    fn record(&self, entry: CapabilityAuditEntry) {
+        if let Err(error) = self.write_entry(&entry) {
+            eprintln!("[capability-audit] {error}");
+        }
+    }
+
+    /// 开始记录一次调用；`target` 形如 "<appId>/<capabilityId>"
+    pub(super) fn start(
+        self: &Arc<Self>,
+        caller: &str,
+        kind: CapabilityAuditKind,
+        target: &str,
+        allow_launch: bool,
+    ) -> CapabilityAuditRecord {
+        CapabilityAuditRecord {
+            log: self.clone(),
+            at: crate::now_ms(),
+            started: Instant::now(),
+            caller: caller.to_string(),
+            kind,
+            target: target.to_string(),
+            allow_launch,
+            finished: false,
+        }
+    }
+}
+
+/// 读文件末尾的完整行；文件不存在时为空
+fn read_tail_lines(path: &Path, max_bytes: u64) -> Result<Vec<String>, String> {
+    let (text, _) = read_file_tail(path, max_bytes, AUDIT_LABEL)?;
+    Ok(text.lines().map(str::to_string).collect())
+}
+
+/// 最近 `count` 条记录，按时间先后排列；当前文件不够时往前补一个轮转文件。
+fn read_recent_entries(path: &Path, count: usize) -> Result<Vec<CapabilityAuditEntry>, String> {
+    let parse = |lines: Vec<String>| -> Vec<CapabilityAuditEntry> {
+        lines
+            .iter()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    };
+    let mut entries = parse(read_tail_lines(path, MAX_AUDIT_BYTES)?);
+    if entries.len() < count {
+        let mut older = parse(read_tail_lines(&rotated_path(path, 1), MAX_AUDIT_BYTES)?);
+        older.append(&mut entries);
+        entries = older;
+    }
+    let start = entries.len().saturating_sub(count);
+    Ok(entries.split_off(start))
+}
+
+#[tauri::command]
+pub(crate) fn capability_audit_log(
+    app: AppHandle,
+    limit: Option<usize>,
+) -> Result<Vec<CapabilityAuditEntry>, String> {
+    let count = limit
+        .unwrap_or(DEFAULT_READ_ENTRIES)
+        .clamp(1, MAX_READ_ENTRIES);
+    read_recent_entries(&audit_log_path(&app), count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_audit_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "fw-capability-audit-{name}-{}-{}",
+            std::process::id(),
+            crate::now_ms()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join(format!("{AUDIT_FILE_STEM}.log"))
+    }
+
+    fn record_call(log: &Arc<CapabilityAuditLog>, target: &str, outcome: CapabilityAuditOutcome) {
+        let error = (outcome != CapabilityAuditOutcome::Ok).then_some("失败");
+        log.start("quick-bar", CapabilityAuditKind::Invoke, target, true)
+            .finish(outcome, error);
+    }
+
+    #[test]
+    fn records_and_reads_back_recent_entries() {
+        let path = temp_audit_path("read");
+        let log = Arc::new(CapabilityAuditLog::at_path(path.clone(), MAX_AUDIT_BYTES));
+        record_call(&log, "ocr/capture", CapabilityAuditOutcome::Ok);
+        record_call(&log, "notes/add", CapabilityAuditOutcome::Denied);
+
+        let entries = read_recent_entries(&path, 10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].target, "ocr/capture");
+        assert_eq!(entries[0].error, None);
+        assert_eq!(entries[1].outcome, CapabilityAuditOutcome::Denied);
+        assert_eq!(entries[1].caller, "quick-bar");
+
+        let latest = read_recent_entries(&path, 1).unwrap();
+        assert_eq!(latest[0].target, "notes/add");
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn dropped_record_is_logged_as_canceled() {
+        let path = temp_audit_path("drop");
+        let log = Arc::new(CapabilityAuditLog::at_path(path.clone(), MAX_AUDIT_BYTES));
+        drop(log.start(
+            "@host",
+            CapabilityAuditKind::InvokeStream,
+            "ocr/capture",
+            true,
+        ));
+        log.start(
+            "@host",
+            CapabilityAuditKind::WorkflowRun,
+            "@workflow/daily",
+            true,
+        )
+        .finish_with::<()>(&Err("x".repeat(MAX_ERROR_CHARS * 2)));
+
+        let entries = read_recent_entries(&path, 10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].outcome, CapabilityAuditOutcome::Canceled);
+        assert_eq!(entries[1].outcome, CapabilityAuditOutcome::Error);
+        assert_eq!(
+            entries[1].error.as_ref().unwrap().chars().count(),
+            MAX_ERROR_CHARS
+        );
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn rotates_when_file_grows_too_large() {
+        let path = temp_audit_path("rotate");
+        let log = Arc::new(CapabilityAuditLog::at_path(path.clone(), 300));
+        for index in 0..6 {
+            record_call(&log, &format!("app/cap{index}"), CapabilityAuditOutcome::Ok);
+        }
+        assert!(rotated_path(&path, 1).is_file());
+        assert!(!rotated_path(&path, MAX_ROTATED_FILES + 1).exists());
+
+        // 当前文件不够时从上一个轮转文件补齐
+        let entries = read_recent_entries(&path, 3).unwrap();
+        let targets: Vec<&str> = entries.iter().map(|e| e.target.as_str()).collect();
+        assert_eq!(targets, ["app/cap3", "app/cap4", "app/cap5"]);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -1,53 +1,124 @@
 use std::sync::Arc;
+use std::time::Duration;
 
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use serde_json::{Map, Value};
 use tauri::AppHandle;
 
+use super::access::{load_capability_access_policy, CallerAccess, WORKFLOW_TARGET_PREFIX};
+use super::audit::{
+    CapabilityAuditKind, CapabilityAuditLog, CapabilityAuditOutcome, CapabilityAuditRecord,
+};
 use super::CapabilityHttpResponse;
 use crate::app_capabilities::{
-    app_capability_invoke_inner, app_capability_query_options_inner, describe_app_capabilities,
+    app_capability_invoke_inner, app_capability_invoke_streaming,
+    app_capability_query_options_inner, describe_app_capabilities, make_capability_invocation_id,
     AppCapabilityInvokeRequest, AppCapabilityLaunchPolicy, AppCapabilityOptionsRequest,
+    AppCapabilityStreamEvent,
 };
 use crate::app_lifecycle::manager::{
     AppLaunchOptions, AppLifecycleManager, RegisteredAppLaunchConfig,
 };
+use crate::app_workflows::{list_saved_workflows, run_saved_workflow};
 
 pub(super) struct CapabilityService {
     app: AppHandle,
     lifecycle: Arc<AppLifecycleManager>,
+    audit: Arc<CapabilityAuditLog>,
 }
 
 impl CapabilityService {
     pub(super) fn new(app: AppHandle, lifecycle: Arc<AppLifecycleManager>) -> Self {
-        Self { app, lifecycle }
+        let audit = Arc::new(CapabilityAuditLog::new(&app));
+        Self {
+            app,
+            lifecycle,
+            audit,
+        }
     }
 
-    pub(super) fn handle_request(
+    /// `caller` 是传输层按令牌认出的调用方；每个请求现读一次访问策略，改了立即生效。
+    pub(super) async fn handle_request(
         &self,
+        caller: &str,
         method: &str,
         path: &str,
         body: &[u8],
     ) -> CapabilityHttpResponse {
+        let access = load_capability_access_policy(&self.app).access_for(caller);
         let route = path.split('?').next().unwrap_or_default();
         match (method, route) {
-            ("GET", "/capabilities") => self.handle_capabilities(path),
-            ("POST", "/capability/invoke") => self.handle_capability_invoke(body),
-            ("POST", "/capability/query-options") => self.handle_capability_query_options(body),
+            ("GET", "/capabilities") => self.handle_capabilities(&access, path).await,
+            ("POST", "/capability/invoke") => self.handle_capability_invoke(&access, body).await,
+            ("POST", "/capability/invoke-stream") => {
+                self.handle_capability_invoke_stream(&access, body)
+            }
+            ("POST", "/capability/query-options") => {
+                self.handle_capability_query_options(&access, body).await
+            }
+            ("GET", "/workflows") => {
+                let workflows: Vec<_> = list_saved_workflows(&self.app)
+                    .into_iter()
+                    .filter(|workflow| {
+                        access.allows(&format!("{WORKFLOW_TARGET_PREFIX}{}", workflow.id()))
+                    })
+                    .collect();
+                CapabilityHttpResponse::json(200, serde_json::json!({ "workflows": workflows }))
+            }
+            ("POST", "/workflow/run") => self.handle_workflow_run(&access, body).await,
             ("GET" | "POST", _) => CapabilityHttpResponse::error(404, "能力HTTP入口不存在"),
             _ => CapabilityHttpResponse::error(405, "能力HTTP入口不支持该请求方法"),
         }
     }
 
-    fn handle_capabilities(&self, path: &str) -> CapabilityHttpResponse {
-        let query = match CapabilityListQuery::from_path(path) {
+    /// 解析带 app 的请求体；启动配置按注册信息补上，见 `resolve_app_launch_config`。
+    fn parse_app_request<T: DeserializeOwned>(
+        &self,
+        access: &CallerAccess,
+        body: &[u8],
+    ) -> Result<T, String> {
+        let mut value = serde_json::from_slice::<Value>(body).map_err(|e| e.to_string())?;
+        resolve_app_launch_config(&mut value, access.is_host(), |app_id| {
+            registered_app_launch_value(&self.app, app_id)
+        })?;
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+
+    fn start_audit(
+        &self,
+        access: &CallerAccess,
+        kind: CapabilityAuditKind,
+        target: &str,
+        allow_launch: bool,
+    ) -> CapabilityAuditRecord {
+        self.audit.start(&access.caller, kind, target, allow_launch)
+    }
+
+    fn deny(
+        &self,
+        access: &CallerAccess,
+        record: CapabilityAuditRecord,
+        target: &str,
+    ) -> CapabilityHttpResponse {
+        let message = access.denied_message(target);
+        record.finish(CapabilityAuditOutcome::Denied, Some(&message));
+        CapabilityHttpResponse::error(403, message)
+    }
+
+    async fn handle_capabilities(
+        &self,
+        access: &CallerAccess,
+        path: &str,
+    ) -> CapabilityHttpResponse {
+        let mut query = match CapabilityListQuery::from_path(path) {
             Ok(query) => query,
             Err(error) => return CapabilityHttpResponse::error(400, error),
         };
-        match tauri::async_runtime::block_on(capability_list(
-            self.app.clone(),
-            self.lifecycle.clone(),
-            query,
-        )) {
+        if !access.allow_launch() {
+            query.launch_policy = AppCapabilityLaunchPolicy::RunningOnly;
+        }
+        match capability_list(self.app.clone(), self.lifecycle.clone(), access, query).await {
             Ok((capabilities, errors)) => CapabilityHttpResponse::json(
                 200,
                 serde_json::json!({ "capabilities": capabilities, "errors": errors }),
@@ -56,8 +127,12 @@ impl CapabilityService {
         }
     }
 
-    fn handle_capability_invoke(&self, body: &[u8]) -> CapabilityHttpResponse {
-        let request = match serde_json::from_slice::<AppCapabilityInvokeRequest>(body) {
+    async fn handle_capability_invoke(
+        &self,
+        access: &CallerAccess,
+        body: &[u8],
+    ) -> CapabilityHttpResponse {
+        let mut request = match self.parse_app_request::<AppCapabilityInvokeRequest>(access, body) {
             Ok(request) => request,
             Err(error) => {
                 return CapabilityHttpResponse::error(
@@ -67,18 +142,106 @@ impl CapabilityService {
             }
         };
 
-        match tauri::async_runtime::block_on(app_capability_invoke_inner(
-            self.app.clone(),
-            self.lifecycle.clone(),
-            request,
-        )) {
+        if !access.allow_launch() {
+            request.restrict_to_running();
+        }
+        let target = request.target();
+        let record = self.start_audit(
+            access,
+            CapabilityAuditKind::Invoke,
+            &target,
+            request.allows_launch(),
+        );
+        if !access.allows(&target) {
+            return self.deny(access, record, &target);
+        }
+
+        let result =
+            app_capability_invoke_inner(self.app.clone(), self.lifecycle.clone(), request).await;
+        record.finish_with(&result);
+        match result {
             Ok(response) => CapabilityHttpResponse::serialized(200, response),
             Err(error) => CapabilityHttpResponse::error(400, error),
         }
     }
 
-    fn handle_capability_query_options(&self, body: &[u8]) -> CapabilityHttpResponse {
-        let request = match serde_json::from_slice::<AppCapabilityOptionsRequest>(body) {
+    /// 流式调用：立即返回 NDJSON 流，调用在后台进行；调用方断开连接即取消。
+    fn handle_capability_invoke_stream(
+        &self,
+        access: &CallerAccess,
+        body: &[u8],
+    ) -> CapabilityHttpResponse {
+        let mut request = match self.parse_app_request::<AppCapabilityInvokeRequest>(access, body) {
+            Ok(request) => request,
+            Err(error) => {
+                return CapabilityHttpResponse::error(
+                    400,
+                    format!("能力调用请求解析失败: {error}"),
+                );
+            }
+        };
+
+        if !access.allow_launch() {
+            request.restrict_to_running();
+        }
+        let target = request.target();
+        let record = self.start_audit(
+            access,
+            CapabilityAuditKind::InvokeStream,
+            &target,
+            request.allows_launch(),
+        );
+        if !access.allows(&target) {
+            return self.deny(access, record, &target);
+        }
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Value>();
+        let app = self.app.clone();
+        let lifecycle = self.lifecycle.clone();
+        tauri::async_runtime::spawn(async move {
+            let invocation_id = make_capability_invocation_id();
+            let send = |event: AppCapabilityStreamEvent| {
+                if let Ok(value) = serde_json::to_value(event) {
+                    let _ = tx.send(value);
+                }
+            };
+            let result = app_capability_invoke_streaming(
+                app,
+                lifecycle,
+                request,
+                &invocation_id,
+                |text| send(AppCapabilityStreamEvent::Chunk { text }),
+                tx.closed(),
+            )
+            .await;
+            let canceled = match result {
+                Ok(Some(result)) => {
+                    record.finish(CapabilityAuditOutcome::Ok, None);
+                    send(AppCapabilityStreamEvent::Done { result });
+                    false
+                }
+                Ok(None) => {
+                    record.finish(CapabilityAuditOutcome::Canceled, None);
+                    true
+                }
+                Err(message) => {
+                    record.finish(CapabilityAuditOutcome::Error, Some(&message));
+                    send(AppCapabilityStreamEvent::Error { message });
+                    false
+                }
+            };
+            send(AppCapabilityStreamEvent::End { canceled });
+        });
+        CapabilityHttpResponse::stream(rx)
+    }
+
+    async fn handle_capability_query_options(
+        &self,
+        access: &CallerAccess,
+        body: &[u8],
+    ) -> CapabilityHttpResponse {
+        let mut request = match self.parse_app_request::<AppCapabilityOptionsRequest>(access, body)
+        {
             Ok(request) => request,
             Err(error) => {
                 return CapabilityHttpResponse::error(
@@ -88,20 +251,92 @@ impl CapabilityService {
             }
         };
 
-        match tauri::async_runtime::block_on(app_capability_query_options_inner(
+        if !access.allow_launch() {
+            request.restrict_to_running();
+        }
+        let target = request.target();
+        let record = self.start_audit(
+            access,
+            CapabilityAuditKind::QueryOptions,
+            &target,
+            request.allows_launch(),
+        );
+        if !access.allows(&target) {
+            return self.deny(access, record, &target);
+        }
+
+        let result =
+            app_capability_query_options_inner(self.app.clone(), self.lifecycle.clone(), request)
+                .await;
+        record.finish_with(&result);
+        match result {
+            Ok(response) => CapabilityHttpResponse::serialized(200, response),
+            Err(error) => CapabilityHttpResponse::error(400, error),
+        }
+    }
+
+    /// 工作流由宿主执行，步骤按工作流自己的设置拉起应用，不受调用方 allowLaunch 限制。
+    async fn handle_workflow_run(
+        &self,
+        access: &CallerAccess,
+        body: &[u8],
+    ) -> CapabilityHttpResponse {
+        let request = match serde_json::from_slice::<WorkflowRunRequest>(body) {
+            Ok(request) => request,
+            Err(error) => {
+                return CapabilityHttpResponse::error(
+                    400,
+                    format!("工作流执行请求解析失败: {error}"),
+                );
+            }
+        };
+
+        let target = format!("{WORKFLOW_TARGET_PREFIX}{}", request.workflow_id.trim());
+        let record = self.start_audit(access, CapabilityAuditKind::WorkflowRun, &target, true);
+        if !access.allows(&target) {
+            return self.deny(access, record, &target);
+        }
+
+        let result = run_saved_workflow(
             self.app.clone(),
             self.lifecycle.clone(),
-            request,
-        )) {
-            Ok(response) => CapabilityHttpResponse::serialized(200, response),
+            &request.workflow_id,
+            &request.input,
+        )
+        .await;
+        record.finish_with(&result);
+        match result {
+            Ok(result) => CapabilityHttpResponse::serialized(200, result),
             Err(error) => CapabilityHttpResponse::error(400, error),
         }
     }
 }
 
+/// 各入口的处理超时；调用应用能力可能要先拉起应用，留出启动时间。
+pub(super) fn request_timeout(route: &str) -> Duration {
+    let secs = match route {
+        "/capabilities" => 60,
+        "/capability/invoke" => 150,
+        "/capability/query-options" => 45,
+        "/workflow/run" => 600,
+        _ => 30,
+    };
+    Duration::from_secs(secs)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkflowRunRequest {
+    workflow_id: String,
+    #[serde(default)]
+    input: String,
+}
+
+/// 只列出调用方有权调用的能力；无权访问的应用也不会被探测或拉起。
 async fn capability_list(
     app: AppHandle,
     lifecycle: Arc<AppLifecycleManager>,
+    access: &CallerAccess,
     query: CapabilityListQuery,
 ) -> Result<(Vec<Value>, Vec<Value>), String> {
     let records = crate::app_registry::load_registered_app_records(&app)?;
@@ -119,6 +354,9 @@ async fn capability_list(
                 continue;
             }
         }
+        if !access.allows_any_of_app(app_id) {
+            continue;
+        }
         let app_name = app_display_name(&record);
         let app_launch = app_launch_value(&record);
         let app_config =
@@ -143,7 +381,7 @@ async fn capability_list(
         )
         .await
         {
-            Ok(capabilities) => capabilities,
+            Ok(described) => described,
             Err(error) => {
                 errors.push(serde_json::json!({
                     "appId": app_id,
@@ -154,14 +392,22 @@ async fn capability_list(
                 continue;
             }
         };
-        for capability in runtime_capabilities {
+        if let Some(message) = runtime_capabilities.rejected_message() {
+            errors.push(serde_json::json!({
+                "appId": app_id,
+                "appName": app_name,
+                "message": message,
+                "canLaunch": false,
+            }));
+        }
+        for capability in runtime_capabilities.declarations {
             let capability = serde_json::to_value(capability)
                 .map_err(|e| format!("序列化应用能力清单失败: {e}"))?;
             let Some(command_id) = capability.get("id").and_then(Value::as_str).map(str::trim)
             else {
                 continue;
             };
-            if command_id.is_empty() {
+            if command_id.is_empty() || !access.allows(&format!("{app_id}/{command_id}")) {
                 continue;
             }
             let mut item = Map::new();
@@ -179,6 +425,9 @@ async fn capability_list(
             copy_command_field(&mut item, &capability, "hotkey");
             copy_command_field(&mut item, &capability, "description");
             copy_command_field(&mut item, &capability, "configFields");
+            copy_command_field(&mut item, &capability, "inputSchema");
+            copy_command_field(&mut item, &capability, "configSchema");
+            copy_command_field(&mut item, &capability, "outputSchema");
             capability_items.push(Value::Object(item));
         }
     }
@@ -228,6 +477,43 @@ impl CapabilityListQuery {
     }
 }
 
+/// 宿主自己的请求可以带完整的 app 启动配置，只给 appId 时按注册信息补上；
+/// 其他调用方一律用注册信息里的启动配置，忽略请求里的 app 和 launchOptions，
+/// 否则受策略限制的调用方可以借应用 id 拉起任意程序、注入任意环境变量。
+fn resolve_app_launch_config(
+    value: &mut Value,
+    trusted: bool,
+    registered: impl FnOnce(&str) -> Result<Value, String>,
+) -> Result<(), String> {
+    let Some(object) = value.as_object_mut() else {
+        return Ok(());
+    };
+    if trusted && object.contains_key("app") {
+        return Ok(());
+    }
+    let app_id = object
+        .get("appId")
+        .or_else(|| object.get("app").and_then(|app| app.get("id")))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| "缺少 appId".to_string())?;
+    if !trusted {
+        object.remove("launchOptions");
+    }
+    object.insert("app".to_string(), registered(&app_id)?);
+    Ok(())
+}
+
+fn registered_app_launch_value(app: &AppHandle, app_id: &str) -> Result<Value, String> {
+    crate::app_registry::load_registered_app_records(app)?
+        .iter()
+        .find(|record| record.get("id").and_then(Value::as_str).map(str::trim) == Some(app_id))
+        .map(app_launch_value)
+        .ok_or_else(|| format!("应用未注册: {app_id}"))
+}
+
 fn app_launch_value(record: &Value) -> Value {
     let mut app = Map::new();
     copy_app_field(&mut app, record, "id");
@@ -260,3 +546,42 @@ fn copy_command_field(target: &mut Map<String, Value>, source: &Value, key: &str
         target.insert(key.to_string(), value.clone());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn registered(app_id: &str) -> Result<Value, String> {
+        match app_id {
+            "translator" => Ok(json!({ "id": "translator", "path": "/apps/translator" })),
+            _ => Err(format!("应用未注册: {app_id}")),
+        }
+    }
+
+    #[test]
+    fn restricted_callers_always_get_registered_launch_config() {
+        let mut value = json!({
+            "app": { "id": "translator", "path": "/tmp/evil" },
+            "capabilityId": "translate",
+            "launchOptions": { "extraEnvs": { "LD_PRELOAD": "/tmp/evil.so" } }
+        });
+        resolve_app_launch_config(&mut value, false, registered).unwrap();
+        assert_eq!(value["app"]["path"], "/apps/translator");
+        assert!(value.get("launchOptions").is_none());
+
+        let mut unknown = json!({ "app": { "id": "evil", "path": "/tmp/evil" } });
+        assert!(resolve_app_launch_config(&mut unknown, false, registered).is_err());
+    }
+
+    #[test]
+    fn host_keeps_its_launch_config_and_app_id_is_filled_in() {
+        let mut value = json!({ "app": { "id": "translator", "path": "/dev/translator" } });
+        resolve_app_launch_config(&mut value, true, registered).unwrap();
+        assert_eq!(value["app"]["path"], "/dev/translator");
+
+        let mut by_id = json!({ "appId": "translator", "capabilityId": "translate" });
+        resolve_app_launch_config(&mut by_id, true, registered).unwrap();
+        assert_eq!(by_id["app"]["path"], "/apps/translator");
+    }
+}
@@ -1,53 +1,146 @@
-use std::io::{Read, Write};
-use std::net::TcpStream;
+use std::future::Future;
+use std::sync::Arc;
 use std::time::Duration;
 
-use super::{capability_service::CapabilityService, CapabilityHttpResponse};
+use serde_json::Value;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::Semaphore;
+
+use super::{capability_service::request_timeout, CapabilityHttpBody, CapabilityHttpResponse};
 
 const CONTROL_TOKEN_HEADER: &str = "x-fw-control-token";
 const MAX_HEADER_BYTES: usize = 64 * 1024;
 const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+// 并发已满时请求最多排队这么久，之后回 503
+const QUEUE_TIMEOUT: Duration = Duration::from_secs(10);
 
-struct CapabilityHttpRequest {
-    method: String,
-    path: String,
+pub(super) struct CapabilityHttpRequest {
+    pub(super) method: String,
+    pub(super) path: String,
     token: String,
-    body: Vec<u8>,
+    /// 令牌对应的调用方，鉴权通过后填入
+    pub(super) caller: String,
+    pub(super) body: Vec<u8>,
 }
 
-pub(super) fn handle_connection(
-    mut stream: TcpStream,
-    expected_token: &str,
-    service: &CapabilityService,
-) {
-    let request = match read_http_request(&mut stream) {
-        Ok(request) => request,
+/// 每个连接一个任务；同时在处理的请求数不超过 `max_concurrent`，
+/// 单个请求按入口各自的超时上限执行，慢请求不会挡住其它应用。
+/// `authenticate` 把请求里的令牌换成调用方，认不出的令牌回 401。
+pub(super) async fn serve<A, H, F>(
+    listener: std::net::TcpListener,
+    authenticate: A,
+    max_concurrent: usize,
+    handler: H,
+) where
+    A: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    H: Fn(CapabilityHttpRequest) -> F + Clone + Send + Sync + 'static,
+    F: Future<Output = CapabilityHttpResponse> + Send + 'static,
+{
+    let listener = match listener
+        .set_nonblocking(true)
+        .and_then(|_| TcpListener::from_std(listener))
+    {
+        Ok(listener) => listener,
         Err(error) => {
-            write_json_response(&mut stream, CapabilityHttpResponse::error(400, error));
+            eprintln!("[capability-server] listener setup failed: {error}");
             return;
         }
     };
+    let authenticate = Arc::new(authenticate);
+    let permits = Arc::new(Semaphore::new(max_concurrent.max(1)));
 
-    if request.token != expected_token {
-        write_json_response(
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(error) => {
+                // 句柄耗尽之类的错误是暂时的，稍等再继续接受连接
+                eprintln!("[capability-server] connection failed: {error}");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let authenticate = authenticate.clone();
+        let permits = permits.clone();
+        let handler = handler.clone();
+        tokio::spawn(async move {
+            handle_connection(stream, authenticate.as_ref(), &permits, handler).await;
+        });
+    }
+}
+
+async fn handle_connection<A, H, F>(
+    mut stream: TcpStream,
+    authenticate: &A,
+    permits: &Semaphore,
+    handler: H,
+) where
+    A: Fn(&str) -> Option<String>,
+    H: Fn(CapabilityHttpRequest) -> F,
+    F: Future<Output = CapabilityHttpResponse>,
+{
+    let mut request = match tokio::time::timeout(READ_TIMEOUT, read_http_request(&mut stream)).await
+    {
+        Ok(Ok(request)) => request,
+        Ok(Err(error)) => {
+            write_response(&mut stream, CapabilityHttpResponse::error(400, error)).await;
+            return;
+        }
+        Err(_) => {
+            write_response(
+                &mut stream,
+                CapabilityHttpResponse::error(408, "读取能力HTTP请求超时"),
+            )
+            .await;
+            return;
+        }
+    };
+
+    let Some(caller) = authenticate(&request.token) else {
+        write_response(
             &mut stream,
             CapabilityHttpResponse::error(401, "控制令牌无效"),
-        );
+        )
+        .await;
         return;
-    }
+    };
+    request.caller = caller;
 
-    let response = service.handle_request(&request.method, &request.path, &request.body);
-    write_json_response(&mut stream, response);
-}
+    let Ok(Ok(permit)) = tokio::time::timeout(QUEUE_TIMEOUT, permits.acquire()).await else {
+        write_response(
+            &mut stream,
+            CapabilityHttpResponse::error(503, "能力HTTP服务繁忙，请稍后重试"),
+        )
+        .await;
+        return;
+    };
 
-fn read_http_request(stream: &mut TcpStream) -> Result<CapabilityHttpRequest, String> {
-    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let route = request.path.split('?').next().unwrap_or_default();
+    let timeout = request_timeout(route);
+    let response = match tokio::time::timeout(timeout, handler(request)).await {
+        Ok(response) => response,
+        Err(_) => CapabilityHttpResponse::error(
+            504,
+            format!("能力HTTP请求处理超时（{} 秒）", timeout.as_secs()),
+        ),
+    };
+    // 流式响应可能持续很久，处理函数返回后就让出名额，长流不挤占其它调用
+    if matches!(response.body, CapabilityHttpBody::Stream(_)) {
+        drop(permit);
+    }
+    write_response(&mut stream, response).await;
+}
 
+async fn read_http_request(stream: &mut TcpStream) -> Result<CapabilityHttpRequest, String> {
     let mut buffer = Vec::new();
     let mut chunk = [0u8; 1024];
     let header_end = loop {
         let n = stream
             .read(&mut chunk)
+            .await
             .map_err(|e| format!("读取能力HTTP请求失败: {e}"))?;
         if n == 0 {
             return Err("能力HTTP请求为空".to_string());
@@ -97,6 +190,7 @@ fn read_http_request(stream: &mut TcpStream) -> Result<CapabilityHttpRequest, St
     while body.len() < content_length {
         let n = stream
             .read(&mut chunk)
+            .await
             .map_err(|e| format!("读取能力HTTP请求体失败: {e}"))?;
         if n == 0 {
             return Err(format!(
@@ -113,6 +207,7 @@ fn read_http_request(stream: &mut TcpStream) -> Result<CapabilityHttpRequest, St
         method,
         path,
         token,
+        caller: String::new(),
         body,
     })
 }
@@ -124,22 +219,273 @@ fn find_header_end(buffer: &[u8]) -> Option<usize> {
         .map(|index| index + 4)
 }
 
-fn write_json_response(stream: &mut TcpStream, response: CapabilityHttpResponse) {
-    let reason = match response.status {
+async fn write_response(stream: &mut TcpStream, response: CapabilityHttpResponse) {
+    match response.body {
+        CapabilityHttpBody::Json(body) => write_json_body(stream, response.status, &body).await,
+        CapabilityHttpBody::Stream(events) => write_stream_body(stream, events).await,
+    }
+}
+
+fn status_reason(status: u16) -> &'static str {
+    match status {
         200 => "OK",
         400 => "Bad Request",
         401 => "Unauthorized",
+        403 => "Forbidden",
         404 => "Not Found",
         405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
         _ => "Internal Server Error",
-    };
-    let payload = response.body.to_string();
+    }
+}
+
+async fn write_json_body(stream: &mut TcpStream, status: u16, body: &Value) {
+    let payload = body.to_string();
     let head = format!(
-        "HTTP/1.1 {} {reason}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
-        response.status,
-        payload.as_bytes().len(),
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status_reason(status),
+        payload.len(),
     );
-    let _ = stream.write_all(head.as_bytes());
-    let _ = stream.write_all(payload.as_bytes());
-    let _ = stream.flush();
+    let write = async {
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(payload.as_bytes()).await?;
+        stream.flush().await
+    };
+    let _ = tokio::time::timeout(WRITE_TIMEOUT, write).await;
+}
+
+/// 每个事件写成一行 JSON 放进一个 chunk；调用方断开（读到 EOF）时直接返回，
+/// 丢弃 `events` 让发送端知道该取消了。
+async fn write_stream_body(stream: &mut TcpStream, mut events: UnboundedReceiver<Value>) {
+    let head = "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson; charset=utf-8\r\nTransfer-Encoding: chunked\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n";
+    if !matches!(
+        tokio::time::timeout(WRITE_TIMEOUT, stream.write_all(head.as_bytes())).await,
+        Ok(Ok(()))
+    ) {
+        return;
+    }
+
+    let (mut reader, mut writer) = stream.split();
+    let mut probe = [0u8; 256];
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let Some(event) = event else {
+                    break;
+                };
+                let line = format!("{event}\n");
+                let chunk = format!("{:x}\r\n{line}\r\n", line.len());
+                let write = async {
+                    writer.write_all(chunk.as_bytes()).await?;
+                    writer.flush().await
+                };
+                if !matches!(tokio::time::timeout(WRITE_TIMEOUT, write).await, Ok(Ok(()))) {
+                    return;
+                }
+            }
+            read = reader.read(&mut probe) => {
+                if matches!(read, Ok(0) | Err(_)) {
+                    return;
+                }
+            }
+        }
+    }
+    let _ = tokio::time::timeout(WRITE_TIMEOUT, writer.write_all(b"0\r\n\r\n")).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn authenticate(token: &str) -> Option<String> {
+        match token {
+            "secret" => Some("@host".to_string()),
+            "app-secret" => Some("quick-bar".to_string()),
+            _ => None,
+        }
+    }
+
+    fn start_slow_server(max_concurrent: usize, delay: Duration) -> std::net::SocketAddr {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(
+            listener,
+            authenticate,
+            max_concurrent,
+            move |request: CapabilityHttpRequest| async move {
+                tokio::time::sleep(delay).await;
+                CapabilityHttpResponse::json(200, serde_json::json!({ "path": request.path }))
+            },
+        ));
+        addr
+    }
+
+    fn start_server<H, F>(handler: H) -> std::net::SocketAddr
+    where
+        H: Fn(CapabilityHttpRequest) -> F + Clone + Send + Sync + 'static,
+        F: Future<Output = CapabilityHttpResponse> + Send + 'static,
+    {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, authenticate, 4, handler));
+        addr
+    }
+
+    async fn send_request(addr: std::net::SocketAddr, token: &str) -> TcpStream {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let body = br#"{"capabilityId":"slow"}"#;
+        let head = format!(
+            "POST /capability/invoke HTTP/1.1\r\nHost: {addr}\r\nX-FW-Control-Token: {token}\r\nContent-Length: {}\r\n\r\n",
+            body.len()
+        );
+        stream.write_all(head.as_bytes()).await.unwrap();
+        stream.write_all(body).await.unwrap();
+        stream
+    }
+
+    async fn post(addr: std::net::SocketAddr, token: &str) -> String {
+        let mut stream = send_request(addr, token).await;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn slow_invocations_overlap() {
+        let delay = Duration::from_millis(400);
+        let addr = start_slow_server(4, delay);
+
+        let started = Instant::now();
+        let (first, second) = tokio::join!(post(addr, "secret"), post(addr, "secret"));
+        let elapsed = started.elapsed();
+
+        assert!(first.starts_with("HTTP/1.1 200"), "{first}");
+        assert!(second.starts_with("HTTP/1.1 200"), "{second}");
+        // 串行处理至少要 800ms
+        assert!(elapsed < delay * 2, "took {elapsed:?}");
+    }
+
+    #[tokio::test]
+    async fn concurrency_limit_queues_extra_requests() {
+        let delay = Duration::from_millis(200);
+        let addr = start_slow_server(1, delay);
+
+        let started = Instant::now();
+        let (first, second) = tokio::join!(post(addr, "secret"), post(addr, "secret"));
+
+        assert!(first.starts_with("HTTP/1.1 200"), "{first}");
+        assert!(second.starts_with("HTTP/1.1 200"), "{second}");
+        assert!(started.elapsed() >= delay * 2);
+    }
+
+    #[tokio::test]
+    async fn wrong_token_is_rejected() {
+        let addr = start_slow_server(1, Duration::ZERO);
+        let response = post(addr, "wrong").await;
+        assert!(response.starts_with("HTTP/1.1 401"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn caller_is_resolved_from_token() {
+        let addr = start_server(|request: CapabilityHttpRequest| async move {
+            CapabilityHttpResponse::json(200, serde_json::json!({ "caller": request.caller }))
+        });
+        let response = post(addr, "app-secret").await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(
+            response.ends_with(r#"{"caller":"quick-bar"}"#),
+            "{response}"
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_response_is_chunked_ndjson() {
+        let addr = start_server(|_request: CapabilityHttpRequest| async {
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            tx.send(serde_json::json!({ "text": "he" })).unwrap();
+            tx.send(serde_json::json!({ "canceled": false })).unwrap();
+            CapabilityHttpResponse::stream(rx)
+        });
+
+        let response = post(addr, "secret").await;
+        assert!(
+            response.contains("Transfer-Encoding: chunked"),
+            "{response}"
+        );
+        let chunk = "{\"text\":\"he\"}\n";
+        assert!(
+            response.contains(&format!("{:x}\r\n{chunk}\r\n", chunk.len())),
+            "{response}"
+        );
+        assert!(response.ends_with("0\r\n\r\n"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn caller_disconnect_closes_stream() {
+        let (closed_tx, closed_rx) = tokio::sync::oneshot::channel::<()>();
+        let closed_tx = Arc::new(std::sync::Mutex::new(Some(closed_tx)));
+        let addr = start_server(move |_request: CapabilityHttpRequest| {
+            let closed_tx = closed_tx.clone();
+            async move {
+                let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Value>();
+                tokio::spawn(async move {
+                    tx.closed().await;
+                    if let Some(closed_tx) = closed_tx.lock().unwrap().take() {
+                        let _ = closed_tx.send(());
+                    }
+                });
+                CapabilityHttpResponse::stream(rx)
+            }
+        });
+
+        let mut stream = send_request(addr, "secret").await;
+        let mut head = [0u8; 32];
+        let n = stream.read(&mut head).await.unwrap();
+        assert!(head[..n].starts_with(b"HTTP/1.1 200"));
+        drop(stream);
+
+        tokio::time::timeout(Duration::from_secs(2), closed_rx)
+            .await
+            .expect("stream was not closed after caller disconnected")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn open_stream_releases_permit() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let senders = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(
+            listener,
+            authenticate,
+            1,
+            move |_request: CapabilityHttpRequest| {
+                let calls = calls.clone();
+                let senders = senders.clone();
+                async move {
+                    if calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) > 0 {
+                        return CapabilityHttpResponse::json(200, serde_json::json!({}));
+                    }
+                    // 第一个请求是一直不结束的流
+                    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Value>();
+                    senders.lock().unwrap().push(tx);
+                    CapabilityHttpResponse::stream(rx)
+                }
+            },
+        ));
+
+        let mut open = send_request(addr, "secret").await;
+        let mut head = [0u8; 32];
+        let n = open.read(&mut head).await.unwrap();
+        assert!(head[..n].starts_with(b"HTTP/1.1 200"));
+
+        let response = tokio::time::timeout(Duration::from_secs(2), post(addr, "secret"))
+            .await
+            .expect("request waited for the open stream");
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    }
 }
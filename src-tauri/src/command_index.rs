@@ -0,0 +1,652 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Listener, Manager};
+
+use crate::app_registry::AppRuntimeDeclaration;
+use crate::usage::UsageKind;
+
+// ── 命令索引 ────────────────────────────────────────────────────────────────
+//
+// 主窗口搜索用的统一索引：插件、注册应用、应用快捷入口（注册表里的 + 运行时声明的）、
+// 应用能力和宿主页面。条目 ID 与主窗口列表项 ID 一致（插件 ID / `app:` / `app-shortcut:` /
+// `app-capability:` / `host:`），前端直接按 ID 对应。
+// - 模糊匹配：子序列匹配，连续、词首、前缀命中加分；多个关键词都要命中。
+// - frecency：取自使用记录（usage），常用的加分；主窗口每次打开用 command_index_record_use 记一笔。
+//   算好的分数缓存起来，记使用时作废；按天衰减，缓存超过 FRECENCY_CACHE_MS 也重算。
+// - 索引惰性构建：注册表变更（registered-apps-changed）、插件重新扫描时作废，下次查询重建。
+//   运行时声明在 app_capability_list / app_host_shortcut_list 拿到后缓存进来。
+
+const DEFAULT_QUERY_LIMIT: usize = 50;
+const MAX_QUERY_LIMIT: usize = 200;
+const MAX_FRECENCY_BONUS: u32 = 400;
+const FRECENCY_CACHE_MS: u64 = 10 * 60 * 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum CommandKind {
+    HostAction,
+    Plugin,
+    App,
+    AppCommand,
+    Capability,
+}
+
+#[derive(Clone, Debug)]
+struct CommandEntry {
+    id: String,
+    kind: CommandKind,
+    title: String,
+    subtitle: String,
+    app_id: Option<String>,
+    keywords: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CommandIndexHit {
+    id: String,
+    kind: CommandKind,
+    title: String,
+    subtitle: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    app_id: Option<String>,
+    score: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+struct RuntimeCommands {
+    capabilities: Vec<AppRuntimeDeclaration>,
+    host_shortcuts: Vec<AppRuntimeDeclaration>,
+}
+
+struct PluginSummary {
+    id: String,
+    name: String,
+    description: String,
+    keyword: String,
+}
+
+#[derive(Default)]
+struct CommandIndex {
+    /// None 表示已作废，下次查询时重建
+    entries: Option<Vec<CommandEntry>>,
+    runtime: HashMap<String, RuntimeCommands>,
+    /// 按条目 ID 的 frecency 和计算时间；None 表示已作废
+    frecency: Option<(u64, Arc<HashMap<String, u32>>)>,
+    /// 每次作废加一：计算期间有新使用记录时不写回旧结果
+    frecency_generation: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct CommandIndexState {
+    inner: Mutex<CommandIndex>,
+}
+
+const HOST_ACTIONS: &[(&str, &str, &str)] = &[
+    ("settings", "设置", "settings preferences 偏好 配置"),
+    ("store", "插件商店", "store market 商店 安装"),
+    (
+        "capabilityRegistry",
+        "能力注册表",
+        "capability registry 能力",
+    ),
+];
+
+fn host_action_entries() -> Vec<CommandEntry> {
+    HOST_ACTIONS
+        .iter()
+        .map(|(id, title, keywords)| CommandEntry {
+            id: format!("host:{id}"),
+            kind: CommandKind::HostAction,
+            title: title.to_string(),
+            subtitle: "宿主".to_string(),
+            app_id: None,
+            keywords: keywords.split(' ').map(str::to_string).collect(),
+        })
+        .collect()
+}
+
+fn value_str<'a>(value: &'a Value, key: &str) -> &'a str {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .unwrap_or("")
+}
+
+fn app_command_entry(
+    app_id: &str,
+    app_name: &str,
+    command_id: &str,
+    title: &str,
+    description: &str,
+) -> CommandEntry {
+    CommandEntry {
+        id: format!("app-shortcut:{app_id}:{command_id}"),
+        kind: CommandKind::AppCommand,
+        title: title.to_string(),
+        subtitle: format!("{app_name} · 快捷入口"),
+        app_id: Some(app_id.to_string()),
+        keywords: vec![
+            app_name.to_string(),
+            command_id.to_string(),
+            description.to_string(),
+        ],
+    }
+}
+
+fn build_entries(
+    plugins: &[PluginSummary],
+    apps: &[Value],
+    runtime: &HashMap<String, RuntimeCommands>,
+) -> Vec<CommandEntry> {
+    let mut entries = host_action_entries();
+
+    for plugin in plugins {
+        entries.push(CommandEntry {
+            id: plugin.id.clone(),
+            kind: CommandKind::Plugin,
+            title: plugin.name.clone(),
+            subtitle: plugin.description.clone(),
+            app_id: None,
+            keywords: vec![plugin.id.clone(), plugin.keyword.clone()],
+        });
+    }
+
+    for app in apps {
+        let app_id = value_str(app, "id");
+        if app_id.is_empty() {
+            continue;
+        }
+        let app_name = match value_str(app, "name") {
+            "" => app_id,
+            name => name,
+        };
+        entries.push(CommandEntry {
+            id: format!("app:{app_id}"),
+            kind: CommandKind::App,
+            title: app_name.to_string(),
+            subtitle: "应用".to_string(),
+            app_id: Some(app_id.to_string()),
+            keywords: vec![app_id.to_string()],
+        });
+
+        for command in app
+            .get("commands")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let command_id = value_str(command, "id");
+            if command_id.is_empty() {
+                continue;
+            }
+            entries.push(app_command_entry(
+                app_id,
+                app_name,
+                command_id,
+                value_str(command, "title"),
+                value_str(command, "description"),
+            ));
+        }
+
+        let Some(declared) = runtime.get(app_id) else {
+            continue;
+        };
+        for shortcut in &declared.host_shortcuts {
+            let id = format!("app-shortcut:{app_id}:{}", shortcut.id);
+            // 注册表里已保存的快捷入口优先
+            if entries.iter().any(|entry| entry.id == id) {
+                continue;
+            }
+            entries.push(app_command_entry(
+                app_id,
+                app_name,
+                &shortcut.id,
+                &shortcut.title,
+                shortcut.description.as_deref().unwrap_or(""),
+            ));
+        }
+        for capability in &declared.capabilities {
+            entries.push(CommandEntry {
+                id: format!("app-capability:{app_id}:{}", capability.id),
+                kind: CommandKind::Capability,
+                title: capability.title.clone(),
+                subtitle: format!("{app_name} · 能力"),
+                app_id: Some(app_id.to_string()),
+                keywords: vec![
+                    app_name.to_string(),
+                    capability.id.clone(),
+                    capability.description.clone().unwrap_or_default(),
+                ],
+            });
+        }
+    }
+
+    entries
+}
+
+fn is_word_boundary(prev: Option<char>, current: char) -> bool {
+    match prev {
+        None => true,
+        Some(prev) => {
+            matches!(prev, ' ' | '-' | '_' | '.' | '/' | ':' | '·')
+                || (prev.is_lowercase() && current.is_uppercase())
+                || (!prev.is_ascii() && current.is_ascii_alphanumeric())
+        }
+    }
+}
+
+/// 单个关键词对一段文本的匹配分；不是子序列时返回 None。
+fn fuzzy_score(needle: &str, haystack: &str) -> Option<u32> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    let lower_haystack = haystack.to_lowercase();
+    if lower_haystack == needle {
+        return Some(1000);
+    }
+    if lower_haystack.starts_with(needle) {
+        return Some(800);
+    }
+    if let Some(pos) = lower_haystack.find(needle) {
+        let position = lower_haystack[..pos].chars().count() as u32;
+        return Some(600u32.saturating_sub(position.min(100)));
+    }
+
+    let original: Vec<char> = haystack.chars().collect();
+    let lower: Vec<char> = lower_haystack.chars().collect();
+    if original.len() != lower.len() {
+        // 大小写转换改变了字符数（少数 Unicode 字符），退化成只看子序列
+        let mut chars = lower.iter();
+        return needle
+            .chars()
+            .all(|c| chars.any(|h| *h == c))
+            .then_some(100);
+    }
+
+    let mut score: u32 = 100;
+    let mut next = 0usize;
+    let mut last_match: Option<usize> = None;
+    for c in needle.chars() {
+        let offset = lower[next..].iter().position(|h| *h == c)?;
+        let index = next + offset;
+        score += 10;
+        if last_match.is_some_and(|last| last + 1 == index) {
+            score += 15;
+        }
+        if is_word_boundary(index.checked_sub(1).map(|i| original[i]), original[index]) {
+            score += 20;
+        }
+        score = score.saturating_sub(offset.min(20) as u32);
+        last_match = Some(index);
+        next = index + 1;
+    }
+    Some(score.min(500))
+}
+
+fn entry_match_score(entry: &CommandEntry, tokens: &[String]) -> Option<u32> {
+    let mut total = 0u32;
+    for token in tokens {
+        let title = fuzzy_score(token, &entry.title);
+        let secondary = std::iter::once(entry.subtitle.as_str())
+            .chain(entry.keywords.iter().map(String::as_str))
+            .filter(|text| !text.is_empty())
+            .filter_map(|text| fuzzy_score(token, text))
+            .max()
+            .map(|score| score / 2);
+        total += title.into_iter().chain(secondary).max()?;
+    }
+    Some(total)
+}
+
+/// 列表项 ID 与使用记录（类型 + 目标）互转。
+fn usage_key(item_id: &str) -> Option<(UsageKind, String)> {
+    let split_pair = |rest: &str| {
+        let (app_id, id) = rest.split_once(':')?;
+        (!app_id.is_empty() && !id.is_empty()).then(|| format!("{app_id}/{id}"))
+    };
+    if let Some(rest) = item_id.strip_prefix("host:") {
+        return Some((UsageKind::HostAction, rest.to_string()));
+    }
+    if let Some(rest) = item_id.strip_prefix("app-shortcut:") {
+        return Some((UsageKind::AppCommand, split_pair(rest)?));
+    }
+    if let Some(rest) = item_id.strip_prefix("app-capability:") {
+        return Some((UsageKind::Capability, split_pair(rest)?));
+    }
+    if let Some(rest) = item_id.strip_prefix("app:") {
+        return Some((UsageKind::App, rest.to_string()));
+    }
+    crate::is_safe_id(item_id).then(|| (UsageKind::Plugin, item_id.to_string()))
+}
+
+fn item_id(kind: UsageKind, target: &str) -> String {
+    match kind {
+        UsageKind::HostAction => format!("host:{target}"),
+        UsageKind::Plugin => target.to_string(),
+        UsageKind::App => format!("app:{target}"),
+        UsageKind::AppCommand => format!("app-shortcut:{}", target.replacen('/', ":", 1)),
+        UsageKind::Capability => format!("app-capability:{}", target.replacen('/', ":", 1)),
+    }
+}
+
+fn rank_entries(
+    entries: &[CommandEntry],
+    frecency: &HashMap<String, u32>,
+    query: &str,
+    limit: usize,
+) -> Vec<CommandIndexHit> {
+    let tokens: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    let mut hits: Vec<(u32, &CommandEntry)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let bonus = frecency
+                .get(&entry.id)
+                .map(|score| (*score).min(MAX_FRECENCY_BONUS))
+                .unwrap_or(0);
+            if tokens.is_empty() {
+                // 没有关键词时只返回用过的，按 frecency 排
+                return (bonus > 0).then_some((bonus, entry));
+            }
+            entry_match_score(entry, &tokens).map(|score| (score + bonus, entry))
+        })
+        .collect();
+    hits.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then_with(|| a.1.kind.cmp(&b.1.kind))
+            .then_with(|| a.1.title.cmp(&b.1.title))
+    });
+    hits.into_iter()
+        .take(limit)
+        .map(|(score, entry)| CommandIndexHit {
+            id: entry.id.clone(),
+            kind: entry.kind,
+            title: entry.title.clone(),
+            subtitle: entry.subtitle.clone(),
+            app_id: entry.app_id.clone(),
+            score,
+        })
+        .collect()
+}
+
+fn load_plugin_summaries(app: &AppHandle) -> Vec<PluginSummary> {
+    let dir = crate::app_plugins_dir(app);
+    crate::plugins::installed_plugin_ids(app)
+        .into_iter()
+        .map(|id| {
+            let manifest = std::fs::read_to_string(dir.join(&id).join("manifest.json"))
+                .ok()
+                .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+                .unwrap_or(Value::Null);
+            let name = match value_str(&manifest, "name") {
+                "" => id.clone(),
+                name => name.to_string(),
+            };
+            PluginSummary {
+                name,
+                description: value_str(&manifest, "description").to_string(),
+                keyword: value_str(&manifest, "keyword").to_string(),
+                id,
+            }
+        })
+        .collect()
+}
+
+impl CommandIndexState {
+    fn lock(&self) -> std::sync::MutexGuard<'_, CommandIndex> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 作废索引，下次查询时重建。
+pub(crate) fn invalidate(app: &AppHandle) {
+    if let Some(state) = app.try_state::<CommandIndexState>() {
+        state.lock().entries = None;
+    }
+}
+
+/// 注册表变更时作废索引（宿主和前端发出的变更事件都会收到）。
+pub(crate) fn watch_registered_apps(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_any(
+        crate::app_registry::REGISTERED_APPS_CHANGED_EVENT,
+        move |_| invalidate(&handle),
+    );
+}
+
+fn remember_runtime(app: &AppHandle, app_id: &str, update: impl FnOnce(&mut RuntimeCommands)) {
+    let Some(state) = app.try_state::<CommandIndexState>() else {
+        return;
+    };
+    let mut index = state.lock();
+    update(index.runtime.entry(app_id.to_string()).or_default());
+    index.entries = None;
+}
+
+pub(crate) fn remember_app_capabilities(
+    app: &AppHandle,
+    app_id: &str,
+    capabilities: &[AppRuntimeDeclaration],
+) {
+    remember_runtime(app, app_id, |runtime| {
+        runtime.capabilities = capabilities.to_vec()
+    });
+}
+
+pub(crate) fn remember_app_host_shortcuts(
+    app: &AppHandle,
+    app_id: &str,
+    host_shortcuts: &[AppRuntimeDeclaration],
+) {
+    remember_runtime(app, app_id, |runtime| {
+        runtime.host_shortcuts = host_shortcuts.to_vec()
+    });
+}
+
+/// 记了一次使用：frecency 缓存作废。
+pub(crate) fn invalidate_frecency(app: &AppHandle) {
+    if let Some(state) = app.try_state::<CommandIndexState>() {
+        let mut index = state.lock();
+        index.frecency = None;
+        index.frecency_generation += 1;
+    }
+}
+
+/// 读缓存的 frecency；过期或作废时查一次使用记录。查库时不持有索引锁。
+fn frecency_by_id(app: &AppHandle, state: &CommandIndexState) -> Arc<HashMap<String, u32>> {
+    let now = crate::now_ms();
+    let generation = {
+        let index = state.lock();
+        if let Some((_, scores)) = index
+            .frecency
+            .as_ref()
+            .filter(|(at, _)| now.saturating_sub(*at) < FRECENCY_CACHE_MS)
+        {
+            return scores.clone();
+        }
+        index.frecency_generation
+    };
+    let scores: Arc<HashMap<String, u32>> = Arc::new(
+        crate::usage::frecency_scores(app)
+            .into_iter()
+            .map(|((kind, target), score)| (item_id(kind, &target), score))
+            .collect(),
+    );
+    let mut index = state.lock();
+    if index.frecency_generation == generation {
+        index.frecency = Some((now, scores.clone()));
+    }
+    scores
+}
+
+/// 主窗口搜索：返回按匹配度 + frecency 排好序的条目；query 为空时返回最近常用的。
+#[tauri::command]
+pub(crate) fn command_index_query(
+    app: AppHandle,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<CommandIndexHit>, String> {
+    let limit = limit
+        .unwrap_or(DEFAULT_QUERY_LIMIT)
+        .clamp(1, MAX_QUERY_LIMIT);
+    let state = app.state::<CommandIndexState>();
+    let frecency = frecency_by_id(&app, &state);
+    let mut index = state.lock();
+    if index.entries.is_none() {
+        let plugins = load_plugin_summaries(&app);
+        let apps = crate::app_registry::load_registered_app_records(&app)?;
+        index.entries = Some(build_entries(&plugins, &apps, &index.runtime));
+    }
+    let entries = index.entries.as_deref().unwrap_or_default();
+    Ok(rank_entries(entries, frecency.as_ref(), &query, limit))
+}
+
+/// 主窗口打开一项时记一次使用，用于 frecency 排序。
+#[tauri::command]
+pub(crate) fn command_index_record_use(app: AppHandle, id: String) -> Result<(), String> {
+    let (kind, target) =
+        usage_key(id.trim()).ok_or_else(|| format!("命令 ID 不合法: {}", id.trim()))?;
+    crate::usage::record_usage(&app, kind, &target);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin(id: &str, name: &str, keyword: &str) -> PluginSummary {
+        PluginSummary {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            keyword: keyword.to_string(),
+        }
+    }
+
+    fn ids(hits: &[CommandIndexHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.id.as_str()).collect()
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_prefix_and_word_starts() {
+        let prefix = fuzzy_score("clip", "Clipboard History").unwrap();
+        let substring = fuzzy_score("board", "Clipboard History").unwrap();
+        let initials = fuzzy_score("ch", "Clipboard History").unwrap();
+        let scattered = fuzzy_score("cpy", "Clipboard History").unwrap();
+
+        assert!(prefix > substring);
+        assert!(substring > initials);
+        assert!(initials > scattered);
+        assert_eq!(fuzzy_score("xyz", "Clipboard History"), None);
+        assert_eq!(fuzzy_score("剪贴", "剪贴板历史"), Some(800));
+    }
+
+    #[test]
+    fn build_entries_covers_all_sources() {
+        let apps = vec![serde_json::json!({
+            "id": "notes",
+            "name": "Notes",
+            "commands": [{ "id": "new", "title": "New Note" }],
+        })];
+        let mut runtime = HashMap::new();
+        runtime.insert(
+            "notes".to_string(),
+            RuntimeCommands {
+                capabilities: vec![AppRuntimeDeclaration {
+                    id: "summarize".to_string(),
+                    title: "Summarize".to_string(),
+                    kind: None,
+                    icon: None,
+                    hotkey: None,
+                    description: None,
+                    config_fields: Vec::new(),
+                    input_schema: None,
+                    config_schema: None,
+                    output_schema: None,
+                }],
+                host_shortcuts: vec![AppRuntimeDeclaration {
+                    id: "new".to_string(),
+                    title: "New Note (runtime)".to_string(),
+                    kind: None,
+                    icon: None,
+                    hotkey: None,
+                    description: None,
+                    config_fields: Vec::new(),
+                    input_schema: None,
+                    config_schema: None,
+                    output_schema: None,
+                }],
+            },
+        );
+        let entries = build_entries(&[plugin("memo", "Memo", "")], &apps, &runtime);
+        let entry_ids: Vec<&str> = entries.iter().map(|entry| entry.id.as_str()).collect();
+
+        assert!(entry_ids.contains(&"host:settings"));
+        assert!(entry_ids.contains(&"memo"));
+        assert!(entry_ids.contains(&"app:notes"));
+        assert!(entry_ids.contains(&"app-capability:notes:summarize"));
+        let commands: Vec<&CommandEntry> = entries
+            .iter()
+            .filter(|entry| entry.id == "app-shortcut:notes:new")
+            .collect();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].title, "New Note");
+    }
+
+    #[test]
+    fn rank_requires_every_token_and_uses_frecency() {
+        let apps = vec![serde_json::json!({ "id": "notes", "name": "Notes" })];
+        let entries = build_entries(
+            &[
+                plugin("note-search", "Note Search", ""),
+                plugin("translate", "翻译", "fanyi"),
+            ],
+            &apps,
+            &HashMap::new(),
+        );
+        let mut frecency = HashMap::new();
+
+        let hits = rank_entries(&entries, &frecency, "note", 10);
+        assert_eq!(ids(&hits)[..2], ["note-search", "app:notes"]);
+        assert_eq!(
+            ids(&rank_entries(&entries, &frecency, "note srch", 10)),
+            ["note-search"]
+        );
+        assert_eq!(
+            ids(&rank_entries(&entries, &frecency, "fy", 10)),
+            ["translate"]
+        );
+
+        frecency.insert("app:notes".to_string(), 300);
+        let hits = rank_entries(&entries, &frecency, "note", 10);
+        assert_eq!(ids(&hits)[0], "app:notes");
+        assert_eq!(
+            ids(&rank_entries(&entries, &frecency, "", 10)),
+            ["app:notes"]
+        );
+    }
+
+    #[test]
+    fn item_ids_map_to_usage_keys() {
+        for id in [
+            "host:settings",
+            "memo",
+            "app:notes",
+            "app-shortcut:notes:new",
+            "app-capability:notes:summarize",
+        ] {
+            let (kind, target) = usage_key(id).unwrap();
+            assert_eq!(item_id(kind, &target), id);
+        }
+        assert_eq!(
+            usage_key("app-shortcut:notes:new"),
+            Some((UsageKind::AppCommand, "notes/new".to_string()))
+        );
+        assert_eq!(usage_key("app-shortcut:notes"), None);
+        assert_eq!(usage_key("../x"), None);
+    }
+}
@@ -211,3 +211,35 @@ pub(crate) fn write_plugin_library_dir_to_config(
         Ok(())
     })
 }
+
+pub(crate) fn read_browser_download_dir_from_config(app: &tauri::AppHandle) -> Option<PathBuf> {
+    let map = read_app_config_map(app);
+    let Some(Value::String(s)) = map.get(crate::BROWSER_DOWNLOAD_DIR_KEY) else {
+        return None;
+    };
+    let raw = s.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    Some(PathBuf::from(raw))
+}
+
+pub(crate) fn write_browser_download_dir_to_config(
+    app: &tauri::AppHandle,
+    dir: Option<&Path>,
+) -> Result<(), String> {
+    update_app_config_map(app, |map| {
+        match dir {
+            Some(dir) => {
+                map.insert(
+                    crate::BROWSER_DOWNLOAD_DIR_KEY.to_string(),
+                    Value::String(dir.to_string_lossy().to_string()),
+                );
+            }
+            None => {
+                map.remove(crate::BROWSER_DOWNLOAD_DIR_KEY);
+            }
+        }
+        Ok(())
+    })
+}
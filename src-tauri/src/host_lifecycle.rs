@@ -35,6 +35,7 @@ pub(crate) fn request_host_shutdown(app: AppHandle) {
     persist_host_window_state(&app);
     tauri::async_runtime::spawn(async move {
         stop_host_managed_apps(&app).await;
+        crate::capability_server::remove_capability_discovery_file(&app);
         app.exit(0);
     });
 }
@@ -55,6 +56,7 @@ fn persist_host_window_state(app: &AppHandle) {
     save_browser_stack_bounds_if_valid(app);
     let browser_state = app.state::<BrowserWindowState>();
     persist_browser_window_bounds(app, &browser_state);
+    crate::browser_tabs::persist_browser_session(app);
 }
 
 async fn stop_host_managed_apps(app: &AppHandle) {
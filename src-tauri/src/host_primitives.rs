@@ -24,11 +24,20 @@ pub(crate) fn emit_toast(app: &tauri::AppHandle, message: impl Into<String>) {
 }
 
 pub(crate) fn emit_activate_plugin(app: &tauri::AppHandle, plugin_id: impl Into<String>) {
+    emit_activate_plugin_with_command(app, plugin_id, None);
+}
+
+pub(crate) fn emit_activate_plugin_with_command(
+    app: &tauri::AppHandle,
+    plugin_id: impl Into<String>,
+    command: Option<String>,
+) {
     let _ = app.emit_to(
         EventTarget::webview_window("main"),
         ACTIVATE_PLUGIN_EVENT,
         ActivatePluginPayload {
             plugin_id: plugin_id.into(),
+            command,
         },
     );
 }
@@ -72,6 +81,7 @@ pub(crate) fn host_activate_plugin(app: tauri::AppHandle, plugin_id: String) ->
         return Err("pluginId 不合法".to_string());
     }
 
+    crate::usage::record_usage(&app, crate::usage::UsageKind::Plugin, &pid);
     emit_activate_plugin(&app, pid);
     Ok(())
 }
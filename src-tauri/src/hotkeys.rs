@@ -0,0 +1,900 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, EventTarget, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+// ── 全局快捷键注册表 ────────────────────────────────────────────────────────
+//
+// 宿主唤醒 / 主窗口模式、注册应用及其宿主快捷命令、能力工作流、插件快捷键都在这里向系统注册：
+// - 各模块按来源整体替换自己的绑定（replace_source）；冲突按来源优先级（宿主 > 应用 > 工作流 > 插件）
+//   和先后顺序解决，输的一方标记为“冲突”不注册，系统注册失败的标记为“失败”，hotkeys_list 一次看全。
+// - 按键序列：空格分隔多个组合键，如 `control+KeyK control+KeyM`。向系统只注册首键，
+//   按下首键后在 CHORD_TIMEOUT 内临时注册后续键。互为前缀的两个序列视为冲突。
+// - 单条绑定可停用（hotkeysDisabled），停用的不注册也不参与冲突。
+// 系统注册 / 注销统一放到主线程串行执行，且执行时不持有注册表的锁：
+// global-shortcut 插件在按键回调里持有自己的锁，回调里也不能直接注册，需要先丢到异步任务。
+
+const HOTKEYS_DISABLED_KEY: &str = "hotkeysDisabled";
+const HOTKEYS_UPDATED_EVENT: &str = "fast-window:hotkeys-updated";
+const MAX_SEQUENCE_LEN: usize = 4;
+const CHORD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+pub(crate) const WAKE_HOTKEY_ID: &str = "host:wake";
+pub(crate) const MAIN_WINDOW_MODE_HOTKEY_ID: &str = "host:mainWindowMode";
+
+pub(crate) type HotkeyAction = Arc<dyn Fn(&AppHandle) + Send + Sync>;
+
+/// 绑定来源；顺序即冲突时的优先级。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum HotkeySource {
+    Wake,
+    MainWindowMode,
+    Apps,
+    Workflows,
+    Plugins,
+}
+
+/// 一个或多个依次按下的组合键。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct HotkeySequence(Vec<Shortcut>);
+
+impl HotkeySequence {
+    pub(crate) fn first(&self) -> Shortcut {
+        self.0[0]
+    }
+
+    fn is_prefix_of(&self, other: &Self) -> bool {
+        other.0.starts_with(&self.0)
+    }
+
+    fn overlaps(&self, other: &Self) -> bool {
+        self.is_prefix_of(other) || other.is_prefix_of(self)
+    }
+}
+
+/// 按空白切分成各步；`control + KeyK` 这类 `+` 两侧带空格的写法仍算一步。
+fn split_strokes(raw: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut glue = false;
+    for token in raw.split_whitespace() {
+        match out.last_mut() {
+            Some(last) if glue || token.starts_with('+') => last.push_str(token),
+            _ => out.push(token.to_string()),
+        }
+        glue = token.ends_with('+');
+    }
+    out
+}
+
+impl FromStr for HotkeySequence {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, String> {
+        let strokes = split_strokes(raw)
+            .iter()
+            .map(|s| Shortcut::from_str(s).map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if strokes.is_empty() {
+            return Err("快捷键不能为空".to_string());
+        }
+        if strokes.len() > MAX_SEQUENCE_LEN {
+            return Err(format!("按键序列最多 {MAX_SEQUENCE_LEN} 步"));
+        }
+        Ok(Self(strokes))
+    }
+}
+
+impl fmt::Display for HotkeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, stroke) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{stroke}")?;
+        }
+        Ok(())
+    }
+}
+
+/// 校验一组快捷键互不冲突（保存注册应用等场景）；按认领顺序报第一个冲突。
+#[derive(Default)]
+pub(crate) struct HotkeyClaims {
+    items: Vec<(HotkeySequence, String)>,
+}
+
+impl HotkeyClaims {
+    pub(crate) fn claim(
+        &mut self,
+        raw_hotkey: &str,
+        owner: String,
+        invalid_message: String,
+    ) -> Result<HotkeySequence, String> {
+        let sequence =
+            HotkeySequence::from_str(raw_hotkey).map_err(|e| format!("{invalid_message}: {e}"))?;
+        self.claim_sequence(&sequence, owner)?;
+        Ok(sequence)
+    }
+
+    fn claim_sequence(&mut self, sequence: &HotkeySequence, owner: String) -> Result<(), String> {
+        if let Some((existing, existing_owner)) =
+            self.items.iter().find(|(seq, _)| seq.overlaps(sequence))
+        {
+            return Err(conflict_message(existing, existing_owner, sequence, &owner));
+        }
+        self.items.push((sequence.clone(), owner));
+        Ok(())
+    }
+}
+
+fn conflict_message(
+    existing: &HotkeySequence,
+    existing_owner: &str,
+    sequence: &HotkeySequence,
+    owner: &str,
+) -> String {
+    if existing == sequence {
+        format!("快捷键重复: {sequence}（{existing_owner} 和 {owner}）")
+    } else {
+        format!("快捷键冲突: {existing} 与 {sequence} 互为前缀（{existing_owner} 和 {owner}）")
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct HotkeySpec {
+    pub(crate) id: String,
+    pub(crate) owner: String,
+    pub(crate) sequence: HotkeySequence,
+    pub(crate) action: HotkeyAction,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum HotkeyStatus {
+    Active,
+    Disabled,
+    Paused,
+    Conflict(String),
+    Failed(String),
+}
+
+struct HotkeyEntry {
+    source: HotkeySource,
+    spec: HotkeySpec,
+    status: HotkeyStatus,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HotkeyInfo {
+    id: String,
+    source: HotkeySource,
+    owner: String,
+    keys: String,
+    enabled: bool,
+    /// active / disabled / paused / conflict / failed
+    status: &'static str,
+    detail: Option<String>,
+}
+
+impl HotkeyEntry {
+    fn info(&self) -> HotkeyInfo {
+        let (status, detail) = match &self.status {
+            HotkeyStatus::Active => ("active", None),
+            HotkeyStatus::Disabled => ("disabled", None),
+            HotkeyStatus::Paused => ("paused", None),
+            HotkeyStatus::Conflict(owner) => ("conflict", Some(format!("与{owner}冲突"))),
+            HotkeyStatus::Failed(error) => ("failed", Some(error.clone())),
+        };
+        HotkeyInfo {
+            id: self.spec.id.clone(),
+            source: self.source,
+            owner: self.spec.owner.clone(),
+            keys: self.spec.sequence.to_string(),
+            enabled: self.status != HotkeyStatus::Disabled,
+            status,
+            detail,
+        }
+    }
+
+    fn issue(&self) -> Option<String> {
+        match &self.status {
+            HotkeyStatus::Conflict(owner) => Some(format!(
+                "{}（{}）与{owner}冲突",
+                self.spec.owner, self.spec.sequence
+            )),
+            HotkeyStatus::Failed(error) => Some(format!(
+                "{}（{}）注册失败：{error}",
+                self.spec.owner, self.spec.sequence
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct PendingChord {
+    prefix: Vec<Shortcut>,
+    /// 为等后续键临时注册的组合键
+    temp: Vec<Shortcut>,
+}
+
+#[derive(Default)]
+struct HotkeyRegistry {
+    entries: Vec<HotkeyEntry>,
+    disabled: HashSet<String>,
+    paused: HashSet<HotkeySource>,
+    /// 当前向系统注册着的首键
+    registered: HashMap<u32, Shortcut>,
+    chord: Option<PendingChord>,
+    chord_seq: u64,
+}
+
+enum StrokeMatch {
+    Exact(usize),
+    /// 还没按完；附带下一步可能的键
+    Prefix(Vec<Shortcut>),
+    None,
+}
+
+fn match_stroke(sequences: &[&HotkeySequence], candidate: &[Shortcut]) -> StrokeMatch {
+    if let Some(i) = sequences.iter().position(|s| s.0 == candidate) {
+        return StrokeMatch::Exact(i);
+    }
+    let mut next: Vec<Shortcut> = Vec::new();
+    for seq in sequences {
+        if seq.0.len() > candidate.len() && seq.0.starts_with(candidate) {
+            let stroke = seq.0[candidate.len()];
+            if !next.contains(&stroke) {
+                next.push(stroke);
+            }
+        }
+    }
+    if next.is_empty() {
+        StrokeMatch::None
+    } else {
+        StrokeMatch::Prefix(next)
+    }
+}
+
+enum StrokeOutcome {
+    Run(HotkeyAction),
+    Chord(Vec<Shortcut>, Vec<Shortcut>),
+    Ignore,
+}
+
+impl HotkeyRegistry {
+    /// 按来源优先级和先后顺序重新判定每条绑定的状态（系统注册结果由 sync 另行写入）。
+    fn resolve(&mut self) {
+        self.entries.sort_by_key(|e| e.source);
+        let mut claims = HotkeyClaims::default();
+        for entry in self.entries.iter_mut() {
+            if self.disabled.contains(&entry.spec.id) {
+                entry.status = HotkeyStatus::Disabled;
+                continue;
+            }
+            if let Some((_, owner)) = claims
+                .items
+                .iter()
+                .find(|(seq, _)| seq.overlaps(&entry.spec.sequence))
+            {
+                entry.status = HotkeyStatus::Conflict(owner.clone());
+                continue;
+            }
+            claims
+                .items
+                .push((entry.spec.sequence.clone(), entry.spec.owner.clone()));
+            entry.status = if self.paused.contains(&entry.source) {
+                HotkeyStatus::Paused
+            } else {
+                HotkeyStatus::Active
+            };
+        }
+    }
+
+    /// 检查 spec 是否和其它来源、或同来源的其它新绑定冲突（停用的不算）。
+    fn check_conflict(
+        &self,
+        source: HotkeySource,
+        spec: &HotkeySpec,
+        siblings: &[HotkeySpec],
+    ) -> Result<(), String> {
+        let others = self
+            .entries
+            .iter()
+            .filter(|e| e.source != source)
+            .map(|e| &e.spec)
+            .chain(siblings.iter().filter(|s| s.id != spec.id));
+        for other in others {
+            if self.disabled.contains(&other.id) {
+                continue;
+            }
+            if other.sequence.overlaps(&spec.sequence) {
+                return Err(conflict_message(
+                    &other.sequence,
+                    &other.owner,
+                    &spec.sequence,
+                    &spec.owner,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn set_source(&mut self, source: HotkeySource, specs: Vec<HotkeySpec>) -> Vec<HotkeySpec> {
+        let (previous, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|e| e.source == source);
+        self.entries = rest;
+        self.entries
+            .extend(specs.into_iter().map(|spec| HotkeyEntry {
+                source,
+                spec,
+                status: HotkeyStatus::Active,
+            }));
+        previous.into_iter().map(|e| e.spec).collect()
+    }
+
+    fn issues(&self, source: HotkeySource) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|e| e.source == source)
+            .filter_map(HotkeyEntry::issue)
+            .collect()
+    }
+
+    fn list(&self) -> Vec<HotkeyInfo> {
+        self.entries.iter().map(HotkeyEntry::info).collect()
+    }
+
+    fn take_chord_keys(&mut self) -> Vec<Shortcut> {
+        self.chord_seq = self.chord_seq.wrapping_add(1);
+        self.chord.take().map(|c| c.temp).unwrap_or_default()
+    }
+
+    /// 按下一个键：先接在未完成的序列后面试，不成再当作新序列的首键。
+    fn press(&self, stroke: Shortcut) -> StrokeOutcome {
+        let active: Vec<&HotkeyEntry> = self
+            .entries
+            .iter()
+            .filter(|e| e.status == HotkeyStatus::Active)
+            .collect();
+        let sequences: Vec<&HotkeySequence> = active.iter().map(|e| &e.spec.sequence).collect();
+
+        let mut candidates = Vec::new();
+        if let Some(chord) = self.chord.as_ref() {
+            let mut candidate = chord.prefix.clone();
+            candidate.push(stroke);
+            candidates.push(candidate);
+        }
+        candidates.push(vec![stroke]);
+
+        for candidate in candidates {
+            match match_stroke(&sequences, &candidate) {
+                StrokeMatch::Exact(i) => return StrokeOutcome::Run(active[i].spec.action.clone()),
+                StrokeMatch::Prefix(next) => return StrokeOutcome::Chord(candidate, next),
+                StrokeMatch::None => {}
+            }
+        }
+        StrokeOutcome::Ignore
+    }
+}
+
+pub(crate) struct HotkeyRegistryState {
+    inner: Mutex<HotkeyRegistry>,
+}
+
+impl HotkeyRegistryState {
+    pub(crate) fn load(app: &AppHandle) -> Self {
+        let disabled = crate::read_app_config_map(app)
+            .get(HOTKEYS_DISABLED_KEY)
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            inner: Mutex::new(HotkeyRegistry {
+                disabled,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HotkeyRegistry> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 在主线程执行并等结果；本来就在主线程时直接执行。
+fn on_main<T: Send + 'static>(
+    app: &AppHandle,
+    task: impl FnOnce(&AppHandle) -> T + Send + 'static,
+) -> Result<T, String> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let handle = app.clone();
+    app.run_on_main_thread(move || {
+        let _ = tx.send(task(&handle));
+    })
+    .map_err(|e| e.to_string())?;
+    rx.recv().map_err(|e| e.to_string())
+}
+
+fn register_stroke(app: &AppHandle, stroke: Shortcut) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(stroke, |app, shortcut, event| {
+            if event.state != ShortcutState::Pressed {
+                return;
+            }
+            handle_stroke(app, *shortcut);
+        })
+        .map_err(|e| e.to_string())
+}
+
+fn unregister_strokes(app: &AppHandle, strokes: &[Shortcut]) {
+    for stroke in strokes {
+        let _ = app.global_shortcut().unregister(*stroke);
+    }
+}
+
+fn handle_stroke(app: &AppHandle, stroke: Shortcut) {
+    let state = app.state::<HotkeyRegistryState>();
+    let mut inner = state.lock();
+    match inner.press(stroke) {
+        StrokeOutcome::Run(action) => {
+            let stale = inner.take_chord_keys();
+            drop(inner);
+            release_chord_keys(app, stale);
+            action(app);
+        }
+        StrokeOutcome::Chord(prefix, next) => {
+            let stale = inner.take_chord_keys();
+            let seq = inner.chord_seq;
+            inner.chord = Some(PendingChord {
+                prefix,
+                temp: Vec::new(),
+            });
+            drop(inner);
+            spawn_chord_wait(app.clone(), seq, next, stale);
+        }
+        StrokeOutcome::Ignore => {
+            let stale = inner.take_chord_keys();
+            drop(inner);
+            release_chord_keys(app, stale);
+        }
+    }
+}
+
+fn release_chord_keys(app: &AppHandle, keys: Vec<Shortcut>) {
+    if keys.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = on_main(&app, move |app| unregister_strokes(app, &keys));
+    });
+}
+
+/// 临时注册后续键，超时后放弃这次序列。
+fn spawn_chord_wait(app: AppHandle, seq: u64, next: Vec<Shortcut>, stale: Vec<Shortcut>) {
+    tauri::async_runtime::spawn(async move {
+        let _ = on_main(&app, move |app| {
+            unregister_strokes(app, &stale);
+            let state = app.state::<HotkeyRegistryState>();
+            let wanted: Vec<Shortcut> = {
+                let inner = state.lock();
+                if inner.chord_seq != seq {
+                    return;
+                }
+                next.into_iter()
+                    .filter(|s| !inner.registered.contains_key(&s.id()))
+                    .collect()
+            };
+            let added: Vec<Shortcut> = wanted
+                .into_iter()
+                .filter(|s| register_stroke(app, *s).is_ok())
+                .collect();
+            let mut inner = state.lock();
+            if inner.chord_seq != seq {
+                drop(inner);
+                unregister_strokes(app, &added);
+            } else if let Some(chord) = inner.chord.as_mut() {
+                chord.temp = added;
+            }
+        });
+
+        tokio::time::sleep(CHORD_TIMEOUT).await;
+        let _ = on_main(&app, move |app| {
+            let state = app.state::<HotkeyRegistryState>();
+            let mut inner = state.lock();
+            if inner.chord_seq != seq {
+                return;
+            }
+            let stale = inner.take_chord_keys();
+            drop(inner);
+            unregister_strokes(app, &stale);
+        });
+    });
+}
+
+/// 把注册表的判定结果同步到系统：先注册新增的首键，再注销不再需要的。
+fn sync_on_main(app: &AppHandle) {
+    let state = app.state::<HotkeyRegistryState>();
+    let (stale, to_add, to_remove) = {
+        let mut inner = state.lock();
+        inner.resolve();
+        let stale = inner.take_chord_keys();
+        let mut desired: HashMap<u32, Shortcut> = HashMap::new();
+        for entry in inner
+            .entries
+            .iter()
+            .filter(|e| e.status == HotkeyStatus::Active)
+        {
+            let first = entry.spec.sequence.first();
+            desired.insert(first.id(), first);
+        }
+        let to_add: Vec<Shortcut> = desired
+            .iter()
+            .filter(|(id, _)| !inner.registered.contains_key(id))
+            .map(|(_, s)| *s)
+            .collect();
+        let to_remove: Vec<Shortcut> = inner
+            .registered
+            .iter()
+            .filter(|(id, _)| !desired.contains_key(id))
+            .map(|(_, s)| *s)
+            .collect();
+        (stale, to_add, to_remove)
+    };
+
+    unregister_strokes(app, &stale);
+    let mut added = Vec::new();
+    let mut failures: HashMap<u32, String> = HashMap::new();
+    for stroke in to_add {
+        match register_stroke(app, stroke) {
+            Ok(()) => added.push(stroke),
+            Err(e) => {
+                eprintln!("[hotkeys] failed to register {stroke}: {e}");
+                failures.insert(stroke.id(), e);
+            }
+        }
+    }
+    unregister_strokes(app, &to_remove);
+
+    {
+        let mut inner = state.lock();
+        for stroke in &to_remove {
+            inner.registered.remove(&stroke.id());
+        }
+        for stroke in added {
+            inner.registered.insert(stroke.id(), stroke);
+        }
+        for entry in inner.entries.iter_mut() {
+            if entry.status != HotkeyStatus::Active {
+                continue;
+            }
+            if let Some(error) = failures.get(&entry.spec.sequence.first().id()) {
+                entry.status = HotkeyStatus::Failed(error.clone());
+            }
+        }
+    }
+
+    let _ = app.emit_to(
+        EventTarget::webview_window("main"),
+        HOTKEYS_UPDATED_EVENT,
+        (),
+    );
+}
+
+fn sync(app: &AppHandle) {
+    if let Err(e) = on_main(app, sync_on_main) {
+        eprintln!("[hotkeys] sync failed: {e}");
+    }
+}
+
+/// 整体替换某个来源的绑定并同步到系统；返回这个来源里没能生效的绑定（冲突 / 注册失败）。
+pub(crate) fn replace_source(
+    app: &AppHandle,
+    source: HotkeySource,
+    specs: Vec<HotkeySpec>,
+) -> Vec<String> {
+    let state = app.state::<HotkeyRegistryState>();
+    state.lock().set_source(source, specs);
+    sync(app);
+    let issues = state.lock().issues(source);
+    issues
+}
+
+/// 用户主动设置时用：`checked` 里的绑定与其它绑定冲突、或系统注册失败都直接报错，
+/// 失败时恢复这个来源原来的绑定。
+pub(crate) fn replace_source_checked(
+    app: &AppHandle,
+    source: HotkeySource,
+    specs: Vec<HotkeySpec>,
+    checked: &[&str],
+) -> Result<(), String> {
+    replace_source_checked_inner(app, source, specs, checked, false)
+}
+
+/// 同 `replace_source_checked`，并解除这个来源的暂停（录制快捷键时会暂停）；
+/// 暂停中的绑定不会向系统注册，只有一起解除才能验证新绑定可用。失败时恢复暂停。
+pub(crate) fn replace_source_checked_and_resume(
+    app: &AppHandle,
+    source: HotkeySource,
+    specs: Vec<HotkeySpec>,
+    checked: &[&str],
+) -> Result<(), String> {
+    replace_source_checked_inner(app, source, specs, checked, true)
+}
+
+fn replace_source_checked_inner(
+    app: &AppHandle,
+    source: HotkeySource,
+    specs: Vec<HotkeySpec>,
+    checked: &[&str],
+    resume: bool,
+) -> Result<(), String> {
+    let state = app.state::<HotkeyRegistryState>();
+    let (previous, was_paused) = {
+        let mut inner = state.lock();
+        for spec in specs.iter().filter(|s| checked.contains(&s.id.as_str())) {
+            if !inner.disabled.contains(&spec.id) {
+                inner.check_conflict(source, spec, &specs)?;
+            }
+        }
+        let was_paused = resume && inner.paused.remove(&source);
+        (inner.set_source(source, specs), was_paused)
+    };
+    sync(app);
+
+    let failed = state.lock().entries.iter().find_map(|e| match &e.status {
+        HotkeyStatus::Failed(error)
+            if e.source == source && checked.contains(&e.spec.id.as_str()) =>
+        {
+            Some(format!("注册全局快捷键失败: {error}"))
+        }
+        _ => None,
+    });
+    match failed {
+        Some(error) => {
+            if was_paused {
+                state.lock().paused.insert(source);
+            }
+            replace_source(app, source, previous);
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
+
+/// 保存前预检：与其它来源的绑定冲突，或首键已被别的程序占用都报错；不改动注册表。
+pub(crate) fn check_source_available(
+    app: &AppHandle,
+    source: HotkeySource,
+    specs: &[HotkeySpec],
+) -> Result<(), String> {
+    let state = app.state::<HotkeyRegistryState>();
+    let probe: Vec<Shortcut> = {
+        let inner = state.lock();
+        for spec in specs {
+            inner.check_conflict(source, spec, specs)?;
+        }
+        let mut probe: Vec<Shortcut> = Vec::new();
+        for spec in specs {
+            let first = spec.sequence.first();
+            if !inner.registered.contains_key(&first.id()) && !probe.contains(&first) {
+                probe.push(first);
+            }
+        }
+        probe
+    };
+    if probe.is_empty() {
+        return Ok(());
+    }
+
+    on_main(app, move |app| {
+        for stroke in probe {
+            app.global_shortcut()
+                .on_shortcut(stroke, |_app, _shortcut, _event| {})
+                .map_err(|e| format!("快捷键不可用：{stroke}（{e}）"))?;
+            let _ = app.global_shortcut().unregister(stroke);
+        }
+        Ok(())
+    })?
+}
+
+/// 录制快捷键时暂停某个来源：保留绑定（仍占着键位），只从系统注销。
+pub(crate) fn set_source_paused(app: &AppHandle, source: HotkeySource, paused: bool) {
+    let state = app.state::<HotkeyRegistryState>();
+    let changed = {
+        let mut inner = state.lock();
+        if paused {
+            inner.paused.insert(source)
+        } else {
+            inner.paused.remove(&source)
+        }
+    };
+    if changed {
+        sync(app);
+    }
+}
+
+#[tauri::command]
+pub(crate) fn hotkeys_list(app: AppHandle) -> Vec<HotkeyInfo> {
+    let state = app.state::<HotkeyRegistryState>();
+    let list = state.lock().list();
+    list
+}
+
+#[tauri::command]
+pub(crate) fn hotkeys_set_enabled(
+    app: AppHandle,
+    id: String,
+    enabled: bool,
+) -> Result<Vec<HotkeyInfo>, String> {
+    let id = id.trim().to_string();
+    if id.is_empty() {
+        return Err("id 不能为空".to_string());
+    }
+    if id == WAKE_HOTKEY_ID && !enabled {
+        return Err("唤醒快捷键不能停用".to_string());
+    }
+
+    let state = app.state::<HotkeyRegistryState>();
+    let disabled = {
+        let mut inner = state.lock();
+        let changed = if enabled {
+            inner.disabled.remove(&id)
+        } else {
+            inner.disabled.insert(id.clone())
+        };
+        if !changed {
+            return Ok(inner.list());
+        }
+        let mut ids: Vec<String> = inner.disabled.iter().cloned().collect();
+        ids.sort();
+        ids
+    };
+    crate::update_app_config_map(&app, |map| {
+        if disabled.is_empty() {
+            map.remove(HOTKEYS_DISABLED_KEY);
+        } else {
+            map.insert(
+                HOTKEYS_DISABLED_KEY.to_string(),
+                Value::Array(disabled.into_iter().map(Value::String).collect()),
+            );
+        }
+        Ok(())
+    })?;
+
+    sync(&app);
+    let list = state.lock().list();
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seq(raw: &str) -> HotkeySequence {
+        HotkeySequence::from_str(raw).unwrap()
+    }
+
+    fn spec(id: &str, raw: &str) -> HotkeySpec {
+        HotkeySpec {
+            id: id.to_string(),
+            owner: id.to_string(),
+            sequence: seq(raw),
+            action: Arc::new(|_app: &AppHandle| {}),
+        }
+    }
+
+    #[test]
+    fn parses_chords_and_tolerates_spaced_plus() {
+        let s = seq("control+KeyK  control + KeyM");
+        assert_eq!(s.0.len(), 2);
+        assert_eq!(s, seq("ctrl+KeyK ctrl+KeyM"));
+        assert_eq!(HotkeySequence::from_str(&s.to_string()).unwrap(), s);
+        assert!(HotkeySequence::from_str("").is_err());
+        assert!(HotkeySequence::from_str("KeyA KeyB KeyC KeyD KeyE").is_err());
+    }
+
+    #[test]
+    fn claims_reject_duplicates_and_prefixes() {
+        let mut claims = HotkeyClaims::default();
+        claims.claim("alt+KeyN", "a".into(), "bad".into()).unwrap();
+        claims
+            .claim("ctrl+KeyK ctrl+KeyM", "b".into(), "bad".into())
+            .unwrap();
+        claims
+            .claim("ctrl+KeyK ctrl+KeyJ", "c".into(), "bad".into())
+            .unwrap();
+
+        let dup = claims
+            .claim("Alt+KeyN", "d".into(), "bad".into())
+            .unwrap_err();
+        assert!(dup.contains("快捷键重复"));
+        let prefix = claims
+            .claim("ctrl+KeyK", "e".into(), "bad".into())
+            .unwrap_err();
+        assert!(prefix.contains("互为前缀") && prefix.contains('b'));
+        assert!(claims
+            .claim("ctrl+NotAKey", "f".into(), "bad".into())
+            .is_err());
+    }
+
+    #[test]
+    fn resolve_prefers_higher_priority_sources() {
+        let mut registry = HotkeyRegistry::default();
+        registry.set_source(
+            HotkeySource::Plugins,
+            vec![
+                spec("plugin:memo/quick", "alt+KeyN"),
+                spec("plugin:todo/add", "alt+KeyT"),
+            ],
+        );
+        registry.set_source(HotkeySource::Apps, vec![spec("app:notes", "alt+KeyN")]);
+        registry.disabled.insert("plugin:todo/add".into());
+        registry.paused.insert(HotkeySource::Apps);
+        registry.resolve();
+
+        let status = |id: &str| {
+            registry
+                .entries
+                .iter()
+                .find(|e| e.spec.id == id)
+                .map(|e| e.status.clone())
+                .unwrap()
+        };
+        assert_eq!(status("app:notes"), HotkeyStatus::Paused);
+        assert_eq!(
+            status("plugin:memo/quick"),
+            HotkeyStatus::Conflict("app:notes".into())
+        );
+        assert_eq!(status("plugin:todo/add"), HotkeyStatus::Disabled);
+        assert_eq!(registry.issues(HotkeySource::Plugins).len(), 1);
+    }
+
+    #[test]
+    fn check_conflict_ignores_disabled_and_same_id() {
+        let mut registry = HotkeyRegistry::default();
+        registry.set_source(HotkeySource::Apps, vec![spec("app:notes", "alt+KeyN")]);
+        let replacement = vec![spec("plugin:memo/quick", "alt+KeyN")];
+        assert!(registry
+            .check_conflict(HotkeySource::Plugins, &replacement[0], &replacement)
+            .is_err());
+
+        registry.disabled.insert("app:notes".into());
+        assert!(registry
+            .check_conflict(HotkeySource::Plugins, &replacement[0], &replacement)
+            .is_ok());
+    }
+
+    #[test]
+    fn strokes_walk_chords() {
+        let a = seq("ctrl+KeyK ctrl+KeyM");
+        let b = seq("ctrl+KeyK ctrl+KeyJ");
+        let c = seq("alt+KeyN");
+        let all = [&a, &b, &c];
+
+        assert!(matches!(match_stroke(&all, &c.0), StrokeMatch::Exact(2)));
+        match match_stroke(&all, &a.0[..1]) {
+            StrokeMatch::Prefix(next) => assert_eq!(next, vec![a.0[1], b.0[1]]),
+            _ => panic!("expected prefix"),
+        }
+        assert!(matches!(match_stroke(&all, &a.0), StrokeMatch::Exact(0)));
+        assert!(matches!(
+            match_stroke(&all, &[a.0[0], c.0[0]]),
+            StrokeMatch::None
+        ));
+    }
+}
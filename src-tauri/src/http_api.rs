@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::error::Error;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use url::Url;
@@ -10,6 +11,12 @@ use serde::{Deserialize, Serialize};
 use tauri::ipc::Channel;
 use tauri::Manager;
 
+use crate::http_cache::{HttpCacheLookup, HttpCacheScope, HttpCachedResponse};
+use crate::http_debug::HttpDebugRecorder;
+use crate::http_host_policy::{plugin_http_allow_list, HttpHostAllowList};
+use crate::http_mock::{
+    http_mock_mode, replay_delay, HttpMockKey, HttpMockMode, HttpMockStreamTape,
+};
 use crate::{is_http_url, make_http_stream_id};
 
 // ── HTTP 客户端策略 ──────────────────────────────────────────────────────────
@@ -66,7 +73,8 @@ impl HttpClientPolicy {
 // ── 结构化 HTTP 错误类型 ─────────────────────────────────────────────────────
 
 /// 对 reqwest 错误的结构化分类，用于调用方判断是否可安全重试。
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub(crate) enum HttpErrorKind {
     /// TCP 连接建立失败（DNS / 连接超时 / 连接被拒绝）
     Connect,
@@ -173,7 +181,7 @@ fn format_reqwest_error(prefix: &str, err: &reqwest::Error) -> String {
     parts.join(" | ")
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub(crate) struct HttpRequest {
     pub(crate) method: String,
     pub(crate) url: String,
@@ -183,6 +191,10 @@ pub(crate) struct HttpRequest {
     pub(crate) body_base64: Option<String>,
     #[serde(rename = "timeoutMs")]
     pub(crate) timeout_ms: Option<u64>,
+    /// 响应缓存开关：缺省时 GET 请求按 HTTP 缓存语义走插件级磁盘缓存；
+    /// false 完全跳过；true 额外允许缓存带 Authorization 的请求。
+    #[serde(default)]
+    pub(crate) cache: Option<bool>,
 }
 
 #[derive(Serialize)]
@@ -224,8 +236,14 @@ pub(crate) enum HttpStreamEvent {
 }
 
 #[tauri::command]
-pub(crate) async fn http_request(req: HttpRequest) -> Result<HttpResponse, String> {
-    let (status, headers, bytes) = http_request_raw(req).await.map_err(|e| e.message)?;
+pub(crate) async fn http_request(
+    app: tauri::AppHandle,
+    req: HttpRequest,
+    plugin_id: Option<String>,
+) -> Result<HttpResponse, String> {
+    let (status, headers, bytes) = http_request_for_plugin(&app, plugin_id.as_deref(), req)
+        .await
+        .map_err(|e| e.message)?;
     // 用 lossy 解码：非 UTF-8 字节用 U+FFFD 替换，确保响应体始终可读（调试/错误分析友好）
     let body = String::from_utf8_lossy(&bytes).into_owned();
     Ok(HttpResponse {
@@ -237,10 +255,12 @@ pub(crate) async fn http_request(req: HttpRequest) -> Result<HttpResponse, Strin
 
 /// tasks.rs 专用：同时返回结构化 HttpGatewayError，
 /// 让任务层直接从 kind 判断是否重试，无需字符串反推。
+/// `hosts` 为创建任务的插件白名单，请求地址与每一跳重定向都要在其中。
 pub(crate) async fn http_request_for_task(
     req: HttpRequest,
+    hosts: Option<&HttpHostAllowList>,
 ) -> Result<HttpResponse, HttpGatewayError> {
-    let (status, headers, bytes) = http_request_raw(req).await?;
+    let (status, headers, bytes) = http_request_raw(req, hosts, None).await?;
     let body = String::from_utf8_lossy(&bytes).into_owned();
     Ok(HttpResponse {
         status,
@@ -250,8 +270,14 @@ pub(crate) async fn http_request_for_task(
 }
 
 #[tauri::command]
-pub(crate) async fn http_request_base64(req: HttpRequest) -> Result<HttpResponseBase64, String> {
-    let (status, headers, bytes) = http_request_raw(req).await.map_err(|e| e.message)?;
+pub(crate) async fn http_request_base64(
+    app: tauri::AppHandle,
+    req: HttpRequest,
+    plugin_id: Option<String>,
+) -> Result<HttpResponseBase64, String> {
+    let (status, headers, bytes) = http_request_for_plugin(&app, plugin_id.as_deref(), req)
+        .await
+        .map_err(|e| e.message)?;
     let body_base64 = general_purpose::STANDARD.encode(bytes);
     Ok(HttpResponseBase64 {
         status,
@@ -265,7 +291,13 @@ pub(crate) async fn http_request_stream(
     app: tauri::AppHandle,
     req: HttpRequest,
     channel: Channel<HttpStreamEvent>,
+    plugin_id: Option<String>,
 ) -> Result<String, String> {
+    let hosts = plugin_http_allow_list(&app, plugin_id.as_deref())?;
+    if let Some(hosts) = hosts.as_ref() {
+        hosts.ensure_allowed(&req.url)?;
+    }
+    let mut recorder = HttpDebugRecorder::begin(&app, plugin_id.as_deref(), &req, true);
     let stream_id = make_http_stream_id();
     let manager = app.state::<Arc<HttpStreamManagerState>>().inner().clone();
 
@@ -298,14 +330,33 @@ pub(crate) async fn http_request_stream(
             stream_id: stream_id_clone,
         };
 
+        let mock = http_mock_mode().map(|mode| (mode, HttpMockKey::for_request(&req)));
+        if let Some((HttpMockMode::Replay(dir), key)) = mock.as_ref() {
+            replay_mock_stream(dir, key, &channel, &mut rx, recorder).await;
+            return;
+        }
+
         const MAX_TIMEOUT_MS: u64 = 15 * 60 * 1000;
-        let (status, headers, mut resp) = match http_request_send(req, MAX_TIMEOUT_MS).await {
-            Ok(v) => v,
-            Err(e) => {
-                let _ = channel.send(HttpStreamEvent::Error { message: e.message });
-                let _ = channel.send(HttpStreamEvent::End { canceled: false });
-                return;
+        let (status, headers, mut resp) =
+            match http_request_send(req, MAX_TIMEOUT_MS, hosts.as_ref()).await {
+                Ok(v) => v,
+                Err(e) => {
+                    if let Some(rec) = recorder {
+                        rec.finish(Some(&e));
+                    }
+                    let _ = channel.send(HttpStreamEvent::Error { message: e.message });
+                    let _ = channel.send(HttpStreamEvent::End { canceled: false });
+                    return;
+                }
+            };
+        if let Some(rec) = recorder.as_mut() {
+            rec.response_started(status, &headers);
+        }
+        let mut tape = match mock {
+            Some((HttpMockMode::Record(dir), key)) => {
+                Some(HttpMockStreamTape::start(dir, key, status, &headers))
             }
+            _ => None,
         };
 
         if channel
@@ -320,6 +371,7 @@ pub(crate) async fn http_request_stream(
         let mut pending: Vec<u8> = Vec::new();
 
         let mut canceled = false;
+        let mut stream_error: Option<HttpGatewayError> = None;
         loop {
             tokio::select! {
                 _ = &mut rx => {
@@ -329,6 +381,9 @@ pub(crate) async fn http_request_stream(
                 chunk = resp.chunk() => {
                     match chunk {
                         Ok(Some(bytes)) => {
+                            if let Some(rec) = recorder.as_mut() {
+                                rec.add_response_bytes(bytes.len());
+                            }
                             total = total.saturating_add(bytes.len());
                             if total > MAX_HTTP_STREAM_BYTES {
                                 let _ = channel.send(HttpStreamEvent::Error { message: "响应过大（超过 50MB）".to_string() });
@@ -342,6 +397,9 @@ pub(crate) async fn http_request_stream(
                                     Ok(s) => {
                                         let text = s.to_string();
                                         pending.clear();
+                                        if let Some(tape) = tape.as_mut() {
+                                            tape.push(&text);
+                                        }
                                         if !text.is_empty()
                                             && channel.send(HttpStreamEvent::Chunk { text }).is_err()
                                         {
@@ -357,6 +415,9 @@ pub(crate) async fn http_request_stream(
                                         }
                                         let text = String::from_utf8_lossy(&pending[..n]).to_string();
                                         pending.drain(..n);
+                                        if let Some(tape) = tape.as_mut() {
+                                            tape.push(&text);
+                                        }
                                         if !text.is_empty() && channel.send(HttpStreamEvent::Chunk { text }).is_err() {
                                             return;
                                         }
@@ -366,6 +427,7 @@ pub(crate) async fn http_request_stream(
                         }
                         Ok(None) => break,
                         Err(e) => {
+                            stream_error = Some(HttpGatewayError::from_reqwest("读取响应失败", &e));
                             let _ = channel.send(HttpStreamEvent::Error { message: format!("读取响应失败: {e}") });
                             break;
                         }
@@ -377,18 +439,94 @@ pub(crate) async fn http_request_stream(
         // flush pending utf8
         if !pending.is_empty() {
             if let Ok(s) = std::str::from_utf8(&pending) {
+                if let Some(tape) = tape.as_mut() {
+                    tape.push(s);
+                }
                 let _ = channel.send(HttpStreamEvent::Chunk {
                     text: s.to_string(),
                 });
             }
         }
 
+        if let Some(tape) = tape {
+            // 只录制完整结束的流，取消/出错的不落盘
+            if !canceled && stream_error.is_none() {
+                tape.finish();
+            }
+        }
+        if let Some(rec) = recorder {
+            rec.finish(stream_error.as_ref());
+        }
         let _ = channel.send(HttpStreamEvent::End { canceled });
     });
 
     Ok(stream_id)
 }
 
+/// mock 回放：按录制时的间隔逐片推送，仍然响应取消。
+async fn replay_mock_stream(
+    dir: &Path,
+    key: &HttpMockKey,
+    channel: &Channel<HttpStreamEvent>,
+    rx: &mut tokio::sync::oneshot::Receiver<()>,
+    mut recorder: Option<HttpDebugRecorder>,
+) {
+    let loaded = key
+        .load(dir)
+        .and_then(|fixture| fixture.stream_chunks().map(|chunks| (fixture, chunks)));
+    let (fixture, chunks) = match loaded {
+        Ok(v) => v,
+        Err(message) => {
+            if let Some(rec) = recorder {
+                rec.finish(Some(&HttpGatewayError::other(message.clone())));
+            }
+            let _ = channel.send(HttpStreamEvent::Error { message });
+            let _ = channel.send(HttpStreamEvent::End { canceled: false });
+            return;
+        }
+    };
+
+    if let Some(rec) = recorder.as_mut() {
+        rec.response_started(fixture.status, &fixture.headers);
+    }
+    if channel
+        .send(HttpStreamEvent::Start {
+            status: fixture.status,
+            headers: fixture.headers.clone(),
+        })
+        .is_err()
+    {
+        return;
+    }
+
+    let mut canceled = false;
+    for chunk in &chunks {
+        tokio::select! {
+            _ = &mut *rx => {
+                canceled = true;
+                break;
+            }
+            _ = tokio::time::sleep(replay_delay(chunk)) => {}
+        }
+        if let Some(rec) = recorder.as_mut() {
+            rec.add_response_bytes(chunk.text.len());
+        }
+        if channel
+            .send(HttpStreamEvent::Chunk {
+                text: chunk.text.clone(),
+            })
+            .is_err()
+        {
+            return;
+        }
+    }
+
+    if let Some(rec) = recorder {
+        rec.finish(None);
+    }
+    let _ = channel.send(HttpStreamEvent::End { canceled });
+}
+
 #[tauri::command]
 pub(crate) fn http_request_stream_cancel(
     app: tauri::AppHandle,
@@ -450,6 +588,7 @@ pub(crate) async fn gateway_test_channel(
 async fn http_request_send(
     req: HttpRequest,
     timeout_cap_ms: u64,
+    hosts: Option<&HttpHostAllowList>,
 ) -> Result<(u16, HashMap<String, String>, reqwest::Response), HttpGatewayError> {
     let method = req.method.trim().to_uppercase();
     if method.is_empty() {
@@ -468,6 +607,28 @@ async fn http_request_send(
 
     let mut client_builder = reqwest::Client::builder().timeout(timeout);
 
+    if let Some(hosts) = hosts {
+        hosts
+            .ensure_allowed(&req.url)
+            .map_err(HttpGatewayError::other)?;
+        // 重定向目标同样要在白名单内，避免借 30x 绕过声明
+        let hosts = hosts.clone();
+        client_builder =
+            client_builder.redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                if attempt.previous().len() >= 10 {
+                    attempt.error("重定向次数过多")
+                } else if hosts.allows(attempt.url()) {
+                    attempt.follow()
+                } else {
+                    let err = hosts
+                        .ensure_allowed(attempt.url().as_str())
+                        .err()
+                        .unwrap_or_default();
+                    attempt.error(err)
+                }
+            }));
+    }
+
     if policy.force_http1 {
         client_builder = client_builder.http1_only();
     }
@@ -553,10 +714,110 @@ async fn http_request_send(
     Ok((status, headers, resp))
 }
 
+/// 插件发起的非流式请求入口；插件开启调试日志时记录本次请求。
+async fn http_request_for_plugin(
+    app: &tauri::AppHandle,
+    plugin_id: Option<&str>,
+    req: HttpRequest,
+) -> Result<(u16, HashMap<String, String>, Vec<u8>), HttpGatewayError> {
+    let mut recorder = HttpDebugRecorder::begin(app, plugin_id, &req, false);
+    let result = http_request_cached(app, plugin_id, req, &mut recorder).await;
+    if let Some(rec) = recorder {
+        rec.finish(result.as_ref().err());
+    }
+    result
+}
+
+fn cached_parts(
+    hit: HttpCachedResponse,
+    recorder: &mut Option<HttpDebugRecorder>,
+) -> (u16, HashMap<String, String>, Vec<u8>) {
+    if let Some(rec) = recorder.as_mut() {
+        rec.response_started(hit.status, &hit.headers);
+        rec.add_response_bytes(hit.body.len());
+    }
+    (hit.status, hit.headers, hit.body)
+}
+
+/// 可缓存的 GET 先查插件级磁盘缓存，过期则带校验器发条件请求。
+async fn http_request_cached(
+    app: &tauri::AppHandle,
+    plugin_id: Option<&str>,
+    mut req: HttpRequest,
+    recorder: &mut Option<HttpDebugRecorder>,
+) -> Result<(u16, HashMap<String, String>, Vec<u8>), HttpGatewayError> {
+    let hosts = plugin_http_allow_list(app, plugin_id).map_err(HttpGatewayError::other)?;
+    if let Some(hosts) = hosts.as_ref() {
+        // 先于缓存检查：撤销声明后，旧缓存也不能再被读取
+        hosts
+            .ensure_allowed(&req.url)
+            .map_err(HttpGatewayError::other)?;
+    }
+    let Some(scope) = HttpCacheScope::for_request(app, plugin_id, &req) else {
+        return http_request_raw(req, hosts.as_ref(), recorder.as_mut()).await;
+    };
+
+    // 加了条件头时留一份原请求：304 却取不到缓存正文时用它重发
+    let mut unconditional = None;
+    match scope.lookup() {
+        HttpCacheLookup::Fresh(hit) => return Ok(cached_parts(hit, recorder)),
+        HttpCacheLookup::Stale {
+            etag,
+            last_modified,
+        } => {
+            unconditional = Some(req.clone());
+            let headers = req.headers.get_or_insert_with(HashMap::new);
+            if let Some(etag) = etag {
+                headers.insert("If-None-Match".to_string(), etag);
+            }
+            if let Some(last_modified) = last_modified {
+                headers.insert("If-Modified-Since".to_string(), last_modified);
+            }
+        }
+        HttpCacheLookup::Miss => {}
+    }
+
+    let (mut status, mut headers, mut bytes) =
+        http_request_raw(req, hosts.as_ref(), recorder.as_mut()).await?;
+    if status == 304 {
+        if let Some(hit) = scope.revalidated(&headers) {
+            return Ok(cached_parts(hit, recorder));
+        }
+        // 缓存正文已被淘汰或损坏：条件头是网关加的，调用方不认识 304，去掉条件头重发
+        if let Some(req) = unconditional {
+            (status, headers, bytes) =
+                http_request_raw(req, hosts.as_ref(), recorder.as_mut()).await?;
+        }
+    }
+    scope.store(status, &headers, &bytes);
+    Ok((status, headers, bytes))
+}
+
 async fn http_request_raw(
     req: HttpRequest,
+    hosts: Option<&HttpHostAllowList>,
+    mut recorder: Option<&mut HttpDebugRecorder>,
 ) -> Result<(u16, HashMap<String, String>, Vec<u8>), HttpGatewayError> {
-    let (status, headers, resp) = http_request_send(req, 120_000).await?;
+    let mock = http_mock_mode().map(|mode| (mode, HttpMockKey::for_request(&req)));
+    if let Some((HttpMockMode::Replay(dir), key)) = mock.as_ref() {
+        if let Some(hosts) = hosts {
+            hosts
+                .ensure_allowed(&req.url)
+                .map_err(HttpGatewayError::other)?;
+        }
+        let fixture = key.load(dir).map_err(HttpGatewayError::other)?;
+        let body = fixture.body().map_err(HttpGatewayError::other)?;
+        if let Some(rec) = recorder {
+            rec.response_started(fixture.status, &fixture.headers);
+            rec.add_response_bytes(body.len());
+        }
+        return Ok((fixture.status, fixture.headers, body));
+    }
+
+    let (status, headers, resp) = http_request_send(req, 120_000, hosts).await?;
+    if let Some(rec) = recorder.as_deref_mut() {
+        rec.response_started(status, &headers);
+    }
 
     // 图片相关的 JSON/base64 响应可能很大（尤其是 chat/completions 返回 b64）。
     // 这里做上限保护，避免插件拉取无限大响应导致内存爆炸。
@@ -573,5 +834,11 @@ async fn http_request_raw(
             MAX_HTTP_RESPONSE_BYTES
         )));
     }
+    if let Some(rec) = recorder {
+        rec.add_response_bytes(bytes.len());
+    }
+    if let Some((HttpMockMode::Record(dir), key)) = mock.as_ref() {
+        key.record_body(dir, status, &headers, &bytes);
+    }
     Ok((status, headers, bytes.to_vec()))
 }
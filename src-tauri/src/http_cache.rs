@@ -0,0 +1,640 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::Manager;
+
+// ── 插件级 HTTP 响应磁盘缓存 ─────────────────────────────────────────────────
+//
+// 只缓存 GET 的 200 响应，按 Cache-Control / Expires / ETag / Last-Modified 判断新鲜度与再验证。
+// 每个插件一个目录：<data>/__app/http-cache/<pluginId>/，index.json 记录元数据，正文按 key 存 .bin。
+// 索引常驻内存，命中只更新内存里的访问时间，写入/淘汰时才落盘。
+
+const HTTP_CACHE_DIR: &str = "http-cache";
+const HTTP_CACHE_INDEX_FILE: &str = "index.json";
+const HTTP_CACHE_MAX_BYTES_PER_PLUGIN: u64 = 64 * 1024 * 1024; // 64MB
+const HTTP_CACHE_MAX_ENTRY_BYTES: usize = 8 * 1024 * 1024; // 8MB
+const HTTP_CACHE_MAX_ENTRIES_PER_PLUGIN: usize = 2000;
+const HEURISTIC_FRESHNESS_MAX_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// 命中缓存时附加到响应头里，方便插件调试。
+pub(crate) const HTTP_CACHE_STATUS_HEADER: &str = "x-fast-window-cache";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HttpCacheEntry {
+    url: String,
+    status: u16,
+    headers: HashMap<String, String>,
+    /// Vary 命中的请求头（小写名 -> 值），查找时必须一致。
+    #[serde(default)]
+    vary: HashMap<String, String>,
+    stored_at_ms: u64,
+    fresh_until_ms: u64,
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+    size: u64,
+    last_access_ms: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct HttpCacheIndex {
+    #[serde(default)]
+    entries: HashMap<String, HttpCacheEntry>,
+}
+
+#[derive(Default)]
+pub(crate) struct HttpCacheState {
+    indexes: Mutex<HashMap<String, HttpCacheIndex>>,
+}
+
+pub(crate) struct HttpCachedResponse {
+    pub(crate) status: u16,
+    pub(crate) headers: HashMap<String, String>,
+    pub(crate) body: Vec<u8>,
+}
+
+pub(crate) enum HttpCacheLookup {
+    Fresh(HttpCachedResponse),
+    /// 已过期但带校验器，可发条件请求。
+    Stale {
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+    Miss,
+}
+
+/// 单次请求的缓存上下文：已确定插件、key 与请求头。
+pub(crate) struct HttpCacheScope {
+    state: Arc<HttpCacheState>,
+    dir: PathBuf,
+    plugin_id: String,
+    key: String,
+    url: String,
+    request_headers: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    max_age: Option<u64>,
+}
+
+fn parse_cache_control(raw: &str) -> CacheControl {
+    let mut cc = CacheControl::default();
+    for part in raw.split(',') {
+        let part = part.trim();
+        let (name, value) = match part.split_once('=') {
+            Some((n, v)) => (n.trim(), Some(v.trim().trim_matches('"'))),
+            None => (part, None),
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "no-store" => cc.no_store = true,
+            "no-cache" => cc.no_cache = true,
+            "max-age" => cc.max_age = value.and_then(|v| v.parse::<u64>().ok()),
+            _ => {}
+        }
+    }
+    cc
+}
+
+fn header_get<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// 解析 IMF-fixdate（如 `Sun, 06 Nov 1994 08:49:37 GMT`），返回毫秒时间戳。
+fn parse_http_date(raw: &str) -> Option<u64> {
+    let parts: Vec<&str> = raw.split_whitespace().collect();
+    if parts.len() != 6 || !parts[5].eq_ignore_ascii_case("GMT") {
+        return None;
+    }
+    let day: u32 = parts[1].parse().ok()?;
+    let month = match parts[2].to_ascii_lowercase().as_str() {
+        "jan" => 1,
+        "feb" => 2,
+        "mar" => 3,
+        "apr" => 4,
+        "may" => 5,
+        "jun" => 6,
+        "jul" => 7,
+        "aug" => 8,
+        "sep" => 9,
+        "oct" => 10,
+        "nov" => 11,
+        "dec" => 12,
+        _ => return None,
+    };
+    let year: i64 = parts[3].parse().ok()?;
+    let mut hms = parts[4].split(':');
+    let h: u64 = hms.next()?.parse().ok()?;
+    let m: u64 = hms.next()?.parse().ok()?;
+    let s: u64 = hms.next()?.parse().ok()?;
+    if hms.next().is_some() || day == 0 || day > 31 || h > 23 || m > 59 || s > 60 {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    if days < 0 {
+        return None;
+    }
+    Some(((days as u64) * 86_400 + h * 3_600 + m * 60 + s) * 1_000)
+}
+
+/// 计算响应的新鲜截止时间；None 表示不可存储。
+fn fresh_until_ms(status: u16, headers: &HashMap<String, String>, now: u64) -> Option<u64> {
+    if status != 200 {
+        return None;
+    }
+    if header_get(headers, "vary").is_some_and(|v| v.trim() == "*") {
+        return None;
+    }
+    let cc = header_get(headers, "cache-control")
+        .map(parse_cache_control)
+        .unwrap_or_default();
+    if cc.no_store {
+        return None;
+    }
+
+    let has_validator =
+        header_get(headers, "etag").is_some() || header_get(headers, "last-modified").is_some();
+    // 没有新鲜期但有校验器：仍然存，下次走条件请求省流量
+    let revalidate_only = if has_validator { Some(now) } else { None };
+
+    if cc.no_cache {
+        return revalidate_only;
+    }
+
+    let age_ms = header_get(headers, "age")
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(0)
+        .saturating_mul(1_000);
+
+    if let Some(max_age) = cc.max_age {
+        let until = now
+            .saturating_add(max_age.saturating_mul(1_000))
+            .saturating_sub(age_ms)
+            .max(now);
+        return if until > now {
+            Some(until)
+        } else {
+            revalidate_only
+        };
+    }
+
+    let date_ms = header_get(headers, "date")
+        .and_then(parse_http_date)
+        .unwrap_or(now);
+
+    if let Some(expires) = header_get(headers, "expires") {
+        let Some(expires_ms) = parse_http_date(expires) else {
+            return revalidate_only;
+        };
+        let lifetime = expires_ms.saturating_sub(date_ms);
+        if lifetime == 0 {
+            return revalidate_only;
+        }
+        return Some(now.saturating_add(lifetime).saturating_sub(age_ms).max(now));
+    }
+
+    if let Some(lm) = header_get(headers, "last-modified").and_then(parse_http_date) {
+        // 启发式新鲜期：距上次修改时长的 10%，最多一天
+        let lifetime = (date_ms.saturating_sub(lm) / 10).min(HEURISTIC_FRESHNESS_MAX_MS);
+        return Some(now.saturating_add(lifetime));
+    }
+
+    revalidate_only
+}
+
+fn cache_key(method: &str, url: &str) -> String {
+    let mut h = Sha256::new();
+    h.update(method.as_bytes());
+    h.update(b" ");
+    h.update(url.as_bytes());
+    crate::to_hex_lower(&h.finalize())
+}
+
+fn vary_values(
+    response_headers: &HashMap<String, String>,
+    request_headers: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut out = HashMap::new();
+    if let Some(vary) = header_get(response_headers, "vary") {
+        for name in vary.split(',') {
+            let name = name.trim().to_ascii_lowercase();
+            if name.is_empty() {
+                continue;
+            }
+            let value = header_get(request_headers, &name).unwrap_or("").to_string();
+            out.insert(name, value);
+        }
+    }
+    out
+}
+
+/// 选出需要淘汰的 key（最久未访问优先），直到满足容量与条数上限。
+fn lru_evictions(
+    entries: &HashMap<String, HttpCacheEntry>,
+    max_bytes: u64,
+    max_entries: usize,
+) -> Vec<String> {
+    let mut total: u64 = entries.values().map(|e| e.size).sum();
+    let mut count = entries.len();
+    if total <= max_bytes && count <= max_entries {
+        return Vec::new();
+    }
+    let mut order: Vec<(&String, u64, u64)> = entries
+        .iter()
+        .map(|(k, e)| (k, e.last_access_ms, e.size))
+        .collect();
+    order.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut out = Vec::new();
+    for (key, _, size) in order {
+        if total <= max_bytes && count <= max_entries {
+            break;
+        }
+        total = total.saturating_sub(size);
+        count -= 1;
+        out.push(key.clone());
+    }
+    out
+}
+
+fn plugin_cache_dir(app: &tauri::AppHandle, plugin_id: &str) -> PathBuf {
+    crate::app_data_dir(app)
+        .join(crate::APP_STORAGE_ID)
+        .join(HTTP_CACHE_DIR)
+        .join(plugin_id)
+}
+
+fn load_index(dir: &std::path::Path) -> HttpCacheIndex {
+    let path = dir.join(HTTP_CACHE_INDEX_FILE);
+    if !path.is_file() {
+        return HttpCacheIndex::default();
+    }
+    crate::json_file::read_value(&path)
+        .ok()
+        .and_then(|v| serde_json::from_value::<HttpCacheIndex>(v).ok())
+        .unwrap_or_default()
+}
+
+fn persist_index(dir: &std::path::Path, index: &HttpCacheIndex) {
+    let Ok(value) = serde_json::to_value(index) else {
+        return;
+    };
+    let _ = crate::json_file::write_pretty(&dir.join(HTTP_CACHE_INDEX_FILE), &value);
+}
+
+fn body_path(dir: &std::path::Path, key: &str) -> PathBuf {
+    dir.join(format!("{key}.bin"))
+}
+
+impl HttpCacheScope {
+    /// 判断请求是否走缓存：仅 GET、无 body、插件 id 合法、未显式关闭，且调用方没有自带条件/范围请求头。
+    pub(crate) fn for_request(
+        app: &tauri::AppHandle,
+        plugin_id: Option<&str>,
+        req: &crate::http_api::HttpRequest,
+    ) -> Option<Self> {
+        // mock 录制/回放时绕开缓存，保证每次都落到 fixture
+        if req.cache == Some(false) || crate::http_mock::http_mock_mode().is_some() {
+            return None;
+        }
+        let plugin_id = plugin_id
+            .map(str::trim)
+            .filter(|id| crate::is_safe_id(id))?;
+        if !req.method.trim().eq_ignore_ascii_case("GET")
+            || req.body.is_some()
+            || req.body_base64.is_some()
+        {
+            return None;
+        }
+        let request_headers = req.headers.clone().unwrap_or_default();
+        let conditional = ["range", "if-none-match", "if-modified-since"]
+            .iter()
+            .any(|name| header_get(&request_headers, name).is_some());
+        // 带凭据的请求默认不缓存，除非显式 cache: true
+        let credentialed =
+            header_get(&request_headers, "authorization").is_some() && req.cache != Some(true);
+        if conditional || credentialed {
+            return None;
+        }
+        if let Some(cc) = header_get(&request_headers, "cache-control") {
+            let cc = parse_cache_control(cc);
+            if cc.no_store || cc.no_cache {
+                return None;
+            }
+        }
+
+        Some(HttpCacheScope {
+            state: app.state::<Arc<HttpCacheState>>().inner().clone(),
+            dir: plugin_cache_dir(app, plugin_id),
+            plugin_id: plugin_id.to_string(),
+            key: cache_key("GET", req.url.trim()),
+            url: req.url.trim().to_string(),
+            request_headers,
+        })
+    }
+
+    fn with_index<T>(&self, f: impl FnOnce(&mut HttpCacheIndex) -> T) -> T {
+        let mut indexes = self.state.indexes.lock().unwrap_or_else(|e| e.into_inner());
+        let index = indexes
+            .entry(self.plugin_id.clone())
+            .or_insert_with(|| load_index(&self.dir));
+        f(index)
+    }
+
+    fn read_entry_body(&self, entry: &HttpCacheEntry) -> Option<Vec<u8>> {
+        let body = std::fs::read(body_path(&self.dir, &self.key)).ok()?;
+        (body.len() as u64 == entry.size).then_some(body)
+    }
+
+    pub(crate) fn lookup(&self) -> HttpCacheLookup {
+        let now = crate::now_ms();
+        let found = self.with_index(|index| {
+            let entry = index.entries.get_mut(&self.key)?;
+            if entry.url != self.url
+                || entry
+                    .vary
+                    .iter()
+                    .any(|(k, v)| header_get(&self.request_headers, k).unwrap_or("") != v)
+            {
+                return None;
+            }
+            entry.last_access_ms = now;
+            Some(entry.clone())
+        });
+        let Some(entry) = found else {
+            return HttpCacheLookup::Miss;
+        };
+
+        if entry.fresh_until_ms > now {
+            return match self.read_entry_body(&entry) {
+                Some(body) => HttpCacheLookup::Fresh(cached_response(entry, body, "hit")),
+                None => {
+                    self.remove();
+                    HttpCacheLookup::Miss
+                }
+            };
+        }
+        if entry.etag.is_none() && entry.last_modified.is_none() {
+            return HttpCacheLookup::Miss;
+        }
+        HttpCacheLookup::Stale {
+            etag: entry.etag,
+            last_modified: entry.last_modified,
+        }
+    }
+
+    /// 条件请求拿到 304：合并新响应头、刷新新鲜期，返回缓存正文。
+    pub(crate) fn revalidated(
+        &self,
+        not_modified_headers: &HashMap<String, String>,
+    ) -> Option<HttpCachedResponse> {
+        let now = crate::now_ms();
+        let entry = self.with_index(|index| {
+            let entry = index.entries.get_mut(&self.key)?;
+            for (k, v) in not_modified_headers {
+                if k.eq_ignore_ascii_case("content-length") {
+                    continue;
+                }
+                entry.headers.insert(k.to_ascii_lowercase(), v.clone());
+            }
+            entry.fresh_until_ms = fresh_until_ms(200, &entry.headers, now).unwrap_or(now);
+            if let Some(etag) = header_get(not_modified_headers, "etag") {
+                entry.etag = Some(etag.to_string());
+            }
+            entry.last_access_ms = now;
+            let entry = entry.clone();
+            persist_index(&self.dir, index);
+            Some(entry)
+        })?;
+        match self.read_entry_body(&entry) {
+            Some(body) => Some(cached_response(entry, body, "revalidated")),
+            None => {
+                self.remove();
+                None
+            }
+        }
+    }
+
+    /// 存储可缓存的响应；不满足条件时顺带清掉旧条目。
+    pub(crate) fn store(&self, status: u16, headers: &HashMap<String, String>, body: &[u8]) {
+        let now = crate::now_ms();
+        let Some(fresh_until) = fresh_until_ms(status, headers, now) else {
+            self.remove();
+            return;
+        };
+        if body.len() > HTTP_CACHE_MAX_ENTRY_BYTES {
+            self.remove();
+            return;
+        }
+        if std::fs::create_dir_all(&self.dir).is_err()
+            || std::fs::write(body_path(&self.dir, &self.key), body).is_err()
+        {
+            return;
+        }
+
+        let entry = HttpCacheEntry {
+            url: self.url.clone(),
+            status,
+            headers: headers.clone(),
+            vary: vary_values(headers, &self.request_headers),
+            stored_at_ms: now,
+            fresh_until_ms: fresh_until,
+            etag: header_get(headers, "etag").map(str::to_string),
+            last_modified: header_get(headers, "last-modified").map(str::to_string),
+            size: body.len() as u64,
+            last_access_ms: now,
+        };
+        self.with_index(|index| {
+            index.entries.insert(self.key.clone(), entry);
+            for key in lru_evictions(
+                &index.entries,
+                HTTP_CACHE_MAX_BYTES_PER_PLUGIN,
+                HTTP_CACHE_MAX_ENTRIES_PER_PLUGIN,
+            ) {
+                index.entries.remove(&key);
+                let _ = std::fs::remove_file(body_path(&self.dir, &key));
+            }
+            persist_index(&self.dir, index);
+        });
+    }
+
+    fn remove(&self) {
+        self.with_index(|index| {
+            if index.entries.remove(&self.key).is_some() {
+                let _ = std::fs::remove_file(body_path(&self.dir, &self.key));
+                persist_index(&self.dir, index);
+            }
+        });
+    }
+}
+
+fn cached_response(entry: HttpCacheEntry, body: Vec<u8>, status_tag: &str) -> HttpCachedResponse {
+    let mut headers = entry.headers;
+    headers.insert(HTTP_CACHE_STATUS_HEADER.to_string(), status_tag.to_string());
+    HttpCachedResponse {
+        status: entry.status,
+        headers,
+        body,
+    }
+}
+
+/// 清空某个插件（或全部插件）的 HTTP 缓存。
+pub(crate) fn clear_http_cache(
+    app: &tauri::AppHandle,
+    plugin_id: Option<&str>,
+) -> Result<(), String> {
+    let state = app.state::<Arc<HttpCacheState>>().inner().clone();
+    let mut indexes = state.indexes.lock().unwrap_or_else(|e| e.into_inner());
+    let root = crate::app_data_dir(app)
+        .join(crate::APP_STORAGE_ID)
+        .join(HTTP_CACHE_DIR);
+    let dir = match plugin_id {
+        Some(id) => {
+            indexes.remove(id);
+            root.join(id)
+        }
+        None => {
+            indexes.clear();
+            root
+        }
+    };
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| format!("清理 HTTP 缓存失败: {e}"))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn http_cache_clear(
+    app: tauri::AppHandle,
+    plugin_id: Option<String>,
+) -> Result<(), String> {
+    let plugin_id = plugin_id.map(|s| s.trim().to_string());
+    if let Some(id) = plugin_id.as_deref() {
+        if !crate::is_safe_id(id) {
+            return Err("pluginId 不合法".to_string());
+        }
+    }
+    clear_http_cache(&app, plugin_id.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_cache_control_directives() {
+        assert_eq!(
+            parse_cache_control("public, max-age=600, No-Cache"),
+            CacheControl {
+                no_store: false,
+                no_cache: true,
+                max_age: Some(600),
+            }
+        );
+        assert!(parse_cache_control("no-store").no_store);
+        assert_eq!(parse_cache_control("max-age=\"30\"").max_age, Some(30));
+        assert_eq!(parse_cache_control("max-age=abc").max_age, None);
+    }
+
+    #[test]
+    fn parses_imf_fixdate() {
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(784_111_777_000)
+        );
+        assert_eq!(parse_http_date("Thu, 01 Jan 1970 00:00:00 GMT"), Some(0));
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+    }
+
+    #[test]
+    fn max_age_sets_freshness_minus_age() {
+        let now = 1_000_000;
+        let h = headers(&[("cache-control", "max-age=60"), ("age", "10")]);
+        assert_eq!(fresh_until_ms(200, &h, now), Some(now + 50_000));
+    }
+
+    #[test]
+    fn no_store_and_non_200_are_not_cached() {
+        let h = headers(&[("cache-control", "no-store, max-age=60")]);
+        assert_eq!(fresh_until_ms(200, &h, 0), None);
+        let h = headers(&[("cache-control", "max-age=60")]);
+        assert_eq!(fresh_until_ms(404, &h, 0), None);
+        let h = headers(&[("cache-control", "max-age=60"), ("vary", "*")]);
+        assert_eq!(fresh_until_ms(200, &h, 0), None);
+    }
+
+    #[test]
+    fn validators_without_freshness_store_as_stale() {
+        let now = 5_000;
+        let h = headers(&[("etag", "\"v1\""), ("cache-control", "no-cache")]);
+        assert_eq!(fresh_until_ms(200, &h, now), Some(now));
+        let h = headers(&[("cache-control", "no-cache")]);
+        assert_eq!(fresh_until_ms(200, &h, now), None);
+        assert_eq!(fresh_until_ms(200, &HashMap::new(), now), None);
+    }
+
+    #[test]
+    fn expires_uses_date_header_as_origin() {
+        let now = 10_000;
+        let h = headers(&[
+            ("date", "Thu, 01 Jan 1970 00:00:00 GMT"),
+            ("expires", "Thu, 01 Jan 1970 00:01:00 GMT"),
+        ]);
+        assert_eq!(fresh_until_ms(200, &h, now), Some(now + 60_000));
+    }
+
+    #[test]
+    fn evicts_least_recently_used_until_within_caps() {
+        let entry = |access: u64, size: u64| HttpCacheEntry {
+            url: String::new(),
+            status: 200,
+            headers: HashMap::new(),
+            vary: HashMap::new(),
+            stored_at_ms: 0,
+            fresh_until_ms: 0,
+            etag: None,
+            last_modified: None,
+            size,
+            last_access_ms: access,
+        };
+        let mut entries = HashMap::new();
+        entries.insert("a".to_string(), entry(3, 40));
+        entries.insert("b".to_string(), entry(1, 40));
+        entries.insert("c".to_string(), entry(2, 40));
+
+        assert_eq!(lru_evictions(&entries, 120, 10), Vec::<String>::new());
+        assert_eq!(lru_evictions(&entries, 80, 10), vec!["b".to_string()]);
+        assert_eq!(
+            lru_evictions(&entries, 1_000, 1),
+            vec!["b".to_string(), "c".to_string()]
+        );
+    }
+}
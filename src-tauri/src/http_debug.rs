@@ -0,0 +1,467 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::Manager;
+use url::Url;
+
+use crate::http_api::{HttpErrorKind, HttpGatewayError, HttpRequest};
+
+// ── 插件 HTTP 调试日志 ───────────────────────────────────────────────────────
+//
+// 按插件开启（配置键 httpDebugLogPlugins），只在内存环形缓冲里保留最近的请求记录，
+// 敏感请求头与 URL 里的密钥参数在入库前就脱敏，导出 HAR 时不会再带出原值。
+
+const HTTP_DEBUG_PLUGINS_KEY: &str = "httpDebugLogPlugins";
+const HTTP_DEBUG_MAX_ENTRIES_PER_PLUGIN: usize = 200;
+const REDACTED: &str = "***";
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpDebugEntry {
+    id: u64,
+    plugin_id: String,
+    started_at_ms: u64,
+    method: String,
+    url: String,
+    request_headers: HashMap<String, String>,
+    request_body_bytes: u64,
+    stream: bool,
+    status: Option<u16>,
+    response_headers: HashMap<String, String>,
+    response_body_bytes: u64,
+    /// 从发出到拿到响应头的耗时
+    wait_ms: Option<u64>,
+    duration_ms: u64,
+    /// 命中插件级缓存时为 hit / revalidated
+    cache: Option<String>,
+    error_kind: Option<HttpErrorKind>,
+    error: Option<String>,
+}
+
+#[derive(Default)]
+pub(crate) struct HttpDebugState {
+    seq: AtomicU64,
+    /// None 表示尚未从配置加载
+    enabled: Mutex<Option<HashSet<String>>>,
+    logs: Mutex<HashMap<String, VecDeque<HttpDebugEntry>>>,
+}
+
+impl HttpDebugState {
+    fn is_enabled(&self, app: &tauri::AppHandle, plugin_id: &str) -> bool {
+        let mut enabled = self.enabled.lock().unwrap_or_else(|e| e.into_inner());
+        enabled
+            .get_or_insert_with(|| load_enabled_plugins(app))
+            .contains(plugin_id)
+    }
+
+    fn push(&self, entry: HttpDebugEntry) {
+        let mut logs = self.logs.lock().unwrap_or_else(|e| e.into_inner());
+        let ring = logs.entry(entry.plugin_id.clone()).or_default();
+        while ring.len() >= HTTP_DEBUG_MAX_ENTRIES_PER_PLUGIN {
+            ring.pop_front();
+        }
+        ring.push_back(entry);
+    }
+}
+
+fn load_enabled_plugins(app: &tauri::AppHandle) -> HashSet<String> {
+    crate::read_app_config_map(app)
+        .get(HTTP_DEBUG_PLUGINS_KEY)
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .filter(|id| crate::is_safe_id(id))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_sensitive_header(name: &str) -> bool {
+    let n = name.to_ascii_lowercase();
+    matches!(
+        n.as_str(),
+        "authorization" | "proxy-authorization" | "cookie" | "set-cookie"
+    ) || n.contains("api-key")
+        || n.contains("apikey")
+        || n.contains("token")
+        || n.contains("secret")
+}
+
+fn redact_headers(headers: &HashMap<String, String>) -> HashMap<String, String> {
+    headers
+        .iter()
+        .map(|(k, v)| {
+            if !is_sensitive_header(k) {
+                return (k.clone(), v.clone());
+            }
+            // 保留认证方案（Bearer/Basic）便于排查格式问题
+            let value = match v.trim().split_once(' ') {
+                Some((scheme, _)) if k.to_ascii_lowercase().ends_with("authorization") => {
+                    format!("{scheme} {REDACTED}")
+                }
+                _ => REDACTED.to_string(),
+            };
+            (k.clone(), value)
+        })
+        .collect()
+}
+
+fn is_sensitive_query_key(key: &str) -> bool {
+    let k = key.to_ascii_lowercase();
+    k == "key"
+        || k == "sig"
+        || k == "signature"
+        || k.contains("token")
+        || k.contains("secret")
+        || k.contains("api_key")
+        || k.contains("apikey")
+        || k.contains("password")
+}
+
+fn redact_url(raw: &str) -> String {
+    let Ok(mut url) = Url::parse(raw.trim()) else {
+        return raw.trim().to_string();
+    };
+    if !url.username().is_empty() || url.password().is_some() {
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+    }
+    if url.query().is_none() {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| {
+            let v = if is_sensitive_query_key(&k) {
+                REDACTED.to_string()
+            } else {
+                v.into_owned()
+            };
+            (k.into_owned(), v)
+        })
+        .collect();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+    url.to_string()
+}
+
+/// 单次请求的记录器；插件未开启调试日志时不会创建。
+pub(crate) struct HttpDebugRecorder {
+    state: Arc<HttpDebugState>,
+    entry: HttpDebugEntry,
+    started: Instant,
+}
+
+impl HttpDebugRecorder {
+    pub(crate) fn begin(
+        app: &tauri::AppHandle,
+        plugin_id: Option<&str>,
+        req: &HttpRequest,
+        stream: bool,
+    ) -> Option<Self> {
+        let plugin_id = plugin_id
+            .map(str::trim)
+            .filter(|id| crate::is_safe_id(id))?;
+        let state = app.state::<Arc<HttpDebugState>>().inner().clone();
+        if !state.is_enabled(app, plugin_id) {
+            return None;
+        }
+
+        let request_body_bytes = match (&req.body_base64, &req.body) {
+            (Some(b64), _) => (b64.trim().len() as u64) * 3 / 4,
+            (None, Some(body)) => body.len() as u64,
+            (None, None) => 0,
+        };
+        let entry = HttpDebugEntry {
+            id: state.seq.fetch_add(1, Ordering::Relaxed) + 1,
+            plugin_id: plugin_id.to_string(),
+            started_at_ms: crate::now_ms(),
+            method: req.method.trim().to_uppercase(),
+            url: redact_url(&req.url),
+            request_headers: req.headers.as_ref().map(redact_headers).unwrap_or_default(),
+            request_body_bytes,
+            stream,
+            status: None,
+            response_headers: HashMap::new(),
+            response_body_bytes: 0,
+            wait_ms: None,
+            duration_ms: 0,
+            cache: None,
+            error_kind: None,
+            error: None,
+        };
+        Some(HttpDebugRecorder {
+            state,
+            entry,
+            started: Instant::now(),
+        })
+    }
+
+    pub(crate) fn response_started(&mut self, status: u16, headers: &HashMap<String, String>) {
+        self.entry.status = Some(status);
+        self.entry.response_headers = redact_headers(headers);
+        if self.entry.wait_ms.is_none() {
+            self.entry.wait_ms = Some(self.started.elapsed().as_millis() as u64);
+        }
+        self.entry.cache = headers
+            .get(crate::http_cache::HTTP_CACHE_STATUS_HEADER)
+            .cloned();
+    }
+
+    pub(crate) fn add_response_bytes(&mut self, n: usize) {
+        self.entry.response_body_bytes = self.entry.response_body_bytes.saturating_add(n as u64);
+    }
+
+    pub(crate) fn finish(mut self, error: Option<&HttpGatewayError>) {
+        if let Some(err) = error {
+            self.entry.error_kind = Some(err.kind.clone());
+            self.entry.error = Some(err.message.clone());
+        }
+        self.entry.duration_ms = self.started.elapsed().as_millis() as u64;
+        self.state.push(self.entry);
+    }
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
+
+pub(crate) fn format_iso8601_ms(ms: u64) -> String {
+    let secs = ms / 1_000;
+    let (y, m, d) = civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+    format!(
+        "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}.{:03}Z",
+        rem / 3_600,
+        (rem % 3_600) / 60,
+        rem % 60,
+        ms % 1_000
+    )
+}
+
+fn har_headers(headers: &HashMap<String, String>) -> Value {
+    let mut items: Vec<(&String, &String)> = headers.iter().collect();
+    items.sort();
+    Value::Array(
+        items
+            .into_iter()
+            .map(|(name, value)| json!({ "name": name, "value": value }))
+            .collect(),
+    )
+}
+
+fn har_entry(entry: &HttpDebugEntry) -> Value {
+    let query: Vec<Value> = Url::parse(&entry.url)
+        .map(|u| {
+            u.query_pairs()
+                .map(|(k, v)| json!({ "name": k, "value": v }))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mime = entry
+        .response_headers
+        .get("content-type")
+        .cloned()
+        .unwrap_or_default();
+    let wait = entry.wait_ms.unwrap_or(entry.duration_ms);
+    json!({
+        "startedDateTime": format_iso8601_ms(entry.started_at_ms),
+        "time": entry.duration_ms,
+        "request": {
+            "method": entry.method,
+            "url": entry.url,
+            "httpVersion": "HTTP/1.1",
+            "cookies": [],
+            "headers": har_headers(&entry.request_headers),
+            "queryString": query,
+            "headersSize": -1,
+            "bodySize": entry.request_body_bytes,
+        },
+        "response": {
+            "status": entry.status.unwrap_or(0),
+            "statusText": entry.error.clone().unwrap_or_default(),
+            "httpVersion": "HTTP/1.1",
+            "cookies": [],
+            "headers": har_headers(&entry.response_headers),
+            "content": { "size": entry.response_body_bytes, "mimeType": mime },
+            "redirectURL": "",
+            "headersSize": -1,
+            "bodySize": if entry.status.is_some() { entry.response_body_bytes as i64 } else { -1 },
+        },
+        "cache": {},
+        "timings": {
+            "send": 0,
+            "wait": wait,
+            "receive": entry.duration_ms.saturating_sub(wait),
+        },
+        "_fastWindow": {
+            "pluginId": entry.plugin_id,
+            "stream": entry.stream,
+            "cache": entry.cache,
+            "errorKind": entry.error_kind,
+        },
+    })
+}
+
+fn har_document(entries: &[HttpDebugEntry]) -> Value {
+    json!({
+        "log": {
+            "version": "1.2",
+            "creator": { "name": "Fast Window", "version": env!("CARGO_PKG_VERSION") },
+            "entries": entries.iter().map(har_entry).collect::<Vec<_>>(),
+        }
+    })
+}
+
+fn validate_plugin_id(plugin_id: &str) -> Result<String, String> {
+    let plugin_id = plugin_id.trim();
+    if !crate::is_safe_id(plugin_id) {
+        return Err("pluginId 不合法".to_string());
+    }
+    Ok(plugin_id.to_string())
+}
+
+fn snapshot(app: &tauri::AppHandle, plugin_id: &str) -> Vec<HttpDebugEntry> {
+    let state = app.state::<Arc<HttpDebugState>>();
+    let logs = state.logs.lock().unwrap_or_else(|e| e.into_inner());
+    logs.get(plugin_id)
+        .map(|ring| ring.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub(crate) fn http_debug_get_enabled(
+    app: tauri::AppHandle,
+    plugin_id: String,
+) -> Result<bool, String> {
+    let plugin_id = validate_plugin_id(&plugin_id)?;
+    let state = app.state::<Arc<HttpDebugState>>();
+    Ok(state.is_enabled(&app, &plugin_id))
+}
+
+#[tauri::command]
+pub(crate) fn http_debug_set_enabled(
+    app: tauri::AppHandle,
+    plugin_id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let plugin_id = validate_plugin_id(&plugin_id)?;
+    let state = app.state::<Arc<HttpDebugState>>();
+    let next = {
+        let mut guard = state.enabled.lock().unwrap_or_else(|e| e.into_inner());
+        let set = guard.get_or_insert_with(|| load_enabled_plugins(&app));
+        if enabled {
+            set.insert(plugin_id.clone());
+        } else {
+            set.remove(&plugin_id);
+        }
+        let mut ids: Vec<String> = set.iter().cloned().collect();
+        ids.sort();
+        ids
+    };
+    if !enabled {
+        state
+            .logs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&plugin_id);
+    }
+    crate::update_app_config_map(&app, |map| {
+        if next.is_empty() {
+            map.remove(HTTP_DEBUG_PLUGINS_KEY);
+        } else {
+            map.insert(
+                HTTP_DEBUG_PLUGINS_KEY.to_string(),
+                Value::Array(next.into_iter().map(Value::String).collect()),
+            );
+        }
+        Ok(())
+    })
+}
+
+/// 最近的请求记录（新的在后）；limit 缺省返回全部。
+#[tauri::command]
+pub(crate) fn http_debug_log(
+    app: tauri::AppHandle,
+    plugin_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<HttpDebugEntry>, String> {
+    let plugin_id = validate_plugin_id(&plugin_id)?;
+    let mut entries = snapshot(&app, &plugin_id);
+    if let Some(limit) = limit {
+        let skip = entries.len().saturating_sub(limit);
+        entries.drain(..skip);
+    }
+    Ok(entries)
+}
+
+#[tauri::command]
+pub(crate) fn http_debug_clear(app: tauri::AppHandle, plugin_id: String) -> Result<(), String> {
+    let plugin_id = validate_plugin_id(&plugin_id)?;
+    let state = app.state::<Arc<HttpDebugState>>();
+    state
+        .logs
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&plugin_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn http_debug_export_har(
+    app: tauri::AppHandle,
+    plugin_id: String,
+) -> Result<Value, String> {
+    let plugin_id = validate_plugin_id(&plugin_id)?;
+    Ok(har_document(&snapshot(&app, &plugin_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_sensitive_headers_but_keeps_scheme() {
+        let mut h = HashMap::new();
+        h.insert("Authorization".to_string(), "Bearer sk-123".to_string());
+        h.insert("x-api-key".to_string(), "abc".to_string());
+        h.insert("content-type".to_string(), "application/json".to_string());
+        let r = redact_headers(&h);
+        assert_eq!(r["Authorization"], "Bearer ***");
+        assert_eq!(r["x-api-key"], "***");
+        assert_eq!(r["content-type"], "application/json");
+    }
+
+    #[test]
+    fn redacts_url_credentials_and_secret_query() {
+        assert_eq!(
+            redact_url("https://user:pw@example.com/v1?key=abc&model=x"),
+            "https://example.com/v1?key=***&model=x"
+        );
+        assert_eq!(redact_url("https://example.com/a"), "https://example.com/a");
+    }
+
+    #[test]
+    fn formats_iso8601() {
+        assert_eq!(format_iso8601_ms(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            format_iso8601_ms(784_111_777_042),
+            "1994-11-06T08:49:37.042Z"
+        );
+    }
+}
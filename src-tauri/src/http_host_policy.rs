@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use url::Url;
+
+// ── 插件网络访问的主机白名单 ─────────────────────────────────────────────────
+//
+// manifest.requires 里的 `cap:http:<host>` 声明插件可访问的主机：
+// - `cap:http:api.openai.com`：精确主机（任意端口）
+// - `cap:http:*.example.com`：example.com 及其所有子域
+// - `cap:http:localhost:8080`：限定端口
+// - `cap:http:*`：任意主机
+// 网关只放行白名单内的主机（含重定向目标）；没有任何 `cap:http:` 声明的插件不能访问网络。
+// 白名单按插件缓存，安装 / 卸载 / 开发同步后失效重读，不在每次请求时读 manifest。
+
+pub(crate) const HTTP_HOST_CAP_PREFIX: &str = "cap:http:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostMatch {
+    Any,
+    Exact(String),
+    /// `*.example.com`：匹配 example.com 本身与其子域
+    Domain(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HostPattern {
+    host: HostMatch,
+    port: Option<u16>,
+}
+
+fn is_valid_host_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= 63
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+}
+
+fn parse_host_pattern(raw: &str) -> Option<HostPattern> {
+    let raw = raw.trim();
+    if raw == "*" {
+        return Some(HostPattern {
+            host: HostMatch::Any,
+            port: None,
+        });
+    }
+
+    let (host, port) = match raw.rsplit_once(':') {
+        Some((h, p)) => (h, Some(p.parse::<u16>().ok().filter(|p| *p > 0)?)),
+        None => (raw, None),
+    };
+    let host = host.to_ascii_lowercase();
+    let (host, wildcard) = match host.strip_prefix("*.") {
+        Some(rest) => (rest.to_string(), true),
+        None => (host, false),
+    };
+    if host.is_empty() || host.len() > 253 || !host.split('.').all(is_valid_host_label) {
+        return None;
+    }
+
+    Some(HostPattern {
+        host: if wildcard {
+            HostMatch::Domain(host)
+        } else {
+            HostMatch::Exact(host)
+        },
+        port,
+    })
+}
+
+impl HostPattern {
+    fn matches(&self, host: &str, port: Option<u16>) -> bool {
+        if self.port.is_some() && self.port != port {
+            return false;
+        }
+        match &self.host {
+            HostMatch::Any => true,
+            HostMatch::Exact(h) => host == h,
+            HostMatch::Domain(d) => {
+                host == d
+                    || host
+                        .strip_suffix(d.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            }
+        }
+    }
+}
+
+/// manifest 校验用：`cap:http:` 后必须是合法主机模式。
+pub(crate) fn is_valid_http_host_capability(cap: &str) -> bool {
+    cap.strip_prefix(HTTP_HOST_CAP_PREFIX)
+        .is_some_and(|rest| parse_host_pattern(rest).is_some())
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct HttpHostAllowList {
+    plugin_id: String,
+    patterns: Vec<HostPattern>,
+}
+
+impl HttpHostAllowList {
+    /// 从 requires 中提取主机声明；没有任何 `cap:http:` 声明时白名单为空，拒绝所有主机。
+    pub(crate) fn from_requires<'a>(
+        plugin_id: &str,
+        requires: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let patterns: Vec<HostPattern> = requires
+            .into_iter()
+            .filter_map(|cap| cap.trim().strip_prefix(HTTP_HOST_CAP_PREFIX))
+            .filter_map(parse_host_pattern)
+            .collect();
+        HttpHostAllowList {
+            plugin_id: plugin_id.to_string(),
+            patterns,
+        }
+    }
+
+    pub(crate) fn allows(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let port = url.port_or_known_default();
+        self.patterns.iter().any(|p| p.matches(&host, port))
+    }
+
+    pub(crate) fn ensure_allowed(&self, url: &str) -> Result<(), String> {
+        let parsed = Url::parse(url.trim()).map_err(|e| format!("url 不合法: {e}"))?;
+        if self.allows(&parsed) {
+            return Ok(());
+        }
+        let host = parsed.host_str().unwrap_or("");
+        if self.patterns.is_empty() {
+            return Err(format!(
+                "插件 {} 未声明网络权限：请在 manifest.requires 中添加 \"{HTTP_HOST_CAP_PREFIX}{host}\"",
+                self.plugin_id
+            ));
+        }
+        Err(format!(
+            "插件 {} 未声明访问 {host} 的网络权限：请在 manifest.requires 中添加 \"{HTTP_HOST_CAP_PREFIX}{host}\"",
+            self.plugin_id
+        ))
+    }
+}
+
+fn allow_list_cache() -> &'static Mutex<HashMap<String, HttpHostAllowList>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, HttpHostAllowList>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 插件文件变化后调用；pluginId 缺省时清空全部
+pub(crate) fn invalidate_plugin_http_allow_list(plugin_id: Option<&str>) {
+    let Ok(mut cache) = allow_list_cache().lock() else {
+        return;
+    };
+    match plugin_id {
+        Some(plugin_id) => {
+            cache.remove(plugin_id.trim());
+        }
+        None => cache.clear(),
+    }
+}
+
+/// 已安装插件 manifest 中的主机白名单；pluginId 缺省（宿主自身调用）时不受限。
+/// 读取失败不缓存，下次请求重试。
+pub(crate) fn plugin_http_allow_list(
+    app: &tauri::AppHandle,
+    plugin_id: Option<&str>,
+) -> Result<Option<HttpHostAllowList>, String> {
+    let Some(plugin_id) = plugin_id.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    if !crate::is_safe_id(plugin_id) {
+        return Err("pluginId 不合法".to_string());
+    }
+    if let Some(list) = allow_list_cache()
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(plugin_id).cloned())
+    {
+        return Ok(Some(list));
+    }
+    let requires = crate::plugins::read_installed_plugin_requires(app, plugin_id)?;
+    let list = HttpHostAllowList::from_requires(plugin_id, requires.iter().map(String::as_str));
+    if let Ok(mut cache) = allow_list_cache().lock() {
+        cache.insert(plugin_id.to_string(), list.clone());
+    }
+    Ok(Some(list))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allow(requires: &[&str]) -> HttpHostAllowList {
+        HttpHostAllowList::from_requires("demo", requires.iter().copied())
+    }
+
+    fn allows(list: &HttpHostAllowList, url: &str) -> bool {
+        list.allows(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn validates_host_capabilities() {
+        assert!(is_valid_http_host_capability("cap:http:api.openai.com"));
+        assert!(is_valid_http_host_capability("cap:http:*.example.com"));
+        assert!(is_valid_http_host_capability("cap:http:localhost:8080"));
+        assert!(is_valid_http_host_capability("cap:http:*"));
+        assert!(!is_valid_http_host_capability("cap:http:"));
+        assert!(!is_valid_http_host_capability("cap:http:api.*.com"));
+        assert!(!is_valid_http_host_capability("cap:http:example.com:0"));
+        assert!(!is_valid_http_host_capability("cap:http:-bad.com"));
+    }
+
+    #[test]
+    fn no_host_caps_denies_every_host() {
+        let list = HttpHostAllowList::from_requires("demo", ["tauri:http_request"]);
+        assert!(!allows(&list, "https://api.openai.com/"));
+        assert!(!allows(&list, "http://localhost:8080/"));
+        let err = list
+            .ensure_allowed("https://api.openai.com/v1")
+            .unwrap_err();
+        assert!(err.contains("未声明网络权限"));
+        assert!(err.contains("cap:http:api.openai.com"));
+    }
+
+    #[test]
+    fn exact_host_matches_any_port_unless_pinned() {
+        let list = allow(&["cap:http:api.openai.com", "cap:http:localhost:8080"]);
+        assert!(allows(&list, "https://api.openai.com/v1/models"));
+        assert!(allows(&list, "http://API.OpenAI.com:8443/"));
+        assert!(!allows(&list, "https://openai.com/"));
+        assert!(allows(&list, "http://localhost:8080/x"));
+        assert!(!allows(&list, "http://localhost:9090/x"));
+    }
+
+    #[test]
+    fn wildcard_matches_apex_and_subdomains_only() {
+        let list = allow(&["cap:http:*.example.com"]);
+        assert!(allows(&list, "https://example.com/"));
+        assert!(allows(&list, "https://a.b.example.com/"));
+        assert!(!allows(&list, "https://badexample.com/"));
+        assert!(!allows(&list, "https://example.com.evil.net/"));
+    }
+
+    #[test]
+    fn reports_missing_capability() {
+        let list = allow(&["cap:http:api.openai.com"]);
+        let err = list.ensure_allowed("https://evil.example/").unwrap_err();
+        assert!(err.contains("cap:http:evil.example"));
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use base64::engine::general_purpose;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::http_api::HttpRequest;
+
+// ── HTTP Mock 后端（录制 / 回放） ────────────────────────────────────────────
+//
+// 通过环境变量开启，便于在 CI（含 Linux）里离线跑插件：
+// - FAST_WINDOW_HTTP_MOCK=record|replay
+// - FAST_WINDOW_HTTP_MOCK_DIR=<fixture 目录>
+// record：照常发真实请求，并把响应（流式请求含每个分片与间隔）写成 fixture；
+// replay：完全不出网，按 method + url + body 摘要查 fixture，缺失即报错。
+
+const HTTP_MOCK_ENV: &str = "FAST_WINDOW_HTTP_MOCK";
+const HTTP_MOCK_DIR_ENV: &str = "FAST_WINDOW_HTTP_MOCK_DIR";
+/// 回放时单个分片的最大等待，避免录制时的长停顿拖慢测试
+const HTTP_MOCK_MAX_CHUNK_DELAY_MS: u64 = 5_000;
+
+pub(crate) enum HttpMockMode {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+fn parse_mock_mode(mode: Option<&str>, dir: Option<&str>) -> Option<HttpMockMode> {
+    let mode = mode.map(str::trim).filter(|s| !s.is_empty())?;
+    let Some(dir) = dir.map(str::trim).filter(|s| !s.is_empty()) else {
+        eprintln!("[http-mock] 已设置 {HTTP_MOCK_ENV} 但缺少 {HTTP_MOCK_DIR_ENV}，忽略 mock 模式");
+        return None;
+    };
+    let dir = PathBuf::from(dir);
+    match mode.to_ascii_lowercase().as_str() {
+        "record" => Some(HttpMockMode::Record(dir)),
+        "replay" => Some(HttpMockMode::Replay(dir)),
+        "off" | "0" | "false" => None,
+        other => {
+            eprintln!("[http-mock] 未知的 {HTTP_MOCK_ENV}={other}，可选 record / replay");
+            None
+        }
+    }
+}
+
+pub(crate) fn http_mock_mode() -> Option<&'static HttpMockMode> {
+    static MODE: OnceLock<Option<HttpMockMode>> = OnceLock::new();
+    MODE.get_or_init(|| {
+        parse_mock_mode(
+            std::env::var(HTTP_MOCK_ENV).ok().as_deref(),
+            std::env::var(HTTP_MOCK_DIR_ENV).ok().as_deref(),
+        )
+    })
+    .as_ref()
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpMockChunk {
+    /// 距上一个分片（首个分片为响应头）的间隔
+    pub(crate) delay_ms: u64,
+    pub(crate) text: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HttpMockRequestInfo {
+    method: String,
+    url: String,
+    body_sha256: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpMockFixture {
+    request: HttpMockRequestInfo,
+    pub(crate) status: u16,
+    #[serde(default)]
+    pub(crate) headers: HashMap<String, String>,
+    /// 非流式响应体
+    #[serde(default)]
+    body_base64: Option<String>,
+    /// 流式响应分片
+    #[serde(default)]
+    pub(crate) chunks: Vec<HttpMockChunk>,
+}
+
+impl HttpMockFixture {
+    pub(crate) fn body(&self) -> Result<Vec<u8>, String> {
+        match self.body_base64.as_deref() {
+            Some(b64) => general_purpose::STANDARD
+                .decode(b64)
+                .map_err(|e| format!("HTTP mock fixture 响应体解码失败: {e}")),
+            // 用流式录制回放非流式请求时，拼接分片
+            None => Ok(self
+                .chunks
+                .iter()
+                .flat_map(|c| c.text.as_bytes().iter().copied())
+                .collect()),
+        }
+    }
+
+    /// 流式回放用的分片；非流式录制只有响应体，整体作为一个分片
+    pub(crate) fn stream_chunks(&self) -> Result<Vec<HttpMockChunk>, String> {
+        if !self.chunks.is_empty() || self.body_base64.is_none() {
+            return Ok(self.chunks.clone());
+        }
+        let body = self.body()?;
+        if body.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(vec![HttpMockChunk {
+            delay_ms: 0,
+            text: String::from_utf8_lossy(&body).into_owned(),
+        }])
+    }
+}
+
+/// 请求在 fixture 目录里的定位信息。
+pub(crate) struct HttpMockKey {
+    method: String,
+    url: String,
+    body_sha256: Option<String>,
+    file_name: String,
+}
+
+impl HttpMockKey {
+    pub(crate) fn for_request(req: &HttpRequest) -> Self {
+        let method = req.method.trim().to_uppercase();
+        let url = req.url.trim().to_string();
+        let body = req
+            .body_base64
+            .as_deref()
+            .map(|b| b.trim().as_bytes())
+            .or_else(|| req.body.as_deref().map(str::as_bytes));
+        let body_sha256 = body.map(|b| crate::to_hex_lower(&Sha256::digest(b)));
+
+        let mut h = Sha256::new();
+        h.update(method.as_bytes());
+        h.update(b" ");
+        h.update(url.as_bytes());
+        if let Some(sum) = body_sha256.as_deref() {
+            h.update(b" ");
+            h.update(sum.as_bytes());
+        }
+        let digest = crate::to_hex_lower(&h.finalize());
+        let host = url::Url::parse(&url)
+            .ok()
+            .and_then(|u| u.host_str().map(sanitize_file_part))
+            .unwrap_or_else(|| "unknown".to_string());
+        let file_name = format!(
+            "{}-{host}-{}.json",
+            method.to_ascii_lowercase(),
+            &digest[..16]
+        );
+
+        HttpMockKey {
+            method,
+            url,
+            body_sha256,
+            file_name,
+        }
+    }
+
+    fn path_in(&self, dir: &Path) -> PathBuf {
+        dir.join(&self.file_name)
+    }
+
+    pub(crate) fn load(&self, dir: &Path) -> Result<HttpMockFixture, String> {
+        let path = self.path_in(dir);
+        if !path.is_file() {
+            return Err(format!(
+                "HTTP mock 回放缺少录制：{} {}（fixture: {}）",
+                self.method,
+                self.url,
+                path.to_string_lossy()
+            ));
+        }
+        let value = crate::json_file::read_value(&path)?;
+        serde_json::from_value(value).map_err(|e| format!("HTTP mock fixture 格式错误: {e}"))
+    }
+
+    fn save(&self, dir: &Path, fixture: &HttpMockFixture) {
+        let value = match serde_json::to_value(fixture) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("[http-mock] fixture 序列化失败: {e}");
+                return;
+            }
+        };
+        if let Err(e) = crate::json_file::write_pretty(&self.path_in(dir), &value) {
+            eprintln!("[http-mock] fixture 写入失败: {e}");
+        }
+    }
+
+    fn request_info(&self) -> HttpMockRequestInfo {
+        HttpMockRequestInfo {
+            method: self.method.clone(),
+            url: self.url.clone(),
+            body_sha256: self.body_sha256.clone(),
+        }
+    }
+
+    pub(crate) fn record_body(
+        &self,
+        dir: &Path,
+        status: u16,
+        headers: &HashMap<String, String>,
+        body: &[u8],
+    ) {
+        self.save(
+            dir,
+            &HttpMockFixture {
+                request: self.request_info(),
+                status,
+                headers: headers.clone(),
+                body_base64: Some(general_purpose::STANDARD.encode(body)),
+                chunks: Vec::new(),
+            },
+        );
+    }
+}
+
+fn sanitize_file_part(raw: &str) -> String {
+    raw.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// 流式请求录制：记下每个文本分片及其间隔，结束时落盘。
+pub(crate) struct HttpMockStreamTape {
+    dir: PathBuf,
+    key: HttpMockKey,
+    status: u16,
+    headers: HashMap<String, String>,
+    last: Instant,
+    chunks: Vec<HttpMockChunk>,
+}
+
+impl HttpMockStreamTape {
+    pub(crate) fn start(
+        dir: &Path,
+        key: HttpMockKey,
+        status: u16,
+        headers: &HashMap<String, String>,
+    ) -> Self {
+        HttpMockStreamTape {
+            dir: dir.to_path_buf(),
+            key,
+            status,
+            headers: headers.clone(),
+            last: Instant::now(),
+            chunks: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, text: &str) {
+        let now = Instant::now();
+        self.chunks.push(HttpMockChunk {
+            delay_ms: now.duration_since(self.last).as_millis() as u64,
+            text: text.to_string(),
+        });
+        self.last = now;
+    }
+
+    pub(crate) fn finish(self) {
+        self.key.save(
+            &self.dir,
+            &HttpMockFixture {
+                request: self.key.request_info(),
+                status: self.status,
+                headers: self.headers,
+                body_base64: None,
+                chunks: self.chunks,
+            },
+        );
+    }
+}
+
+pub(crate) fn replay_delay(chunk: &HttpMockChunk) -> std::time::Duration {
+    std::time::Duration::from_millis(chunk.delay_ms.min(HTTP_MOCK_MAX_CHUNK_DELAY_MS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, url: &str, body: Option<&str>) -> HttpRequest {
+        HttpRequest {
+            method: method.to_string(),
+            url: url.to_string(),
+            headers: None,
+            body: body.map(str::to_string),
+            body_base64: None,
+            timeout_ms: None,
+            cache: None,
+        }
+    }
+
+    #[test]
+    fn parses_mode_only_with_dir() {
+        assert!(matches!(
+            parse_mock_mode(Some("Replay"), Some("/tmp/fx")),
+            Some(HttpMockMode::Replay(_))
+        ));
+        assert!(matches!(
+            parse_mock_mode(Some("record"), Some("fx")),
+            Some(HttpMockMode::Record(_))
+        ));
+        assert!(parse_mock_mode(Some("replay"), None).is_none());
+        assert!(parse_mock_mode(Some("off"), Some("fx")).is_none());
+        assert!(parse_mock_mode(None, Some("fx")).is_none());
+    }
+
+    #[test]
+    fn fixture_names_are_stable_and_body_sensitive() {
+        let a =
+            HttpMockKey::for_request(&request("post", "https://api.example.com/v1", Some("{}")));
+        let b =
+            HttpMockKey::for_request(&request("POST", " https://api.example.com/v1 ", Some("{}")));
+        let c = HttpMockKey::for_request(&request(
+            "POST",
+            "https://api.example.com/v1",
+            Some("{\"x\":1}"),
+        ));
+        assert_eq!(a.file_name, b.file_name);
+        assert_ne!(a.file_name, c.file_name);
+        assert!(a.file_name.starts_with("post-api.example.com-"));
+    }
+
+    #[test]
+    fn body_only_fixture_replays_as_single_chunk() {
+        let key = HttpMockKey::for_request(&request("GET", "https://api.example.com/v1", None));
+        let fixture = HttpMockFixture {
+            request: key.request_info(),
+            status: 200,
+            headers: HashMap::new(),
+            body_base64: Some(general_purpose::STANDARD.encode("data: hello\n\n")),
+            chunks: Vec::new(),
+        };
+        let chunks = fixture.stream_chunks().unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].text, "data: hello\n\n");
+        assert_eq!(chunks[0].delay_ms, 0);
+
+        let streamed = HttpMockFixture {
+            body_base64: None,
+            chunks: vec![
+                HttpMockChunk {
+                    delay_ms: 5,
+                    text: "a".to_string(),
+                },
+                HttpMockChunk {
+                    delay_ms: 7,
+                    text: "b".to_string(),
+                },
+            ],
+            ..fixture
+        };
+        assert_eq!(streamed.stream_chunks().unwrap().len(), 2);
+    }
+}
@@ -11,7 +11,6 @@ use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::collections::HashMap;
 use tauri::{Emitter, EventTarget, Manager};
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
 mod app;
 mod app_autostart;
@@ -22,11 +21,20 @@ mod app_layout;
 mod app_lifecycle;
 mod app_registry;
 mod app_shortcuts;
+mod app_workflows;
+mod browser_downloads;
+mod browser_filters;
+mod browser_history;
 mod browser_stack;
+mod browser_tabs;
+mod browser_user_scripts;
+mod capability_cli;
+mod capability_schema;
 mod capability_server;
 mod clipboard;
 mod clipboard_snapshot;
 mod clipboard_watch;
+mod command_index;
 mod config_store;
 mod core;
 mod dev_terminal;
@@ -35,7 +43,11 @@ mod host_dialog;
 mod host_lifecycle;
 mod host_primitives;
 mod host_updater;
+mod hotkeys;
 mod http_api;
+mod http_cache;
+mod http_debug;
+mod http_host_policy;
 mod install_fs;
 pub(crate) mod json_file;
 mod migrations;
@@ -43,13 +55,16 @@ mod os_actions;
 mod plugin_assets;
 mod plugin_files;
 mod plugin_files_delete_tree;
+mod plugin_shortcuts;
 mod plugin_uninstall;
 mod plugins;
 mod process_commands;
 mod process_runtime;
+mod rotating_file;
 mod sqlite_gateway;
 mod tasks;
 mod thumbnails;
+mod usage;
 mod wake_logic;
 mod wallpaper;
 mod windowing;
@@ -98,6 +113,7 @@ pub(crate) use config_store::{
     write_plugin_library_dir_to_config, write_plugin_output_dir_to_config,
 };
 use host_primitives::emit_toast;
+use hotkeys::{HotkeySequence, HotkeySource, HotkeySpec};
 use http_api::*;
 pub(crate) use os_actions::{open_dir_in_file_manager, open_external_uri, open_external_url};
 pub(crate) use plugins::{is_safe_id, query_get_param, safe_relative_path};
@@ -118,8 +134,12 @@ const MAIN_WINDOW_BOUNDS_KEY: &str = "mainWindowBounds";
 const MAIN_WINDOW_FOCUS_MODE_KEY: &str = "mainWindowFocusMode";
 const MAIN_WINDOW_MODE_SHORTCUT_KEY: &str = "mainWindowModeShortcut";
 const BROWSER_WINDOW_BOUNDS_KEY: &str = "browserWindowBounds";
+const BROWSER_MINI_BOUNDS_KEY: &str = "browserMiniBounds";
+const BROWSER_MINI_OPACITY_KEY: &str = "browserMiniOpacity";
+const BROWSER_MINI_CLICK_THROUGH_KEY: &str = "browserMiniClickThrough";
 const PLUGIN_OUTPUT_DIRS_KEY: &str = "pluginOutputDirs";
 const PLUGIN_LIBRARY_DIRS_KEY: &str = "pluginLibraryDirs";
+const BROWSER_DOWNLOAD_DIR_KEY: &str = "browserDownloadDir";
 const WEBVIEW_SETTINGS_KEY: &str = "webview";
 const PLUGIN_STORE_MAX_ZIP_BYTES: usize = 50 * 1024 * 1024; // 50MB
 const PLUGIN_STORE_MAX_EXTRACT_BYTES: usize = 120 * 1024 * 1024; // 120MB
@@ -132,6 +152,7 @@ const AUTO_START_REG_VALUE: &str = "Fast Window-dev";
 const AUTO_START_REG_VALUE: &str = "Fast Window";
 
 const DATA_DIR_ENV: &str = "FAST_WINDOW_DATA_DIR";
+// 浏览栈标签内容窗口的 label 前缀：`browser-<标签 id>`
 const BROWSER_WINDOW_LABEL: &str = "browser";
 const BROWSER_BAR_WINDOW_LABEL: &str = "browser_bar";
 const WEBVIEW_SETTINGS_UPDATED_EVENT: &str = "fast-window:webview-settings-updated";
@@ -207,6 +228,46 @@ fn apply_bottom_rounded_corners(window: &tauri::WebviewWindow, radius_dip: f64)
 #[cfg(not(windows))]
 fn apply_bottom_rounded_corners(_window: &tauri::WebviewWindow, _radius_dip: f64) {}
 
+#[cfg(windows)]
+fn apply_window_opacity(window: &tauri::WebviewWindow, opacity: f64) -> Result<(), String> {
+    use windows::Win32::Foundation::COLORREF;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE, LWA_ALPHA,
+        WS_EX_LAYERED,
+    };
+
+    let hwnd = window
+        .hwnd()
+        .map_err(|e| format!("读取窗口句柄失败: {e}"))?;
+    let alpha = (opacity.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let layered = WS_EX_LAYERED.0 as isize;
+
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        // 完全不透明：去掉分层样式，避免额外的合成开销
+        if alpha == 255 {
+            if style & layered != 0 {
+                SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style & !layered);
+            }
+            return Ok(());
+        }
+        if style & layered == 0 {
+            SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | layered);
+        }
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA)
+            .map_err(|e| format!("设置窗口透明度失败: {e}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn apply_window_opacity(_window: &tauri::WebviewWindow, opacity: f64) -> Result<(), String> {
+    if opacity < 1.0 {
+        return Err("当前系统不支持调整窗口透明度".to_string());
+    }
+    Ok(())
+}
+
 fn make_http_stream_id() -> String {
     let stamp = now_ms();
     let seq = HTTP_STREAM_ID_SEQ.fetch_add(1, Ordering::Relaxed);
@@ -236,10 +297,43 @@ pub(crate) async fn open_browser_window_impl(
 
     let parsed = tauri::Url::parse(&u).map_err(|e| format!("url 解析失败: {e}"))?;
 
+    // 插件主动打开网页时，上次未关闭的会话直接合并进来（不再等唤醒时恢复）
+    let _ = browser_tabs::take_pending_restore(&app);
+    let prev = browser_tabs::browser_content_window(&app);
+    browser_tabs::browser_tabs_open(&app, parsed.as_str(), Some(&plugin_id))?;
+    browser_stack_enter(&app, Some(plugin_id));
+
+    if browser_stack_exists(&app) {
+        browser_tabs::browser_tabs_show_active(&app, prev)?;
+        browser_stack_show(&app);
+        return Ok(());
+    }
+
+    build_browser_stack(&app, parsed)
+}
+
+/// 宿主重启后首次唤醒：按上次的标签页重建浏览栈。
+async fn browser_session_restore(app: tauri::AppHandle) -> Result<(), String> {
+    let Some(url) = browser_tabs::take_pending_restore(&app) else {
+        return Ok(());
+    };
+    let parsed = tauri::Url::parse(&url).map_err(|e| format!("url 解析失败: {e}"))?;
+    browser_stack_enter(&app, None);
+    if browser_stack_exists(&app) {
+        browser_stack_show(&app);
+        return Ok(());
+    }
+    build_browser_stack(&app, parsed)
+}
+
+/// 进入“浏览栈模式”：记录返回插件、标记会话活跃，并隐藏主窗口。
+fn browser_stack_enter(app: &tauri::AppHandle, plugin_id: Option<String>) {
     {
         let state = app.state::<BrowserWindowState>();
-        if let Ok(mut g) = state.return_to_plugin_id.lock() {
-            *g = Some(plugin_id);
+        if let Some(plugin_id) = plugin_id {
+            if let Ok(mut g) = state.return_to_plugin_id.lock() {
+                *g = Some(plugin_id);
+            }
         }
         if let Ok(mut g) = state.active.lock() {
             *g = true;
@@ -249,11 +343,11 @@ pub(crate) async fn open_browser_window_impl(
         };
     }
     // 首次打开会经历“创建两个窗口 + 定位 + 聚焦”的抖动期，先加门闩避免误隐藏。
-    browser_stack_set_suppress_hide(&app, 1500);
+    browser_stack_set_suppress_hide(app, 1500);
 
     // 进入“浏览栈模式”时隐藏主窗口：快捷键将优先唤醒这个浏览栈。
     // 首次打开时把主窗口位置当作浏览栈初始位置，避免“只顶部栏居中”造成的错位感。
-    if !browser_stack_exists(&app) {
+    if !browser_stack_exists(app) {
         if let Some(main) = app.get_webview_window("main") {
             if let Ok(pos) = main.outer_position() {
                 if pos.x > -9000 && pos.y > -9000 {
@@ -265,26 +359,19 @@ pub(crate) async fn open_browser_window_impl(
             }
         }
     }
-    hide_main_window(&app);
-
-    if browser_stack_exists(&app) {
-        if let Some(w) = app.get_webview_window(BROWSER_WINDOW_LABEL) {
-            let _ = w.navigate(parsed);
-        }
-        browser_stack_show(&app);
-        return Ok(());
-    }
+    hide_main_window(app);
+}
 
-    let title = "Web";
-    let webview_settings = load_webview_settings(&app);
-    let video_script = browser_video_injection_script(&webview_settings.video)?;
+fn build_browser_stack(app: &tauri::AppHandle, parsed: tauri::Url) -> Result<(), String> {
+    let label = browser_tabs::assign_active_window_label(app)
+        .ok_or_else(|| "没有打开的标签页".to_string())?;
 
     let bar = tauri::WebviewWindowBuilder::new(
-        &app,
+        app,
         BROWSER_BAR_WINDOW_LABEL,
         tauri::WebviewUrl::App("index.html".into()),
     )
-    .title(title)
+    .title("Web")
     .inner_size(1020.0, BROWSER_BAR_HEIGHT)
     .resizable(false)
     .maximizable(false)
@@ -298,41 +385,7 @@ pub(crate) async fn open_browser_window_impl(
     .build()
     .map_err(|e| format!("创建顶部栏窗口失败: {e}"))?;
 
-    let app_ = app.clone();
-    let content = tauri::WebviewWindowBuilder::new(
-        &app,
-        BROWSER_WINDOW_LABEL,
-        tauri::WebviewUrl::External(parsed),
-    )
-    .title(title)
-    .initialization_script(video_script)
-    .on_new_window(move |url, _features| {
-        // 很多网站会用 window.open / target=_blank 打开“新标签页”。
-        // 我们没有标签页：把它折叠成“当前窗口跳转”。
-        if is_http_url(url.as_str()) {
-            if let Some(w) = app_.get_webview_window(BROWSER_WINDOW_LABEL) {
-                let _ = w.navigate(url);
-            }
-        } else {
-            let _ = open::that(url.as_str());
-        }
-        tauri::webview::NewWindowResponse::Deny
-    })
-    .inner_size(
-        1020.0,
-        (BROWSER_STACK_TOTAL_HEIGHT - BROWSER_BAR_HEIGHT).max(200.0),
-    )
-    .resizable(true)
-    .maximizable(false)
-    .minimizable(false)
-    .decorations(false)
-    .transparent(false)
-    .shadow(false)
-    .always_on_top(true)
-    .skip_taskbar(true)
-    .visible(false)
-    .build()
-    .map_err(|e| format!("创建浏览窗口失败: {e}"))?;
+    let content = build_browser_content(app, &label, parsed)?;
 
     // 初次创建时不要跟随 main（因为 main 已被移到屏幕外隐藏了），用浏览栈的恢复/居中逻辑。
     let saved = {
@@ -342,14 +395,14 @@ pub(crate) async fn open_browser_window_impl(
             .lock()
             .ok()
             .and_then(|g| g.clone())
-            .or_else(|| load_browser_window_bounds_from_config(&app))
+            .or_else(|| load_browser_window_bounds_from_config(app))
     };
     if let Some((pos, total)) = saved {
         let state = app.state::<BrowserWindowState>();
         if let Ok(mut g) = state.last_bounds.lock() {
             *g = Some((pos, total));
         };
-        restore_browser_stack_bounds_or_center(&app, &bar, &content, pos, total);
+        restore_browser_stack_bounds_or_center(app, &bar, &content, pos, total);
         if let Ok(p) = bar.outer_position() {
             if p.x > -9000 && p.y > -9000 {
                 if let Ok(mut g) = state.last_position.lock() {
@@ -358,9 +411,9 @@ pub(crate) async fn open_browser_window_impl(
             }
         }
         // 兜底：把“实际应用后的尺寸/位置”同步回内存（供 hide/show 使用）
-        save_browser_stack_bounds_if_valid(&app);
+        save_browser_stack_bounds_if_valid(app);
     } else {
-        browser_stack_restore_or_center(&app);
+        browser_stack_restore_or_center(app);
     }
 
     // 让“网页主体窗口”只有底部两个角是圆角（顶部两个角会和顶部栏拼接，不要圆角）。
@@ -369,10 +422,106 @@ pub(crate) async fn open_browser_window_impl(
     let _ = bar.show();
     let _ = content.show();
     let _ = content.set_focus();
-    browser_ui_set_mode(&app, wake_logic::UiMode::BrowserVisible);
+    browser_ui_set_mode(app, wake_logic::UiMode::BrowserVisible);
     Ok(())
 }
 
+/// 创建一个标签的内容窗口（隐藏状态，由调用方摆位置并显示）。
+pub(crate) fn build_browser_content(
+    app: &tauri::AppHandle,
+    label: &str,
+    parsed: tauri::Url,
+) -> Result<tauri::WebviewWindow, String> {
+    let webview_settings = load_webview_settings(app);
+    let video_script = browser_video_injection_script(&webview_settings.video)?;
+    let user_scripts = browser_user_scripts::browser_user_scripts_injection_scripts(
+        &webview_settings.user_scripts,
+    )?;
+    browser_filters::browser_filters_ensure_loaded(app);
+
+    let app_ = app.clone();
+    let app_nav = app.clone();
+    let source_label = label.to_string();
+    let nav_label = label.to_string();
+    let mut content_builder =
+        tauri::WebviewWindowBuilder::new(app, label, tauri::WebviewUrl::External(parsed))
+            .title("Web")
+            .initialization_script(video_script)
+            .on_new_window(move |url, _features| {
+                // 很多网站会用 window.open / target=_blank 打开“新标签页”：在浏览栈里开新标签。
+                if is_http_url(url.as_str()) {
+                    browser_tabs::browser_tabs_open_in_new_tab(
+                        &app_,
+                        url.as_str(),
+                        Some(&source_label),
+                    );
+                } else {
+                    let _ = open::that(url.as_str());
+                }
+                tauri::webview::NewWindowResponse::Deny
+            })
+            .on_navigation(move |url| {
+                if !browser_filters::browser_filters_on_navigation(&app_nav, &nav_label, url) {
+                    return false;
+                }
+                // 这个窗口创建后用户脚本改过：换一个注册了新脚本的窗口打开这次导航
+                if is_http_url(url.as_str())
+                    && browser_tabs::window_scripts_stale(&app_nav, &nav_label)
+                {
+                    browser_tabs::browser_tabs_reopen_with_new_scripts(
+                        &app_nav,
+                        &nav_label,
+                        url.to_string(),
+                    );
+                    return false;
+                }
+                true
+            })
+            .on_download(|w, event| {
+                browser_downloads::browser_downloads_on_event(w.app_handle(), event)
+            })
+            .on_page_load(|w, payload| {
+                if matches!(payload.event(), tauri::webview::PageLoadEvent::Started) {
+                    browser_filters::browser_filters_on_page_started(
+                        w.app_handle(),
+                        w.label(),
+                        payload.url().as_str(),
+                    );
+                }
+                browser_tabs::browser_tabs_on_page_load(
+                    w.app_handle(),
+                    w.label(),
+                    payload.event(),
+                    payload.url().as_str(),
+                );
+            })
+            .on_document_title_changed(|w, title| {
+                browser_tabs::browser_tabs_on_title_changed(w.app_handle(), w.label(), &title);
+            })
+            .inner_size(
+                1020.0,
+                (BROWSER_STACK_TOTAL_HEIGHT - BROWSER_BAR_HEIGHT).max(200.0),
+            )
+            .resizable(true)
+            .maximizable(false)
+            .minimizable(false)
+            .decorations(false)
+            .transparent(false)
+            .shadow(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .visible(false);
+    // 每条用户脚本单独注入，某条有语法错误时不影响其它条目
+    for script in user_scripts {
+        content_builder = content_builder.initialization_script(script);
+    }
+    let content = content_builder
+        .build()
+        .map_err(|e| format!("创建浏览窗口失败: {e}"))?;
+    browser_filters::browser_filters_attach(&content);
+    Ok(content)
+}
+
 #[tauri::command]
 async fn close_browser_window(app: tauri::AppHandle) -> Result<(), String> {
     browser_stack_close(&app);
@@ -387,7 +536,10 @@ async fn hide_browser_stack(app: tauri::AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 async fn browser_go_back(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(w) = app.get_webview_window(BROWSER_WINDOW_LABEL) {
+    if browser_tabs::browser_tabs_go(&app, -1) {
+        return Ok(());
+    }
+    if let Some(w) = browser_tabs::browser_content_window(&app) {
         let _ = w.eval("history.back()");
     }
     Ok(())
@@ -395,7 +547,10 @@ async fn browser_go_back(app: tauri::AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 async fn browser_go_forward(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(w) = app.get_webview_window(BROWSER_WINDOW_LABEL) {
+    if browser_tabs::browser_tabs_go(&app, 1) {
+        return Ok(());
+    }
+    if let Some(w) = browser_tabs::browser_content_window(&app) {
         let _ = w.eval("history.forward()");
     }
     Ok(())
@@ -403,7 +558,7 @@ async fn browser_go_forward(app: tauri::AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 async fn browser_reload(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(w) = app.get_webview_window(BROWSER_WINDOW_LABEL) {
+    if let Some(w) = browser_tabs::browser_content_window(&app) {
         let _ = w.eval("location.reload()");
     }
     Ok(())
@@ -417,12 +572,14 @@ fn get_webview_settings(app: tauri::AppHandle) -> WebviewSettings {
 #[tauri::command]
 fn set_webview_settings(
     app: tauri::AppHandle,
-    settings: WebviewSettings,
+    mut settings: WebviewSettings,
 ) -> Result<WebviewSettings, String> {
+    // 用户脚本由 browser_user_script_* 单独管理，这里保留已保存的列表
+    settings.user_scripts = load_webview_settings(&app).user_scripts;
     let next = write_webview_settings(&app, settings)?;
 
-    if let Some(w) = app.get_webview_window(BROWSER_WINDOW_LABEL) {
-        if let Ok(script) = browser_video_injection_script(&next.video) {
+    if let Ok(script) = browser_video_injection_script(&next.video) {
+        for w in browser_tabs::browser_content_windows(&app) {
             let _ = w.eval(&script);
         }
     }
@@ -446,7 +603,7 @@ fn browser_video_set_rate(app: tauri::AppHandle, rate: f64) -> Result<(), String
     let settings = load_webview_settings(&app);
     let r = clamp_video_rate(rate, settings.video.max_rate);
 
-    let Some(w) = app.get_webview_window(BROWSER_WINDOW_LABEL) else {
+    let Some(w) = browser_tabs::browser_content_window(&app) else {
         return Ok(());
     };
 
@@ -489,7 +646,7 @@ fn browser_video_toggle_preset(
     let settings = load_webview_settings(&app);
     let r = clamp_video_rate(rate, settings.video.max_rate);
 
-    let Some(w) = app.get_webview_window(BROWSER_WINDOW_LABEL) else {
+    let Some(w) = browser_tabs::browser_content_window(&app) else {
         return Ok(());
     };
 
@@ -528,6 +685,36 @@ async fn browser_stack_toggle_fullscreen(app: tauri::AppHandle) -> Result<(), St
     Ok(())
 }
 
+#[tauri::command]
+async fn browser_stack_toggle_mini(app: tauri::AppHandle) -> Result<bool, String> {
+    if browser_stack_is_mini(&app) {
+        browser_stack_leave_mini(&app, true)?;
+        return Ok(false);
+    }
+    browser_stack_enter_mini(&app)?;
+    Ok(true)
+}
+
+#[tauri::command]
+fn browser_mini_get_settings(app: tauri::AppHandle) -> BrowserMiniSettings {
+    load_browser_mini_settings(&app)
+}
+
+#[tauri::command]
+fn browser_mini_set_settings(
+    app: tauri::AppHandle,
+    opacity: f64,
+    click_through: bool,
+) -> Result<BrowserMiniSettings, String> {
+    save_browser_mini_settings(
+        &app,
+        BrowserMiniSettings {
+            opacity,
+            click_through,
+        },
+    )
+}
+
 #[tauri::command]
 async fn browser_stack_get_pinned(app: tauri::AppHandle) -> Result<bool, String> {
     Ok(browser_stack_is_pinned(&app))
@@ -544,6 +731,7 @@ async fn browser_stack_toggle_pinned(app: tauri::AppHandle) -> Result<bool, Stri
         *g = !*g;
         *g
     };
+    browser_tabs::persist_browser_session(&app);
     if next {
         // 保险：确保窗口处于置顶态
         browser_stack_set_always_on_top(&app, true);
@@ -671,6 +859,8 @@ struct WebviewVideoSettings {
 #[serde(rename_all = "camelCase")]
 struct WebviewSettings {
     video: WebviewVideoSettings,
+    #[serde(default)]
+    user_scripts: Vec<browser_user_scripts::WebviewUserScript>,
 }
 
 impl Default for WebviewVideoSettings {
@@ -703,6 +893,7 @@ impl Default for WebviewSettings {
     fn default() -> Self {
         Self {
             video: WebviewVideoSettings::default(),
+            user_scripts: Vec::new(),
         }
     }
 }
@@ -816,6 +1007,7 @@ fn sanitize_webview_settings_for_load(mut settings: WebviewSettings) -> WebviewS
     }
 
     settings.video.presets = presets;
+    settings.user_scripts = browser_user_scripts::sanitize_user_scripts(settings.user_scripts);
     settings
 }
 
@@ -1625,7 +1817,7 @@ pub(crate) fn load_main_window_focus_mode_pref(app: &tauri::AppHandle) -> MainWi
     MainWindowFocusMode::AutoHide
 }
 
-fn load_main_window_mode_shortcut(app: &tauri::AppHandle) -> (Option<Shortcut>, String) {
+fn load_main_window_mode_shortcut(app: &tauri::AppHandle) -> (Option<HotkeySequence>, String) {
     let cfg_path = app_config_path(app);
     let map = read_app_config_map(app);
     let raw = map
@@ -1639,8 +1831,11 @@ fn load_main_window_mode_shortcut(app: &tauri::AppHandle) -> (Option<Shortcut>,
         return (None, "-".to_string());
     }
 
-    match Shortcut::from_str(raw.trim()) {
-        Ok(s) => (Some(s), s.to_string()),
+    match HotkeySequence::from_str(raw.trim()) {
+        Ok(s) => {
+            let text = s.to_string();
+            (Some(s), text)
+        }
         Err(e) => {
             eprintln!(
                 "[config] invalid mainWindowModeShortcut \"{}\" in {:?}: {}",
@@ -1651,7 +1846,7 @@ fn load_main_window_mode_shortcut(app: &tauri::AppHandle) -> (Option<Shortcut>,
     }
 }
 
-fn load_wake_shortcut(app: &tauri::AppHandle) -> (Shortcut, String) {
+fn load_wake_shortcut(app: &tauri::AppHandle) -> (HotkeySequence, String) {
     let cfg_path = app_config_path(app);
     let map = read_app_config_map(app);
 
@@ -1666,20 +1861,45 @@ fn load_wake_shortcut(app: &tauri::AppHandle) -> (Shortcut, String) {
         })
         .unwrap_or_else(|| DEFAULT_WAKE_SHORTCUT.to_string());
 
-    match Shortcut::from_str(raw.trim()) {
-        Ok(s) => (s, s.to_string()),
+    match HotkeySequence::from_str(raw.trim()) {
+        Ok(s) => {
+            let text = s.to_string();
+            (s, text)
+        }
         Err(e) => {
             eprintln!(
                 "[config] invalid wakeShortcut \"{}\" in {:?}: {}",
                 raw, cfg_path, e
             );
-            let fallback = Shortcut::from_str(DEFAULT_WAKE_SHORTCUT)
+            let fallback = HotkeySequence::from_str(DEFAULT_WAKE_SHORTCUT)
                 .expect("DEFAULT_WAKE_SHORTCUT must be parseable");
-            (fallback, fallback.to_string())
+            let text = fallback.to_string();
+            (fallback, text)
         }
     }
 }
 
+pub(crate) fn wake_hotkey_spec(sequence: HotkeySequence) -> HotkeySpec {
+    HotkeySpec {
+        id: hotkeys::WAKE_HOTKEY_ID.to_string(),
+        owner: "唤醒快捷键".to_string(),
+        sequence,
+        action: Arc::new(handle_wake_shortcut),
+    }
+}
+
+pub(crate) fn main_window_mode_hotkey_specs(sequence: Option<HotkeySequence>) -> Vec<HotkeySpec> {
+    sequence
+        .map(|sequence| HotkeySpec {
+            id: hotkeys::MAIN_WINDOW_MODE_HOTKEY_ID.to_string(),
+            owner: "主窗口模式快捷键".to_string(),
+            sequence,
+            action: Arc::new(handle_main_window_mode_shortcut),
+        })
+        .into_iter()
+        .collect()
+}
+
 fn browser_ui_get_mode(app: &tauri::AppHandle) -> wake_logic::UiMode {
     let state = app.state::<BrowserWindowState>();
     state
@@ -1728,6 +1948,26 @@ fn hide_main_window(app: &tauri::AppHandle) {
 }
 
 fn handle_wake_shortcut(app: &tauri::AppHandle) {
+    // 宿主重启后首次唤醒：恢复上次未关闭的浏览会话
+    if !browser_stack_exists(app) && browser_tabs::has_pending_restore(app) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = browser_session_restore(app.clone()).await {
+                eprintln!("[browser] failed to restore session: {e}");
+                show_main_window(&app);
+            }
+        });
+        return;
+    }
+
+    // 迷你模式（可能开着点击穿透）：唤醒键用来还原浏览栈
+    if browser_stack_is_mini(app) {
+        if let Err(e) = browser_stack_leave_mini(app, true) {
+            eprintln!("[browser] failed to leave mini mode: {e}");
+        }
+        return;
+    }
+
     let state = app.state::<BrowserWindowState>();
     let mode = browser_ui_get_mode(app);
     let browser_active = state.active.lock().ok().map(|g| *g).unwrap_or(false);
@@ -2109,53 +2349,23 @@ fn set_wake_shortcut(app: tauri::AppHandle, shortcut: String) -> Result<String,
         return Err("快捷键不能为空".to_string());
     }
 
-    let next = Shortcut::from_str(raw).map_err(|e| format!("快捷键格式不合法: {e}"))?;
+    let next = HotkeySequence::from_str(raw).map_err(|e| format!("快捷键格式不合法: {e}"))?;
     let normalized = next.to_string();
 
     let state = app.state::<WakeShortcutState>();
-    let mut guard = state
+    let prev = state
         .current
         .lock()
-        .map_err(|_| "内部状态锁失败".to_string())?;
-    let prev = *guard;
-    let was_paused = state.paused.lock().map(|g| *g).unwrap_or(false);
-
-    if prev.id() == next.id() {
-        update_app_config_map(&app, |map| {
-            map.insert(
-                WAKE_SHORTCUT_KEY.to_string(),
-                Value::String(normalized.clone()),
-            );
-            Ok(())
-        })?;
-        *guard = next;
-
-        if was_paused {
-            app.global_shortcut()
-                .on_shortcut(prev, move |app, _shortcut, event| {
-                    if event.state != ShortcutState::Pressed {
-                        return;
-                    }
-                    handle_wake_shortcut(app);
-                })
-                .map_err(|e| format!("注册全局快捷键失败: {e}"))?;
-
-            if let Ok(mut p) = state.paused.lock() {
-                *p = false;
-            }
-        }
-        return Ok(normalized);
-    }
+        .map_err(|_| "内部状态锁失败".to_string())?
+        .clone();
 
-    // 先尝试注册新快捷键：避免先删后加导致用户短暂失去可用热键。
-    app.global_shortcut()
-        .on_shortcut(next, move |app, _shortcut, event| {
-            if event.state != ShortcutState::Pressed {
-                return;
-            }
-            handle_wake_shortcut(app);
-        })
-        .map_err(|e| format!("注册全局快捷键失败: {e}"))?;
+    // 注册表先注册新快捷键再注销旧的：避免先删后加导致用户短暂失去可用热键。
+    hotkeys::replace_source_checked_and_resume(
+        &app,
+        HotkeySource::Wake,
+        vec![wake_hotkey_spec(next.clone())],
+        &[hotkeys::WAKE_HOTKEY_ID],
+    )?;
 
     if let Err(e) = update_app_config_map(&app, |map| {
         map.insert(
@@ -2164,65 +2374,25 @@ fn set_wake_shortcut(app: tauri::AppHandle, shortcut: String) -> Result<String,
         );
         Ok(())
     }) {
-        let _ = app.global_shortcut().unregister(next);
+        hotkeys::replace_source(&app, HotkeySource::Wake, vec![wake_hotkey_spec(prev)]);
         return Err(e);
     }
 
-    let _ = app.global_shortcut().unregister(prev);
-    *guard = next;
-    if let Ok(mut p) = state.paused.lock() {
-        *p = false;
+    if let Ok(mut g) = state.current.lock() {
+        *g = next;
     }
     Ok(normalized)
 }
 
 #[tauri::command]
 fn pause_wake_shortcut(app: tauri::AppHandle) -> Result<(), String> {
-    let state = app.state::<WakeShortcutState>();
-    let current = state
-        .current
-        .lock()
-        .map_err(|_| "内部状态锁失败".to_string())?;
-
-    if let Ok(mut p) = state.paused.lock() {
-        if *p {
-            return Ok(());
-        }
-        let _ = app.global_shortcut().unregister(*current);
-        *p = true;
-    }
-
+    hotkeys::set_source_paused(&app, HotkeySource::Wake, true);
     Ok(())
 }
 
 #[tauri::command]
 fn resume_wake_shortcut(app: tauri::AppHandle) -> Result<(), String> {
-    let state = app.state::<WakeShortcutState>();
-    let current = state
-        .current
-        .lock()
-        .map_err(|_| "内部状态锁失败".to_string())?;
-
-    let mut should_resume = false;
-    if let Ok(p) = state.paused.lock() {
-        should_resume = *p;
-    }
-    if !should_resume {
-        return Ok(());
-    }
-
-    app.global_shortcut()
-        .on_shortcut(*current, move |app, _shortcut, event| {
-            if event.state != ShortcutState::Pressed {
-                return;
-            }
-            handle_wake_shortcut(app);
-        })
-        .map_err(|e| format!("注册全局快捷键失败: {e}"))?;
-
-    if let Ok(mut p) = state.paused.lock() {
-        *p = false;
-    }
+    hotkeys::set_source_paused(&app, HotkeySource::Wake, false);
     Ok(())
 }
 
@@ -2343,41 +2513,33 @@ fn set_main_window_mode_shortcut(
     let raw = shortcut.trim();
 
     let state = app.state::<MainWindowModeShortcutState>();
-    let prev = state.current.lock().ok().and_then(|g| *g);
+    let prev = state.current.lock().ok().and_then(|g| g.clone());
 
     // 空字符串：视为“禁用快捷键”
     if raw.is_empty() {
-        if let Some(s) = prev {
-            let _ = app.global_shortcut().unregister(s);
-        }
-        if let Err(e) = update_app_config_map(&app, |map| {
+        update_app_config_map(&app, |map| {
             map.remove(MAIN_WINDOW_MODE_SHORTCUT_KEY);
             Ok(())
-        }) {
-            return Err(e);
-        }
+        })?;
+        hotkeys::replace_source(&app, HotkeySource::MainWindowMode, Vec::new());
+        hotkeys::set_source_paused(&app, HotkeySource::MainWindowMode, false);
 
         if let Ok(mut g) = state.current.lock() {
             *g = None;
         }
-        if let Ok(mut p) = state.paused.lock() {
-            *p = false;
-        }
         return Ok("".to_string());
     }
 
-    let next = Shortcut::from_str(raw).map_err(|e| format!("快捷键格式不合法: {e}"))?;
+    let next = HotkeySequence::from_str(raw).map_err(|e| format!("快捷键格式不合法: {e}"))?;
     let normalized = next.to_string();
 
-    // 先尝试注册新快捷键：避免先删后加导致用户短暂失去可用热键。
-    app.global_shortcut()
-        .on_shortcut(next, move |app, _shortcut, event| {
-            if event.state != ShortcutState::Pressed {
-                return;
-            }
-            handle_main_window_mode_shortcut(app);
-        })
-        .map_err(|e| format!("注册全局快捷键失败: {e}"))?;
+    // 注册表先注册新快捷键再注销旧的：避免先删后加导致用户短暂失去可用热键。
+    hotkeys::replace_source_checked_and_resume(
+        &app,
+        HotkeySource::MainWindowMode,
+        main_window_mode_hotkey_specs(Some(next.clone())),
+        &[hotkeys::MAIN_WINDOW_MODE_HOTKEY_ID],
+    )?;
 
     if let Err(e) = update_app_config_map(&app, |map| {
         map.insert(
@@ -2386,81 +2548,29 @@ fn set_main_window_mode_shortcut(
         );
         Ok(())
     }) {
-        let _ = app.global_shortcut().unregister(next);
+        hotkeys::replace_source(
+            &app,
+            HotkeySource::MainWindowMode,
+            main_window_mode_hotkey_specs(prev),
+        );
         return Err(e);
     }
 
-    if let Some(s) = prev {
-        let _ = app.global_shortcut().unregister(s);
-    }
-
     if let Ok(mut g) = state.current.lock() {
         *g = Some(next);
     }
-    if let Ok(mut p) = state.paused.lock() {
-        *p = false;
-    }
     Ok(normalized)
 }
 
 #[tauri::command]
 fn pause_main_window_mode_shortcut(app: tauri::AppHandle) -> Result<(), String> {
-    let state = app.state::<MainWindowModeShortcutState>();
-    let current = state
-        .current
-        .lock()
-        .map_err(|_| "内部状态锁失败".to_string())?
-        .clone();
-
-    if let Ok(mut p) = state.paused.lock() {
-        if *p {
-            return Ok(());
-        }
-        if let Some(s) = current {
-            let _ = app.global_shortcut().unregister(s);
-        }
-        *p = true;
-    }
-
+    hotkeys::set_source_paused(&app, HotkeySource::MainWindowMode, true);
     Ok(())
 }
 
 #[tauri::command]
 fn resume_main_window_mode_shortcut(app: tauri::AppHandle) -> Result<(), String> {
-    let state = app.state::<MainWindowModeShortcutState>();
-    let current = state
-        .current
-        .lock()
-        .map_err(|_| "内部状态锁失败".to_string())?
-        .clone();
-
-    let mut should_resume = false;
-    if let Ok(p) = state.paused.lock() {
-        should_resume = *p;
-    }
-    if !should_resume {
-        return Ok(());
-    }
-
-    let Some(s) = current else {
-        if let Ok(mut p) = state.paused.lock() {
-            *p = false;
-        }
-        return Ok(());
-    };
-
-    app.global_shortcut()
-        .on_shortcut(s, move |app, _shortcut, event| {
-            if event.state != ShortcutState::Pressed {
-                return;
-            }
-            handle_main_window_mode_shortcut(app);
-        })
-        .map_err(|e| format!("注册全局快捷键失败: {e}"))?;
-
-    if let Ok(mut p) = state.paused.lock() {
-        *p = false;
-    }
+    hotkeys::set_source_paused(&app, HotkeySource::MainWindowMode, false);
     Ok(())
 }
 
@@ -2514,6 +2624,11 @@ fn set_auto_start(app: tauri::AppHandle, enabled: bool) -> Result<AutoStartStatu
 }
 
 fn main() {
+    // fast-window capability …：命令行调用能力，不启动界面
+    if let Some(code) = capability_cli::run_from_args() {
+        std::process::exit(code);
+    }
+
     let builder = app::builder_base().invoke_handler(tauri::generate_handler![
         get_plugins_dir,
         plugin_dev_sync,
@@ -2562,7 +2677,12 @@ fn main() {
         app_capabilities::app_host_shortcut_list,
         app_capabilities::app_capability_list,
         app_capabilities::app_capability_invoke,
+        app_capabilities::app_capability_invoke_stream,
+        app_capabilities::app_capability_invoke_stream_cancel,
         app_capabilities::app_capability_query_options,
+        capability_server::access::capability_access_get,
+        capability_server::access::capability_access_set,
+        capability_server::audit::capability_audit_log,
         app_lifecycle::manager::app_restart,
         app_lifecycle::manager::app_open_folder,
         app_lifecycle::manager::app_force_stop,
@@ -2570,6 +2690,8 @@ fn main() {
         app_lifecycle::manager::app_status,
         app_lifecycle::manager::app_status_many,
         app_lifecycle::manager::app_icon_data_url,
+        app_lifecycle::logs::app_logs_tail,
+        app_lifecycle::logs::app_logs_tail_cancel,
         app_dev_actions::app_dev_run_terminal_command,
         host_dev_actions::host_dev_run_terminal_command,
         app_registry::app_registry_load,
@@ -2580,6 +2702,22 @@ fn main() {
         app_registry::app_registry_update,
         app_shortcuts::pause_registered_app_shortcuts,
         app_shortcuts::resume_registered_app_shortcuts,
+        app_workflows::app_workflow_list,
+        app_workflows::app_workflow_save,
+        app_workflows::app_workflow_remove,
+        app_workflows::app_workflow_run,
+        command_index::command_index_query,
+        command_index::command_index_record_use,
+        usage::usage_stats,
+        usage::usage_top,
+        hotkeys::hotkeys_list,
+        hotkeys::hotkeys_set_enabled,
+        plugin_shortcuts::plugin_shortcuts_sync,
+        plugin_shortcuts::plugin_shortcut_register,
+        plugin_shortcuts::plugin_shortcut_unregister,
+        plugin_shortcuts::plugin_shortcut_take,
+        plugin_shortcuts::pause_plugin_shortcuts,
+        plugin_shortcuts::resume_plugin_shortcuts,
         open_external_url,
         open_external_uri,
         open_browser_window,
@@ -2588,17 +2726,51 @@ fn main() {
         browser_go_back,
         browser_go_forward,
         browser_reload,
+        browser_tabs::browser_tabs_list,
+        browser_tabs::browser_tab_new,
+        browser_tabs::browser_tab_activate,
+        browser_tabs::browser_tab_close,
+        browser_tabs::browser_tab_move,
+        browser_downloads::browser_downloads_list,
+        browser_downloads::browser_downloads_clear,
+        browser_downloads::browser_download_open_dir,
+        browser_downloads::browser_download_get_dir,
+        browser_downloads::browser_download_pick_dir,
+        browser_downloads::browser_download_reset_dir,
+        browser_filters::browser_filter_status,
+        browser_filters::browser_filter_reload,
+        browser_filters::browser_filter_set_enabled,
+        browser_filters::browser_filter_toggle_site,
+        browser_filters::browser_filter_open_dir,
+        browser_history::browser_history_search,
+        browser_history::browser_history_clear,
+        browser_history::browser_capture_get_target,
+        browser_history::browser_capture_set_target,
+        browser_history::browser_capture_current_page,
+        browser_history::browser_capture_take,
         get_webview_settings,
         set_webview_settings,
+        browser_user_scripts::browser_user_scripts_list,
+        browser_user_scripts::browser_user_script_save,
+        browser_user_scripts::browser_user_script_remove,
         browser_video_set_rate,
         browser_video_toggle_preset,
         browser_stack_toggle_fullscreen,
+        browser_stack_toggle_mini,
+        browser_mini_get_settings,
+        browser_mini_set_settings,
         browser_stack_get_pinned,
         browser_stack_toggle_pinned,
         http_request,
         http_request_base64,
         http_request_stream,
         http_request_stream_cancel,
+        http_cache::http_cache_clear,
+        http_debug::http_debug_get_enabled,
+        http_debug::http_debug_set_enabled,
+        http_debug::http_debug_log,
+        http_debug::http_debug_clear,
+        http_debug::http_debug_export_har,
         gateway_test_channel,
         clipboard_read_text,
         clipboard_write_text,
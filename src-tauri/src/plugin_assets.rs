@@ -48,7 +48,7 @@ fn hex_val(c: u8) -> Option<u8> {
     }
 }
 
-fn percent_decode_path(raw: &str) -> Result<String, String> {
+pub(crate) fn percent_decode_path(raw: &str) -> Result<String, String> {
     let bytes = raw.as_bytes();
     let mut out = Vec::with_capacity(bytes.len());
     let mut i = 0usize;
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, EventTarget, Manager};
+
+use crate::hotkeys::{HotkeySequence, HotkeySource, HotkeySpec};
+
+// ── 插件全局快捷键 ──────────────────────────────────────────────────────────
+//
+// 两种来源：
+// - manifest.hotkeys：主窗口载入插件列表后用 plugin_shortcuts_sync 整体同步（只含已启用插件）；
+// - 运行时：插件通过网关调用 plugin_shortcut_register / plugin_shortcut_unregister（pluginId 由网关注入）。
+// 按下时宿主把命令暂存为“待领取”，显示主窗口并激活插件（激活事件带 command）；
+// 插件启动后用 plugin_shortcut_take 领取，已在运行的插件也可以监听 `fast-window:plugin-shortcut-pressed`。
+// 注册、冲突检测与暂停统一交给 hotkeys 注册表；快捷键可以是按键序列（如 ctrl+K ctrl+M）。
+
+const PLUGIN_SHORTCUT_PRESSED_EVENT: &str = "fast-window:plugin-shortcut-pressed";
+
+#[derive(Default)]
+pub(crate) struct PluginShortcutState {
+    bindings: Mutex<Vec<PluginShortcutBinding>>,
+    /// 插件 -> 尚未领取的按键
+    pending: Mutex<HashMap<String, PluginShortcutPress>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PluginShortcutSource {
+    Manifest,
+    Runtime,
+}
+
+#[derive(Clone)]
+struct PluginShortcutBinding {
+    plugin_id: String,
+    shortcut_id: String,
+    shortcut: HotkeySequence,
+    source: PluginShortcutSource,
+}
+
+impl PluginShortcutBinding {
+    fn is(&self, plugin_id: &str, shortcut_id: &str) -> bool {
+        self.plugin_id == plugin_id && self.shortcut_id == shortcut_id
+    }
+
+    fn hotkey_id(&self) -> String {
+        plugin_hotkey_id(&self.plugin_id, &self.shortcut_id)
+    }
+
+    fn spec(&self) -> HotkeySpec {
+        let plugin_id = self.plugin_id.clone();
+        let shortcut_id = self.shortcut_id.clone();
+        HotkeySpec {
+            id: self.hotkey_id(),
+            owner: plugin_shortcut_owner(&self.plugin_id, &self.shortcut_id),
+            sequence: self.shortcut.clone(),
+            action: Arc::new(move |app: &AppHandle| {
+                handle_plugin_shortcut_pressed(app, &plugin_id, &shortcut_id)
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PluginShortcutDeclaration {
+    plugin_id: String,
+    id: String,
+    hotkey: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PluginShortcutPress {
+    plugin_id: String,
+    command: String,
+    pressed_at_ms: u64,
+}
+
+fn plugin_shortcut_owner(plugin_id: &str, shortcut_id: &str) -> String {
+    format!("{plugin_id}/{shortcut_id} 的插件快捷键")
+}
+
+fn plugin_hotkey_id(plugin_id: &str, shortcut_id: &str) -> String {
+    format!("plugin:{plugin_id}/{shortcut_id}")
+}
+
+fn normalize_plugin_id(plugin_id: Option<String>) -> Result<String, String> {
+    let pid = plugin_id
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "pluginId 不能为空".to_string())?;
+    if !crate::is_safe_id(&pid) {
+        return Err("pluginId 不合法".to_string());
+    }
+    Ok(pid)
+}
+
+fn normalize_shortcut_id(id: &str) -> Result<String, String> {
+    let id = id.trim();
+    if id.is_empty() {
+        return Err("快捷键 ID 不能为空".to_string());
+    }
+    if !crate::is_safe_id(id) {
+        return Err(format!("快捷键 ID 不合法: {id}"));
+    }
+    Ok(id.to_string())
+}
+
+/// 校验声明的快捷键：ID / 格式不合法、同插件 ID 重复的跳过并记错误，其余返回；冲突由注册表判定。
+fn plan_plugin_shortcuts(
+    declared: &[PluginShortcutDeclaration],
+) -> (Vec<PluginShortcutBinding>, Vec<String>) {
+    let mut planned = Vec::new();
+    let mut errors = Vec::new();
+
+    for item in declared {
+        let result = normalize_plugin_id(Some(item.plugin_id.clone())).and_then(|plugin_id| {
+            let shortcut_id = normalize_shortcut_id(&item.id)?;
+            if planned
+                .iter()
+                .any(|b: &PluginShortcutBinding| b.is(&plugin_id, &shortcut_id))
+            {
+                return Err(format!("{plugin_id} 的快捷键 ID 重复: {shortcut_id}"));
+            }
+            let owner = plugin_shortcut_owner(&plugin_id, &shortcut_id);
+            let shortcut = HotkeySequence::from_str(item.hotkey.trim())
+                .map_err(|e| format!("{owner}格式不合法: {e}"))?;
+            Ok(PluginShortcutBinding {
+                plugin_id,
+                shortcut_id,
+                shortcut,
+                source: PluginShortcutSource::Manifest,
+            })
+        });
+        match result {
+            Ok(binding) => planned.push(binding),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    (planned, errors)
+}
+
+fn binding_specs(bindings: &[PluginShortcutBinding]) -> Vec<HotkeySpec> {
+    bindings.iter().map(PluginShortcutBinding::spec).collect()
+}
+
+fn handle_plugin_shortcut_pressed(app: &AppHandle, plugin_id: &str, shortcut_id: &str) {
+    let press = PluginShortcutPress {
+        plugin_id: plugin_id.to_string(),
+        command: shortcut_id.to_string(),
+        pressed_at_ms: crate::now_ms(),
+    };
+    {
+        let state = app.state::<PluginShortcutState>();
+        let mut g = state.pending.lock().unwrap_or_else(|e| e.into_inner());
+        g.insert(plugin_id.to_string(), press.clone());
+    }
+    let _ = app.emit_to(
+        EventTarget::webview_window("main"),
+        PLUGIN_SHORTCUT_PRESSED_EVENT,
+        press,
+    );
+
+    crate::usage::record_usage(app, crate::usage::UsageKind::Plugin, plugin_id);
+    crate::browser_stack::browser_stack_hide(app);
+    crate::show_main_window(app);
+    crate::host_primitives::emit_activate_plugin_with_command(
+        app,
+        plugin_id,
+        Some(shortcut_id.to_string()),
+    );
+}
+
+/// 主窗口载入插件后同步 manifest.hotkeys；`plugin_ids` 是当前已启用的插件，
+/// 其它插件（已禁用、已卸载）运行时绑定的快捷键一并注销。返回未能注册的项（冲突、格式错误等）。
+#[tauri::command]
+pub(crate) fn plugin_shortcuts_sync(
+    app: AppHandle,
+    declared: Vec<PluginShortcutDeclaration>,
+    plugin_ids: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let state = app.state::<PluginShortcutState>();
+    let mut bindings = state
+        .bindings
+        .lock()
+        .map_err(|_| "插件快捷键状态锁定失败".to_string())?;
+
+    let runtime: Vec<PluginShortcutBinding> = std::mem::take(&mut *bindings)
+        .into_iter()
+        .filter(|b| b.source == PluginShortcutSource::Runtime && plugin_ids.contains(&b.plugin_id))
+        .collect();
+    // 运行时注册过的同名快捷键优先
+    let declared: Vec<PluginShortcutDeclaration> = declared
+        .into_iter()
+        .filter(|d| {
+            !runtime
+                .iter()
+                .any(|b| b.is(d.plugin_id.trim(), d.id.trim()))
+        })
+        .collect();
+    let (planned, mut errors) = plan_plugin_shortcuts(&declared);
+
+    let mut next = runtime;
+    next.extend(planned);
+    errors.extend(crate::hotkeys::replace_source(
+        &app,
+        HotkeySource::Plugins,
+        binding_specs(&next),
+    ));
+    *bindings = next;
+
+    for error in &errors {
+        eprintln!("[plugin-shortcuts] {error}");
+    }
+    Ok(errors)
+}
+
+/// 插件运行时绑定快捷键（覆盖同 ID 的旧绑定）；返回规范化后的快捷键。
+#[tauri::command]
+pub(crate) fn plugin_shortcut_register(
+    app: AppHandle,
+    plugin_id: Option<String>,
+    id: String,
+    hotkey: String,
+) -> Result<String, String> {
+    let plugin_id = normalize_plugin_id(plugin_id)?;
+    let shortcut_id = normalize_shortcut_id(&id)?;
+    let owner = plugin_shortcut_owner(&plugin_id, &shortcut_id);
+    let shortcut =
+        HotkeySequence::from_str(hotkey.trim()).map_err(|e| format!("{owner}格式不合法: {e}"))?;
+
+    let state = app.state::<PluginShortcutState>();
+    let mut bindings = state
+        .bindings
+        .lock()
+        .map_err(|_| "插件快捷键状态锁定失败".to_string())?;
+
+    let next = PluginShortcutBinding {
+        plugin_id: plugin_id.clone(),
+        shortcut_id: shortcut_id.clone(),
+        shortcut: shortcut.clone(),
+        source: PluginShortcutSource::Runtime,
+    };
+    let hotkey_id = next.hotkey_id();
+    let mut updated: Vec<PluginShortcutBinding> = bindings
+        .iter()
+        .filter(|b| !b.is(&plugin_id, &shortcut_id))
+        .cloned()
+        .collect();
+    updated.push(next);
+    // 冲突或系统注册失败时注册表会回滚，旧绑定保持可用
+    crate::hotkeys::replace_source_checked(
+        &app,
+        HotkeySource::Plugins,
+        binding_specs(&updated),
+        &[hotkey_id.as_str()],
+    )?;
+    *bindings = updated;
+    Ok(shortcut.to_string())
+}
+
+#[tauri::command]
+pub(crate) fn plugin_shortcut_unregister(
+    app: AppHandle,
+    plugin_id: Option<String>,
+    id: String,
+) -> Result<bool, String> {
+    let plugin_id = normalize_plugin_id(plugin_id)?;
+    let shortcut_id = normalize_shortcut_id(&id)?;
+
+    let state = app.state::<PluginShortcutState>();
+    let mut bindings = state
+        .bindings
+        .lock()
+        .map_err(|_| "插件快捷键状态锁定失败".to_string())?;
+    let Some(index) = bindings.iter().position(|b| b.is(&plugin_id, &shortcut_id)) else {
+        return Ok(false);
+    };
+    bindings.remove(index);
+    for issue in
+        crate::hotkeys::replace_source(&app, HotkeySource::Plugins, binding_specs(&bindings))
+    {
+        eprintln!("[plugin-shortcuts] {issue}");
+    }
+    Ok(true)
+}
+
+/// 插件领取暂存的按键（领取后清除）；pluginId 由网关按调用方注入。
+#[tauri::command]
+pub(crate) fn plugin_shortcut_take(
+    app: AppHandle,
+    plugin_id: Option<String>,
+) -> Result<Option<PluginShortcutPress>, String> {
+    let plugin_id = normalize_plugin_id(plugin_id)?;
+    let state = app.state::<PluginShortcutState>();
+    let mut g = state.pending.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(g.remove(&plugin_id))
+}
+
+#[tauri::command]
+pub(crate) fn pause_plugin_shortcuts(app: AppHandle) -> Result<(), String> {
+    crate::hotkeys::set_source_paused(&app, HotkeySource::Plugins, true);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn resume_plugin_shortcuts(app: AppHandle) -> Result<(), String> {
+    crate::hotkeys::set_source_paused(&app, HotkeySource::Plugins, false);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decl(plugin_id: &str, id: &str, hotkey: &str) -> PluginShortcutDeclaration {
+        PluginShortcutDeclaration {
+            plugin_id: plugin_id.to_string(),
+            id: id.to_string(),
+            hotkey: hotkey.to_string(),
+        }
+    }
+
+    #[test]
+    fn plan_parses_chords_and_skips_bad_hotkeys() {
+        let (planned, errors) = plan_plugin_shortcuts(&[
+            decl("memo", "quick", "Ctrl+KeyK Ctrl+KeyM"),
+            decl("todo", "bad", "alt+NotAKey"),
+        ]);
+
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].shortcut.to_string().split(' ').count(), 2);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("todo/bad"));
+    }
+
+    #[test]
+    fn plan_rejects_invalid_ids() {
+        let (planned, errors) = plan_plugin_shortcuts(&[
+            decl("../x", "a", "Alt+KeyA"),
+            decl("memo", "", "Alt+KeyB"),
+            decl("memo", "a", "Alt+KeyC"),
+            decl("memo", "a", "Alt+KeyD"),
+        ]);
+
+        assert_eq!(planned.len(), 1);
+        assert_eq!(errors.len(), 3);
+    }
+}
@@ -268,6 +268,7 @@ pub(crate) fn uninstall_plugin(
     remember_dev_sync_uninstalled_plugin(&app, &plugin_id)?;
 
     let mut warnings = remove_plugin_dir(&app, &plugin_id)?;
+    crate::http_host_policy::invalidate_plugin_http_allow_list(Some(&plugin_id));
     warnings.extend(cleanup_uninstalled_plugin_metadata(
         &app,
         &plugin_id,
@@ -276,6 +277,9 @@ pub(crate) fn uninstall_plugin(
     if delete_data {
         warnings.extend(remove_plugin_data(&app, &plugin_id));
     }
+    if let Err(e) = crate::http_cache::clear_http_cache(&app, Some(&plugin_id)) {
+        warnings.push(e);
+    }
 
     Ok(PluginUninstallResult {
         plugin_id,
@@ -48,6 +48,9 @@ fn is_valid_manifest_capability(cap: &str) -> bool {
     if !s.starts_with("cap:") {
         return false;
     }
+    if s.starts_with(crate::http_host_policy::HTTP_HOST_CAP_PREFIX) {
+        return crate::http_host_policy::is_valid_http_host_capability(s);
+    }
     s["cap:".len()..]
         .chars()
         .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | ':' | '*' | '|' | '-'))
@@ -139,6 +142,16 @@ fn read_package_manifest(root: &Path) -> Result<Value, String> {
     serde_json::from_str(&text).map_err(|e| format!("manifest.json 解析失败: {e}"))
 }
 
+/// 读取已安装插件 manifest 声明的 requires（供 Rust 侧网关做权限判断）。
+pub(crate) fn read_installed_plugin_requires(
+    app: &tauri::AppHandle,
+    plugin_id: &str,
+) -> Result<BTreeSet<String>, String> {
+    let root = app_plugins_dir(app).join(plugin_id);
+    let manifest = read_package_manifest(&root)?;
+    parse_manifest_requires(&manifest, false, "manifest")
+}
+
 fn validate_installed_package(
     root: &Path,
     expected_id: &str,
@@ -484,6 +497,7 @@ pub(crate) async fn plugin_dev_sync(app: tauri::AppHandle) -> Result<bool, Strin
         if repo_plugins.is_dir() && !crate::same_path(&repo_plugins, &plugins_dir) {
             // 开发同步：v2 legacy 插件只同步运行所需文件，避免 node_modules 等开发目录拖慢/失败。
             sync_repo_plugins_into(&app, &repo_plugins, &plugins_dir).await?;
+            crate::http_host_policy::invalidate_plugin_http_allow_list(None);
             return Ok(true);
         }
         Ok(false)
@@ -627,7 +641,13 @@ pub(crate) struct FsDirEntry {
 
 #[tauri::command]
 pub(crate) fn list_plugins(app: tauri::AppHandle) -> Vec<String> {
-    let dir = app_plugins_dir(&app);
+    // 主窗口重新扫描插件时命令索引跟着刷新
+    crate::command_index::invalidate(&app);
+    installed_plugin_ids(&app)
+}
+
+pub(crate) fn installed_plugin_ids(app: &tauri::AppHandle) -> Vec<String> {
+    let dir = app_plugins_dir(app);
     let mut out: Vec<String> = Vec::new();
 
     let Ok(entries) = std::fs::read_dir(&dir) else {
@@ -849,6 +869,7 @@ pub(crate) async fn install_plugin_files(
     if let Err(e) = replace_dir_from_tmp(&plugin_dir, &tmp_dir, &format!("install-{plugin_id}")) {
         return Err(format!("安装插件失败: {e}"));
     }
+    crate::http_host_policy::invalidate_plugin_http_allow_list(Some(&plugin_id));
     crate::plugin_uninstall::forget_dev_sync_uninstalled_plugin(&app, &plugin_id);
     Ok(())
 }
@@ -1119,6 +1140,7 @@ pub(crate) async fn plugin_store_install(
                 return Err(format!("安装插件失败: {e}"));
             }
 
+            crate::http_host_policy::invalidate_plugin_http_allow_list(Some(&plugin_id));
             crate::plugin_uninstall::forget_dev_sync_uninstalled_plugin(&app, &plugin_id);
 
             Ok(PluginStoreInstallResult { plugin_id, version })
@@ -0,0 +1,186 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+// ── 轮转追加文件 ────────────────────────────────────────────────────────────
+//
+// 应用输出日志和能力审计日志共用：按行追加到 <stem>.log，超过上限时轮转为
+// <stem>.1.log … <stem>.<keep>.log，最旧的丢弃。`label` 只用于错误信息（如“应用日志”）。
+
+/// <stem>.log 的第 `index` 个轮转文件 <stem>.<index>.log
+pub(crate) fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path.extension() {
+        Some(ext) => path.with_file_name(format!("{stem}.{index}.{}", ext.to_string_lossy())),
+        None => path.with_file_name(format!("{stem}.{index}")),
+    }
+}
+
+/// x.log -> x.1.log -> x.2.log …，超出保留数的直接覆盖掉。
+fn rotate_files(path: &Path, keep: usize, label: &str) -> Result<(), String> {
+    for index in (1..keep).rev() {
+        let from = rotated_path(path, index);
+        if from.is_file() {
+            std::fs::rename(&from, rotated_path(path, index + 1))
+                .map_err(|e| format!("轮转{label}失败: {e}"))?;
+        }
+    }
+    if path.is_file() {
+        std::fs::rename(path, rotated_path(path, 1))
+            .map_err(|e| format!("轮转{label}失败: {e}"))?;
+    }
+    Ok(())
+}
+
+struct OpenFile {
+    file: File,
+    size: u64,
+}
+
+pub(crate) struct RotatingAppendFile {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    label: &'static str,
+    current: Option<OpenFile>,
+}
+
+impl RotatingAppendFile {
+    /// 不立即打开文件，首次写入时才创建
+    pub(crate) fn new(path: PathBuf, max_bytes: u64, keep: usize, label: &'static str) -> Self {
+        Self {
+            path,
+            max_bytes,
+            keep,
+            label,
+            current: None,
+        }
+    }
+
+    /// 立即打开，打不开时直接报错
+    pub(crate) fn open(
+        path: PathBuf,
+        max_bytes: u64,
+        keep: usize,
+        label: &'static str,
+    ) -> Result<Self, String> {
+        let mut file = Self::new(path, max_bytes, keep, label);
+        file.current()?;
+        Ok(file)
+    }
+
+    fn current(&mut self) -> Result<&mut OpenFile, String> {
+        if self.current.is_none() {
+            let label = self.label;
+            if let Some(parent) = self.path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| format!("创建{label}目录失败: {e}"))?;
+            }
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .map_err(|e| format!("打开{label}失败: {e}"))?;
+            let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+            self.current = Some(OpenFile { file, size });
+        }
+        self.current
+            .as_mut()
+            .ok_or_else(|| format!("打开{}失败", self.label))
+    }
+
+    /// 追加一段内容（调用方自带换行）；写不下时先轮转。
+    /// 轮转失败时这段仍写进当前文件，再把轮转错误返回给调用方记录。
+    pub(crate) fn append(&mut self, text: &str) -> Result<(), String> {
+        let len = text.len() as u64;
+        let rotated = if self
+            .current
+            .as_ref()
+            .is_some_and(|c| c.size > 0 && c.size + len > self.max_bytes)
+        {
+            self.current = None;
+            rotate_files(&self.path, self.keep, self.label)
+        } else {
+            Ok(())
+        };
+        let label = self.label;
+        let current = self.current()?;
+        current
+            .file
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("写入{label}失败: {e}"))?;
+        current.size += len;
+        rotated
+    }
+}
+
+/// 读文件末尾至多 `max_bytes` 字节，返回文本和文件长度；
+/// 从中间截断时丢掉第一行残片，文件不存在时为空。
+pub(crate) fn read_file_tail(
+    path: &Path,
+    max_bytes: u64,
+    label: &str,
+) -> Result<(String, u64), String> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((String::new(), 0)),
+        Err(e) => return Err(format!("读取{label}失败: {e}")),
+    };
+    let len = file
+        .metadata()
+        .map_err(|e| format!("读取{label}失败: {e}"))?
+        .len();
+    let start = len.saturating_sub(max_bytes);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| format!("读取{label}失败: {e}"))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)
+        .map_err(|e| format!("读取{label}失败: {e}"))?;
+    let mut text = String::from_utf8_lossy(&bytes).into_owned();
+    if start > 0 {
+        text = match text.split_once('\n') {
+            Some((_, rest)) => rest.to_string(),
+            None => String::new(),
+        };
+    }
+    Ok((text, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotated_path_keeps_stem_and_extension() {
+        let path = Path::new("/data/capability-audit.log");
+        assert_eq!(
+            rotated_path(path, 2),
+            Path::new("/data/capability-audit.2.log")
+        );
+    }
+
+    #[test]
+    fn appends_rotate_and_tail_drops_partial_line() {
+        let dir = std::env::temp_dir().join(format!(
+            "fw-rotating-file-{}-{}",
+            std::process::id(),
+            crate::now_ms()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("x.log");
+        let mut file = RotatingAppendFile::new(path.clone(), 10, 2, "测试日志");
+        for line in ["aaaa\n", "bbbb\n", "cccc\n", "dddd\n", "eeee\n"] {
+            file.append(line).unwrap();
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "eeee\n");
+        assert_eq!(
+            std::fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+            "cccc\ndddd\n"
+        );
+        assert!(!rotated_path(&path, 3).exists());
+
+        let (text, len) = read_file_tail(&rotated_path(&path, 1), 7, "测试日志").unwrap();
+        assert_eq!(text, "dddd\n");
+        assert_eq!(len, 10);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::http_api::{http_request_for_task, HttpRequest};
+use crate::http_host_policy::{plugin_http_allow_list, HttpHostAllowList};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
@@ -35,7 +36,20 @@ pub(crate) fn validate_payload(payload: &Value) -> Result<(), String> {
     Ok(())
 }
 
-pub(crate) async fn run(payload: Value) -> Result<Value, String> {
+pub(crate) async fn run(
+    app: &tauri::AppHandle,
+    plugin_id: &str,
+    payload: Value,
+) -> Result<Value, String> {
+    // 与 http_request 命令同一份白名单：插件不能借后台任务访问未声明的主机
+    let hosts = plugin_http_allow_list(app, Some(plugin_id))?;
+    run_with_hosts(payload, hosts.as_ref()).await
+}
+
+async fn run_with_hosts(
+    payload: Value,
+    hosts: Option<&HttpHostAllowList>,
+) -> Result<Value, String> {
     let payload: HttpRequestTaskPayload =
         serde_json::from_value(payload).map_err(|e| format!("任务参数无效: {e}"))?;
 
@@ -53,8 +67,9 @@ pub(crate) async fn run(payload: Value) -> Result<Value, String> {
             body: payload.body.clone(),
             body_base64: payload.body_base64.clone(),
             timeout_ms: payload.timeout_ms,
+            cache: None,
         };
-        match http_request_for_task(req).await {
+        match http_request_for_task(req, hosts).await {
             Ok(resp) => {
                 return serde_json::to_value(HttpRequestTaskResult {
                     status: resp.status,
@@ -80,3 +95,19 @@ pub(crate) async fn run(payload: Value) -> Result<Value, String> {
     }
     Err(last_err)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_undeclared_host() {
+        let hosts = HttpHostAllowList::from_requires("demo", ["cap:http:api.example.com"]);
+        let payload = serde_json::json!({
+            "method": "GET",
+            "url": "https://evil.example.org/steal",
+        });
+        let err = run_with_hosts(payload, Some(&hosts)).await.unwrap_err();
+        assert!(err.contains("evil.example.org"), "{err}");
+    }
+}
@@ -56,7 +56,7 @@ pub(crate) async fn run_task_kind(
     payload: Value,
 ) -> Result<Value, String> {
     match kind.as_str() {
-        KIND_HTTP_REQUEST => http_request::run(payload).await,
+        KIND_HTTP_REQUEST => http_request::run(app, &plugin_id, payload).await,
         KIND_CLIPBOARD_WATCH => {
             clipboard_watch::run(app, manager, task_id, plugin_id, payload).await
         }
@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+// ── 使用记录 ────────────────────────────────────────────────────────────────
+//
+// 记录插件、注册应用、应用快捷入口、能力和宿主页面的每次激活，存在 <data>/__app/usage.sqlite：
+// - usage_totals：每个目标的累计次数与首次 / 最近使用时间（不随明细清理丢失）；
+// - usage_events：激活明细，只保留最近 USAGE_EVENT_RETENTION_DAYS 天，用来算 7/30 天次数和 frecency。
+// frecency 参照浏览器地址栏：取最近 FRECENCY_SAMPLE 次激活按时间远近加权取平均，再乘累计次数。
+// 同一目标 USAGE_DEDUPE_MS 内的重复激活只记一次（主窗口点击和随后的启动命令会各报一次）。
+
+const USAGE_DB_FILE: &str = "usage.sqlite";
+const USAGE_EVENT_RETENTION_DAYS: u64 = 180;
+const USAGE_DEDUPE_MS: u64 = 2_000;
+const FRECENCY_SAMPLE: usize = 10;
+const USAGE_TOP_DEFAULT_LIMIT: usize = 20;
+const USAGE_TOP_MAX_LIMIT: usize = 500;
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum UsageKind {
+    HostAction,
+    Plugin,
+    App,
+    AppCommand,
+    Capability,
+}
+
+impl UsageKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::HostAction => "hostAction",
+            Self::Plugin => "plugin",
+            Self::App => "app",
+            Self::AppCommand => "appCommand",
+            Self::Capability => "capability",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "hostAction" => Some(Self::HostAction),
+            "plugin" => Some(Self::Plugin),
+            "app" => Some(Self::App),
+            "appCommand" => Some(Self::AppCommand),
+            "capability" => Some(Self::Capability),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UsageStat {
+    kind: UsageKind,
+    /// 插件 ID / 应用 ID / `应用ID/命令ID` / `应用ID/能力ID` / 宿主页面
+    target: String,
+    count: u64,
+    first_used_ms: u64,
+    last_used_ms: u64,
+    count_7d: u64,
+    count_30d: u64,
+    frecency: u32,
+}
+
+struct UsageTotal {
+    kind: UsageKind,
+    target: String,
+    count: u64,
+    first_used_ms: u64,
+    last_used_ms: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct UsageState {
+    /// 首次使用时打开
+    conn: Mutex<Option<Connection>>,
+}
+
+fn usage_db_path(app: &AppHandle) -> PathBuf {
+    crate::app_data_dir(app)
+        .join(crate::APP_STORAGE_ID)
+        .join(USAGE_DB_FILE)
+}
+
+fn open_usage_db(path: &Path, now_ms: u64) -> Result<Connection, String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建使用记录目录失败: {e}"))?;
+    }
+    let conn = Connection::open(path).map_err(|e| format!("打开使用记录失败: {e}"))?;
+    let _ = conn.busy_timeout(std::time::Duration::from_millis(2_000));
+    let _ = conn.pragma_update(None, "journal_mode", "WAL");
+    let _ = conn.pragma_update(None, "synchronous", "NORMAL");
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS usage_totals (
+            kind TEXT NOT NULL,
+            target TEXT NOT NULL,
+            count INTEGER NOT NULL,
+            first_used_ms INTEGER NOT NULL,
+            last_used_ms INTEGER NOT NULL,
+            PRIMARY KEY (kind, target)
+        );
+        CREATE TABLE IF NOT EXISTS usage_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            target TEXT NOT NULL,
+            used_at_ms INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS usage_events_used_at ON usage_events (used_at_ms);",
+    )
+    .map_err(|e| format!("初始化使用记录失败: {e}"))?;
+    let cutoff = now_ms.saturating_sub(USAGE_EVENT_RETENTION_DAYS * DAY_MS);
+    let _ = conn.execute(
+        "DELETE FROM usage_events WHERE used_at_ms < ?1",
+        params![cutoff as i64],
+    );
+    Ok(conn)
+}
+
+fn with_usage_db<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&Connection) -> Result<T, String>,
+) -> Result<T, String> {
+    let state = app.state::<UsageState>();
+    let mut g = state.conn.lock().unwrap_or_else(|e| e.into_inner());
+    if g.is_none() {
+        *g = Some(open_usage_db(&usage_db_path(app), crate::now_ms())?);
+    }
+    match g.as_ref() {
+        Some(conn) => f(conn),
+        None => Err("使用记录不可用".to_string()),
+    }
+}
+
+fn is_duplicate_activation(last_used_ms: Option<u64>, now_ms: u64) -> bool {
+    last_used_ms.is_some_and(|last| now_ms.saturating_sub(last) < USAGE_DEDUPE_MS)
+}
+
+fn record_usage_in(
+    conn: &Connection,
+    kind: UsageKind,
+    target: &str,
+    now_ms: u64,
+) -> Result<(), String> {
+    let last_used_ms: Option<i64> = conn
+        .query_row(
+            "SELECT last_used_ms FROM usage_totals WHERE kind = ?1 AND target = ?2",
+            params![kind.as_str(), target],
+            |row| row.get(0),
+        )
+        .ok();
+    if is_duplicate_activation(last_used_ms.map(|v| v.max(0) as u64), now_ms) {
+        return Ok(());
+    }
+    let now = now_ms as i64;
+    conn.execute(
+        "INSERT INTO usage_totals (kind, target, count, first_used_ms, last_used_ms)
+         VALUES (?1, ?2, 1, ?3, ?3)
+         ON CONFLICT (kind, target) DO UPDATE SET count = count + 1, last_used_ms = ?3",
+        params![kind.as_str(), target, now],
+    )
+    .and_then(|_| {
+        conn.execute(
+            "INSERT INTO usage_events (kind, target, used_at_ms) VALUES (?1, ?2, ?3)",
+            params![kind.as_str(), target, now],
+        )
+    })
+    .map(|_| ())
+    .map_err(|e| format!("写入使用记录失败: {e}"))
+}
+
+/// 记录一次激活；失败只打日志，不影响激活本身。
+pub(crate) fn record_usage(app: &AppHandle, kind: UsageKind, target: &str) {
+    let target = target.trim();
+    if target.is_empty() {
+        return;
+    }
+    match with_usage_db(app, |conn| {
+        record_usage_in(conn, kind, target, crate::now_ms())
+    }) {
+        Ok(()) => crate::command_index::invalidate_frecency(app),
+        Err(e) => eprintln!("[usage] {e}"),
+    }
+}
+
+fn recency_weight(age_ms: u64) -> u64 {
+    match age_ms / DAY_MS {
+        0..=3 => 100,
+        4..=14 => 70,
+        15..=31 => 50,
+        32..=90 => 30,
+        _ => 10,
+    }
+}
+
+/// `recent` 为最近的若干次激活时间（新到旧）。
+fn frecency_score(count: u64, recent: &[u64], now_ms: u64) -> u32 {
+    let sample = &recent[..recent.len().min(FRECENCY_SAMPLE)];
+    if sample.is_empty() {
+        return 0;
+    }
+    let weights: u64 = sample
+        .iter()
+        .map(|used_at| recency_weight(now_ms.saturating_sub(*used_at)))
+        .sum();
+    let score = count.max(sample.len() as u64) * weights / sample.len() as u64;
+    u32::try_from(score).unwrap_or(u32::MAX)
+}
+
+/// events 需按时间新到旧排列。
+fn aggregate_stats(
+    totals: Vec<UsageTotal>,
+    events: &[(UsageKind, String, u64)],
+    now_ms: u64,
+) -> Vec<UsageStat> {
+    let mut recent: HashMap<(UsageKind, &str), Vec<u64>> = HashMap::new();
+    for (kind, target, used_at) in events {
+        recent
+            .entry((*kind, target.as_str()))
+            .or_default()
+            .push(*used_at);
+    }
+
+    totals
+        .into_iter()
+        .map(|total| {
+            let used = recent
+                .get(&(total.kind, total.target.as_str()))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let count_since = |days: u64| {
+                let cutoff = now_ms.saturating_sub(days * DAY_MS);
+                used.iter().filter(|used_at| **used_at >= cutoff).count() as u64
+            };
+            UsageStat {
+                kind: total.kind,
+                count_7d: count_since(7),
+                count_30d: count_since(30),
+                frecency: frecency_score(total.count, used, now_ms),
+                target: total.target,
+                count: total.count,
+                first_used_ms: total.first_used_ms,
+                last_used_ms: total.last_used_ms,
+            }
+        })
+        .collect()
+}
+
+fn load_stats(
+    conn: &Connection,
+    kind: Option<UsageKind>,
+    now_ms: u64,
+) -> Result<Vec<UsageStat>, String> {
+    let kind_filter = kind.map(UsageKind::as_str);
+    let mut stmt = conn
+        .prepare(
+            "SELECT kind, target, count, first_used_ms, last_used_ms FROM usage_totals
+             WHERE ?1 IS NULL OR kind = ?1",
+        )
+        .map_err(|e| format!("读取使用记录失败: {e}"))?;
+    let totals: Vec<UsageTotal> = stmt
+        .query_map(params![kind_filter], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })
+        .map_err(|e| format!("读取使用记录失败: {e}"))?
+        .filter_map(Result::ok)
+        .filter_map(|(kind, target, count, first, last)| {
+            Some(UsageTotal {
+                kind: UsageKind::parse(&kind)?,
+                target,
+                count: count.max(0) as u64,
+                first_used_ms: first.max(0) as u64,
+                last_used_ms: last.max(0) as u64,
+            })
+        })
+        .collect();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT kind, target, used_at_ms FROM usage_events
+             WHERE ?1 IS NULL OR kind = ?1
+             ORDER BY used_at_ms DESC",
+        )
+        .map_err(|e| format!("读取使用记录失败: {e}"))?;
+    let events: Vec<(UsageKind, String, u64)> = stmt
+        .query_map(params![kind_filter], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|e| format!("读取使用记录失败: {e}"))?
+        .filter_map(Result::ok)
+        .filter_map(|(kind, target, used_at)| {
+            Some((UsageKind::parse(&kind)?, target, used_at.max(0) as u64))
+        })
+        .collect();
+
+    Ok(aggregate_stats(totals, &events, now_ms))
+}
+
+/// 各目标的 frecency（没有记录的不在表里）；命令索引排序用。
+pub(crate) fn frecency_scores(app: &AppHandle) -> HashMap<(UsageKind, String), u32> {
+    match with_usage_db(app, |conn| load_stats(conn, None, crate::now_ms())) {
+        Ok(stats) => stats
+            .into_iter()
+            .filter(|stat| stat.frecency > 0)
+            .map(|stat| ((stat.kind, stat.target), stat.frecency))
+            .collect(),
+        Err(e) => {
+            eprintln!("[usage] {e}");
+            HashMap::new()
+        }
+    }
+}
+
+fn parse_kind_filter(kind: Option<String>) -> Result<Option<UsageKind>, String> {
+    match kind
+        .as_deref()
+        .map(str::trim)
+        .filter(|kind| !kind.is_empty())
+    {
+        None => Ok(None),
+        Some(kind) => UsageKind::parse(kind)
+            .map(Some)
+            .ok_or_else(|| format!("使用记录类型不合法: {kind}")),
+    }
+}
+
+/// 使用统计；可按类型 / 目标过滤，按最近使用时间排序。
+#[tauri::command]
+pub(crate) fn usage_stats(
+    app: AppHandle,
+    kind: Option<String>,
+    target: Option<String>,
+) -> Result<Vec<UsageStat>, String> {
+    let kind = parse_kind_filter(kind)?;
+    let target = target
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    let mut stats = with_usage_db(&app, |conn| load_stats(conn, kind, crate::now_ms()))?;
+    if let Some(target) = target {
+        stats.retain(|stat| stat.target == target);
+    }
+    stats.sort_by(|a, b| b.last_used_ms.cmp(&a.last_used_ms));
+    Ok(stats)
+}
+
+/// 最常用的目标，按 frecency 排序。
+#[tauri::command]
+pub(crate) fn usage_top(
+    app: AppHandle,
+    kind: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<UsageStat>, String> {
+    let kind = parse_kind_filter(kind)?;
+    let limit = limit
+        .unwrap_or(USAGE_TOP_DEFAULT_LIMIT)
+        .clamp(1, USAGE_TOP_MAX_LIMIT);
+    let mut stats = with_usage_db(&app, |conn| load_stats(conn, kind, crate::now_ms()))?;
+    stats.sort_by(|a, b| {
+        b.frecency
+            .cmp(&a.frecency)
+            .then_with(|| b.last_used_ms.cmp(&a.last_used_ms))
+    });
+    stats.truncate(limit);
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(kind: UsageKind, target: &str, count: u64, last_used_ms: u64) -> UsageTotal {
+        UsageTotal {
+            kind,
+            target: target.to_string(),
+            count,
+            first_used_ms: 0,
+            last_used_ms,
+        }
+    }
+
+    #[test]
+    fn frecency_prefers_recent_and_frequent_use() {
+        let now = 365 * DAY_MS;
+        let recent = frecency_score(3, &[now, now - DAY_MS, now - 2 * DAY_MS], now);
+        let stale = frecency_score(3, &[now - 100 * DAY_MS; 3], now);
+        let frequent = frecency_score(30, &[now; 10], now);
+
+        assert_eq!(recent, 300);
+        assert_eq!(stale, 30);
+        assert!(frequent > recent);
+        assert_eq!(frecency_score(5, &[], now), 0);
+    }
+
+    #[test]
+    fn aggregate_counts_windows_per_target() {
+        let now = 365 * DAY_MS;
+        let events = vec![
+            (UsageKind::Plugin, "memo".to_string(), now),
+            (UsageKind::App, "memo".to_string(), now - DAY_MS),
+            (UsageKind::Plugin, "memo".to_string(), now - 10 * DAY_MS),
+            (UsageKind::Plugin, "memo".to_string(), now - 60 * DAY_MS),
+        ];
+        let stats = aggregate_stats(
+            vec![
+                total(UsageKind::Plugin, "memo", 8, now),
+                total(UsageKind::App, "memo", 1, now - DAY_MS),
+                total(UsageKind::Plugin, "old", 2, now - 300 * DAY_MS),
+            ],
+            &events,
+            now,
+        );
+
+        assert_eq!((stats[0].count_7d, stats[0].count_30d), (1, 2));
+        assert_eq!((stats[1].count_7d, stats[1].count_30d), (1, 1));
+        assert_eq!(stats[2].frecency, 0);
+        assert!(stats[0].frecency > stats[1].frecency);
+    }
+
+    #[test]
+    fn repeated_activation_is_deduplicated() {
+        assert!(!is_duplicate_activation(None, 10_000));
+        assert!(is_duplicate_activation(Some(9_000), 10_000));
+        assert!(!is_duplicate_activation(Some(7_000), 10_000));
+    }
+
+    #[test]
+    fn kind_round_trips() {
+        for kind in [
+            UsageKind::HostAction,
+            UsageKind::Plugin,
+            UsageKind::App,
+            UsageKind::AppCommand,
+            UsageKind::Capability,
+        ] {
+            assert_eq!(UsageKind::parse(kind.as_str()), Some(kind));
+        }
+        assert!(parse_kind_filter(Some("nope".to_string())).is_err());
+        assert_eq!(parse_kind_filter(Some(" ".to_string())), Ok(None));
+    }
+}
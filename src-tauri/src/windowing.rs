@@ -4,7 +4,8 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use tauri::Manager;
-use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::hotkeys::HotkeySequence;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -52,6 +53,13 @@ pub(crate) struct BrowserWindowState {
     pub(crate) closing: Mutex<bool>,
     pub(crate) last_bounds: Mutex<Option<(tauri::PhysicalPosition<i32>, tauri::PhysicalSize<u32>)>>,
     pub(crate) save_seq: AtomicU64,
+    pub(crate) mini: Mutex<bool>,
+    /// 迷你窗口（仅内容窗口）的位置与尺寸
+    pub(crate) mini_bounds: Mutex<Option<(tauri::PhysicalPosition<i32>, tauri::PhysicalSize<u32>)>>,
+    /// 进入迷你模式前的浏览栈边界，退出时还原
+    pub(crate) mini_restore_bounds:
+        Mutex<Option<(tauri::PhysicalPosition<i32>, tauri::PhysicalSize<u32>)>>,
+    pub(crate) mini_snap_seq: AtomicU64,
 }
 
 impl Default for BrowserWindowState {
@@ -70,6 +78,10 @@ impl Default for BrowserWindowState {
             closing: Mutex::new(false),
             last_bounds: Mutex::new(None),
             save_seq: AtomicU64::new(0),
+            mini: Mutex::new(false),
+            mini_bounds: Mutex::new(None),
+            mini_restore_bounds: Mutex::new(None),
+            mini_snap_seq: AtomicU64::new(0),
         }
     }
 }
@@ -78,16 +90,17 @@ impl Default for BrowserWindowState {
 #[serde(rename_all = "camelCase")]
 pub(crate) struct ActivatePluginPayload {
     pub(crate) plugin_id: String,
+    /// 插件快捷键等触发的命令 ID；普通激活为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) command: Option<String>,
 }
 
 pub(crate) struct WakeShortcutState {
-    pub(crate) current: Mutex<Shortcut>,
-    pub(crate) paused: Mutex<bool>,
+    pub(crate) current: Mutex<HotkeySequence>,
 }
 
 pub(crate) struct MainWindowModeShortcutState {
-    pub(crate) current: Mutex<Option<Shortcut>>,
-    pub(crate) paused: Mutex<bool>,
+    pub(crate) current: Mutex<Option<HotkeySequence>>,
 }
 
 pub(crate) trait Boundsable {
@@ -380,15 +393,116 @@ pub(crate) fn schedule_persist_browser_window_bounds(app: &tauri::AppHandle) {
     });
 }
 
+pub(crate) fn load_browser_mini_bounds_from_config(
+    app: &tauri::AppHandle,
+) -> Option<(tauri::PhysicalPosition<i32>, tauri::PhysicalSize<u32>)> {
+    let map = crate::read_app_config_map(app);
+    let raw = map.get(crate::BROWSER_MINI_BOUNDS_KEY)?.clone();
+    let parsed = serde_json::from_value::<PersistedWindowBounds>(raw).ok()?;
+
+    if parsed.x <= -9000 || parsed.y <= -9000 {
+        return None;
+    }
+    if parsed.width < 160 || parsed.height < 90 {
+        return None;
+    }
+    if parsed.width > 20000 || parsed.height > 20000 {
+        return None;
+    }
+
+    Some((
+        tauri::PhysicalPosition::new(parsed.x, parsed.y),
+        tauri::PhysicalSize::new(parsed.width, parsed.height),
+    ))
+}
+
+pub(crate) fn persist_browser_mini_bounds(app: &tauri::AppHandle, state: &BrowserWindowState) {
+    let saved = state.mini_bounds.lock().ok().and_then(|g| g.clone());
+    let Some((pos, size)) = saved else {
+        return;
+    };
+
+    let bounds = PersistedWindowBounds {
+        x: pos.x,
+        y: pos.y,
+        width: size.width,
+        height: size.height,
+    };
+
+    let Ok(v) = serde_json::to_value(bounds) else {
+        return;
+    };
+    if let Err(e) = crate::update_app_config_map(app, |map| {
+        map.insert(crate::BROWSER_MINI_BOUNDS_KEY.to_string(), v);
+        Ok(())
+    }) {
+        eprintln!("[config] failed to persist browser mini bounds: {e}");
+    }
+}
+
+/// 窗口是否至少有一部分落在某块屏幕的工作区内。
+pub(crate) fn bounds_on_any_monitor(
+    window: &tauri::WebviewWindow,
+    pos: tauri::PhysicalPosition<i32>,
+    size: tauri::PhysicalSize<u32>,
+) -> bool {
+    window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .any(|m| {
+            let wa = *m.work_area();
+            rect_intersects(pos, size, wa.position, wa.size)
+        })
+}
+
+/// 离工作区某条边不到 threshold 就贴到该边（留 margin）；两条边都贴近即落在角上。
+/// 结果总会被夹回工作区内。
+pub(crate) fn snap_rect_to_work_area(
+    pos: tauri::PhysicalPosition<i32>,
+    size: tauri::PhysicalSize<u32>,
+    wa_pos: tauri::PhysicalPosition<i32>,
+    wa_size: tauri::PhysicalSize<u32>,
+    threshold: i32,
+    margin: i32,
+) -> tauri::PhysicalPosition<i32> {
+    let (w, h) = (size.width as i32, size.height as i32);
+    let (left, top) = (wa_pos.x, wa_pos.y);
+    let right = wa_pos.x + wa_size.width as i32;
+    let bottom = wa_pos.y + wa_size.height as i32;
+
+    let mut x = pos.x;
+    if x - left < threshold {
+        x = left + margin;
+    } else if right - (x + w) < threshold {
+        x = right - w - margin;
+    }
+    let mut y = pos.y;
+    if y - top < threshold {
+        y = top + margin;
+    } else if bottom - (y + h) < threshold {
+        y = bottom - h - margin;
+    }
+
+    tauri::PhysicalPosition::new(
+        clamp_i32(x, left, (right - w).max(left)),
+        clamp_i32(y, top, (bottom - h).max(top)),
+    )
+}
+
 pub(crate) fn save_browser_stack_bounds_if_valid(app: &tauri::AppHandle) {
     let state = app.state::<BrowserWindowState>();
     if state.fullscreen.lock().ok().map(|g| *g).unwrap_or(false) {
         return;
     }
+    // 迷你模式下内容窗口是小窗，不能当成浏览栈的尺寸记下来
+    if state.mini.lock().ok().map(|g| *g).unwrap_or(false) {
+        return;
+    }
 
     let (Some(bar), Some(content)) = (
         app.get_webview_window(crate::BROWSER_BAR_WINDOW_LABEL),
-        app.get_webview_window(crate::BROWSER_WINDOW_LABEL),
+        crate::browser_tabs::browser_content_window(app),
     ) else {
         return;
     };
@@ -501,3 +615,32 @@ pub(crate) fn restore_browser_stack_bounds_or_center(
     ));
     let _ = content.set_size(tauri::PhysicalSize::new(next_total.width, content_h));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snap(x: i32, y: i32) -> (i32, i32) {
+        let p = snap_rect_to_work_area(
+            tauri::PhysicalPosition::new(x, y),
+            tauri::PhysicalSize::new(400, 225),
+            tauri::PhysicalPosition::new(0, 0),
+            tauri::PhysicalSize::new(1920, 1040),
+            48,
+            16,
+        );
+        (p.x, p.y)
+    }
+
+    #[test]
+    fn snap_rect_to_work_area_snaps_near_edges_into_corners() {
+        // 右下角附近 -> 吸附到右下角
+        assert_eq!(snap(1500, 800), (1504, 799));
+        // 只贴近左边 -> 只吸附水平方向
+        assert_eq!(snap(10, 400), (16, 400));
+        // 远离所有边 -> 不动
+        assert_eq!(snap(600, 400), (600, 400));
+        // 拖出屏幕 -> 夹回工作区
+        assert_eq!(snap(-300, 2000), (16, 799));
+    }
+}
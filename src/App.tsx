@@ -53,8 +53,9 @@ import {
   buildRegisteredAppListItems,
   parseRegisteredAppListItemId,
   registeredAppFromListItem,
+  registeredAppShortcuts,
 } from './apps/listItems'
-import type { AppRegistrationEditRequest, AppStatus, RegisteredApp, RegisteredAppCapabilitySelection, RegisteredAppShortcut } from './apps/types'
+import type { AppRegistrationEditRequest, AppRuntimeCommand, AppRuntimeStatus, AppStatus, RegisteredApp, RegisteredAppCapabilitySelection, RegisteredAppShortcut } from './apps/types'
 import { usePlugins } from './usePlugins'
 import { useWallpaper } from './useWallpaper'
 import { useSearch } from './useSearch'
@@ -68,6 +69,28 @@ import { HostAppearanceProvider, useHostAppearanceValue } from './components/hos
 
 type HostPageId = 'settings' | 'store' | 'appBackground' | 'capabilityRegistry'
 
+// 宿主页面作为搜索结果出现（ID 与宿主命令索引一致）
+const HOST_ACTION_ITEM_PREFIX = 'host:'
+const HOST_ACTION_ITEMS: Plugin[] = [
+  { page: 'settings', name: '设置', icon: '⚙️', keyword: 'settings' },
+  { page: 'store', name: '插件商店', icon: '🛒', keyword: 'store' },
+  { page: 'capabilityRegistry', name: '能力注册表', icon: '🧩', keyword: 'capability' },
+].map(action => ({
+  id: `${HOST_ACTION_ITEM_PREFIX}${action.page}`,
+  name: action.name,
+  description: '宿主页面',
+  icon: action.icon,
+  keyword: action.keyword,
+  disabled: false,
+  component: () => null,
+}))
+
+function hostPageFromListItemId(itemId: string): HostPageId | null {
+  if (!itemId.startsWith(HOST_ACTION_ITEM_PREFIX)) return null
+  const page = itemId.slice(HOST_ACTION_ITEM_PREFIX.length)
+  return page === 'settings' || page === 'store' || page === 'capabilityRegistry' ? page : null
+}
+
 type AppTerminalMenuCommand = {
   id: string
   label: string
@@ -111,7 +134,7 @@ const QUICK_BAR_APP_ID = 'quick-bar'
 
 async function launchOptionsForApp(app: RegisteredApp): Promise<AppLaunchOptions | undefined> {
   if (app.id !== QUICK_BAR_APP_ID) return undefined
-  return { extraEnvs: await getAppCapabilityEnvVars() }
+  return { extraEnvs: await getAppCapabilityEnvVars(app.id) }
 }
 
 function App() {
@@ -166,7 +189,7 @@ function App() {
   }, [registeredAppPlugins, plugins])
 
   // Search
-  const search = useSearch(homeItems)
+  const search = useSearch(homeItems, HOST_ACTION_ITEMS)
   const { query, setQuery, filtered: displayItems } = search
 
   // Active plugin
@@ -289,6 +312,28 @@ function App() {
     }
   }, [loadRegisteredApps])
 
+  // 应用经 stdout 上报的徽标/忙碌状态和宿主快捷命令即时生效，不等下一轮状态轮询
+  useEffect(() => {
+    const unlisteners: UnlistenFn[] = []
+    let disposed = false
+    const keep = (fn: UnlistenFn) => {
+      if (disposed) fn()
+      else unlisteners.push(fn)
+    }
+    void listen<{ appId: string; status: AppRuntimeStatus }>('fast-window:app-runtime-status', event => {
+      const { appId, status } = event.payload
+      setRegisteredAppStatuses(prev => prev[appId] ? { ...prev, [appId]: { ...prev[appId], runtimeStatus: status } } : prev)
+    }).then(keep).catch(() => {})
+    void listen<{ appId: string; commands: AppRuntimeCommand[] }>('fast-window:app-runtime-commands', event => {
+      const { appId, commands } = event.payload
+      setRegisteredAppStatuses(prev => prev[appId] ? { ...prev, [appId]: { ...prev[appId], runtimeCommands: commands } } : prev)
+    }).then(keep).catch(() => {})
+    return () => {
+      disposed = true
+      for (const unlisten of unlisteners) unlisten()
+    }
+  }, [])
+
   const refreshRegisteredAppStatuses = useCallback(async () => {
     if (!registeredApps.length) {
       setRegisteredAppStatuses({})
@@ -349,6 +394,24 @@ function App() {
     }
   }, [allPlugins, allPluginsRef])
 
+  // Plugin global hotkeys (manifest.hotkeys of enabled plugins)
+  const pluginHotkeysKey = useMemo(() => {
+    const enabled = allPlugins.filter(p => !p.disabled)
+    return JSON.stringify({
+      declared: enabled.flatMap(p => (p.manifest?.hotkeys || []).map(h => ({ pluginId: p.id, id: h.id, hotkey: h.hotkey }))),
+      pluginIds: enabled.map(p => p.id),
+    })
+  }, [allPlugins])
+
+  useEffect(() => {
+    if (loading) return
+    void invoke<string[]>('plugin_shortcuts_sync', JSON.parse(pluginHotkeysKey))
+      .then(errors => {
+        if (Array.isArray(errors) && errors.length) showToast(`部分插件快捷键未生效：${errors.join('；')}`)
+      })
+      .catch(e => console.warn('[plugin] hotkey sync failed:', e))
+  }, [loading, pluginHotkeysKey, showToast])
+
   // Activate plugin (from Tauri event / DOM event)
   useEffect(() => {
     let unlisten: UnlistenFn | null = null
@@ -443,6 +506,13 @@ function App() {
   }, [query, refreshRegisteredAppStatuses, showToast])
 
   const activateListItem = useCallback((plugin: Plugin) => {
+    void invoke('command_index_record_use', { id: plugin.id }).catch(() => {})
+    const hostPage = hostPageFromListItemId(plugin.id)
+    if (hostPage) {
+      setActivePlugin(null)
+      setActiveHostPage(hostPage)
+      return
+    }
     const selection = parseRegisteredAppListItemId(plugin.id)
     if (selection.type === 'appShortcut') {
       const app = registeredApps.find(app => app.id === selection.appId)
@@ -451,7 +521,7 @@ function App() {
           showToast(`开发命令运行中，暂不打开：${app.name}`)
           return
         }
-        const shortcut = app.commands.find(command => command.id === selection.shortcutId)
+        const shortcut = registeredAppShortcuts(app, registeredAppStatuses[app.id]).find(command => command.id === selection.shortcutId)
         if (!shortcut) {
           showToast(`快捷入口不存在：${selection.shortcutId}`)
           return
@@ -497,7 +567,7 @@ function App() {
     }
     setActiveHostPage(null)
     setActivePlugin(plugin)
-  }, [activateRegisteredAppCapability, activateRegisteredAppShortcut, appCapabilitySelections, appDevCommandRuns, registeredApps, refreshRegisteredAppStatuses, showToast])
+  }, [activateRegisteredAppCapability, activateRegisteredAppShortcut, appCapabilitySelections, appDevCommandRuns, registeredAppStatuses, registeredApps, refreshRegisteredAppStatuses, showToast])
 
   const registeredAppFromMenuItem = useCallback((plugin: Plugin): RegisteredApp | null => {
     return registeredAppFromListItem(registeredApps, plugin.id)
@@ -509,12 +579,18 @@ function App() {
     updateShortcut: (shortcut: RegisteredAppShortcut) => RegisteredAppShortcut,
   ) => {
     const shortcutExists = app.commands.some(shortcut => shortcut.id === shortcutId)
-    if (!shortcutExists) return false
+    if (!shortcutExists) {
+      // 应用运行中新声明、还没保存的入口：改图标或快捷键时顺带保存下来
+      const declared = registeredAppShortcuts(app, registeredAppStatuses[app.id]).find(shortcut => shortcut.id === shortcutId)
+      if (!declared) return false
+      await updateRegisteredApp(app.id, { commands: [...app.commands, updateShortcut(declared)] })
+      return true
+    }
     await updateRegisteredApp(app.id, {
       commands: app.commands.map(shortcut => shortcut.id === shortcutId ? updateShortcut(shortcut) : shortcut),
     })
     return true
-  }, [updateRegisteredApp])
+  }, [registeredAppStatuses, updateRegisteredApp])
 
   const appDetail = useMemo(() => {
     if (!appDetailId) return null
@@ -739,7 +815,7 @@ function App() {
       ? { id: 'detail', label: '详情', onSelect: () => setAppDetailId(app.id) }
       : { id: 'detail', label: '详情', onSelect: () => setPluginDetail(plugin) }
     const selectedShortcut = app && selection.type === 'appShortcut'
-      ? app.commands.find(shortcut => shortcut.id === selection.shortcutId)
+      ? registeredAppShortcuts(app, registeredAppStatuses[app.id]).find(shortcut => shortcut.id === selection.shortcutId)
       : null
     const selectedCapability = selection.type === 'appCapability'
       ? appCapabilitySelections.find(item => item.appId === selection.appId && item.capabilityId === selection.capabilityId) ?? null
@@ -832,7 +908,7 @@ function App() {
       },
       ...commonActions,
     ]
-  }, [appCapabilitySelections, appDevCommandRuns, changeMenuItemIcon, loading, openRegisteredAppFolderFromMenu, pluginMenu?.plugin, refreshingId, refreshPlugin, registeredAppFromMenuItem, releaseRegisteredAppFromMenu, removeRegisteredAppCapabilityFromMenu, removeRegisteredAppShortcutFromMenu, requestAppRegistrationEdit, requestPluginUninstall, resetMenuItemIcon, restartRegisteredAppFromMenu, stageRegisteredAppDevFromMenu, uninstallingPluginId])
+  }, [appCapabilitySelections, appDevCommandRuns, changeMenuItemIcon, loading, openRegisteredAppFolderFromMenu, pluginMenu?.plugin, refreshingId, refreshPlugin, registeredAppFromMenuItem, registeredAppStatuses, releaseRegisteredAppFromMenu, removeRegisteredAppCapabilityFromMenu, removeRegisteredAppShortcutFromMenu, requestAppRegistrationEdit, requestPluginUninstall, resetMenuItemIcon, restartRegisteredAppFromMenu, stageRegisteredAppDevFromMenu, uninstallingPluginId])
 
   const handleShellKeyDown = useCallback((e: React.KeyboardEvent) => {
     if (e.key === 'Escape') {
@@ -857,7 +933,7 @@ function App() {
   const handleContextMenu = useCallback((e: React.MouseEvent, plugin: Plugin) => {
     e.preventDefault()
     e.stopPropagation()
-    if (reorderMode) return
+    if (reorderMode || hostPageFromListItemId(plugin.id)) return
     setPluginMenu({ plugin, mouseX: e.clientX, mouseY: e.clientY })
   }, [reorderMode])
 
@@ -52,6 +52,9 @@ export function PluginCardContent(props: { plugin: Plugin; layout: PluginBrowseL
   const { plugin, layout } = props
   const isRegisteredAppRunning = plugin.appStatus?.type === 'registered-app' && plugin.appStatus.running
   const isDevCommandRunning = plugin.appStatus?.type === 'registered-app' && plugin.appStatus.devCommandRunning === true
+  const isAppBusy = isRegisteredAppRunning && plugin.appStatus?.busy === true
+  const appBadge = isRegisteredAppRunning ? plugin.appStatus?.badge : undefined
+  const appStatusText = plugin.appStatus?.statusText
   const avatarWithBadges = (
     avatar: React.ReactNode,
     size: number,
@@ -75,9 +78,50 @@ export function PluginCardContent(props: { plugin: Plugin; layout: PluginBrowseL
           })}
         />
       ) : null}
-      {isRegisteredAppRunning ? (
+      {isAppBusy && !isDevCommandRunning ? (
         <Box
-          title="运行中"
+          title={appStatusText || '忙碌中'}
+          aria-label={appStatusText || '忙碌中'}
+          sx={theme => ({
+            ...devCommandSpinKeyframes,
+            position: 'absolute',
+            inset: -4,
+            borderRadius: '999px',
+            border: `2px solid ${theme.palette.warning.main}`,
+            borderTopColor: 'transparent',
+            animation: 'fastWindowDevCommandSpin 1200ms linear infinite',
+            pointerEvents: 'none',
+          })}
+        />
+      ) : null}
+      {appBadge ? (
+        <Box
+          title={appStatusText || appBadge}
+          aria-label={appStatusText || appBadge}
+          sx={theme => ({
+            position: 'absolute',
+            right: -6,
+            top: -6,
+            minWidth: 16,
+            height: 16,
+            px: 0.5,
+            borderRadius: '999px',
+            display: 'grid',
+            placeItems: 'center',
+            fontSize: 10,
+            fontWeight: 700,
+            lineHeight: 1,
+            whiteSpace: 'nowrap',
+            color: theme.palette.common.white,
+            bgcolor: theme.palette.error.main,
+            boxShadow: `0 0 0 2px ${theme.palette.background.paper}`,
+          })}
+        >
+          {appBadge}
+        </Box>
+      ) : isRegisteredAppRunning ? (
+        <Box
+          title={appStatusText || '运行中'}
           aria-label="运行中"
           sx={theme => ({
             position: 'absolute',
@@ -1,9 +1,11 @@
+import { useEffect, useRef, useState } from 'react'
+import { Channel, invoke } from '@tauri-apps/api/core'
 import {
   Avatar, Box, Button, Chip, Dialog, DialogActions, DialogContent,
   DialogTitle, IconButton, Typography,
 } from '@mui/material'
 import CloseRoundedIcon from '@mui/icons-material/CloseRounded'
-import type { AppStatus, RegisteredApp, RegisteredAppShortcut } from './types'
+import type { AppCrashRecord, AppStatus, RegisteredApp, RegisteredAppShortcut } from './types'
 import { isDataImageUrl } from '../utils'
 import { hostButtonSx, hostSoftChipSx } from '../components/hostUiStyles'
 
@@ -28,6 +30,27 @@ const hotkeyLaunchBehaviorLabels: Record<NonNullable<RegisteredApp['hotkeyLaunch
   runningOnly: '仅控制已运行应用',
 }
 
+const restartPolicyLabels: Record<NonNullable<RegisteredApp['supervision']>['restart'], string> = {
+  never: '不重启',
+  onFailure: '异常退出时重启',
+  always: '退出后总是重启',
+}
+
+const crashOutcomeLabels: Record<AppCrashRecord['outcome'], string> = {
+  notRestarted: '未重启',
+  restarting: '等待重启',
+  restarted: '已重启',
+  restartFailed: '重启失败',
+  gaveUp: '重启次数用完',
+}
+
+function crashText(crash: AppCrashRecord): string {
+  const time = new Date(crash.at).toLocaleString()
+  const code = crash.exitCode !== undefined ? `退出码 ${crash.exitCode}` : '被强制结束'
+  const error = crash.error ? `：${crash.error}` : ''
+  return `${time} · ${code} · ${crashOutcomeLabels[crash.outcome] || crash.outcome}${error}`
+}
+
 function formatDuration(ms: number): string {
   const totalSeconds = Math.max(0, Math.floor(ms / 1000))
   if (totalSeconds < 60) return `${totalSeconds} 秒`
@@ -44,6 +67,13 @@ function formatDuration(ms: number): string {
   return restHours ? `${days} 天 ${restHours} 小时` : `${days} 天`
 }
 
+function healthText(status: AppStatus): string {
+  const state = status.unresponsive ? '无响应' : '正常'
+  const latency = status.pingLatencyMs !== undefined ? ` · 往返 ${status.pingLatencyMs}ms` : ''
+  const last = status.lastHealthyAt ? ` · 最近响应 ${new Date(status.lastHealthyAt).toLocaleTimeString()}` : ''
+  return `${state}${latency}${last}`
+}
+
 function runningText(status?: AppStatus): string {
   if (!status?.running) return '未运行'
   if (!status.startedAt) return '运行中'
@@ -75,6 +105,73 @@ function HostShortcutList({ shortcuts }: { shortcuts?: RegisteredAppShortcut[] }
   )
 }
 
+type AppLogTailEvent =
+  | { type: 'lines'; lines: string[] }
+  | { type: 'end'; canceled: boolean }
+  | { type: 'error'; message: string }
+
+const LOG_VIEW_MAX_LINES = 500
+
+function AppLogView({ appId }: { appId: string }) {
+  const [lines, setLines] = useState<string[]>([])
+  const [error, setError] = useState('')
+  const boxRef = useRef<HTMLDivElement | null>(null)
+
+  useEffect(() => {
+    let disposed = false
+    let streamId = ''
+    setLines([])
+    setError('')
+    const channel = new Channel<AppLogTailEvent>(ev => {
+      if (disposed) return
+      if (ev.type === 'lines') setLines(prev => [...prev, ...ev.lines].slice(-LOG_VIEW_MAX_LINES))
+      else if (ev.type === 'error') setError(ev.message)
+    })
+    void invoke<string>('app_logs_tail', { appId, lines: 200, follow: true, channel })
+      .then(id => {
+        streamId = id
+        if (disposed) void invoke('app_logs_tail_cancel', { streamId: id }).catch(() => {})
+      })
+      .catch(e => {
+        if (!disposed) setError(String(e?.message || e || '读取日志失败'))
+      })
+    return () => {
+      disposed = true
+      if (streamId) void invoke('app_logs_tail_cancel', { streamId }).catch(() => {})
+    }
+  }, [appId])
+
+  useEffect(() => {
+    const el = boxRef.current
+    if (el) el.scrollTop = el.scrollHeight
+  }, [lines])
+
+  return (
+    <Box
+      ref={boxRef}
+      sx={{
+        ...valueSx,
+        fontSize: 12,
+        maxHeight: 220,
+        overflow: 'auto',
+        whiteSpace: 'pre-wrap',
+        wordBreak: 'break-all',
+        p: 1,
+        borderRadius: 1,
+        bgcolor: 'action.hover',
+      }}
+    >
+      {error ? (
+        <Typography sx={{ ...valueSx, fontSize: 12 }} color="error.main">{error}</Typography>
+      ) : lines.length ? (
+        lines.join('\n')
+      ) : (
+        <Typography sx={{ ...valueSx, fontSize: 12 }} color="text.secondary">(暂无日志)</Typography>
+      )}
+    </Box>
+  )
+}
+
 export default function AppDetailDialog({ app, status, onClose }: AppDetailDialogProps) {
   const iconAsImage = app?.icon && isDataImageUrl(app.icon) ? app.icon : undefined
 
@@ -143,6 +240,14 @@ export default function AppDetailDialog({ app, status, onClose }: AppDetailDialo
               <Typography sx={labelSx}>FW 自启</Typography>
               <Typography sx={valueSx}>{app.autoStart ? '开启' : '关闭'}</Typography>
             </Box>
+            <Box sx={fieldRowSx}>
+              <Typography sx={labelSx}>崩溃重启</Typography>
+              <Typography sx={valueSx}>{restartPolicyLabels[app.supervision?.restart ?? 'never']}</Typography>
+            </Box>
+            <Box sx={fieldRowSx}>
+              <Typography sx={labelSx}>无响应重启</Typography>
+              <Typography sx={valueSx}>{app.healthCheck?.restartUnresponsive ? '开启' : '关闭'}</Typography>
+            </Box>
             <Box sx={fieldRowSx}>
               <Typography sx={labelSx}>窗口记忆</Typography>
               <Typography sx={valueSx}>{windowBoundsText(app)}</Typography>
@@ -157,17 +262,56 @@ export default function AppDetailDialog({ app, status, onClose }: AppDetailDialo
                 <Typography sx={valueSx}>{status.pid}</Typography>
               </Box>
             ) : null}
+            {status?.running && (status.unresponsive || status.lastHealthyAt) ? (
+              <Box sx={fieldRowSx}>
+                <Typography sx={labelSx}>健康检查</Typography>
+                <Typography sx={valueSx} color={status.unresponsive ? 'error.main' : undefined}>{healthText(status)}</Typography>
+              </Box>
+            ) : null}
+            {status?.running && status.runtimeStatus ? (
+              <Box sx={fieldRowSx}>
+                <Typography sx={labelSx}>应用状态</Typography>
+                <Typography sx={valueSx}>
+                  {[status.runtimeStatus.busy ? '忙碌中' : '', status.runtimeStatus.badge ? `徽标 ${status.runtimeStatus.badge}` : '', status.runtimeStatus.text || '']
+                    .filter(Boolean)
+                    .join(' · ')}
+                </Typography>
+              </Box>
+            ) : null}
             {status?.exitCode !== undefined ? (
               <Box sx={fieldRowSx}>
                 <Typography sx={labelSx}>最近退出码</Typography>
                 <Typography sx={valueSx}>{status.exitCode}</Typography>
               </Box>
             ) : null}
+            {status?.crashes?.length ? (
+              <Box sx={fieldRowSx}>
+                <Typography sx={labelSx}>崩溃记录</Typography>
+                <Box>
+                  {status.crashes.slice(-5).reverse().map(crash => (
+                    <Typography key={crash.at} sx={valueSx}>{crashText(crash)}</Typography>
+                  ))}
+                </Box>
+              </Box>
+            ) : null}
+            {status?.logPath ? (
+              <Box sx={fieldRowSx}>
+                <Typography sx={labelSx}>日志文件</Typography>
+                <Typography sx={{ ...valueSx, wordBreak: 'break-all' }}>{status.logPath}</Typography>
+              </Box>
+            ) : null}
 
             <Box sx={{ mt: 1.25 }}>
               <Typography sx={{ color: 'text.secondary', fontSize: 13, mb: 0.5 }}>已注册宿主快捷命令</Typography>
               <HostShortcutList shortcuts={app.commands} />
             </Box>
+
+            {app.id ? (
+              <Box sx={{ mt: 1.25 }}>
+                <Typography sx={{ color: 'text.secondary', fontSize: 13, mb: 0.5 }}>运行日志（stdout / stderr）</Typography>
+                <AppLogView appId={app.id} />
+              </Box>
+            ) : null}
           </Box>
         ) : null}
       </DialogContent>
@@ -8,7 +8,7 @@ import {
 import CloseRoundedIcon from '@mui/icons-material/CloseRounded'
 import DeleteRoundedIcon from '@mui/icons-material/DeleteRounded'
 import MoreVertRoundedIcon from '@mui/icons-material/MoreVertRounded'
-import type { AppDisplayMode, AppHotkeyLaunchBehavior, AppRegistrationEditRequest, RegisteredApp, RegisteredAppShortcut, RegisteredAppUpdatePatch } from './types'
+import type { AppDisplayMode, AppHotkeyLaunchBehavior, AppRegistrationEditRequest, AppHealthCheck, AppRestartPolicy, AppSupervision, RegisteredApp, RegisteredAppShortcut, RegisteredAppUpdatePatch } from './types'
 import AppCardView from './AppCardView'
 import AppHostShortcutEditor from './AppHostShortcutEditor'
 import AppIconEditor from './AppIconEditor'
@@ -78,6 +78,8 @@ export default function AppRegistrationPanel({
   const [recordingHostShortcutHotkeyId, setRecordingHostShortcutHotkeyId] = useState<string | null>(null)
   const [displayMode, setDisplayMode] = useState<AppDisplayMode>('default')
   const [autoStart, setAutoStart] = useState(false)
+  const [restartPolicy, setRestartPolicy] = useState<AppRestartPolicy>('never')
+  const [restartUnresponsive, setRestartUnresponsive] = useState(false)
   const [hostShortcuts, setHostShortcuts] = useState<RegisteredAppShortcut[]>([])
   const [hostShortcutsEdited, setHostShortcutsEdited] = useState(false)
   const [saving, setSaving] = useState(false)
@@ -91,6 +93,20 @@ export default function AppRegistrationPanel({
 
   const editingApp = editingId ? apps.find(app => app.id === editingId) ?? null : null
 
+  // 只在界面上切换重启策略；次数上限等其它字段沿用注册表里已有的值
+  const supervisionForSave = (existingApp?: RegisteredApp | null): AppSupervision | undefined => {
+    if (restartPolicy === 'never') return undefined
+    return { ...existingApp?.supervision, restart: restartPolicy }
+  }
+
+  // 只切换无响应时是否重启；检查间隔、超时等沿用注册表里已有的值
+  const healthCheckForSave = (existingApp?: RegisteredApp | null): AppHealthCheck | undefined => {
+    const next: AppHealthCheck = { ...existingApp?.healthCheck }
+    if (restartUnresponsive) next.restartUnresponsive = true
+    else delete next.restartUnresponsive
+    return Object.keys(next).length ? next : undefined
+  }
+
   const closeEditMenu = () => {
     setEditMenuAnchorEl(null)
   }
@@ -117,6 +133,8 @@ export default function AppRegistrationPanel({
     setRecordingHostShortcutHotkeyId(null)
     setDisplayMode('default')
     setAutoStart(false)
+    setRestartPolicy('never')
+    setRestartUnresponsive(false)
     setHostShortcuts([])
     setHostShortcutsEdited(false)
     setPickingPath(false)
@@ -137,6 +155,8 @@ export default function AppRegistrationPanel({
     setRecordingHostShortcutHotkeyId(null)
     setDisplayMode(app.displayMode)
     setAutoStart(app.autoStart)
+    setRestartPolicy(app.supervision?.restart ?? 'never')
+    setRestartUnresponsive(!!app.healthCheck?.restartUnresponsive)
     setHostShortcuts(Array.isArray(app.commands) ? app.commands : [])
     setHostShortcutsEdited(false)
     setPickingPath(false)
@@ -204,6 +224,8 @@ export default function AppRegistrationPanel({
       displayMode,
       commands: normalizedHostShortcuts(),
       autoStart,
+      supervision: supervisionForSave(existingApp),
+      healthCheck: healthCheckForSave(existingApp),
       windowWidth: existingApp?.windowWidth,
       windowHeight: existingApp?.windowHeight,
       windowX: existingApp?.windowX,
@@ -382,6 +404,8 @@ export default function AppRegistrationPanel({
         displayMode,
         commands: hostShortcutsToSave,
         autoStart,
+        supervision: supervisionForSave(existingApp),
+        healthCheck: healthCheckForSave(existingApp),
         windowWidth: existingApp?.windowWidth,
         windowHeight: existingApp?.windowHeight,
         windowX: existingApp?.windowX,
@@ -399,6 +423,8 @@ export default function AppRegistrationPanel({
             hotkeyLaunchBehavior: nextHotkeyLaunchBehavior ?? null,
             displayMode,
             autoStart,
+            supervision: nextApp.supervision ?? null,
+            healthCheck: nextApp.healthCheck ?? null,
             commands: hostShortcutsToSave,
           })
         } else {
@@ -633,6 +659,44 @@ export default function AppRegistrationPanel({
               开启后，Fast Window 启动时会自动启动这个应用；关闭后仍可手动启动或通过快捷键唤醒。
             </Typography>
           </Box>
+          <Box>
+            <Typography variant="caption" color="text.secondary" sx={{ mb: 0.5, display: 'block' }}>崩溃后自动重启</Typography>
+            <ToggleButtonGroup
+              value={restartPolicy}
+              exclusive
+              onChange={(_, v) => v && setRestartPolicy(v)}
+              size="small"
+              disabled={saving}
+              aria-label="崩溃后自动重启"
+              sx={hostToggleGroupSx}
+            >
+              <ToggleButton value="never">不重启</ToggleButton>
+              <ToggleButton value="onFailure">异常退出时</ToggleButton>
+              <ToggleButton value="always">总是</ToggleButton>
+            </ToggleButtonGroup>
+            <Typography variant="caption" color="text.secondary" sx={{ mt: 0.75, display: 'block' }}>
+              手动停止不会触发重启；短时间内反复崩溃会停止自动重启（默认 5 分钟内最多 3 次）。
+            </Typography>
+          </Box>
+          <Box>
+            <Typography variant="caption" color="text.secondary" sx={{ mb: 0.5, display: 'block' }}>无响应时强制重启</Typography>
+            <FormControlLabel
+              sx={{ m: 0 }}
+              control={
+                <Switch
+                  size="small"
+                  checked={restartUnresponsive}
+                  disabled={saving}
+                  onChange={e => setRestartUnresponsive(e.target.checked)}
+                  inputProps={{ 'aria-label': '无响应时强制重启' }}
+                />
+              }
+              label={restartUnresponsive ? '已开启' : '已关闭'}
+            />
+            <Typography variant="caption" color="text.secondary" sx={{ mt: 0.75, display: 'block' }}>
+              宿主默认每 15 秒通过控制通道检查一次应用，连续 3 次没有响应即视为卡死；开启后会强制结束并重新启动，次数上限与崩溃重启共用。
+            </Typography>
+          </Box>
           <AppHostShortcutEditor
             shortcuts={hostShortcuts}
             appIcon={icon}
@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core'
+import { Channel, invoke } from '@tauri-apps/api/core'
 import type { AppLaunchOptions } from './appLauncher'
 import type { AppCapabilityConfigField, AppCapabilityOption, AppCapabilityDescriptor, RegisteredApp } from './types'
 
@@ -7,6 +7,8 @@ export type AppCapabilityInvokeHostResponse = {
   capabilityId: string
   response: unknown
   text: string
+  /** 声明了 outputSchema 的能力返回的结构化结果 */
+  result?: unknown
 }
 
 type AppCapabilityOptionsHostResponse = {
@@ -46,8 +48,87 @@ export async function invokeAppCapability(request: AppCapabilityRequest): Promis
   return invoke<AppCapabilityInvokeHostResponse>('app_capability_invoke', { request })
 }
 
-export async function getAppCapabilityEnvVars(): Promise<Array<[string, string]>> {
-  return invoke<Array<[string, string]>>('app_capability_env_vars')
+export type AppCapabilityStreamEvent =
+  | { type: 'chunk'; text: string }
+  | { type: 'done'; result: AppCapabilityInvokeHostResponse }
+  | { type: 'error'; message: string }
+  | { type: 'end'; canceled: boolean }
+
+export type AppCapabilityStream = {
+  /** 取消时 resolve 为 null */
+  result: Promise<AppCapabilityInvokeHostResponse | null>
+  cancel: () => void
+}
+
+/** 流式调用能力：支持流式的应用会陆续推送文本分块，不支持的应用只给最终结果。 */
+export function invokeAppCapabilityStream(
+  request: AppCapabilityRequest,
+  onChunk: (text: string) => void,
+): AppCapabilityStream {
+  let invocationId = ''
+  let canceled = false
+  const result = new Promise<AppCapabilityInvokeHostResponse | null>((resolve, reject) => {
+    let finalResult: AppCapabilityInvokeHostResponse | null = null
+    let error = ''
+    const channel = new Channel<AppCapabilityStreamEvent>(ev => {
+      if (ev.type === 'chunk') onChunk(ev.text)
+      else if (ev.type === 'done') finalResult = ev.result
+      else if (ev.type === 'error') error = ev.message
+      else if (error) reject(new Error(error))
+      else resolve(ev.canceled ? null : finalResult)
+    })
+    invoke<string>('app_capability_invoke_stream', { request, channel })
+      .then(id => {
+        invocationId = id
+        if (canceled) void invoke('app_capability_invoke_stream_cancel', { invocationId: id }).catch(() => {})
+      })
+      .catch(reject)
+  })
+  return {
+    result,
+    cancel: () => {
+      canceled = true
+      if (invocationId) void invoke('app_capability_invoke_stream_cancel', { invocationId }).catch(() => {})
+    },
+  }
+}
+
+export async function getAppCapabilityEnvVars(appId: string): Promise<Array<[string, string]>> {
+  return invoke<Array<[string, string]>>('app_capability_env_vars', { appId })
+}
+
+/** 能力服务的访问策略；allow 条目形如 "appId/capabilityId"、"appId/*"、"*"、"@workflow/<id>" */
+export type CapabilityCallerPolicy = {
+  allow: string[]
+  allowLaunch: boolean
+}
+
+export type CapabilityAccessPolicy = {
+  default: CapabilityCallerPolicy
+  callers: Record<string, CapabilityCallerPolicy>
+}
+
+export type CapabilityAuditEntry = {
+  at: number
+  caller: string
+  kind: 'invoke' | 'invokeStream' | 'queryOptions' | 'workflowRun'
+  target: string
+  allowLaunch: boolean
+  durationMs: number
+  outcome: 'ok' | 'error' | 'denied' | 'canceled'
+  error?: string
+}
+
+export async function getCapabilityAccessPolicy(): Promise<CapabilityAccessPolicy> {
+  return invoke<CapabilityAccessPolicy>('capability_access_get')
+}
+
+export async function setCapabilityAccessPolicy(policy: CapabilityAccessPolicy): Promise<CapabilityAccessPolicy> {
+  return invoke<CapabilityAccessPolicy>('capability_access_set', { policy })
+}
+
+export async function getCapabilityAuditLog(limit?: number): Promise<CapabilityAuditEntry[]> {
+  return invoke<CapabilityAuditEntry[]>('capability_audit_log', { limit })
 }
 
 export async function listAppCapabilities(apps: RegisteredApp[], options: AppCapabilityListOptions = {}): Promise<AppCapabilityListHostResponse> {
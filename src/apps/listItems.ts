@@ -1,7 +1,7 @@
 import type { ComponentType } from 'react'
 import type { Plugin, PluginIconBadge } from '../constants'
 import { appDevCommandIsRunning, type AppDevCommandRuns } from './appDevCommandState'
-import type { AppStatus, RegisteredApp, RegisteredAppCapabilitySelection } from './types'
+import type { AppRuntimeCommand, AppStatus, RegisteredApp, RegisteredAppCapabilitySelection, RegisteredAppShortcut } from './types'
 
 const REGISTERED_APP_ITEM_PREFIX = 'app:'
 const REGISTERED_APP_SHORTCUT_ITEM_PREFIX = 'app-shortcut:'
@@ -73,6 +73,28 @@ export function registeredAppFromListItem(apps: RegisteredApp[], itemId: string)
   return apps.find(app => app.id === selection.appId) ?? null
 }
 
+// 应用运行中声明了命令时以声明为准：已保存的入口按声明刷新名称，不再声明的先隐藏，
+// 新声明的直接追加显示；用户保存的列表、图标和快捷键都不改动。
+export function displayedAppShortcuts(
+  commands: RegisteredAppShortcut[],
+  runtimeCommands?: AppRuntimeCommand[],
+): RegisteredAppShortcut[] {
+  if (!runtimeCommands) return commands
+  const saved = commands.flatMap(shortcut => {
+    const declared = runtimeCommands.find(command => command.id === shortcut.id)
+    if (!declared) return []
+    return [{ ...shortcut, title: declared.title || shortcut.title, icon: shortcut.icon || declared.icon }]
+  })
+  const added = runtimeCommands
+    .filter(command => !commands.some(shortcut => shortcut.id === command.id))
+    .map(command => ({ id: command.id, title: command.title, icon: command.icon }))
+  return [...saved, ...added]
+}
+
+export function registeredAppShortcuts(app: RegisteredApp, status?: AppStatus): RegisteredAppShortcut[] {
+  return displayedAppShortcuts(app.commands || [], status?.running ? status.runtimeCommands : undefined)
+}
+
 export function buildRegisteredAppListItems(
   apps: RegisteredApp[],
   statuses: Record<string, AppStatus>,
@@ -81,6 +103,7 @@ export function buildRegisteredAppListItems(
 ): Plugin[] {
   return apps.flatMap(app => {
     const icon = app.icon || app.name[0] || 'A'
+    const runtimeStatus = statuses[app.id]?.running ? statuses[app.id]?.runtimeStatus : undefined
     const appItem: Plugin = {
       id: registeredAppListItemId(app.id),
       name: app.name,
@@ -93,10 +116,13 @@ export function buildRegisteredAppListItems(
         type: 'registered-app',
         running: statuses[app.id]?.running === true,
         devCommandRunning: appDevCommandIsRunning(devCommandRuns, app.id),
+        busy: runtimeStatus?.busy === true,
+        badge: runtimeStatus?.badge,
+        statusText: runtimeStatus?.text,
       },
     }
 
-    const shortcutItems: Plugin[] = (app.commands || []).map(shortcut => ({
+    const shortcutItems: Plugin[] = registeredAppShortcuts(app, statuses[app.id]).map(shortcut => ({
       id: registeredAppShortcutListItemId(app.id, shortcut.id),
       name: shortcut.title,
       description: `${app.name} · 快捷入口`,
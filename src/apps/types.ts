@@ -4,6 +4,23 @@ export type AppActivationAction = 'toggle' | 'show' | 'hide' | 'close'
 
 export type AppHotkeyLaunchBehavior = 'launch' | 'runningOnly'
 
+export type AppRestartPolicy = 'never' | 'onFailure' | 'always'
+
+export interface AppSupervision {
+  restart: AppRestartPolicy
+  maxRestarts?: number
+  windowSecs?: number
+  backoffMs?: number
+}
+
+export interface AppHealthCheck {
+  enabled?: boolean
+  intervalSecs?: number
+  timeoutMs?: number
+  failureThreshold?: number
+  restartUnresponsive?: boolean
+}
+
 export interface RegisteredAppShortcut {
   id: string
   title: string
@@ -19,6 +36,10 @@ export interface AppCapabilityDescriptor {
   description?: string
   configFields?: AppCapabilityConfigField[]
   config?: Record<string, unknown>
+  /** JSON Schema：能力输入、配置与输出的声明 */
+  inputSchema?: Record<string, unknown> | boolean
+  configSchema?: Record<string, unknown> | boolean
+  outputSchema?: Record<string, unknown> | boolean
 }
 
 export interface AppCapabilityConfigField {
@@ -53,6 +74,8 @@ export interface RegisteredApp {
   displayMode: AppDisplayMode
   commands: RegisteredAppShortcut[]
   autoStart: boolean
+  supervision?: AppSupervision
+  healthCheck?: AppHealthCheck
   windowWidth?: number
   windowHeight?: number
   windowX?: number
@@ -64,9 +87,11 @@ export interface AppRegistrationEditRequest {
   requestId: number
 }
 
-export type RegisteredAppUpdatePatch = Partial<Omit<RegisteredApp, 'id' | 'hotkey' | 'hotkeyLaunchBehavior'>> & {
+export type RegisteredAppUpdatePatch = Partial<Omit<RegisteredApp, 'id' | 'hotkey' | 'hotkeyLaunchBehavior' | 'supervision' | 'healthCheck'>> & {
   hotkey?: string | null
   hotkeyLaunchBehavior?: AppHotkeyLaunchBehavior | null
+  supervision?: AppSupervision | null
+  healthCheck?: AppHealthCheck | null
 }
 
 export interface RegisteredAppCapabilitySelection {
@@ -79,11 +104,41 @@ export interface RegisteredAppCapabilitySelection {
   config?: Record<string, unknown>
 }
 
+export type AppCrashOutcome = 'notRestarted' | 'restarting' | 'restarted' | 'restartFailed' | 'gaveUp'
+
+export type AppCrashRecord = {
+  at: number
+  exitCode?: number
+  outcome: AppCrashOutcome
+  error?: string
+}
+
+export type AppRuntimeStatus = {
+  badge?: string
+  busy?: boolean
+  text?: string
+}
+
+/** 应用运行中经 fw-app-commands 上报的宿主快捷命令 */
+export type AppRuntimeCommand = {
+  id: string
+  title: string
+  icon?: string
+  description?: string
+}
+
 export type AppStatus = {
   running: boolean
   pid?: number
   startedAt?: number
   exitCode?: number
+  crashes?: AppCrashRecord[]
+  logPath?: string
+  unresponsive?: boolean
+  lastHealthyAt?: number
+  pingLatencyMs?: number
+  runtimeStatus?: AppRuntimeStatus
+  runtimeCommands?: AppRuntimeCommand[]
 }
 
 export type AppStopMethod = 'graceful' | 'killed' | 'alreadyStopped'
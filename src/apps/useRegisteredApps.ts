@@ -15,6 +15,14 @@ function applyRegisteredAppPatch(app: RegisteredApp, patch: RegisteredAppUpdateP
   }
   if (patch.commands !== undefined) next.commands = patch.commands
   if (patch.autoStart !== undefined) next.autoStart = patch.autoStart
+  if (patch.supervision !== undefined) {
+    if (patch.supervision) next.supervision = patch.supervision
+    else delete next.supervision
+  }
+  if (patch.healthCheck !== undefined) {
+    if (patch.healthCheck) next.healthCheck = patch.healthCheck
+    else delete next.healthCheck
+  }
   if (patch.windowWidth !== undefined) next.windowWidth = patch.windowWidth
   if (patch.windowHeight !== undefined) next.windowHeight = patch.windowHeight
   if (patch.windowX !== undefined) next.windowX = patch.windowX
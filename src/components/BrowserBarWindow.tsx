@@ -2,14 +2,20 @@ import { useCallback, useEffect, useMemo, useRef, useState } from 'react'
 import { invoke } from '@tauri-apps/api/core'
 import { getCurrentWindow } from '@tauri-apps/api/window'
 import { listen, type UnlistenFn } from '@tauri-apps/api/event'
-import { Box, IconButton, Typography } from '@mui/material'
+import { Badge, Box, IconButton, Tooltip, Typography } from '@mui/material'
 import { alpha } from '@mui/material/styles'
+import AddRoundedIcon from '@mui/icons-material/AddRounded'
 import ArrowBackRoundedIcon from '@mui/icons-material/ArrowBackRounded'
+import BookmarkAddRoundedIcon from '@mui/icons-material/BookmarkAddRounded'
 import ArrowForwardRoundedIcon from '@mui/icons-material/ArrowForwardRounded'
 import ChevronLeftRoundedIcon from '@mui/icons-material/ChevronLeftRounded'
 import ChevronRightRoundedIcon from '@mui/icons-material/ChevronRightRounded'
 import CloseRoundedIcon from '@mui/icons-material/CloseRounded'
+import DownloadRoundedIcon from '@mui/icons-material/DownloadRounded'
 import FullscreenRoundedIcon from '@mui/icons-material/FullscreenRounded'
+import GppBadRoundedIcon from '@mui/icons-material/GppBadRounded'
+import GppGoodRoundedIcon from '@mui/icons-material/GppGoodRounded'
+import PictureInPictureAltRoundedIcon from '@mui/icons-material/PictureInPictureAltRounded'
 import PushPinOutlinedIcon from '@mui/icons-material/PushPinOutlined'
 import PushPinRoundedIcon from '@mui/icons-material/PushPinRounded'
 import RefreshRoundedIcon from '@mui/icons-material/RefreshRounded'
@@ -36,7 +42,46 @@ type SpeedCycleItem = {
   rate: number
 }
 
+type BrowserTabInfo = {
+  id: string
+  url: string
+  title: string
+  canGoBack: boolean
+  canGoForward: boolean
+}
+
+type BrowserTabsSnapshot = {
+  tabs: BrowserTabInfo[]
+  activeTabId: string | null
+}
+
+type BrowserFilterStatus = {
+  enabled: boolean
+  ruleCount: number
+  pageHost: string
+  siteDisabled: boolean
+  blocked: number
+}
+
+type BrowserDownloadEvent = {
+  fileName: string
+  status: 'downloading' | 'completed' | 'failed'
+}
+
 const WEBVIEW_SETTINGS_UPDATED_EVENT = 'fast-window:webview-settings-updated'
+const BROWSER_TABS_UPDATED_EVENT = 'fast-window:browser-tabs-updated'
+const BROWSER_DOWNLOADS_UPDATED_EVENT = 'fast-window:browser-downloads-updated'
+const BROWSER_FILTER_UPDATED_EVENT = 'fast-window:browser-filter-updated'
+
+function tabLabel(tab: BrowserTabInfo) {
+  const title = (tab.title || '').trim()
+  if (title) return title
+  try {
+    return new URL(tab.url).hostname || tab.url
+  } catch {
+    return tab.url
+  }
+}
 
 function buildShortcutFromEvent(e: KeyboardEvent): string | null {
   const code = typeof e.code === 'string' ? e.code : ''
@@ -71,6 +116,85 @@ export default function BrowserBarWindow() {
   const currentRateRef = useRef(1)
   const defaultRateRef = useRef(1)
   const toggleRef = useRef<{ activeKey: string | null; prevRate: number | null }>({ activeKey: null, prevRate: null })
+  const [tabs, setTabs] = useState<BrowserTabsSnapshot>({ tabs: [], activeTabId: null })
+  const dragTabRef = useRef<string | null>(null)
+  const [notice, setNotice] = useState('')
+  const [filter, setFilter] = useState<BrowserFilterStatus | null>(null)
+  const noticeTimerRef = useRef<number | null>(null)
+
+  // 浏览栈显示时主窗口是隐藏的，宿主 toast 看不到：错误直接显示在顶部栏
+  const showNotice = useCallback((message: string) => {
+    setNotice(message)
+    if (noticeTimerRef.current != null) window.clearTimeout(noticeTimerRef.current)
+    noticeTimerRef.current = window.setTimeout(() => setNotice(''), 3000)
+  }, [])
+
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null
+    let disposed = false
+
+    void listen<BrowserDownloadEvent>(BROWSER_DOWNLOADS_UPDATED_EVENT, event => {
+      const payload = (event as any)?.payload as BrowserDownloadEvent | undefined
+      if (!payload?.fileName) return
+      if (payload.status === 'downloading') showNotice(`开始下载：${payload.fileName}`)
+      else if (payload.status === 'completed') showNotice(`下载完成：${payload.fileName}`)
+      else showNotice(`下载失败：${payload.fileName}`)
+    }).then(fn => {
+      if (disposed) fn()
+      else unlisten = fn
+    })
+
+    return () => {
+      disposed = true
+      if (unlisten) unlisten()
+    }
+  }, [showNotice])
+
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null
+    let disposed = false
+
+    void invoke<BrowserFilterStatus>('browser_filter_status')
+      .then(v => {
+        if (!disposed && v) setFilter(v)
+      })
+      .catch(() => {})
+    void listen<BrowserFilterStatus>(BROWSER_FILTER_UPDATED_EVENT, event => {
+      const payload = (event as any)?.payload as BrowserFilterStatus | undefined
+      if (payload) setFilter(payload)
+    }).then(fn => {
+      if (disposed) fn()
+      else unlisten = fn
+    })
+
+    return () => {
+      disposed = true
+      if (unlisten) unlisten()
+    }
+  }, [])
+
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null
+    let disposed = false
+
+    void invoke<BrowserTabsSnapshot>('browser_tabs_list')
+      .then(v => {
+        if (!disposed && v) setTabs(v)
+      })
+      .catch(() => {})
+    void listen<BrowserTabsSnapshot>(BROWSER_TABS_UPDATED_EVENT, event => {
+      const payload = (event as any)?.payload as BrowserTabsSnapshot | undefined
+      if (payload) setTabs(payload)
+    }).then(fn => {
+      if (disposed) fn()
+      else unlisten = fn
+    })
+
+    return () => {
+      disposed = true
+      if (unlisten) unlisten()
+    }
+  }, [])
 
   useEffect(() => {
     void invoke<boolean>('browser_stack_get_pinned')
@@ -201,6 +325,12 @@ export default function BrowserBarWindow() {
     }
   }, [busy])
 
+  const tabCall = useCallback((cmd: string, args: Record<string, unknown>) => {
+    void invoke(cmd, args).catch(() => {})
+  }, [])
+
+  const activeTab = tabs.tabs.find(t => t.id === tabs.activeTabId) || null
+
   const speedLabel = `${(Number.isFinite(currentRate) ? currentRate : 1).toFixed(2).replace(/\.00$/, '')}x`
 
   useEffect(() => {
@@ -234,7 +364,7 @@ export default function BrowserBarWindow() {
       <Box
         data-tauri-drag-region="false"
         onPointerDown={e => e.stopPropagation()}
-        sx={{ position: 'absolute', left: 6, display: 'flex', alignItems: 'center', gap: 0.5, WebkitAppRegion: 'no-drag' }}
+        sx={{ position: 'absolute', left: 6, zIndex: 1, display: 'flex', alignItems: 'center', gap: 0.5, WebkitAppRegion: 'no-drag' }}
       >
         <IconButton aria-label="关闭浏览" size="small" onClick={() => call('close_browser_window')}>
           <CloseRoundedIcon fontSize="small" />
@@ -245,6 +375,19 @@ export default function BrowserBarWindow() {
         <IconButton aria-label="全屏切换" size="small" onClick={() => call('browser_stack_toggle_fullscreen')}>
           <FullscreenRoundedIcon fontSize="small" />
         </IconButton>
+        <Tooltip title="迷你模式（按唤醒快捷键恢复）" disableInteractive>
+          <IconButton
+            aria-label="迷你模式"
+            size="small"
+            onClick={() => {
+              void invoke('browser_stack_toggle_mini').catch(e => {
+                showNotice(String((e as any)?.message || e || '切换失败'))
+              })
+            }}
+          >
+            <PictureInPictureAltRoundedIcon fontSize="small" />
+          </IconButton>
+        </Tooltip>
         <IconButton
           aria-label={pinned ? '取消图钉' : '图钉置顶'}
           size="small"
@@ -253,15 +396,80 @@ export default function BrowserBarWindow() {
         >
           {pinned ? <PushPinRoundedIcon fontSize="small" /> : <PushPinOutlinedIcon fontSize="small" />}
         </IconButton>
-        <IconButton aria-label="后退" size="small" onClick={() => call('browser_go_back')}>
+        <IconButton
+          aria-label="后退"
+          size="small"
+          disabled={Boolean(activeTab) && !activeTab?.canGoBack}
+          onClick={() => call('browser_go_back')}
+        >
           <ArrowBackRoundedIcon fontSize="small" />
         </IconButton>
-        <IconButton aria-label="前进" size="small" onClick={() => call('browser_go_forward')}>
+        <IconButton
+          aria-label="前进"
+          size="small"
+          disabled={Boolean(activeTab) && !activeTab?.canGoForward}
+          onClick={() => call('browser_go_forward')}
+        >
           <ArrowForwardRoundedIcon fontSize="small" />
         </IconButton>
         <IconButton aria-label="刷新" size="small" onClick={() => call('browser_reload')}>
           <RefreshRoundedIcon fontSize="small" />
         </IconButton>
+        <IconButton
+          aria-label="保存当前网页"
+          size="small"
+          disabled={!activeTab}
+          onClick={() => {
+            void invoke('browser_capture_current_page').catch(e => {
+              showNotice(String((e as any)?.message || e || '保存失败'))
+            })
+          }}
+        >
+          <BookmarkAddRoundedIcon fontSize="small" />
+        </IconButton>
+        <IconButton
+          aria-label="打开下载目录"
+          size="small"
+          onClick={() => {
+            void invoke('browser_download_open_dir').catch(e => {
+              showNotice(String((e as any)?.message || e || '打开失败'))
+            })
+          }}
+        >
+          <DownloadRoundedIcon fontSize="small" />
+        </IconButton>
+        <Tooltip
+          title={
+            !filter?.enabled || !filter.ruleCount
+              ? '内容拦截未启用'
+              : filter.siteDisabled
+                ? `已对 ${filter.pageHost} 停用拦截，点击恢复`
+                : `已拦截 ${filter.blocked} 个请求，点击对本站停用`
+          }
+          disableInteractive
+        >
+          <span>
+            <IconButton
+              aria-label="内容拦截"
+              size="small"
+              disabled={!filter?.enabled || !filter.ruleCount || !filter.pageHost}
+              onClick={() => {
+                void invoke<BrowserFilterStatus>('browser_filter_toggle_site')
+                  .then(v => setFilter(v))
+                  .catch(e => showNotice(String((e as any)?.message || e || '切换失败')))
+              }}
+            >
+              <Badge
+                color="primary"
+                max={99}
+                badgeContent={filter && !filter.siteDisabled ? filter.blocked : 0}
+                sx={{ '& .MuiBadge-badge': { fontSize: 9, height: 14, minWidth: 14, px: 0.5 } }}
+              >
+                {filter?.siteDisabled ? <GppBadRoundedIcon fontSize="small" /> : <GppGoodRoundedIcon fontSize="small" />}
+              </Badge>
+            </IconButton>
+          </span>
+        </Tooltip>
         <IconButton
           aria-label={`倍速 ${speedLabel}`}
           size="small"
@@ -348,20 +556,123 @@ export default function BrowserBarWindow() {
         ) : null}
       </Box>
 
-      <Typography
-        variant="body2"
-        color="text.secondary"
+      <Box
+        data-tauri-drag-region="false"
+        onPointerDown={e => e.stopPropagation()}
         sx={{
-          width: '100%',
-          textAlign: 'center',
-          fontWeight: 600,
-          letterSpacing: 0.2,
-          px: 4,
-          pointerEvents: 'none',
+          position: 'absolute',
+          left: 420,
+          right: 6,
+          display: 'flex',
+          alignItems: 'center',
+          gap: 0.5,
+          overflow: 'hidden',
+          WebkitAppRegion: 'no-drag',
         }}
       >
-        Web
-      </Typography>
+        <Box sx={{ display: 'flex', alignItems: 'center', gap: 0.5, minWidth: 0, overflowX: 'auto', scrollbarWidth: 'none' }}>
+          {tabs.tabs.map((tab, index) => {
+            const active = tab.id === tabs.activeTabId
+            const label = tabLabel(tab)
+            return (
+              <Tooltip key={tab.id} title={tab.url} enterDelay={600} disableInteractive>
+                <Box
+                  draggable
+                  onDragStart={() => {
+                    dragTabRef.current = tab.id
+                  }}
+                  onDragOver={e => e.preventDefault()}
+                  onDrop={e => {
+                    e.preventDefault()
+                    const dragged = dragTabRef.current
+                    dragTabRef.current = null
+                    if (!dragged || dragged === tab.id) return
+                    tabCall('browser_tab_move', { tabId: dragged, toIndex: index })
+                  }}
+                  onClick={() => {
+                    if (!active) tabCall('browser_tab_activate', { tabId: tab.id })
+                  }}
+                  onAuxClick={e => {
+                    // 中键关闭
+                    if (e.button === 1) tabCall('browser_tab_close', { tabId: tab.id })
+                  }}
+                  sx={{
+                    flex: '0 1 auto',
+                    minWidth: 72,
+                    maxWidth: 180,
+                    height: 28,
+                    pl: 1.25,
+                    pr: 0.25,
+                    display: 'flex',
+                    alignItems: 'center',
+                    gap: 0.25,
+                    borderRadius: 999,
+                    cursor: 'default',
+                    bgcolor: theme => (active ? alpha(theme.palette.primary.main, 0.12) : 'transparent'),
+                    '&:hover': {
+                      bgcolor: theme => alpha(theme.palette.primary.main, active ? 0.16 : 0.06),
+                    },
+                  }}
+                >
+                  <Typography
+                    variant="body2"
+                    color={active ? 'text.primary' : 'text.secondary'}
+                    sx={{
+                      flex: 1,
+                      minWidth: 0,
+                      fontWeight: active ? 700 : 500,
+                      whiteSpace: 'nowrap',
+                      overflow: 'hidden',
+                      textOverflow: 'ellipsis',
+                    }}
+                  >
+                    {label}
+                  </Typography>
+                  <IconButton
+                    aria-label={`关闭标签页 ${label}`}
+                    size="small"
+                    sx={{ p: 0.25 }}
+                    onClick={e => {
+                      e.stopPropagation()
+                      tabCall('browser_tab_close', { tabId: tab.id })
+                    }}
+                  >
+                    <CloseRoundedIcon sx={{ fontSize: 14 }} />
+                  </IconButton>
+                </Box>
+              </Tooltip>
+            )
+          })}
+        </Box>
+        <IconButton
+          aria-label="复制当前标签页"
+          size="small"
+          disabled={!activeTab}
+          onClick={() => tabCall('browser_tab_new', {})}
+        >
+          <AddRoundedIcon fontSize="small" />
+        </IconButton>
+      </Box>
+
+      {notice ? (
+        <Typography
+          variant="caption"
+          color="warning.main"
+          sx={theme => ({
+            position: 'absolute',
+            right: 44,
+            zIndex: 2,
+            px: 1,
+            py: 0.25,
+            borderRadius: 999,
+            bgcolor: alpha(theme.palette.background.paper, 0.96),
+            boxShadow: `0 6px 16px ${alpha(theme.palette.common.black, 0.08)}`,
+            pointerEvents: 'none',
+          })}
+        >
+          {notice}
+        </Typography>
+      ) : null}
     </Box>
   )
 }
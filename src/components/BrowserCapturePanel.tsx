@@ -0,0 +1,97 @@
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { Box, Button, MenuItem, Stack, TextField, Typography } from '@mui/material'
+import type { Theme } from '@mui/material/styles'
+import { hostToast } from '../host/hostPrimitives'
+import { hostButtonSx, hostSelectSx } from './hostUiStyles'
+
+type BrowserCapturePanelProps = {
+  panelSx: (theme: Theme) => object
+}
+
+const NO_TARGET = '__none__'
+
+function toast(message: string) {
+  void hostToast(message)
+}
+
+export default function BrowserCapturePanel({ panelSx }: BrowserCapturePanelProps) {
+  const [pluginIds, setPluginIds] = useState<string[]>([])
+  const [target, setTarget] = useState<string | null>(null)
+  const [busy, setBusy] = useState(false)
+
+  useEffect(() => {
+    void Promise.all([
+      invoke<string[]>('list_plugins').catch(() => [] as string[]),
+      invoke<string | null>('browser_capture_get_target').catch(() => null),
+    ]).then(([ids, current]) => {
+      setPluginIds(Array.isArray(ids) ? [...ids].sort() : [])
+      setTarget(current || null)
+    })
+  }, [])
+
+  async function changeTarget(next: string | null) {
+    setBusy(true)
+    try {
+      const saved = await invoke<string | null>('browser_capture_set_target', { pluginId: next })
+      setTarget(saved || null)
+      toast(saved ? `网页将保存到插件：${saved}` : '已关闭“保存当前网页”')
+    } catch (e: any) {
+      toast(String(e?.message || e || '设置失败'))
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  async function clearHistory() {
+    setBusy(true)
+    try {
+      await invoke('browser_history_clear')
+      toast('已清空浏览历史')
+    } catch (e: any) {
+      toast(String(e?.message || e || '清空失败'))
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  const options = target && !pluginIds.includes(target) ? [target, ...pluginIds] : pluginIds
+
+  return (
+    <Box sx={theme => ({ ...panelSx(theme), display: 'flex', flexDirection: 'column', gap: 1 })}>
+      <Box>
+        <Typography variant="body2" sx={{ fontWeight: 700 }}>
+          浏览历史与保存网页
+        </Typography>
+        <Typography variant="caption" color="text.secondary">
+          浏览窗口打开过的网页会记录在本地；顶部栏的“保存当前网页”会把网址和标题交给下面选择的插件（例如书签）
+        </Typography>
+      </Box>
+
+      <Stack direction="row" spacing={1} alignItems="center">
+        <TextField
+          size="small"
+          select
+          label="接收网页的插件"
+          value={target || NO_TARGET}
+          disabled={busy}
+          sx={{ ...hostSelectSx, minWidth: 240 }}
+          onChange={e => {
+            const v = String(e.target.value || '')
+            void changeTarget(v === NO_TARGET ? null : v)
+          }}
+        >
+          <MenuItem value={NO_TARGET}>不启用</MenuItem>
+          {options.map(id => (
+            <MenuItem key={id} value={id}>
+              {id}
+            </MenuItem>
+          ))}
+        </TextField>
+        <Button size="small" variant="outlined" sx={hostButtonSx} disabled={busy} onClick={() => void clearHistory()}>
+          清空浏览历史
+        </Button>
+      </Stack>
+    </Box>
+  )
+}
@@ -0,0 +1,166 @@
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import { Box, Button, IconButton, Stack, Typography } from '@mui/material'
+import type { Theme } from '@mui/material/styles'
+import FolderOpenRoundedIcon from '@mui/icons-material/FolderOpenRounded'
+import { hostToast } from '../host/hostPrimitives'
+import { hostButtonSx } from './hostUiStyles'
+
+type BrowserDownloadStatus = 'downloading' | 'completed' | 'failed'
+
+type BrowserDownload = {
+  id: string
+  url: string
+  fileName: string
+  path: string
+  status: BrowserDownloadStatus
+  startedAtMs: number
+  finishedAtMs: number | null
+}
+
+type BrowserDownloadsPanelProps = {
+  panelSx: (theme: Theme) => object
+}
+
+const BROWSER_DOWNLOADS_UPDATED_EVENT = 'fast-window:browser-downloads-updated'
+
+const STATUS_LABELS: Record<BrowserDownloadStatus, string> = {
+  downloading: '下载中',
+  completed: '已完成',
+  failed: '失败',
+}
+
+function toast(message: string) {
+  void hostToast(message)
+}
+
+export default function BrowserDownloadsPanel({ panelSx }: BrowserDownloadsPanelProps) {
+  const [dir, setDir] = useState('')
+  const [downloads, setDownloads] = useState<BrowserDownload[]>([])
+  const [busy, setBusy] = useState(false)
+
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null
+    let disposed = false
+
+    const refresh = () => {
+      void invoke<BrowserDownload[]>('browser_downloads_list')
+        .then(list => {
+          if (!disposed) setDownloads(Array.isArray(list) ? list : [])
+        })
+        .catch(() => {})
+    }
+
+    void invoke<string>('browser_download_get_dir')
+      .then(v => {
+        if (!disposed) setDir(String(v || ''))
+      })
+      .catch(() => {})
+    refresh()
+    void listen(BROWSER_DOWNLOADS_UPDATED_EVENT, refresh).then(fn => {
+      if (disposed) fn()
+      else unlisten = fn
+    })
+
+    return () => {
+      disposed = true
+      if (unlisten) unlisten()
+    }
+  }, [])
+
+  async function run<T>(task: () => Promise<T>, fallback: string): Promise<T | undefined> {
+    setBusy(true)
+    try {
+      return await task()
+    } catch (e: any) {
+      toast(String(e?.message || e || fallback))
+      return undefined
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  async function pickDir() {
+    const picked = await run(() => invoke<string | null>('browser_download_pick_dir'), '选择目录失败')
+    if (picked) {
+      setDir(picked)
+      toast('已更新下载目录')
+    }
+  }
+
+  async function resetDir() {
+    const next = await run(() => invoke<string>('browser_download_reset_dir'), '恢复默认失败')
+    if (next) setDir(next)
+  }
+
+  async function clearFinished() {
+    const next = await run(() => invoke<BrowserDownload[]>('browser_downloads_clear'), '清空失败')
+    if (next) setDownloads(next)
+  }
+
+  function openDir(id?: string) {
+    void invoke('browser_download_open_dir', { id: id ?? null }).catch(e => {
+      toast(String((e as any)?.message || e || '打开失败'))
+    })
+  }
+
+  return (
+    <Box sx={theme => ({ ...panelSx(theme), display: 'flex', flexDirection: 'column', gap: 1 })}>
+      <Box>
+        <Typography variant="body2" sx={{ fontWeight: 700 }}>
+          下载
+        </Typography>
+        <Typography variant="caption" color="text.secondary" sx={{ wordBreak: 'break-all' }}>
+          浏览窗口中的下载保存到：{dir || '（读取中）'}
+        </Typography>
+      </Box>
+
+      <Stack direction="row" spacing={1}>
+        <Button size="small" variant="outlined" sx={hostButtonSx} disabled={busy} onClick={() => void pickDir()}>
+          更改目录
+        </Button>
+        <Button size="small" sx={hostButtonSx} disabled={busy} onClick={() => void resetDir()}>
+          恢复默认
+        </Button>
+        <Button size="small" sx={hostButtonSx} disabled={busy} onClick={() => openDir()}>
+          打开目录
+        </Button>
+      </Stack>
+
+      {downloads.length ? (
+        <Stack spacing={0.5}>
+          {downloads.map(item => (
+            <Box key={item.id} sx={{ display: 'flex', alignItems: 'center', gap: 1 }}>
+              <Box sx={{ flex: 1, minWidth: 0 }}>
+                <Typography variant="body2" noWrap>
+                  {item.fileName}
+                </Typography>
+                <Typography
+                  variant="caption"
+                  color={item.status === 'failed' ? 'error.main' : 'text.secondary'}
+                  noWrap
+                  sx={{ display: 'block' }}
+                >
+                  {STATUS_LABELS[item.status] || item.status} · {item.url}
+                </Typography>
+              </Box>
+              <IconButton aria-label="打开所在文件夹" size="small" onClick={() => openDir(item.id)}>
+                <FolderOpenRoundedIcon fontSize="small" />
+              </IconButton>
+            </Box>
+          ))}
+          <Box>
+            <Button size="small" sx={hostButtonSx} disabled={busy} onClick={() => void clearFinished()}>
+              清除已结束的记录
+            </Button>
+          </Box>
+        </Stack>
+      ) : (
+        <Typography variant="caption" color="text.secondary">
+          本次运行还没有下载
+        </Typography>
+      )}
+    </Box>
+  )
+}
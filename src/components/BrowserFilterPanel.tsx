@@ -0,0 +1,95 @@
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { Box, Button, Stack, Switch, Typography } from '@mui/material'
+import type { Theme } from '@mui/material/styles'
+import { hostToast } from '../host/hostPrimitives'
+import { hostButtonSx } from './hostUiStyles'
+
+type BrowserFilterStatus = {
+  enabled: boolean
+  listCount: number
+  ruleCount: number
+  subresourcesSupported: boolean
+}
+
+type BrowserFilterPanelProps = {
+  panelSx: (theme: Theme) => object
+}
+
+function toast(message: string) {
+  void hostToast(message)
+}
+
+export default function BrowserFilterPanel({ panelSx }: BrowserFilterPanelProps) {
+  const [status, setStatus] = useState<BrowserFilterStatus | null>(null)
+  const [busy, setBusy] = useState(false)
+
+  useEffect(() => {
+    void invoke<BrowserFilterStatus>('browser_filter_status')
+      .then(v => setStatus(v))
+      .catch(() => {})
+  }, [])
+
+  async function run(command: string, args?: Record<string, unknown>) {
+    setBusy(true)
+    try {
+      const next = await invoke<BrowserFilterStatus>(command, args)
+      setStatus(next)
+      return next
+    } catch (e: any) {
+      toast(String(e?.message || e || '操作失败'))
+      return null
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  async function reload() {
+    const next = await run('browser_filter_reload')
+    if (next) toast(`已载入 ${next.listCount} 个规则文件，共 ${next.ruleCount} 条规则`)
+  }
+
+  return (
+    <Box sx={theme => ({ ...panelSx(theme), display: 'flex', flexDirection: 'column', gap: 1 })}>
+      <Box sx={{ display: 'flex', alignItems: 'center', gap: 1 }}>
+        <Box sx={{ flex: 1, minWidth: 0 }}>
+          <Typography variant="body2" sx={{ fontWeight: 700 }}>
+            内容拦截
+          </Typography>
+          <Typography variant="caption" color="text.secondary">
+            把 EasyList 等规则文件（.txt）放进规则目录后点“重新载入”；顶部栏的盾牌显示本页拦截数，点击可对当前站点停用
+          </Typography>
+        </Box>
+        <Switch
+          checked={Boolean(status?.enabled)}
+          disabled={busy || !status}
+          onChange={(_, checked) => void run('browser_filter_set_enabled', { enabled: checked })}
+        />
+      </Box>
+
+      {status ? (
+        <Typography variant="caption" color="text.secondary">
+          {status.listCount} 个规则文件 · {status.ruleCount} 条规则
+          {status.subresourcesSupported ? '' : ' · 当前系统只能拦截页面跳转，页面内的资源请求无法拦截'}
+        </Typography>
+      ) : null}
+
+      <Stack direction="row" spacing={1}>
+        <Button
+          size="small"
+          variant="outlined"
+          sx={hostButtonSx}
+          disabled={busy}
+          onClick={() => {
+            void invoke('browser_filter_open_dir').catch(e => toast(String((e as any)?.message || e || '打开失败')))
+          }}
+        >
+          打开规则目录
+        </Button>
+        <Button size="small" sx={hostButtonSx} disabled={busy} onClick={() => void reload()}>
+          重新载入
+        </Button>
+      </Stack>
+    </Box>
+  )
+}
@@ -0,0 +1,93 @@
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { Box, Slider, Switch, Typography } from '@mui/material'
+import type { Theme } from '@mui/material/styles'
+import { hostToast } from '../host/hostPrimitives'
+
+type BrowserMiniSettings = {
+  opacity: number
+  clickThrough: boolean
+}
+
+type BrowserMiniPanelProps = {
+  panelSx: (theme: Theme) => object
+}
+
+function toast(message: string) {
+  void hostToast(message)
+}
+
+export default function BrowserMiniPanel({ panelSx }: BrowserMiniPanelProps) {
+  const [settings, setSettings] = useState<BrowserMiniSettings | null>(null)
+  const [busy, setBusy] = useState(false)
+
+  useEffect(() => {
+    void invoke<BrowserMiniSettings>('browser_mini_get_settings')
+      .then(v => setSettings(v))
+      .catch(() => {})
+  }, [])
+
+  async function save(next: BrowserMiniSettings) {
+    setBusy(true)
+    try {
+      setSettings(await invoke<BrowserMiniSettings>('browser_mini_set_settings', next))
+    } catch (e: any) {
+      toast(String(e?.message || e || '保存失败'))
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  const percent = Math.round((settings?.opacity ?? 1) * 100)
+
+  return (
+    <Box sx={theme => ({ ...panelSx(theme), display: 'flex', flexDirection: 'column', gap: 1 })}>
+      <Box>
+        <Typography variant="body2" sx={{ fontWeight: 700 }}>
+          迷你模式
+        </Typography>
+        <Typography variant="caption" color="text.secondary">
+          顶部栏的画中画按钮把网页缩成角落小窗，拖动后会吸附到屏幕边缘；按唤醒快捷键恢复。透明度仅 Windows 支持
+        </Typography>
+      </Box>
+
+      <Box>
+        <Typography variant="caption" color="text.secondary" sx={{ display: 'block' }}>
+          透明度：{percent}%
+        </Typography>
+        <Slider
+          value={percent}
+          min={20}
+          max={100}
+          step={5}
+          disabled={busy || !settings}
+          onChange={(_, v) => {
+            const val = typeof v === 'number' ? v : v[0] ?? 100
+            setSettings(prev => (prev ? { ...prev, opacity: val / 100 } : prev))
+          }}
+          onChangeCommitted={(_, v) => {
+            const val = typeof v === 'number' ? v : v[0] ?? 100
+            if (settings) void save({ ...settings, opacity: val / 100 })
+          }}
+          aria-label="迷你模式透明度"
+        />
+      </Box>
+
+      <Box sx={{ display: 'flex', alignItems: 'center', gap: 1 }}>
+        <Box sx={{ flex: 1, minWidth: 0 }}>
+          <Typography variant="body2">点击穿透</Typography>
+          <Typography variant="caption" color="text.secondary">
+            开启后鼠标会穿过小窗操作下面的窗口，只能用唤醒快捷键退出
+          </Typography>
+        </Box>
+        <Switch
+          checked={Boolean(settings?.clickThrough)}
+          disabled={busy || !settings}
+          onChange={(_, checked) => {
+            if (settings) void save({ ...settings, clickThrough: checked })
+          }}
+        />
+      </Box>
+    </Box>
+  )
+}
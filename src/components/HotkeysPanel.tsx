@@ -0,0 +1,131 @@
+import { useCallback, useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import { Box, Switch, Typography } from '@mui/material'
+import type { Theme } from '@mui/material/styles'
+import { hostToast } from '../host/hostPrimitives'
+
+const HOTKEYS_UPDATED_EVENT = 'fast-window:hotkeys-updated'
+
+type HotkeyInfo = {
+  id: string
+  source: 'wake' | 'mainWindowMode' | 'apps' | 'workflows' | 'plugins'
+  owner: string
+  keys: string
+  enabled: boolean
+  status: 'active' | 'disabled' | 'paused' | 'conflict' | 'failed'
+  detail?: string | null
+}
+
+type HotkeysPanelProps = {
+  panelSx: (theme: Theme) => object
+}
+
+const STATUS_LABELS: Record<HotkeyInfo['status'], string> = {
+  active: '已生效',
+  disabled: '已停用',
+  paused: '录制中暂停',
+  conflict: '冲突',
+  failed: '注册失败',
+}
+
+function toast(message: string) {
+  void hostToast(message)
+}
+
+function statusColor(status: HotkeyInfo['status']) {
+  if (status === 'active') return 'success.main'
+  if (status === 'conflict' || status === 'failed') return 'error.main'
+  return 'text.secondary'
+}
+
+export default function HotkeysPanel({ panelSx }: HotkeysPanelProps) {
+  const [items, setItems] = useState<HotkeyInfo[]>([])
+  const [busyId, setBusyId] = useState<string | null>(null)
+
+  const reload = useCallback(() => {
+    void invoke<HotkeyInfo[]>('hotkeys_list')
+      .then(v => setItems(Array.isArray(v) ? v : []))
+      .catch(() => {})
+  }, [])
+
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null
+    let disposed = false
+
+    reload()
+    void listen(HOTKEYS_UPDATED_EVENT, () => reload()).then(fn => {
+      if (disposed) fn()
+      else unlisten = fn
+    })
+
+    return () => {
+      disposed = true
+      if (unlisten) unlisten()
+    }
+  }, [reload])
+
+  async function setEnabled(id: string, enabled: boolean) {
+    setBusyId(id)
+    try {
+      setItems(await invoke<HotkeyInfo[]>('hotkeys_set_enabled', { id, enabled }))
+    } catch (e: any) {
+      toast(String(e?.message || e || '保存失败'))
+    } finally {
+      setBusyId(null)
+    }
+  }
+
+  const problems = items.filter(item => item.status === 'conflict' || item.status === 'failed').length
+
+  return (
+    <Box sx={theme => ({ ...panelSx(theme), display: 'flex', flexDirection: 'column', gap: 1 })}>
+      <Box>
+        <Typography variant="body2" sx={{ fontWeight: 700 }}>
+          全部全局快捷键
+        </Typography>
+        <Typography variant="caption" color="text.secondary">
+          宿主、注册应用、工作流和插件的快捷键都在这里；冲突时依次按宿主、应用、工作流、插件的优先级。支持按键序列，如 ctrl+K ctrl+M
+        </Typography>
+      </Box>
+
+      {problems > 0 ? (
+        <Typography variant="caption" color="error.main">
+          有 {problems} 个快捷键未生效
+        </Typography>
+      ) : null}
+
+      {items.length === 0 ? (
+        <Typography variant="caption" color="text.secondary">
+          暂无
+        </Typography>
+      ) : (
+        items.map(item => (
+          <Box key={item.id} sx={{ display: 'flex', alignItems: 'center', gap: 1 }}>
+            <Box sx={{ flex: 1, minWidth: 0 }}>
+              <Typography variant="body2" noWrap title={item.owner}>
+                {item.owner}
+              </Typography>
+              <Typography
+                variant="caption"
+                sx={{ fontFamily: 'ui-monospace, SFMono-Regular, Menlo, Monaco, Consolas, monospace' }}
+              >
+                {item.keys}
+              </Typography>
+              <Typography variant="caption" color={statusColor(item.status)} sx={{ display: 'block' }}>
+                {STATUS_LABELS[item.status] || item.status}
+                {item.detail ? `：${item.detail}` : ''}
+              </Typography>
+            </Box>
+            <Switch
+              checked={item.enabled}
+              disabled={busyId != null || item.source === 'wake'}
+              onChange={(_, checked) => void setEnabled(item.id, checked)}
+              inputProps={{ 'aria-label': `启用 ${item.owner}` }}
+            />
+          </Box>
+        ))
+      )}
+    </Box>
+  )
+}
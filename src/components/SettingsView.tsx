@@ -31,6 +31,14 @@ import WallpaperViewEditorDialog from './WallpaperViewEditorDialog'
 import HostPageHeader from './HostPageHeader'
 import HostUpdatePanel from './HostUpdatePanel'
 import HostDevToolsPanel from './HostDevToolsPanel'
+import WebviewUserScriptsPanel from './WebviewUserScriptsPanel'
+import BrowserCapturePanel from './BrowserCapturePanel'
+import BrowserDownloadsPanel from './BrowserDownloadsPanel'
+import BrowserFilterPanel from './BrowserFilterPanel'
+import BrowserMiniPanel from './BrowserMiniPanel'
+import HotkeysPanel from './HotkeysPanel'
+import UsagePanel from './UsagePanel'
+import WorkflowsPanel from './WorkflowsPanel'
 import {
   hostButtonSx,
   hostPageRootSx,
@@ -1182,6 +1190,8 @@ export default function SettingsView(props: {
                 </Button>
               </Box>
             </Box>
+
+            <UsagePanel panelSx={panelSx} />
           </Stack>
         ) : null}
       </Box>
@@ -1324,18 +1334,22 @@ export default function SettingsView(props: {
 
       <Box role="tabpanel" hidden={tabIndex !== TAB_APP_REGISTRATION} id="settings-tabpanel-4" aria-labelledby="settings-tab-4" sx={{ pt: 0.5 }}>
         {tabIndex === TAB_APP_REGISTRATION ? (
-          <Box sx={panelSx}>
-            <AppRegistrationPanel
-              embedded
-              apps={registeredApps}
-              onAdd={onAddRegisteredApp}
-              onReplace={onReplaceRegisteredApp}
-              onRemove={onRemoveRegisteredApp}
-              onUpdate={onUpdateRegisteredApp}
-              editRequest={appRegistrationEditRequest}
-              onEditRequestHandled={onAppRegistrationEditRequestHandled}
-            />
-          </Box>
+          <Stack spacing={1.25}>
+            <Box sx={panelSx}>
+              <AppRegistrationPanel
+                embedded
+                apps={registeredApps}
+                onAdd={onAddRegisteredApp}
+                onReplace={onReplaceRegisteredApp}
+                onRemove={onRemoveRegisteredApp}
+                onUpdate={onUpdateRegisteredApp}
+                editRequest={appRegistrationEditRequest}
+                onEditRequestHandled={onAppRegistrationEditRequestHandled}
+              />
+            </Box>
+
+            <WorkflowsPanel panelSx={panelSx} />
+          </Stack>
         ) : null}
       </Box>
 
@@ -1477,6 +1491,8 @@ export default function SettingsView(props: {
                 {modeShortcutHint}
               </Typography>
             </Box>
+
+            <HotkeysPanel panelSx={panelSx} />
           </Stack>
         ) : null}
       </Box>
@@ -1657,6 +1673,12 @@ export default function SettingsView(props: {
                 {recordingPresetIndex != null ? '录制中…按下组合键（ESC 取消）。' : '提示：快捷键会在浏览窗口里即时生效。'}
               </Typography>
             </Box>
+
+            <WebviewUserScriptsPanel panelSx={panelSx} />
+            <BrowserCapturePanel panelSx={panelSx} />
+            <BrowserDownloadsPanel panelSx={panelSx} />
+            <BrowserFilterPanel panelSx={panelSx} />
+            <BrowserMiniPanel panelSx={panelSx} />
           </Stack>
         ) : null}
       </Box>
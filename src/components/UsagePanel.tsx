@@ -0,0 +1,73 @@
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { Box, Typography } from '@mui/material'
+import type { Theme } from '@mui/material/styles'
+
+type UsageStat = {
+  kind: 'hostAction' | 'plugin' | 'app' | 'appCommand' | 'capability'
+  target: string
+  count: number
+  firstUsedMs: number
+  lastUsedMs: number
+  count7d: number
+  count30d: number
+  frecency: number
+}
+
+type UsagePanelProps = {
+  panelSx: (theme: Theme) => object
+}
+
+const KIND_LABELS: Record<UsageStat['kind'], string> = {
+  hostAction: '宿主页面',
+  plugin: '插件',
+  app: '应用',
+  appCommand: '快捷入口',
+  capability: '能力',
+}
+
+function formatDate(ms: number) {
+  if (!ms) return '-'
+  const d = new Date(ms)
+  return `${d.getFullYear()}-${String(d.getMonth() + 1).padStart(2, '0')}-${String(d.getDate()).padStart(2, '0')}`
+}
+
+export default function UsagePanel({ panelSx }: UsagePanelProps) {
+  const [items, setItems] = useState<UsageStat[] | null>(null)
+
+  useEffect(() => {
+    void invoke<UsageStat[]>('usage_top', { limit: 20 })
+      .then(v => setItems(Array.isArray(v) ? v : []))
+      .catch(() => setItems([]))
+  }, [])
+
+  return (
+    <Box sx={theme => ({ ...panelSx(theme), display: 'flex', flexDirection: 'column', gap: 0.75 })}>
+      <Box>
+        <Typography variant="body2" sx={{ fontWeight: 700 }}>
+          常用
+        </Typography>
+        <Typography variant="caption" color="text.secondary">
+          按使用次数和最近使用时间排序；主窗口搜索也按这个顺序排在前面。记录只保存在本机
+        </Typography>
+      </Box>
+
+      {items == null ? null : items.length === 0 ? (
+        <Typography variant="caption" color="text.secondary">
+          暂无使用记录
+        </Typography>
+      ) : (
+        items.map(item => (
+          <Box key={`${item.kind}:${item.target}`} sx={{ display: 'flex', alignItems: 'baseline', gap: 1 }}>
+            <Typography variant="body2" noWrap sx={{ flex: 1, minWidth: 0 }} title={item.target}>
+              {item.target}
+            </Typography>
+            <Typography variant="caption" color="text.secondary" sx={{ flexShrink: 0 }}>
+              {KIND_LABELS[item.kind] || item.kind} · 共 {item.count} 次 · 近 7 天 {item.count7d} 次 · 最近 {formatDate(item.lastUsedMs)}
+            </Typography>
+          </Box>
+        ))
+      )}
+    </Box>
+  )
+}
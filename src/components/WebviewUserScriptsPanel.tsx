@@ -0,0 +1,234 @@
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import {
+  Box,
+  Button,
+  Dialog,
+  DialogActions,
+  DialogContent,
+  DialogTitle,
+  IconButton,
+  MenuItem,
+  Stack,
+  Switch,
+  TextField,
+  Typography,
+} from '@mui/material'
+import type { Theme } from '@mui/material/styles'
+import DeleteRoundedIcon from '@mui/icons-material/DeleteRounded'
+import EditRoundedIcon from '@mui/icons-material/EditRounded'
+import { hostToast } from '../host/hostPrimitives'
+import { hostButtonSx, hostSelectSx, hostTextFieldSx } from './hostUiStyles'
+
+type UserScriptRunAt = 'documentStart' | 'documentEnd' | 'documentIdle'
+
+type WebviewUserScript = {
+  id: string
+  name: string
+  enabled: boolean
+  matches: string[]
+  runAt: UserScriptRunAt
+  js: string
+  css: string
+}
+
+type WebviewUserScriptsPanelProps = {
+  panelSx: (theme: Theme) => object
+}
+
+const RUN_AT_LABELS: Record<UserScriptRunAt, string> = {
+  documentStart: '页面开始加载时',
+  documentEnd: 'DOM 就绪后',
+  documentIdle: '页面加载完成后',
+}
+
+const EMPTY_SCRIPT: WebviewUserScript = {
+  id: '',
+  name: '',
+  enabled: true,
+  matches: [],
+  runAt: 'documentEnd',
+  js: '',
+  css: '',
+}
+
+function toast(message: string) {
+  void hostToast(message)
+}
+
+export default function WebviewUserScriptsPanel({ panelSx }: WebviewUserScriptsPanelProps) {
+  const [scripts, setScripts] = useState<WebviewUserScript[]>([])
+  const [editing, setEditing] = useState<WebviewUserScript | null>(null)
+  const [matchesText, setMatchesText] = useState('')
+  const [busy, setBusy] = useState(false)
+
+  useEffect(() => {
+    void invoke<WebviewUserScript[]>('browser_user_scripts_list')
+      .then(list => setScripts(Array.isArray(list) ? list : []))
+      .catch(() => {})
+  }, [])
+
+  async function save(script: WebviewUserScript, closeDialog: boolean) {
+    setBusy(true)
+    try {
+      const next = await invoke<WebviewUserScript[]>('browser_user_script_save', { script })
+      setScripts(next)
+      if (closeDialog) {
+        setEditing(null)
+        toast('已保存用户脚本（重新打开浏览后生效）')
+      }
+    } catch (e: any) {
+      toast(String(e?.message || e || '保存失败'))
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  async function remove(id: string) {
+    setBusy(true)
+    try {
+      const next = await invoke<WebviewUserScript[]>('browser_user_script_remove', { id })
+      setScripts(next)
+    } catch (e: any) {
+      toast(String(e?.message || e || '删除失败'))
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  function openEditor(script: WebviewUserScript) {
+    setEditing(script)
+    setMatchesText(script.matches.join('\n'))
+  }
+
+  return (
+    <Box sx={theme => ({ ...panelSx(theme), display: 'flex', flexDirection: 'column', gap: 1 })}>
+      <Box>
+        <Typography variant="body2" sx={{ fontWeight: 700 }}>
+          用户脚本与样式
+        </Typography>
+        <Typography variant="caption" color="text.secondary">
+          按网址匹配规则向浏览窗口注入 JS / CSS（如 {'*://*.example.com/*'}、{'<all_urls>'}），修改后重新打开浏览生效
+        </Typography>
+      </Box>
+
+      {scripts.length ? (
+        <Stack spacing={0.5}>
+          {scripts.map(script => (
+            <Box key={script.id} sx={{ display: 'flex', alignItems: 'center', gap: 1 }}>
+              <Switch
+                size="small"
+                checked={script.enabled}
+                disabled={busy}
+                onChange={(_, checked) => void save({ ...script, enabled: checked }, false)}
+              />
+              <Box sx={{ flex: 1, minWidth: 0 }}>
+                <Typography variant="body2" sx={{ fontWeight: 600 }} noWrap>
+                  {script.name}
+                </Typography>
+                <Typography variant="caption" color="text.secondary" noWrap sx={{ display: 'block' }}>
+                  {RUN_AT_LABELS[script.runAt] || script.runAt} · {script.matches.join('，')}
+                </Typography>
+              </Box>
+              <IconButton aria-label="编辑用户脚本" size="small" disabled={busy} onClick={() => openEditor(script)}>
+                <EditRoundedIcon fontSize="small" />
+              </IconButton>
+              <IconButton aria-label="删除用户脚本" size="small" disabled={busy} onClick={() => void remove(script.id)}>
+                <DeleteRoundedIcon fontSize="small" />
+              </IconButton>
+            </Box>
+          ))}
+        </Stack>
+      ) : (
+        <Typography variant="caption" color="text.secondary">
+          暂无用户脚本
+        </Typography>
+      )}
+
+      <Box>
+        <Button size="small" variant="outlined" sx={hostButtonSx} disabled={busy} onClick={() => openEditor(EMPTY_SCRIPT)}>
+          添加用户脚本
+        </Button>
+      </Box>
+
+      <Dialog open={Boolean(editing)} onClose={() => !busy && setEditing(null)} fullWidth maxWidth="sm">
+        <DialogTitle>{editing?.id ? '编辑用户脚本' : '添加用户脚本'}</DialogTitle>
+        <DialogContent>
+          {editing ? (
+            <Stack spacing={1.25} sx={{ pt: 1 }}>
+              <TextField
+                size="small"
+                label="名称"
+                value={editing.name}
+                sx={hostTextFieldSx}
+                onChange={e => setEditing({ ...editing, name: e.target.value })}
+              />
+              <TextField
+                size="small"
+                label="匹配规则（每行一条）"
+                value={matchesText}
+                multiline
+                minRows={2}
+                sx={hostTextFieldSx}
+                onChange={e => setMatchesText(e.target.value)}
+              />
+              <TextField
+                size="small"
+                select
+                label="注入时机"
+                value={editing.runAt}
+                sx={hostSelectSx}
+                onChange={e => setEditing({ ...editing, runAt: e.target.value as UserScriptRunAt })}
+              >
+                {(Object.keys(RUN_AT_LABELS) as UserScriptRunAt[]).map(key => (
+                  <MenuItem key={key} value={key}>
+                    {RUN_AT_LABELS[key]}
+                  </MenuItem>
+                ))}
+              </TextField>
+              <TextField
+                size="small"
+                label="JavaScript"
+                value={editing.js}
+                multiline
+                minRows={4}
+                sx={{ ...hostTextFieldSx, '& textarea': { fontFamily: 'monospace' } }}
+                onChange={e => setEditing({ ...editing, js: e.target.value })}
+              />
+              <TextField
+                size="small"
+                label="CSS"
+                value={editing.css}
+                multiline
+                minRows={3}
+                sx={{ ...hostTextFieldSx, '& textarea': { fontFamily: 'monospace' } }}
+                onChange={e => setEditing({ ...editing, css: e.target.value })}
+              />
+            </Stack>
+          ) : null}
+        </DialogContent>
+        <DialogActions>
+          <Button size="small" sx={hostButtonSx} disabled={busy} onClick={() => setEditing(null)}>
+            取消
+          </Button>
+          <Button
+            size="small"
+            variant="contained"
+            sx={hostButtonSx}
+            disabled={busy || !editing}
+            onClick={() => {
+              if (!editing) return
+              const matches = matchesText
+                .split('\n')
+                .map(s => s.trim())
+                .filter(Boolean)
+              void save({ ...editing, matches }, true)
+            }}
+          >
+            保存
+          </Button>
+        </DialogActions>
+      </Dialog>
+    </Box>
+  )
+}
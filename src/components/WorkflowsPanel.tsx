@@ -0,0 +1,215 @@
+import { useCallback, useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { Box, Button, TextField, Typography } from '@mui/material'
+import type { Theme } from '@mui/material/styles'
+import { hostToast } from '../host/hostPrimitives'
+
+type WorkflowStep = {
+  appId: string
+  capabilityId: string
+  input?: string
+  config?: unknown
+}
+
+type Workflow = {
+  id: string
+  title: string
+  hotkey?: string
+  steps: WorkflowStep[]
+}
+
+type WorkflowStepResult = {
+  index: number
+  appId: string
+  capabilityId: string
+  status: 'ok' | 'failed' | 'skipped'
+  text?: string
+  error?: string
+  elapsedMs: number
+}
+
+type WorkflowRunResult = {
+  workflowId: string
+  ok: boolean
+  text?: string
+  steps: WorkflowStepResult[]
+}
+
+type WorkflowsPanelProps = {
+  panelSx: (theme: Theme) => object
+}
+
+const STEP_STATUS_LABELS: Record<WorkflowStepResult['status'], string> = {
+  ok: '完成',
+  failed: '失败',
+  skipped: '跳过',
+}
+
+const EMPTY_DRAFT = JSON.stringify(
+  { id: '', title: '', hotkey: '', steps: [{ appId: '', capabilityId: '', input: '{{prev}}' }] },
+  null,
+  2,
+)
+
+function toast(message: string) {
+  void hostToast(message)
+}
+
+function errorText(e: any, fallback: string) {
+  return String(e?.message || e || fallback)
+}
+
+export default function WorkflowsPanel({ panelSx }: WorkflowsPanelProps) {
+  const [items, setItems] = useState<Workflow[]>([])
+  const [draft, setDraft] = useState<string | null>(null)
+  const [runInput, setRunInput] = useState('')
+  const [runResult, setRunResult] = useState<WorkflowRunResult | null>(null)
+  const [busy, setBusy] = useState(false)
+
+  const reload = useCallback(() => {
+    void invoke<Workflow[]>('app_workflow_list')
+      .then(v => setItems(Array.isArray(v) ? v : []))
+      .catch(() => {})
+  }, [])
+
+  useEffect(() => {
+    reload()
+  }, [reload])
+
+  async function save() {
+    if (draft == null) return
+    let workflow: Workflow
+    try {
+      workflow = JSON.parse(draft)
+    } catch {
+      toast('工作流 JSON 格式不正确')
+      return
+    }
+    if (workflow && typeof workflow.hotkey === 'string' && !workflow.hotkey.trim()) delete workflow.hotkey
+    setBusy(true)
+    try {
+      setItems(await invoke<Workflow[]>('app_workflow_save', { workflow }))
+      setDraft(null)
+    } catch (e: any) {
+      toast(errorText(e, '保存失败'))
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  async function remove(workflowId: string) {
+    setBusy(true)
+    try {
+      setItems(await invoke<Workflow[]>('app_workflow_remove', { workflowId }))
+    } catch (e: any) {
+      toast(errorText(e, '删除失败'))
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  async function run(workflowId: string) {
+    setBusy(true)
+    setRunResult(null)
+    try {
+      setRunResult(await invoke<WorkflowRunResult>('app_workflow_run', { workflowId, input: runInput }))
+    } catch (e: any) {
+      toast(errorText(e, '执行失败'))
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  return (
+    <Box sx={theme => ({ ...panelSx(theme), display: 'flex', flexDirection: 'column', gap: 1 })}>
+      <Box>
+        <Typography variant="body2" sx={{ fontWeight: 700 }}>
+          能力工作流
+        </Typography>
+        <Typography variant="caption" color="text.secondary">
+          按顺序调用多个应用能力，上一步的输出作为下一步的输入。步骤输入可写模板：{'{{input}}'}、{'{{prev}}'}、{'{{steps.1}}'}；
+          绑定快捷键后以剪贴板文本作为输入执行
+        </Typography>
+      </Box>
+
+      <TextField size="small" label="执行时的输入" value={runInput} onChange={e => setRunInput(e.target.value)} />
+
+      {items.length === 0 ? (
+        <Typography variant="caption" color="text.secondary">
+          暂无
+        </Typography>
+      ) : (
+        items.map(item => (
+          <Box key={item.id} sx={{ display: 'flex', alignItems: 'center', gap: 1 }}>
+            <Box sx={{ flex: 1, minWidth: 0 }}>
+              <Typography variant="body2" noWrap title={item.id}>
+                {item.title}
+              </Typography>
+              <Typography variant="caption" color="text.secondary" noWrap sx={{ display: 'block' }}>
+                {item.steps.map(step => `${step.appId}/${step.capabilityId}`).join(' → ')}
+                {item.hotkey ? ` · ${item.hotkey}` : ''}
+              </Typography>
+            </Box>
+            <Button size="small" disabled={busy} onClick={() => void run(item.id)}>
+              执行
+            </Button>
+            <Button size="small" disabled={busy} onClick={() => setDraft(JSON.stringify(item, null, 2))}>
+              编辑
+            </Button>
+            <Button size="small" color="error" disabled={busy} onClick={() => void remove(item.id)}>
+              删除
+            </Button>
+          </Box>
+        ))
+      )}
+
+      {runResult ? (
+        <Box sx={{ display: 'flex', flexDirection: 'column', gap: 0.25 }}>
+          {runResult.steps.map(step => (
+            <Typography
+              key={step.index}
+              variant="caption"
+              color={step.status === 'failed' ? 'error.main' : 'text.secondary'}
+            >
+              {step.index}. {step.appId}/{step.capabilityId} · {STEP_STATUS_LABELS[step.status]}
+              {step.status === 'skipped' ? '' : ` · ${step.elapsedMs}ms`}
+              {step.error ? `：${step.error}` : ''}
+            </Typography>
+          ))}
+          {runResult.text ? (
+            <Typography variant="body2" sx={{ whiteSpace: 'pre-wrap', wordBreak: 'break-word' }}>
+              {runResult.text}
+            </Typography>
+          ) : null}
+        </Box>
+      ) : null}
+
+      {draft == null ? (
+        <Box>
+          <Button size="small" disabled={busy} onClick={() => setDraft(EMPTY_DRAFT)}>
+            新建工作流
+          </Button>
+        </Box>
+      ) : (
+        <Box sx={{ display: 'flex', flexDirection: 'column', gap: 1 }}>
+          <TextField
+            multiline
+            minRows={6}
+            size="small"
+            value={draft}
+            onChange={e => setDraft(e.target.value)}
+            inputProps={{ spellCheck: false, style: { fontFamily: 'ui-monospace, SFMono-Regular, Menlo, Monaco, Consolas, monospace' } }}
+          />
+          <Box sx={{ display: 'flex', gap: 1 }}>
+            <Button size="small" variant="contained" disabled={busy} onClick={() => void save()}>
+              保存
+            </Button>
+            <Button size="small" disabled={busy} onClick={() => setDraft(null)}>
+              取消
+            </Button>
+          </Box>
+        </Box>
+      )}
+    </Box>
+  )
+}
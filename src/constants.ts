@@ -29,6 +29,9 @@ export interface Plugin {
     type: 'registered-app'
     running: boolean
     devCommandRunning?: boolean
+    busy?: boolean
+    badge?: string
+    statusText?: string
   }
   iconBadge?: PluginIconBadge
 }
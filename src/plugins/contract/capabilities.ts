@@ -13,6 +13,23 @@ export type PluginCapability =
   | `cap:${string}`
 
 const CAPABILITY_TEXT_RE = /^[A-Za-z0-9._:*|-]+$/
+const HTTP_HOST_CAPABILITY_PREFIX = 'cap:http:'
+const HOST_LABEL_RE = /^[A-Za-z0-9_](?:[A-Za-z0-9_-]{0,61}[A-Za-z0-9_])?$/
+
+// 与 Rust 侧 http_host_policy 保持一致：host / *.domain / * ，可带端口
+function isValidHttpHostPattern(raw: string): boolean {
+  if (raw === '*') return true
+  let host = raw
+  const colon = raw.lastIndexOf(':')
+  if (colon >= 0) {
+    const port = raw.slice(colon + 1)
+    if (!/^\d{1,5}$/.test(port) || Number(port) < 1 || Number(port) > 65535) return false
+    host = raw.slice(0, colon)
+  }
+  if (host.startsWith('*.')) host = host.slice(2)
+  if (!host || host.length > 253) return false
+  return host.split('.').every(label => HOST_LABEL_RE.test(label))
+}
 
 export function isSupportedPluginApiVersion(value: unknown): value is SupportedPluginApiVersion {
   return typeof value === 'number' && SUPPORTED_PLUGIN_API_VERSIONS.includes(value as SupportedPluginApiVersion)
@@ -26,6 +43,7 @@ export function isValidPluginCapability(item: unknown): item is PluginCapability
 
   if (s.startsWith('tauri:')) return true
   if (!s.startsWith('cap:')) return false
+  if (s.startsWith(HTTP_HOST_CAPABILITY_PREFIX)) return isValidHttpHostPattern(s.slice(HTTP_HOST_CAPABILITY_PREFIX.length))
 
   return CAPABILITY_TEXT_RE.test(s.slice('cap:'.length))
 }
@@ -2,6 +2,12 @@ import type { PluginCapability, SupportedPluginApiVersion } from './capabilities
 
 export type PluginUiType = 'iframe'
 
+export interface PluginHotkeyDeclaration {
+  id: string
+  hotkey: string
+  title?: string
+}
+
 export interface PluginManifest {
   id: string
   name: string
@@ -14,6 +20,7 @@ export interface PluginManifest {
   allowOverwriteOnUpdate?: boolean
   apiVersion?: SupportedPluginApiVersion
   requires?: PluginCapability[]
+  hotkeys?: PluginHotkeyDeclaration[]
   ui?: {
     type: PluginUiType
     keepAlive?: boolean
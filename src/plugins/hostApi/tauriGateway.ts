@@ -37,6 +37,17 @@ const MAX_TAURI_STREAMS_PER_PLUGIN = 32
 
 const STORE_LOAD_COMMAND = 'plugin:store|load'
 
+// 这些命令按插件隔离状态（HTTP 缓存、待领取的网页等），pluginId 由宿主注入，不信任插件自报
+const PLUGIN_SCOPED_COMMANDS: ReadonlySet<string> = new Set([
+  'http_request',
+  'http_request_base64',
+  'http_request_stream',
+  'browser_capture_take',
+  'plugin_shortcut_register',
+  'plugin_shortcut_unregister',
+  'plugin_shortcut_take',
+])
+
 type StreamHandle = {
   pluginId: string
   closed: boolean
@@ -219,6 +230,12 @@ async function invokeWithTimeout<T>(command: string, payload: any, timeoutMs: nu
   }
 }
 
+function withPluginScope(command: string, pluginId: string, payload: any): any {
+  if (!PLUGIN_SCOPED_COMMANDS.has(command)) return payload
+  if (!payload || typeof payload !== 'object' || Array.isArray(payload)) return payload
+  return { ...payload, pluginId }
+}
+
 function validatePluginIdNotForged(pluginId: string, payload: unknown) {
   if (!payload || typeof payload !== 'object' || Array.isArray(payload)) return
 
@@ -246,7 +263,9 @@ const tauriInvokeMethod: PluginMethodDef = {
     validatePluginIdNotForged(ctx.id, spec?.payload)
 
     const payload0 =
-      command === STORE_LOAD_COMMAND ? await rewriteStoreLoadPayload(ctx.id, spec?.payload) : (spec?.payload ?? {})
+      command === STORE_LOAD_COMMAND
+        ? await rewriteStoreLoadPayload(ctx.id, spec?.payload)
+        : withPluginScope(command, ctx.id, spec?.payload ?? {})
 
     const maxBytes = resolveTauriPayloadMaxBytes(command)
     if (maxBytes != null) {
@@ -378,7 +397,7 @@ const tauriStreamOpenMethod: PluginMethodDef = {
       }
     })
 
-    const payload = { ...(rawPayload as any), [channelKey]: channel }
+    const payload = { ...withPluginScope(command, ctx.id, rawPayload), [channelKey]: channel }
     post({ type: '__gateway_start', kind: 'channel', command })
 
     void Promise.resolve()
@@ -1,5 +1,6 @@
 import {
   type PluginCapability,
+  type PluginHotkeyDeclaration,
   type PluginManifest,
   SUPPORTED_PLUGIN_API_VERSIONS,
   isSupportedPluginApiVersion,
@@ -36,6 +37,34 @@ function parseRequires(value: unknown, required: boolean): { ok: true; requires:
   return { ok: true, requires }
 }
 
+// 格式不对的项只丢弃并给出警告：快捷键是附加能力，不应让整个插件载入失败
+function parseHotkeys(value: unknown, warnings: string[]): PluginHotkeyDeclaration[] {
+  if (value === undefined) return []
+  if (!Array.isArray(value)) {
+    warnings.push('manifest.hotkeys must be an array; ignored')
+    return []
+  }
+
+  const hotkeys: PluginHotkeyDeclaration[] = []
+  const seen = new Set<string>()
+  for (const item of value) {
+    const id = normalizeText(item?.id)
+    const hotkey = normalizeText(item?.hotkey)
+    if (!id || !isSafePluginId(id) || !hotkey) {
+      warnings.push(`invalid hotkey entry ignored: ${JSON.stringify(item)}`)
+      continue
+    }
+    if (seen.has(id)) {
+      warnings.push(`duplicate hotkey id ignored: ${id}`)
+      continue
+    }
+    seen.add(id)
+    const title = normalizeText(item?.title)
+    hotkeys.push(title ? { id, hotkey, title } : { id, hotkey })
+  }
+  return hotkeys
+}
+
 function validateUiType(value: unknown, required: boolean): ManifestParseResult | null {
   if (value === 'iframe') return null
   if (value === undefined && !required) return null
@@ -92,6 +121,8 @@ export function parsePluginManifest(pluginId: string, manifestContent: string):
     if (bg.main !== undefined && typeof bg.main !== 'string') return { ok: false, reason: 'background.main must be a string when provided' }
   }
 
+  const hotkeys = parseHotkeys(raw?.hotkeys, warnings)
+
   const manifest: PluginManifest = {
     ...raw,
     id: manifestId,
@@ -101,6 +132,7 @@ export function parsePluginManifest(pluginId: string, manifestContent: string):
     main,
     apiVersion,
     requires: requiresResult.requires,
+    hotkeys,
   }
 
   return { ok: true, manifest, warnings }
@@ -13,6 +13,7 @@ export type {
 } from './contract/capabilities'
 
 export type {
+  PluginHotkeyDeclaration,
   PluginManifest,
   PluginUiType,
 } from './contract/manifest'
@@ -29,6 +29,7 @@ const recordingGuardCommands = [
   ['pause_wake_shortcut', 'resume_wake_shortcut'],
   ['pause_main_window_mode_shortcut', 'resume_main_window_mode_shortcut'],
   ['pause_registered_app_shortcuts', 'resume_registered_app_shortcuts'],
+  ['pause_plugin_shortcuts', 'resume_plugin_shortcuts'],
 ] as const
 
 export function pauseShortcutRecordingGuards(): void {
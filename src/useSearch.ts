@@ -1,19 +1,66 @@
-import { useMemo, useState } from 'react'
+import { useEffect, useMemo, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
 import type { Plugin } from './constants'
 
-export function useSearch(plugins: Plugin[]) {
+type CommandIndexHit = {
+  id: string
+  kind: 'hostAction' | 'plugin' | 'app' | 'appCommand' | 'capability'
+  title: string
+  subtitle: string
+  appId?: string
+  score: number
+}
+
+const COMMAND_INDEX_LIMIT = 200
+
+function filterLocally(items: Plugin[], q: string) {
+  const needle = q.toLowerCase()
+  return items.filter(p => {
+    const keyword = p.keyword?.toLowerCase() || ''
+    return p.name.toLowerCase().includes(needle) || keyword.includes(needle)
+  })
+}
+
+// 有关键词时走宿主命令索引（模糊匹配 + 常用排序）；索引不可用时退回本地包含匹配。
+// searchOnlyItems（宿主页面等）只在搜索结果里出现。
+export function useSearch(plugins: Plugin[], searchOnlyItems: Plugin[] = []) {
   const [query, setQuery] = useState('')
+  const [hits, setHits] = useState<{ query: string; ids: string[] } | null>(null)
+
+  useEffect(() => {
+    const q = query.trim()
+    if (!q) {
+      setHits(null)
+      return
+    }
+    let disposed = false
+    void invoke<CommandIndexHit[]>('command_index_query', { query: q, limit: COMMAND_INDEX_LIMIT })
+      .then(result => {
+        if (!disposed) setHits({ query: q, ids: Array.isArray(result) ? result.map(hit => hit.id) : [] })
+      })
+      .catch(() => {
+        if (!disposed) setHits(null)
+      })
+    return () => {
+      disposed = true
+    }
+  }, [query, plugins])
 
   const filtered = useMemo(() => {
     const q = query.trim()
     if (!q) return plugins
+    if (!hits || hits.query !== q) return filterLocally(plugins, q)
 
-    const needle = q.toLowerCase()
-    return plugins.filter(p => {
-      const keyword = p.keyword?.toLowerCase() || ''
-      return p.name.toLowerCase().includes(needle) || keyword.includes(needle)
+    const byId = new Map<string, Plugin>()
+    for (const item of [...plugins, ...searchOnlyItems]) byId.set(item.id, item)
+    const ranked = hits.ids.flatMap(id => {
+      const item = byId.get(id)
+      return item ? [item] : []
     })
-  }, [query, plugins])
+    // 索引里还没有的（如刚加入的能力）按本地匹配补在后面
+    const rankedIds = new Set(ranked.map(item => item.id))
+    return ranked.concat(filterLocally(plugins, q).filter(item => !rankedIds.has(item.id)))
+  }, [hits, query, plugins, searchOnlyItems])
 
   return { query, setQuery, filtered }
 }